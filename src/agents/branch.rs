@@ -1,7 +1,7 @@
 use crate::model::agent::AgentName;
 
-/// Each agent gets a single persistent branch that gets force-reset to origin/main
-/// before each dispatch. No item-specific branches — agents always push to main.
+/// Each agent gets a single persistent branch that gets force-reset to the base
+/// branch before each dispatch. No item-specific branches.
 pub fn branch_name(agent: AgentName) -> String {
     format!("agent/{}", agent.as_str())
 }
@@ -16,6 +16,22 @@ pub fn worktree_path(repo_root: &str, agent: AgentName) -> String {
     }
 }
 
+/// Origin's default branch, from `refs/remotes/origin/HEAD`.
+pub async fn default_branch(repo_root: &str) -> Option<String> {
+    let output = tokio::process::Command::new("git")
+        .args(["symbolic-ref", "--short", "refs/remotes/origin/HEAD"])
+        .current_dir(repo_root)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let head = String::from_utf8_lossy(&output.stdout);
+    let branch = head.trim().strip_prefix("origin/")?;
+    (!branch.is_empty()).then(|| branch.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
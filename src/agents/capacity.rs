@@ -0,0 +1,112 @@
+use crate::config::AgentsConfig;
+
+/// Why a new dispatch into `repo_root` has to wait, or `None` if there is
+/// room for one more agent. `active` holds the repo root of each agent
+/// currently provisioning or running. The global cap counts all of them and
+/// always applies; a repo's own cap counts only its agents on top of that.
+pub fn dispatch_blocked(cfg: &AgentsConfig, repo_root: &str, active: &[&str]) -> Option<String> {
+    if let Some(max) = cfg.max_concurrent {
+        if active.len() >= max {
+            return Some(format!("{}/{max} agents running", active.len()));
+        }
+    }
+    if let Some(&max) = cfg.repo_max_concurrent.get(repo_root) {
+        let running = active.iter().filter(|root| **root == repo_root).count();
+        if running >= max {
+            return Some(format!("{running}/{max} agents running in {repo_root}"));
+        }
+    }
+
+    if let Some(max_load) = cfg.max_load_avg {
+        if let Some(load) = load_average() {
+            if load >= max_load {
+                return Some(format!("load average {load:.1} ≥ {max_load:.1}"));
+            }
+        }
+    }
+
+    if let Some(min_mb) = cfg.min_free_memory_mb {
+        if let Some(free) = available_memory_mb() {
+            if free < min_mb {
+                return Some(format!("{free} MB free < {min_mb} MB"));
+            }
+        }
+    }
+
+    None
+}
+
+/// One-minute load average.
+fn load_average() -> Option<f64> {
+    let mut loads = [0.0f64; 3];
+    let n = unsafe { libc::getloadavg(loads.as_mut_ptr(), 3) };
+    (n >= 1).then_some(loads[0])
+}
+
+/// Available memory from `/proc/meminfo`; `None` where that doesn't exist.
+fn available_memory_mb() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    parse_mem_available(&meminfo)
+}
+
+fn parse_mem_available(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|l| l.starts_with("MemAvailable:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb / 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caps_concurrent_agents() {
+        let cfg = AgentsConfig {
+            max_concurrent: Some(2),
+            ..Default::default()
+        };
+        assert!(dispatch_blocked(&cfg, "/repo", &["/repo"]).is_none());
+        assert_eq!(
+            dispatch_blocked(&cfg, "/repo", &["/repo", "/other"]).as_deref(),
+            Some("2/2 agents running")
+        );
+    }
+
+    #[test]
+    fn repo_caps_apply_within_the_global_one() {
+        let mut cfg = AgentsConfig {
+            max_concurrent: Some(2),
+            ..Default::default()
+        };
+        cfg.repo_max_concurrent.insert("/a".into(), 2);
+        cfg.repo_max_concurrent.insert("/big-repo".into(), 1);
+        assert_eq!(
+            dispatch_blocked(&cfg, "/big-repo", &["/big-repo"]).as_deref(),
+            Some("1/1 agents running in /big-repo")
+        );
+        // Agents in other repos don't count against its cap
+        assert!(dispatch_blocked(&cfg, "/big-repo", &["/other"]).is_none());
+        assert!(dispatch_blocked(&cfg, "/a", &["/a"]).is_none());
+        // Room in a repo's own cap doesn't lift the machine-wide one
+        assert_eq!(
+            dispatch_blocked(&cfg, "/a", &["/big-repo", "/other"]).as_deref(),
+            Some("2/2 agents running")
+        );
+        assert_eq!(
+            dispatch_blocked(&cfg, "/a", &["/a", "/a"]).as_deref(),
+            Some("2/2 agents running")
+        );
+    }
+
+    #[test]
+    fn no_limits_never_blocks() {
+        assert!(dispatch_blocked(&AgentsConfig::default(), "/repo", &["/repo"; 10]).is_none());
+    }
+
+    #[test]
+    fn parses_mem_available() {
+        let meminfo = "MemTotal:       16318480 kB\nMemFree:         1200000 kB\nMemAvailable:    8159240 kB\n";
+        assert_eq!(parse_mem_available(meminfo), Some(7968));
+        assert_eq!(parse_mem_available("MemTotal: 1 kB\n"), None);
+    }
+}
@@ -0,0 +1,243 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::agents::hygiene::git_output;
+use crate::model::agent::AgentName;
+use crate::model::work_item::WorkItem;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CiStatus {
+    Pending,
+    Passed,
+    Failed,
+    /// Nothing reported CI for the commit; final once the watch gives up
+    Missing,
+}
+
+/// How long a commit may go without any CI run before the watch gives up.
+const MISSING_CI_TIMEOUT: chrono::Duration = chrono::Duration::minutes(30);
+
+impl CiStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            CiStatus::Pending => "pending",
+            CiStatus::Passed => "green",
+            CiStatus::Failed => "red",
+            CiStatus::Missing => "none",
+        }
+    }
+}
+
+/// A commit an agent pushed, watched until its CI run finishes. The latest
+/// watch per agent is kept so the panel can show the result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CiWatch {
+    pub agent: AgentName,
+    pub sha: String,
+    /// Repository the commit was pushed from; `gh`/`glab` resolve the remote here
+    pub repo_root: String,
+    pub item: WorkItem,
+    pub status: CiStatus,
+    /// Names of the failed checks or jobs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<String>,
+    /// Whether a follow-up dispatch has gone out for a red run
+    #[serde(default)]
+    pub followed_up: bool,
+    /// When the watch started (RFC 3339)
+    #[serde(default)]
+    pub pushed_at: Option<String>,
+}
+
+impl CiWatch {
+    /// Whether a commit with no CI run yet has waited long enough that none
+    /// is coming.
+    pub fn ci_overdue(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.pushed_at
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .is_none_or(|pushed| now.signed_duration_since(pushed) >= MISSING_CI_TIMEOUT)
+    }
+
+    /// Failure context handed to the follow-up dispatch.
+    pub fn failure_summary(&self) -> String {
+        let short = &self.sha[..self.sha.len().min(8)];
+        if self.failed.is_empty() {
+            format!("CI failed on {short}")
+        } else {
+            format!("CI failed on {short}: {}", self.failed.join(", "))
+        }
+    }
+}
+
+pub async fn head_sha(wt_path: &str) -> Result<String> {
+    Ok(git_output(wt_path, &["rev-parse", "HEAD"])
+        .await?
+        .trim()
+        .to_string())
+}
+
+/// CI status of `sha`, from GitHub check runs or GitLab pipelines depending
+/// on where `origin` points.
+pub async fn ci_status(repo_root: &str, sha: &str) -> Result<(CiStatus, Vec<String>)> {
+    let remote = git_output(repo_root, &["remote", "get-url", "origin"]).await?;
+    if remote.contains("gitlab") {
+        let json = api(
+            "glab",
+            repo_root,
+            &format!("projects/:id/pipelines?sha={sha}"),
+        )
+        .await?;
+        Ok((parse_gitlab_pipelines(&json), Vec::new()))
+    } else {
+        let json = api(
+            "gh",
+            repo_root,
+            &format!("repos/{{owner}}/{{repo}}/commits/{sha}/check-runs"),
+        )
+        .await?;
+        Ok(parse_github_checks(&json))
+    }
+}
+
+/// Overall status of a GitHub check-runs response, with the failed check names.
+/// No checks at all is `Missing`: CI may not have picked the commit up yet,
+/// or the repo may have none.
+pub fn parse_github_checks(json: &serde_json::Value) -> (CiStatus, Vec<String>) {
+    let runs = json
+        .get("check_runs")
+        .and_then(|r| r.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    if runs.is_empty() {
+        return (CiStatus::Missing, Vec::new());
+    }
+
+    let failed: Vec<String> = runs
+        .iter()
+        .filter(|r| {
+            matches!(
+                r.get("conclusion").and_then(|c| c.as_str()),
+                Some("failure" | "timed_out" | "cancelled" | "action_required")
+            )
+        })
+        .filter_map(|r| r.get("name").and_then(|n| n.as_str()).map(String::from))
+        .collect();
+    let pending = runs
+        .iter()
+        .any(|r| r.get("status").and_then(|s| s.as_str()) != Some("completed"));
+
+    if !failed.is_empty() {
+        (CiStatus::Failed, failed)
+    } else if pending {
+        (CiStatus::Pending, Vec::new())
+    } else {
+        (CiStatus::Passed, Vec::new())
+    }
+}
+
+/// Status of the newest GitLab pipeline for a commit.
+pub fn parse_gitlab_pipelines(json: &serde_json::Value) -> CiStatus {
+    let Some(newest) = json.as_array().and_then(|p| p.first()) else {
+        return CiStatus::Missing;
+    };
+    match newest.get("status").and_then(|s| s.as_str()) {
+        Some("success") => CiStatus::Passed,
+        Some("failed" | "canceled") => CiStatus::Failed,
+        _ => CiStatus::Pending,
+    }
+}
+
+async fn api(program: &str, cwd: &str, endpoint: &str) -> Result<serde_json::Value> {
+    let output = tokio::process::Command::new(program)
+        .args(["api", endpoint])
+        .current_dir(cwd)
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {program} api"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{program} api {endpoint} failed: {stderr}");
+    }
+    serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("Failed to parse {program} api output"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn run(status: &str, conclusion: Option<&str>, name: &str) -> serde_json::Value {
+        json!({ "name": name, "status": status, "conclusion": conclusion })
+    }
+
+    #[test]
+    fn github_checks_roll_up() {
+        let checks =
+            |runs: Vec<serde_json::Value>| parse_github_checks(&json!({ "check_runs": runs }));
+
+        assert_eq!(checks(vec![]).0, CiStatus::Missing);
+        assert_eq!(
+            checks(vec![
+                run("completed", Some("success"), "test"),
+                run("in_progress", None, "lint")
+            ])
+            .0,
+            CiStatus::Pending
+        );
+        assert_eq!(
+            checks(vec![
+                run("completed", Some("success"), "test"),
+                run("completed", Some("skipped"), "docs")
+            ])
+            .0,
+            CiStatus::Passed
+        );
+
+        let (status, failed) = checks(vec![
+            run("completed", Some("failure"), "test"),
+            run("in_progress", None, "lint"),
+        ]);
+        assert_eq!(status, CiStatus::Failed);
+        assert_eq!(failed, vec!["test"]);
+    }
+
+    #[test]
+    fn watch_gives_up_on_missing_ci() {
+        let mut watch = CiWatch {
+            agent: AgentName::Ember,
+            sha: "abc123".into(),
+            repo_root: "/repo".into(),
+            item: WorkItem::test("ENG-1"),
+            status: CiStatus::Pending,
+            failed: Vec::new(),
+            followed_up: false,
+            pushed_at: Some("2026-03-04T10:00:00Z".into()),
+        };
+        let at = |t: &str| t.parse::<chrono::DateTime<chrono::Utc>>().unwrap();
+        assert!(!watch.ci_overdue(at("2026-03-04T10:20:00Z")));
+        assert!(watch.ci_overdue(at("2026-03-04T10:30:00Z")));
+        // Watches saved before the timestamp existed give up straight away
+        watch.pushed_at = None;
+        assert!(watch.ci_overdue(at("2026-03-04T10:00:00Z")));
+    }
+
+    #[test]
+    fn gitlab_uses_newest_pipeline() {
+        assert_eq!(parse_gitlab_pipelines(&json!([])), CiStatus::Missing);
+        assert_eq!(
+            parse_gitlab_pipelines(&json!([{ "status": "failed" }, { "status": "success" }])),
+            CiStatus::Failed
+        );
+        assert_eq!(
+            parse_gitlab_pipelines(&json!([{ "status": "running" }])),
+            CiStatus::Pending
+        );
+        assert_eq!(
+            parse_gitlab_pipelines(&json!([{ "status": "success" }])),
+            CiStatus::Passed
+        );
+    }
+}
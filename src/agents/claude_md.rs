@@ -1,71 +1,103 @@
 use anyhow::Result;
 use std::path::Path;
 
+use crate::config::Workflow;
 use crate::model::agent::AgentName;
 use crate::model::personality::personality;
 
-pub fn write_claude_md(worktree_path: &Path, agent_name: AgentName) -> Result<()> {
-    let p = personality(agent_name);
-    let traits = p.traits.join(", ");
-
-    let content = format!(
-        r#"# work pipeline
-
-## Project Overview
-A terminal dashboard CLI (`work`) that aggregates work items from Trello, Linear, Jira, and GitHub.
-Built with Rust and Ratatui (terminal UI).
-
-## Tech Stack
-- **Language**: Rust (edition 2021)
-- **UI**: Ratatui + Crossterm
-- **Async**: Tokio
-- **HTTP**: reqwest
-- **Build**: cargo
-- **Test**: cargo test
-
-## Conventions
-- Models in `src/model/`, providers in `src/providers/`, UI in `src/ui/`
-- Agent infrastructure in `src/agents/`
-- Use `anyhow` for error handling, `thiserror` for custom errors
-- Use `serde` for serialization/deserialization
-- Config stored at `~/.localpipeline/config.toml`
-- Agent state stored at `~/.localpipeline/agents.json`
-- Activity log at `~/.localpipeline/agent-activity.jsonl`
-
-## Testing
-- Run: `cargo test`
-
-## Commit Format
-- Short imperative subject line (e.g., "Add login validation")
-- Reference the work item ID in the commit body
+/// Marks the generated section so a rewrite replaces it instead of stacking.
+const BEGIN: &str = "<!-- work:agent -->";
+const END: &str = "<!-- /work:agent -->";
 
-## Git Workflow
-You work on the main branch. Your worktree is a temporary branch that gets pushed to main.
-- Always rebase on `origin/main` before pushing: `git fetch origin main && git rebase origin/main`
-- Push with: `git push origin HEAD:main`
+/// Built-in agent section; `agents.claude_md_template` replaces it.
+const DEFAULT_TEMPLATE: &str = "## Git Workflow
+{git_workflow}
 - Your git status MUST be empty before you finish. If build artifacts or generated files appear, add them to `.gitignore` and commit.
 - Never create feature branches. Never delete worktrees or stashes.
 
 ## Agent Identity
-You are **{display}**, an autonomous agent working in a git worktree.
-Your changes will be pushed directly to main.
+You are **{agent}**, an autonomous agent working in a git worktree.
+Your changes land on `{base}`.
 
 ### Personality: {tagline}
 - **Focus**: {focus}
 - **Traits**: {traits}
-- **Working style**: {system_prompt}
-"#,
-        display = agent_name.display_name(),
-        tagline = p.tagline,
-        focus = p.focus,
-        traits = traits,
-        system_prompt = p.system_prompt,
-    );
+- **Working style**: {working_style}
+";
 
-    std::fs::write(worktree_path.join("CLAUDE.md"), content)?;
+/// Add the agent's identity and git workflow to the worktree's CLAUDE.md.
+/// A CLAUDE.md checked in to the repository is kept and the section appended
+/// to it. `template` may use `{agent}`, `{tagline}`, `{focus}`, `{traits}`,
+/// `{working_style}`, `{git_workflow}` and `{base}`.
+pub fn write_claude_md(
+    worktree_path: &Path,
+    agent_name: AgentName,
+    workflow: Workflow,
+    base: &str,
+    template: Option<&str>,
+) -> Result<()> {
+    let section = agent_section(agent_name, workflow, base, template);
+    let path = worktree_path.join("CLAUDE.md");
+    let existing = std::fs::read_to_string(&path).unwrap_or_default();
+    std::fs::write(&path, merge_section(&existing, &section))?;
     Ok(())
 }
 
+fn agent_section(
+    agent_name: AgentName,
+    workflow: Workflow,
+    base: &str,
+    template: Option<&str>,
+) -> String {
+    let p = personality(agent_name);
+
+    let git_workflow = match workflow {
+        Workflow::Push => format!(
+            "You work on the {base} branch. Your worktree is a temporary branch that gets pushed to {base}.
+- Always rebase on `origin/{base}` before pushing: `git fetch origin {base} && git rebase origin/{base}`
+- Push with: `git push origin HEAD:{base}`"
+        ),
+        Workflow::Pr => format!(
+            "Your worktree is a temporary branch that the dispatcher opens as a pull request against {base}.
+- Always rebase on `origin/{base}` before finishing: `git fetch origin {base} && git rebase origin/{base}`
+- Do not push — the dispatcher pushes your branch and opens the pull request"
+        ),
+        Workflow::Review => format!(
+            "Your worktree is a temporary branch that is reviewed locally before it lands on {base}.
+- Always rebase on `origin/{base}` before finishing: `git fetch origin {base} && git rebase origin/{base}`
+- Do not push — your branch is pushed to {base} once the review is approved"
+        ),
+    };
+
+    template
+        .unwrap_or(DEFAULT_TEMPLATE)
+        .replace("{agent}", agent_name.display_name())
+        .replace("{tagline}", p.tagline)
+        .replace("{focus}", p.focus)
+        .replace("{traits}", &p.traits.join(", "))
+        .replace("{working_style}", p.system_prompt)
+        .replace("{git_workflow}", &git_workflow)
+        .replace("{base}", base)
+}
+
+/// `existing` with `section` appended, replacing any earlier agent section.
+fn merge_section(existing: &str, section: &str) -> String {
+    let mut project = existing.to_string();
+    if let Some(start) = project.find(BEGIN) {
+        let end = project[start..]
+            .find(END)
+            .map_or(project.len(), |i| start + i + END.len());
+        project.replace_range(start..end, "");
+    }
+    let project = project.trim_end();
+    let section = section.trim_end();
+    if project.is_empty() {
+        format!("{BEGIN}\n{section}\n{END}\n")
+    } else {
+        format!("{project}\n\n{BEGIN}\n{section}\n{END}\n")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -74,7 +106,7 @@ mod tests {
     fn claude_md_includes_personality_for_all_agents() {
         let dir = tempfile::tempdir().unwrap();
         for name in AgentName::ALL {
-            write_claude_md(dir.path(), name).unwrap();
+            write_claude_md(dir.path(), name, Workflow::Push, "main", None).unwrap();
             let content = std::fs::read_to_string(dir.path().join("CLAUDE.md")).unwrap();
             let p = personality(name);
             assert!(
@@ -109,19 +141,45 @@ mod tests {
     }
 
     #[test]
-    fn claude_md_includes_project_conventions() {
+    fn claude_md_keeps_the_repos_own_instructions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("CLAUDE.md");
+        std::fs::write(&path, "# My project\n\nUse pnpm.\n").unwrap();
+        write_claude_md(dir.path(), AgentName::Ember, Workflow::Push, "main", None).unwrap();
+        write_claude_md(dir.path(), AgentName::Flow, Workflow::Push, "main", None).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# My project\n\nUse pnpm.\n\n<!-- work:agent -->"));
+        assert!(!content.contains("work pipeline"));
+        assert_eq!(content.matches(BEGIN).count(), 1);
+        assert!(content.contains(AgentName::Flow.display_name()));
+        assert!(!content.contains(AgentName::Ember.display_name()));
+    }
+
+    #[test]
+    fn claude_md_uses_configured_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let template = "Agent {agent} lands on {base}.\n{git_workflow}";
+        write_claude_md(
+            dir.path(),
+            AgentName::Ember,
+            Workflow::Push,
+            "develop",
+            Some(template),
+        )
+        .unwrap();
+        let content = std::fs::read_to_string(dir.path().join("CLAUDE.md")).unwrap();
+        let name = AgentName::Ember.display_name();
+        assert!(content.contains(&format!("Agent {name} lands on develop.")));
+        assert!(content.contains("git push origin HEAD:develop"));
+        assert!(!content.contains("**Traits**"));
+    }
+
+    #[test]
+    fn claude_md_describes_pr_workflow() {
         let dir = tempfile::tempdir().unwrap();
-        write_claude_md(dir.path(), AgentName::Ember).unwrap();
+        write_claude_md(dir.path(), AgentName::Flow, Workflow::Pr, "main", None).unwrap();
         let content = std::fs::read_to_string(dir.path().join("CLAUDE.md")).unwrap();
-        assert!(content.contains("src/agents/"), "missing agents convention");
-        assert!(
-            content.contains("config.toml"),
-            "missing config path convention"
-        );
-        assert!(
-            content.contains("agents.json"),
-            "missing agent state convention"
-        );
-        assert!(content.contains("thiserror"), "missing thiserror convention");
+        assert!(content.contains("pull request"));
+        assert!(!content.contains("git push origin HEAD:main"));
     }
 }
@@ -1,30 +1,61 @@
+use anyhow::{Context, Result};
+use minijinja::{context, Environment, UndefinedBehavior};
+
+use crate::agents::questions;
+use crate::config::Workflow;
 use crate::model::agent::AgentName;
 use crate::model::personality::personality;
-use crate::model::work_item::WorkItem;
+use crate::model::work_item::{Comment, Link, WorkItem};
+use crate::providers;
 
-pub fn build_prompt(item: &WorkItem, agent_name: AgentName) -> String {
+pub fn build_prompt(
+    item: &WorkItem,
+    agent_name: AgentName,
+    workflow: Workflow,
+    base: &str,
+    previous_failure: Option<&str>,
+) -> String {
     let p = personality(agent_name);
-    let labels = if item.labels.is_empty() {
-        "none".to_string()
-    } else {
-        item.labels.join(", ")
+
+    let finish = match workflow {
+        Workflow::Push => format!(
+            "7. Run `git fetch origin {base} && git rebase origin/{base}`. Resolve any conflicts.
+8. Run `git push origin HEAD:{base}`.
+9. Verify `git status --porcelain` is empty. If not, fix it — do NOT finish with a dirty working tree.
+
+Work autonomously. Do not ask for clarification — make reasonable decisions.
+You are working on the {base} branch. All your changes push directly to {base}."
+        ),
+        Workflow::Pr => format!(
+            "7. Run `git fetch origin {base} && git rebase origin/{base}`. Resolve any conflicts.
+8. Do NOT push. When you exit, the dispatcher pushes your branch and opens a pull request.
+9. Verify `git status --porcelain` is empty. If not, fix it — do NOT finish with a dirty working tree.
+
+Work autonomously. Do not ask for clarification — make reasonable decisions.
+Your commits will be reviewed in a pull request against {base}."
+        ),
+        Workflow::Review => format!(
+            "7. Run `git fetch origin {base} && git rebase origin/{base}`. Resolve any conflicts.
+8. Do NOT push. When you exit, your branch is reviewed and pushed to {base} once approved.
+9. Verify `git status --porcelain` is empty. If not, fix it — do NOT finish with a dirty working tree.
+
+Work autonomously. Do not ask for clarification — make reasonable decisions.
+Your commits will be reviewed before they land on {base}."
+        ),
+    };
+
+    let previous_attempt = match previous_failure {
+        Some(failure) => format!(
+            "\n## Previous Attempt\nA previous attempt at this task failed:\n```\n{}\n```\nFix the cause of this failure before finishing.\n",
+            failure.trim_end()
+        ),
+        None => String::new(),
     };
 
     format!(
         r#"You are agent "{agent}" working on the following task. Your personality: {tagline}.
 
-# {title}
-- ID: {id}
-- Source: {source}
-- URL: {url}
-- Priority: {priority}
-- Labels: {labels}
-- Status: {status}
-- Team: {team}
-
-## Description
-{description}
-
+{task}{previous_attempt}{asking}
 ## Instructions
 1. Read CLAUDE.md in the project root for conventions and context.
 2. Implement the task described above.
@@ -32,12 +63,7 @@ pub fn build_prompt(item: &WorkItem, agent_name: AgentName) -> String {
 4. Run `cargo test`. Fix any failures before continuing.
 5. Commit your changes with a message referencing {id}.
 6. Check `git status --porcelain`. If untracked files remain (build artifacts, generated files, caches), add them to `.gitignore` and commit. Your git status MUST be completely clean before proceeding.
-7. Run `git fetch origin main && git rebase origin/main`. Resolve any conflicts.
-8. Run `git push origin HEAD:main`.
-9. Verify `git status --porcelain` is empty. If not, fix it — do NOT finish with a dirty working tree.
-
-Work autonomously. Do not ask for clarification — make reasonable decisions.
-You are working on the main branch. All your changes push directly to main.
+{finish}
 
 ## Personality: {tagline}
 - Focus: {focus}
@@ -46,44 +72,228 @@ You are working on the main branch. All your changes push directly to main.
         agent = agent_name.display_name(),
         tagline = p.tagline,
         focus = p.focus,
+        task = task_section(item),
+        id = item.id,
+        traits = p.traits.join(", "),
+        system_prompt = p.system_prompt,
+        finish = finish,
+        previous_attempt = previous_attempt,
+        asking = questions::prompt_section(),
+    )
+}
+
+/// Prompt for a plan-only run: the agent reads the code and answers with a
+/// plan, which is approved before the implementation run starts.
+pub fn build_plan_prompt(item: &WorkItem, agent_name: AgentName, base: &str) -> String {
+    let p = personality(agent_name);
+    format!(
+        r#"You are agent "{agent}" planning the following task. Your personality: {tagline}.
+
+{task}
+## Instructions
+This is a planning run. Do NOT edit files, commit or push — the plan is reviewed before anyone starts implementing it.
+1. Read CLAUDE.md in the project root for conventions and context.
+2. Explore the code the task touches.
+3. Reply with an implementation plan in Markdown: the files to change and how, the tests to add, and open questions or risks.
+Your final message must be the plan itself; it is shown to the user for approval. Work lands on {base} once implemented."#,
+        agent = agent_name.display_name(),
+        tagline = p.tagline,
+        task = task_section(item),
+    )
+}
+
+/// Prompt for reviewing a pull request. Like a plan, the run is read-only and
+/// its final answer, the draft review, waits for approval before it's posted.
+pub fn build_review_prompt(item: &WorkItem, agent_name: AgentName) -> String {
+    let p = personality(agent_name);
+    format!(
+        r#"You are agent "{agent}" reviewing the following pull request. Your personality: {tagline}.
+
+{task}
+## Instructions
+This is a review run. Do NOT edit files, commit or push.
+1. Read CLAUDE.md in the project root for conventions and context.
+2. Read the diff under Linked Resources, and the surrounding code in this checkout, to understand the change.
+3. Reply with a draft review in Markdown: a short summary, then one bullet per comment as `path:line — comment`, most important first, and end with a verdict (approve, comment or request changes).
+Your final message must be the review itself; it is shown to the user, who posts it to the pull request once approved."#,
+        agent = agent_name.display_name(),
+        tagline = p.tagline,
+        task = task_section(item),
+    )
+}
+
+/// Prompt for the implementation run after its plan was approved.
+pub fn with_plan(prompt: &str, plan: &str) -> String {
+    format!(
+        "{prompt}\n\n## Approved Plan\nThe user approved this plan, including any reviewer notes. Follow it.\n\n{}",
+        plan.trim_end()
+    )
+}
+
+/// The item's fields, description and discussion.
+fn task_section(item: &WorkItem) -> String {
+    let labels = if item.labels.is_empty() {
+        "none".to_string()
+    } else {
+        item.labels.join(", ")
+    };
+    format!(
+        r#"# {title}
+- ID: {id}
+- Source: {source}
+- URL: {url}
+- Priority: {priority}
+- Labels: {labels}
+- Status: {status}
+- Team: {team}
+
+## Description
+{description}
+{comments}{links}"#,
         title = item.title,
         id = item.id,
         source = item.source,
         url = item.url.as_deref().unwrap_or("n/a"),
         priority = item.priority.as_deref().unwrap_or("n/a"),
-        labels = labels,
         status = item.status.as_deref().unwrap_or("n/a"),
         team = item.team.as_deref().unwrap_or("n/a"),
-        description = item.description.as_deref().unwrap_or("No description provided."),
-        traits = p.traits.join(", "),
-        system_prompt = p.system_prompt,
+        description = item
+            .description
+            .as_deref()
+            .unwrap_or("No description provided."),
+        comments = comments_section(&item.comments),
+        links = links_section(
+            &item.links,
+            if providers::is_review_request(item) {
+                MAX_DIFF_CHARS
+            } else {
+                MAX_LINK_CHARS
+            }
+        ),
     )
 }
 
+/// Most characters of comment text in the prompt; older comments are
+/// dropped first, and the newest is cut short if it alone is over.
+const MAX_COMMENT_CHARS: usize = 6000;
+
+fn comments_section(comments: &[Comment]) -> String {
+    let mut budget = MAX_COMMENT_CHARS;
+    let mut kept = Vec::new();
+    for comment in comments.iter().rev() {
+        let len = comment.body.chars().count();
+        if len > budget {
+            if kept.is_empty() {
+                let cut: String = comment.body.chars().take(budget).collect();
+                kept.push((comment, format!("{}\n…(truncated)", cut.trim_end())));
+            }
+            break;
+        }
+        budget -= len;
+        kept.push((comment, comment.body.trim_end().to_string()));
+    }
+    if kept.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::from("\n## Comments\n");
+    section.push_str("Newer comments may refine or override the description.\n");
+    let omitted = comments.len() - kept.len();
+    if omitted > 0 {
+        let noun = if omitted == 1 { "comment" } else { "comments" };
+        section.push_str(&format!("({omitted} earlier {noun} omitted)\n"));
+    }
+    for (comment, body) in kept.into_iter().rev() {
+        let when = comment
+            .created_at
+            .as_deref()
+            .and_then(|t| t.get(..10))
+            .map(|date| format!(" on {date}"))
+            .unwrap_or_default();
+        section.push_str(&format!("\n**{}**{when}:\n{body}\n", comment.author));
+    }
+    section
+}
+
+/// Most characters of one linked resource quoted in the prompt.
+const MAX_LINK_CHARS: usize = 8000;
+
+/// Most characters of a pull request's diff in a review prompt.
+const MAX_DIFF_CHARS: usize = 60000;
+
+fn links_section(links: &[Link], max_chars: usize) -> String {
+    if links.is_empty() {
+        return String::new();
+    }
+    let mut section = String::from("\n## Linked Resources\n");
+    for link in links {
+        section.push_str(&format!("- [{}]({})\n", link.title, link.url));
+        if let Some(content) = &link.content {
+            let quoted: String = content.chars().take(max_chars).collect();
+            section.push_str(&format!("```\n{}\n```\n", quoted.trim_end()));
+        }
+    }
+    section
+}
+
+/// Render a user prompt template (Jinja syntax) instead of the built-in
+/// prompt. Templates see `item`, `agent`, `personality`, `workflow`, `base`,
+/// `previous_failure` and `default_prompt`, the built-in prompt in full, so
+/// a team can keep it and add its own rules around it.
+pub fn render_prompt(
+    template: &str,
+    item: &WorkItem,
+    agent_name: AgentName,
+    workflow: Workflow,
+    base: &str,
+    previous_failure: Option<&str>,
+) -> Result<String> {
+    let p = personality(agent_name);
+    let mut env = Environment::new();
+    // A misspelt variable should fail the dispatch, not vanish from the prompt
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    let ctx = context! {
+        item => item,
+        agent => agent_name.display_name(),
+        personality => context! {
+            tagline => p.tagline,
+            focus => p.focus,
+            traits => p.traits,
+            working_style => p.system_prompt,
+        },
+        workflow => match workflow {
+            Workflow::Push => "push",
+            Workflow::Pr => "pr",
+            Workflow::Review => "review",
+        },
+        base => base,
+        previous_failure => previous_failure,
+        default_prompt => build_prompt(item, agent_name, workflow, base, previous_failure),
+    };
+    env.render_str(template, ctx)
+        .context("Failed to render prompt template")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     fn test_item() -> WorkItem {
-        WorkItem {
-            id: "TEST-1".to_string(),
-            source_id: None,
-            title: "Test task".to_string(),
-            description: Some("A test description".to_string()),
-            status: Some("Todo".to_string()),
-            priority: None,
-            labels: vec!["bug".to_string()],
-            source: "trello".to_string(),
-            team: Some("TestTeam".to_string()),
-            url: Some("https://example.com".to_string()),
-        }
+        WorkItem::test("TEST-1")
+            .with_title("Test task")
+            .with_description("A test description")
+            .with_status("Todo")
+            .with_labels(&["bug"])
+            .with_source("trello")
+            .with_team("TestTeam")
+            .with_url("https://example.com")
     }
 
     #[test]
     fn prompt_includes_focus_for_all_agents() {
         let item = test_item();
         for name in AgentName::ALL {
-            let prompt = build_prompt(&item, name);
+            let prompt = build_prompt(&item, name, Workflow::Push, "main", None);
             let p = personality(name);
             assert!(
                 prompt.contains("Focus:"),
@@ -99,11 +309,184 @@ mod tests {
     #[test]
     fn prompt_includes_personality_section() {
         let item = test_item();
-        let prompt = build_prompt(&item, AgentName::Ember);
+        let prompt = build_prompt(&item, AgentName::Ember, Workflow::Push, "main", None);
         let p = personality(AgentName::Ember);
         assert!(prompt.contains(&format!("Personality: {}", p.tagline)));
         assert!(prompt.contains("Traits:"));
         assert!(prompt.contains("Working style:"));
         assert!(prompt.contains(r#"You are agent "Ember""#));
     }
+
+    #[test]
+    fn push_workflow_pushes_to_main() {
+        let prompt = build_prompt(&test_item(), AgentName::Flow, Workflow::Push, "main", None);
+        assert!(prompt.contains("git push origin HEAD:main"));
+    }
+
+    #[test]
+    fn pr_workflow_does_not_push() {
+        let prompt = build_prompt(&test_item(), AgentName::Flow, Workflow::Pr, "main", None);
+        assert!(!prompt.contains("git push origin HEAD:main"));
+        assert!(prompt.contains("Do NOT push"));
+        assert!(prompt.contains("pull request"));
+    }
+
+    #[test]
+    fn review_workflow_waits_for_approval() {
+        let prompt = build_prompt(
+            &test_item(),
+            AgentName::Terra,
+            Workflow::Review,
+            "main",
+            None,
+        );
+        assert!(!prompt.contains("git push origin HEAD:main"));
+        assert!(prompt.contains("pushed to main once approved"));
+    }
+
+    #[test]
+    fn prompt_uses_configured_base_branch() {
+        let prompt = build_prompt(&test_item(), AgentName::Flow, Workflow::Push, "develop", None);
+        assert!(prompt.contains("git rebase origin/develop"));
+        assert!(prompt.contains("git push origin HEAD:develop"));
+        assert!(!prompt.contains("origin/main"));
+    }
+
+    #[test]
+    fn template_renders_item_and_default_prompt() {
+        let template = "{{ default_prompt }}\n\nNever touch migrations in {{ item.id }} \
+                        ({{ item.labels | join(\", \") }}, {{ personality.focus }}).";
+        let item = test_item();
+        let prompt = render_prompt(
+            template,
+            &item,
+            AgentName::Ember,
+            Workflow::Push,
+            "main",
+            None,
+        )
+        .unwrap();
+        let default = build_prompt(&item, AgentName::Ember, Workflow::Push, "main", None);
+        assert!(prompt.starts_with(&default));
+        let focus = personality(AgentName::Ember).focus;
+        assert!(prompt.ends_with(&format!("Never touch migrations in TEST-1 (bug, {focus}).")));
+    }
+
+    #[test]
+    fn template_rejects_unknown_variables() {
+        let err = render_prompt(
+            "{{ item.titel }}",
+            &test_item(),
+            AgentName::Ember,
+            Workflow::Push,
+            "main",
+            None,
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn prompt_quotes_linked_resources() {
+        let mut item = test_item();
+        assert!(!build_prompt(&item, AgentName::Ember, Workflow::Push, "main", None)
+            .contains("Linked Resources"));
+
+        item.links = vec![
+            Link {
+                title: "Design doc".into(),
+                url: "https://example.com/doc".into(),
+                content: None,
+            },
+            Link {
+                title: "crash.log".into(),
+                url: "https://example.com/crash.log".into(),
+                content: Some("panicked at src/main.rs:3\n".into()),
+            },
+        ];
+        let prompt = build_prompt(&item, AgentName::Ember, Workflow::Push, "main", None);
+        assert!(prompt.contains("## Linked Resources"));
+        assert!(prompt.contains("- [Design doc](https://example.com/doc)\n- [crash.log]"));
+        assert!(prompt.contains("```\npanicked at src/main.rs:3\n```"));
+    }
+
+    #[test]
+    fn prompt_includes_newest_comments_within_cap() {
+        let comment = |author: &str, body: String| Comment {
+            author: author.into(),
+            created_at: Some("2026-03-04T10:00:00Z".into()),
+            body,
+        };
+        let mut item = test_item();
+        item.comments = vec![
+            comment("ann", "x".repeat(MAX_COMMENT_CHARS)),
+            comment("bob", "Use the v2 endpoint instead.".into()),
+        ];
+        let prompt = build_prompt(&item, AgentName::Ember, Workflow::Push, "main", None);
+        assert!(prompt.contains("## Comments"));
+        assert!(prompt.contains("(1 earlier comment omitted)"));
+        assert!(prompt.contains("**bob** on 2026-03-04:\nUse the v2 endpoint instead."));
+        assert!(!prompt.contains("**ann**"));
+
+        // A newest comment over the cap alone is cut short, not dropped
+        item.comments = vec![comment("cat", "y".repeat(MAX_COMMENT_CHARS + 10))];
+        let prompt = build_prompt(&item, AgentName::Ember, Workflow::Push, "main", None);
+        let kept = format!(
+            "**cat** on 2026-03-04:\n{}\n…(truncated)",
+            "y".repeat(MAX_COMMENT_CHARS)
+        );
+        assert!(prompt.contains(&kept));
+    }
+
+    #[test]
+    fn plan_prompt_forbids_changes_and_keeps_task() {
+        let prompt = build_plan_prompt(&test_item(), AgentName::Terra, "main");
+        assert!(prompt.contains("Do NOT edit files"));
+        assert!(prompt.contains("# Test task\n- ID: TEST-1"));
+        assert!(!prompt.contains("git push origin"));
+
+        let run = with_plan("Implement it.", "1. Add the flag\n");
+        assert!(run.starts_with("Implement it.\n\n## Approved Plan\n"));
+        assert!(run.ends_with("Follow it.\n\n1. Add the flag"));
+    }
+
+    #[test]
+    fn review_prompt_quotes_the_whole_diff() {
+        let mut item = test_item();
+        item.source = "GitHub".into();
+        item.url = Some("https://github.com/acme/app/pull/7".into());
+        let diff = format!("+{}\n", "x".repeat(MAX_LINK_CHARS));
+        item.links = vec![Link {
+            title: "Diff".into(),
+            url: "https://github.com/acme/app/pull/7/files".into(),
+            content: Some(diff.clone()),
+        }];
+
+        let prompt = build_review_prompt(&item, AgentName::Terra);
+        assert!(prompt.contains("Do NOT edit files"));
+        assert!(prompt.contains(diff.trim_end()));
+        assert!(!prompt.contains("git push origin"));
+    }
+
+    #[test]
+    fn prompt_explains_how_to_ask() {
+        let prompt = build_prompt(&test_item(), AgentName::Tempest, Workflow::Push, "main", None);
+        assert!(prompt.contains("## Asking the User"));
+    }
+
+    #[test]
+    fn prompt_includes_previous_failure() {
+        let fresh = build_prompt(&test_item(), AgentName::Ember, Workflow::Push, "main", None);
+        assert!(!fresh.contains("Previous Attempt"));
+
+        let failure = "test result: FAILED. 1 passed; 1 failed";
+        let retry = build_prompt(
+            &test_item(),
+            AgentName::Ember,
+            Workflow::Push,
+            "main",
+            Some(failure),
+        );
+        assert!(retry.contains("## Previous Attempt"));
+        assert!(retry.contains(failure));
+    }
 }
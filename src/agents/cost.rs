@@ -0,0 +1,173 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::model::agent::AgentName;
+
+/// Cost and token counts reported by claude's final `result` line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Usage {
+    pub cost_usd: f64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl Usage {
+    /// Read usage from a `result` line. Cache reads and writes count as input.
+    pub fn from_result(json: &Value) -> Option<Self> {
+        let cost_usd = json
+            .get("total_cost_usd")
+            .or_else(|| json.get("cost_usd"))
+            .and_then(|c| c.as_f64());
+        let usage = json.get("usage");
+        if cost_usd.is_none() && usage.is_none() {
+            return None;
+        }
+        let tokens = |key: &str| {
+            usage
+                .and_then(|u| u.get(key))
+                .and_then(|t| t.as_u64())
+                .unwrap_or(0)
+        };
+        Some(Self {
+            cost_usd: cost_usd.unwrap_or(0.0),
+            input_tokens: tokens("input_tokens")
+                + tokens("cache_creation_input_tokens")
+                + tokens("cache_read_input_tokens"),
+            output_tokens: tokens("output_tokens"),
+        })
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+
+    fn add(&mut self, other: &Usage) {
+        self.cost_usd += other.cost_usd;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+    }
+}
+
+impl std::fmt::Display for Usage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "${:.2} · {} in / {} out",
+            self.cost_usd,
+            format_tokens(self.input_tokens),
+            format_tokens(self.output_tokens)
+        )
+    }
+}
+
+/// Compact token count, e.g. `950`, `12.3k`, `1.2M`.
+pub fn format_tokens(n: u64) -> String {
+    match n {
+        0..=999 => n.to_string(),
+        1_000..=999_999 => format!("{:.1}k", n as f64 / 1_000.0),
+        _ => format!("{:.1}M", n as f64 / 1_000_000.0),
+    }
+}
+
+/// Running usage totals per agent, per work item and per day.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CostLedger {
+    #[serde(default)]
+    by_agent: HashMap<String, Usage>,
+    #[serde(default)]
+    by_item: HashMap<String, Usage>,
+    /// Spend keyed by local date (`YYYY-MM-DD`)
+    #[serde(default)]
+    by_day: BTreeMap<String, f64>,
+}
+
+impl CostLedger {
+    pub fn record(&mut self, agent: AgentName, item_id: &str, usage: &Usage, day: &str) {
+        self.by_agent
+            .entry(agent.as_str().to_string())
+            .or_default()
+            .add(usage);
+        self.by_item
+            .entry(item_id.to_string())
+            .or_default()
+            .add(usage);
+        *self.by_day.entry(day.to_string()).or_default() += usage.cost_usd;
+    }
+
+    pub fn agent_total(&self, agent: AgentName) -> Usage {
+        self.by_agent
+            .get(agent.as_str())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn item_total(&self, item_id: &str) -> Option<Usage> {
+        self.by_item.get(item_id).copied()
+    }
+
+    pub fn spent_on(&self, day: &str) -> f64 {
+        self.by_day.get(day).copied().unwrap_or(0.0)
+    }
+}
+
+/// Today's date as used for the daily budget.
+pub fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_result_usage() {
+        let json = serde_json::json!({
+            "type": "result",
+            "total_cost_usd": 0.4213,
+            "usage": {
+                "input_tokens": 1200,
+                "cache_read_input_tokens": 800,
+                "output_tokens": 950
+            }
+        });
+        let usage = Usage::from_result(&json).unwrap();
+        assert_eq!(usage.input_tokens, 2000);
+        assert_eq!(usage.output_tokens, 950);
+        assert!((usage.cost_usd - 0.4213).abs() < 1e-9);
+        assert_eq!(usage.to_string(), "$0.42 · 2.0k in / 950 out");
+    }
+
+    #[test]
+    fn result_without_usage_is_none() {
+        let json = serde_json::json!({ "type": "result", "result": "done" });
+        assert!(Usage::from_result(&json).is_none());
+    }
+
+    #[test]
+    fn ledger_accumulates_per_agent_item_and_day() {
+        let mut ledger = CostLedger::default();
+        let run = Usage {
+            cost_usd: 1.5,
+            input_tokens: 100,
+            output_tokens: 10,
+        };
+        ledger.record(AgentName::Ember, "ENG-1", &run, "2026-10-16");
+        ledger.record(AgentName::Ember, "ENG-2", &run, "2026-10-16");
+        ledger.record(AgentName::Flow, "ENG-1", &run, "2026-10-17");
+
+        assert_eq!(ledger.agent_total(AgentName::Ember).cost_usd, 3.0);
+        assert_eq!(ledger.agent_total(AgentName::Terra), Usage::default());
+        assert_eq!(ledger.item_total("ENG-1").unwrap().total_tokens(), 220);
+        assert_eq!(ledger.spent_on("2026-10-16"), 3.0);
+        assert_eq!(ledger.spent_on("2026-10-18"), 0.0);
+    }
+
+    #[test]
+    fn formats_token_counts() {
+        assert_eq!(format_tokens(950), "950");
+        assert_eq!(format_tokens(12_345), "12.3k");
+        assert_eq!(format_tokens(1_240_000), "1.2M");
+    }
+}
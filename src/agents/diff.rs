@@ -0,0 +1,194 @@
+use anyhow::{Context, Result};
+
+use super::hygiene::git_output;
+use crate::model::agent::AgentName;
+use crate::util::highlight::{highlight_diff, Run};
+
+/// One file's section of a unified diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffFile {
+    pub path: String,
+    /// Index of its `diff --git` line
+    pub start: usize,
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// An agent's diff as opened in its detail view, parsed and highlighted once
+/// when it loads rather than on every frame.
+#[derive(Debug, Clone)]
+pub struct BranchDiff {
+    pub agent: AgentName,
+    pub lines: Vec<String>,
+    pub files: Vec<DiffFile>,
+    /// Syntax colours per line; empty where the plain diff colours apply
+    pub highlights: Vec<Vec<Run>>,
+}
+
+impl BranchDiff {
+    /// A diff view showing just a message, while loading or on failure.
+    pub fn message(agent: AgentName, text: String) -> Self {
+        Self {
+            agent,
+            lines: vec![text],
+            files: Vec::new(),
+            highlights: vec![Vec::new()],
+        }
+    }
+
+    /// Load the agent's working diff against `origin/<base>`.
+    pub async fn load(agent: AgentName, wt_path: &str, base: &str) -> Self {
+        let diff = match working_diff(wt_path, base).await {
+            Ok(diff) if diff.trim().is_empty() => {
+                return Self::message(agent, format!("No changes against origin/{base}"));
+            }
+            Ok(diff) => diff,
+            Err(e) => return Self::message(agent, format!("Failed to load diff: {e}")),
+        };
+        let parsed = tokio::task::spawn_blocking(move || {
+            let lines: Vec<String> = diff.lines().map(String::from).collect();
+            let files = parse_files(&lines);
+            let highlights = highlight_diff(&lines, &files);
+            (lines, files, highlights)
+        })
+        .await;
+        match parsed {
+            Ok((lines, files, highlights)) => Self {
+                agent,
+                lines,
+                files,
+                highlights,
+            },
+            Err(e) => Self::message(agent, format!("Failed to load diff: {e}")),
+        }
+    }
+}
+
+/// Everything the agent's worktree changes against the point it branched
+/// from `origin/<base>`: its commits, uncommitted edits to tracked files and
+/// files it created but hasn't added yet.
+pub async fn working_diff(wt_path: &str, base: &str) -> Result<String> {
+    let merge_base =
+        git_output(wt_path, &["merge-base", &format!("origin/{base}"), "HEAD"]).await?;
+    let mut diff = git_output(wt_path, &["diff", "--patch", merge_base.trim()]).await?;
+    let untracked = git_output(
+        wt_path,
+        &["ls-files", "--others", "--exclude-standard", "-z"],
+    )
+    .await?;
+    for path in untracked.split('\0').filter(|p| !p.is_empty()) {
+        diff.push_str(&new_file_diff(wt_path, path).await?);
+    }
+    Ok(diff)
+}
+
+/// A diff adding an untracked file in full.
+async fn new_file_diff(wt_path: &str, path: &str) -> Result<String> {
+    let output = tokio::process::Command::new("git")
+        .args(["diff", "--no-index", "--", "/dev/null", path])
+        .current_dir(wt_path)
+        .output()
+        .await
+        .context("Failed to run git diff --no-index")?;
+    // It exits 1 when the files differ, which they always do here
+    if output.status.code() != Some(1) {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git diff --no-index {path} failed: {stderr}");
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// The files in a unified diff, in order, with their line counts.
+pub fn parse_files(lines: &[String]) -> Vec<DiffFile> {
+    let mut files: Vec<DiffFile> = Vec::new();
+    // `---`/`+++` are headers before the first hunk and content after it
+    let mut in_hunk = false;
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            // `a/old b/new`; the new path names renames by where they went
+            let path = paths
+                .rsplit_once(" b/")
+                .map_or(paths, |(_, new)| new)
+                .to_string();
+            files.push(DiffFile {
+                path,
+                start: i,
+                added: 0,
+                removed: 0,
+            });
+            in_hunk = false;
+        } else if line.starts_with("@@") {
+            in_hunk = true;
+        } else if let Some(file) = files.last_mut().filter(|_| in_hunk) {
+            if line.starts_with('+') {
+                file.added += 1;
+            } else if line.starts_with('-') {
+                file.removed += 1;
+            }
+        }
+    }
+    files
+}
+
+/// Index into `files` of the file the line at `offset` belongs to.
+pub fn file_at(files: &[DiffFile], offset: usize) -> Option<usize> {
+    files.iter().rposition(|f| f.start <= offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_a_diff_into_files() {
+        let diff = "diff --git a/src/app.rs b/src/app.rs\n--- a/src/app.rs\n+++ b/src/app.rs\n@@ -1,2 +1,3 @@ fn main\n use a;\n-use b;\n+use c;\n+use d;\ndiff --git a/old.rs b/new.rs\nsimilarity index 90%\n--- a/old.rs\n+++ b/new.rs\n@@ -4 +4 @@\n--- x;\n+-y;";
+        let lines: Vec<String> = diff.lines().map(String::from).collect();
+        let files = parse_files(&lines);
+        assert_eq!(
+            files,
+            [
+                DiffFile {
+                    path: "src/app.rs".into(),
+                    start: 0,
+                    added: 2,
+                    removed: 1,
+                },
+                DiffFile {
+                    path: "new.rs".into(),
+                    start: 8,
+                    added: 1,
+                    removed: 1,
+                },
+            ]
+        );
+        assert_eq!(file_at(&files, 5), Some(0));
+        assert_eq!(file_at(&files, 9), Some(1));
+    }
+
+    #[tokio::test]
+    async fn working_diff_includes_untracked_files() {
+        let repo = tempfile::tempdir().unwrap();
+        let path = repo.path().to_str().unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .current_dir(path)
+                .output()
+                .unwrap()
+        };
+        git(&["init", "-q"]);
+        std::fs::write(repo.path().join("lib.rs"), "fn a() {}\n").unwrap();
+        git(&["add", "lib.rs"]);
+        git(&["commit", "-qm", "init"]);
+        git(&["update-ref", "refs/remotes/origin/main", "HEAD"]);
+
+        std::fs::write(repo.path().join("lib.rs"), "fn b() {}\n").unwrap();
+        std::fs::write(repo.path().join("new.rs"), "fn c() {}\n").unwrap();
+        let diff = working_diff(path, "main").await.unwrap();
+        let lines: Vec<String> = diff.lines().map(String::from).collect();
+        let paths: Vec<String> = parse_files(&lines).into_iter().map(|f| f.path).collect();
+        assert_eq!(paths, ["lib.rs", "new.rs"]);
+        assert!(diff.contains("+fn c() {}"));
+    }
+}
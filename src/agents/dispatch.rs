@@ -1,32 +1,68 @@
 use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::io::Write;
 use std::path::Path;
 use std::process::Stdio;
 use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::sync::mpsc;
 
 const GIT_TIMEOUT: Duration = Duration::from_secs(30);
 
 use super::branch::{branch_name, worktree_path};
 use super::claude_md::write_claude_md;
-use super::claude_prompt::build_prompt;
+use super::claude_prompt::{
+    build_plan_prompt, build_prompt, build_review_prompt, render_prompt, with_plan,
+};
+use super::hygiene::{install_hook, repo_hooks_dir};
 use super::log::{append_event, new_event};
+use super::plan;
+use super::progress::parse_stream_line;
+use super::questions;
+use super::report;
 use super::store::AgentStore;
+use super::verify::run_verify;
 use crate::app::Action;
+use crate::config::Workflow;
 use crate::model::agent::AgentName;
 use crate::model::work_item::WorkItem;
+use crate::providers::is_review_request;
+
+/// Per-dispatch settings the app resolves from config and agent state.
+pub struct DispatchOptions {
+    pub env: BTreeMap<String, String>,
+    pub workflow: Workflow,
+    /// Command that must pass in the worktree before the run counts as done
+    pub verify_command: Option<String>,
+    /// Why the previous attempt failed, when this is a retry
+    pub previous_failure: Option<String>,
+    /// Branch to start from and land on, e.g. `main` or `develop`
+    pub base_branch: String,
+    /// Path of a template for the agent section of CLAUDE.md
+    pub claude_md_template: Option<String>,
+    /// Path of a template replacing the built-in prompt
+    pub prompt_template: Option<String>,
+    /// Start with a plan-only run and wait for the plan to be approved
+    pub plan_first: bool,
+}
 
 pub async fn dispatch(
     agent_name: AgentName,
     item: &WorkItem,
     repo_root: &str,
+    opts: &DispatchOptions,
     store: &mut AgentStore,
     action_tx: mpsc::UnboundedSender<Action>,
 ) -> Result<()> {
     let branch = branch_name(agent_name);
     let wt_path = worktree_path(repo_root, agent_name);
+    tracing::info!(agent = agent_name.as_str(), item = %item.id, %wt_path, "dispatching");
 
     // Mark provisioning
-    store.mark_provisioning(agent_name, &item.id, &item.title, &branch, &wt_path)?;
+    store.mark_provisioning(agent_name, &item.id, &item.title, &branch, &wt_path, repo_root)?;
+    store.update_agent(agent_name, |agent| {
+        agent.base_branch = Some(opts.base_branch.clone());
+    })?;
     let _ = append_event(&new_event(
         agent_name,
         "dispatched",
@@ -36,12 +72,18 @@ pub async fn dispatch(
     ));
 
     // Run provisioning steps — if anything fails, mark agent as Error
-    match provision_and_spawn(agent_name, item, repo_root, &branch, &wt_path, action_tx).await {
+    match provision_and_spawn(agent_name, item, repo_root, &wt_path, opts, action_tx).await {
         Ok(pid) => {
             store.mark_working(agent_name, pid)?;
             Ok(())
         }
         Err(e) => {
+            tracing::warn!(
+                agent = agent_name.as_str(),
+                item = %item.id,
+                error = %format!("{e:#}"),
+                "provisioning failed"
+            );
             let msg = format!("Provisioning failed: {e}");
             let _ = append_event(&new_event(
                 agent_name,
@@ -60,19 +102,23 @@ async fn provision_and_spawn(
     agent_name: AgentName,
     item: &WorkItem,
     repo_root: &str,
-    branch: &str,
     wt_path: &str,
+    opts: &DispatchOptions,
     action_tx: mpsc::UnboundedSender<Action>,
 ) -> Result<u32> {
+    let branch = &branch_name(agent_name);
+    let base = &opts.base_branch;
+    let origin_base = format!("origin/{base}");
+
     // Git operations
     let _ = append_event(&new_event(
         agent_name,
         "provisioning",
         Some(&item.id),
         Some(&item.title),
-        Some("Fetching latest from origin/main"),
+        Some(&format!("Fetching latest from {origin_base}")),
     ));
-    run_git(repo_root, &["fetch", "origin", "main"]).await?;
+    run_git(repo_root, &["fetch", "origin", base]).await?;
 
     // Clean up existing worktree
     let wt = Path::new(wt_path);
@@ -84,12 +130,23 @@ async fn provision_and_spawn(
     }
     let _ = run_git(repo_root, &["worktree", "prune"]).await;
 
+    // A review checks out the pull request itself, not the base branch
+    let start = match pull_request_number(item) {
+        Some(number) => {
+            let pr_ref = format!("refs/work/pr-{number}");
+            run_git(
+                repo_root,
+                &["fetch", "origin", &format!("+pull/{number}/head:{pr_ref}")],
+            )
+            .await?;
+            pr_ref
+        }
+        None => origin_base.clone(),
+    };
+
     // Create branch (force if exists)
-    if run_git(repo_root, &["branch", branch, "origin/main"])
-        .await
-        .is_err()
-    {
-        run_git(repo_root, &["branch", "-f", branch, "origin/main"]).await?;
+    if run_git(repo_root, &["branch", branch, &start]).await.is_err() {
+        run_git(repo_root, &["branch", "-f", branch, &start]).await?;
     }
 
     // Create worktree
@@ -103,11 +160,132 @@ async fn provision_and_spawn(
         Some(&format!("Worktree at {wt_path}")),
     ));
 
-    // Write CLAUDE.md
-    write_claude_md(Path::new(wt_path), agent_name)?;
+    // Add the agent section to CLAUDE.md
+    let template = opts
+        .claude_md_template
+        .as_ref()
+        .map(|path| {
+            std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read CLAUDE.md template {path}"))
+        })
+        .transpose()?;
+    write_claude_md(
+        Path::new(wt_path),
+        agent_name,
+        opts.workflow,
+        base,
+        template.as_deref(),
+    )?;
+    hide_claude_md(wt_path).await?;
 
-    // Build prompt
-    let prompt = build_prompt(item, agent_name);
+    // Questions from a previous run must not look pending
+    questions::reset(Path::new(wt_path))?;
+    exclude_untracked(wt_path, &format!("/{}/", questions::CHANNEL_DIR)).await?;
+    report::reset(Path::new(wt_path))?;
+
+    if is_review_request(item) {
+        plan::clear_plan(agent_name);
+        let prompt = build_review_prompt(item, agent_name);
+        return spawn_claude(agent_name, item, wt_path, opts, &prompt, true, action_tx);
+    }
+    if opts.plan_first {
+        plan::clear_plan(agent_name);
+        let prompt = build_plan_prompt(item, agent_name, base);
+        return spawn_claude(agent_name, item, wt_path, opts, &prompt, true, action_tx);
+    }
+    let prompt = agent_prompt(item, agent_name, opts)?;
+    spawn_claude(agent_name, item, wt_path, opts, &prompt, false, action_tx)
+}
+
+/// The pull request number of a review request, from its URL.
+fn pull_request_number(item: &WorkItem) -> Option<u64> {
+    if !is_review_request(item) {
+        return None;
+    }
+    let (_, rest) = item.url.as_deref()?.split_once("/pull/")?;
+    rest.split(['/', '#', '?']).next()?.parse().ok()
+}
+
+/// Start the implementation run for an approved plan, in the worktree the
+/// plan was written in.
+pub fn execute_plan(
+    agent_name: AgentName,
+    item: &WorkItem,
+    wt_path: &str,
+    opts: &DispatchOptions,
+    store: &mut AgentStore,
+    action_tx: mpsc::UnboundedSender<Action>,
+) -> Result<()> {
+    let plan = plan::load_plan(agent_name).context("The plan is gone")?;
+    let _ = append_event(&new_event(
+        agent_name,
+        "plan-approved",
+        Some(&item.id),
+        Some(&item.title),
+        None,
+    ));
+    let spawned = agent_prompt(item, agent_name, opts).and_then(|prompt| {
+        let prompt = with_plan(&prompt, &plan);
+        spawn_claude(agent_name, item, wt_path, opts, &prompt, false, action_tx)
+    });
+    match spawned {
+        Ok(pid) => {
+            plan::clear_plan(agent_name);
+            store.mark_working(agent_name, pid)?;
+            // Time spent waiting for approval doesn't count towards the runtime
+            store.update_agent(agent_name, |agent| {
+                agent.started_at = Some(chrono::Utc::now().to_rfc3339());
+            })?;
+            Ok(())
+        }
+        Err(e) => {
+            tracing::warn!(
+                agent = agent_name.as_str(),
+                item = %item.id,
+                error = %format!("{e:#}"),
+                "implementation run failed to start"
+            );
+            let msg = format!("Failed to start the implementation run: {e}");
+            let _ = append_event(&new_event(
+                agent_name,
+                "error",
+                Some(&item.id),
+                Some(&item.title),
+                Some(&msg),
+            ));
+            store.mark_error(agent_name, &msg)?;
+            Err(e)
+        }
+    }
+}
+
+/// The implementation prompt: the configured template, or the built-in one.
+fn agent_prompt(item: &WorkItem, agent_name: AgentName, opts: &DispatchOptions) -> Result<String> {
+    let base = &opts.base_branch;
+    let previous_failure = opts.previous_failure.as_deref();
+    Ok(match &opts.prompt_template {
+        Some(path) => {
+            let template = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read prompt template {path}"))?;
+            render_prompt(&template, item, agent_name, opts.workflow, base, previous_failure)
+                .with_context(|| format!("Invalid prompt template {path}"))?
+        }
+        None => build_prompt(item, agent_name, opts.workflow, base, previous_failure),
+    })
+}
+
+/// Launch claude in the worktree and monitor it. A plan-only run may read but
+/// not change anything, and hands its final answer back as the plan.
+fn spawn_claude(
+    agent_name: AgentName,
+    item: &WorkItem,
+    wt_path: &str,
+    opts: &DispatchOptions,
+    prompt: &str,
+    plan_only: bool,
+    action_tx: mpsc::UnboundedSender<Action>,
+) -> Result<u32> {
+    let base = &opts.base_branch;
 
     // Set up log file
     let log_dir = crate::config::data_dir().join("logs");
@@ -115,32 +293,145 @@ async fn provision_and_spawn(
     let log_file_path = log_dir.join(format!("agent-{}.log", agent_name.as_str()));
     let log_file = std::fs::File::create(&log_file_path)?;
 
+    // Route pushes through the hygiene check without touching the repo's
+    // hooks; ours chain to whichever directory the repo configured
+    let hooks_dir = install_hook()?;
+    let repo_hooks = repo_hooks_dir(wt_path);
+
     // Spawn claude process
-    let child = tokio::process::Command::new("claude")
-        .args(["-p", &prompt, "--dangerously-skip-permissions"])
+    let permissions: &[&str] = if plan_only {
+        &["--permission-mode", "plan"]
+    } else {
+        &["--dangerously-skip-permissions"]
+    };
+    // Every run reports its status, whatever prompt or template it was given
+    let prompt = format!("{prompt}\n{}", report::prompt_section());
+    let mut command = tokio::process::Command::new("claude");
+    if let Some(dir) = &repo_hooks {
+        command.env("WORK_REPO_HOOKS", dir);
+    }
+    let mut child = command
+        .args(["-p", &prompt])
+        .args(permissions)
+        .args(["--output-format", "stream-json", "--verbose"])
         .current_dir(wt_path)
+        .envs(&opts.env)
+        .env("GIT_CONFIG_COUNT", "1")
+        .env("GIT_CONFIG_KEY_0", "core.hooksPath")
+        .env("GIT_CONFIG_VALUE_0", &hooks_dir)
+        // Tells the pre-push hygiene check what to diff against
+        .env("WORK_BASE_BRANCH", base)
         .stdin(Stdio::null())
-        .stdout(Stdio::from(log_file.try_clone()?))
-        .stderr(Stdio::from(log_file))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
         .spawn()
         .context("Failed to spawn claude")?;
 
+    // Tee output into the log file and the TUI
+    let stage = if plan_only { "plan " } else { "" };
+    let _ = action_tx.send(Action::AgentOutput(
+        agent_name,
+        format!("── {stage}{}: {} ──", item.id, item.title),
+    ));
+    let progress_log = log_file.try_clone()?;
+    let progress = child.stdout.take().map(|stdout| {
+        tokio::spawn(stream_progress(
+            stdout,
+            agent_name,
+            item.id.clone(),
+            item.title.clone(),
+            progress_log,
+            action_tx.clone(),
+        ))
+    });
+    if let Some(stderr) = child.stderr.take() {
+        tokio::spawn(stream_output(stderr, agent_name, log_file, action_tx.clone()));
+    }
+
     let pid = child.id().unwrap_or(0);
+    tracing::info!(agent = agent_name.as_str(), item = %item.id, pid, plan_only, "claude started");
+    let env = &opts.env;
+    let started = if env.is_empty() {
+        format!("Process started (pid {pid})")
+    } else {
+        // Log variable names only — values may be secrets
+        let names: Vec<&str> = env.keys().map(String::as_str).collect();
+        format!("Process started (pid {pid}) with env {}", names.join(", "))
+    };
     let _ = append_event(&new_event(
         agent_name,
         "working",
         Some(&item.id),
         Some(&item.title),
-        Some(&format!("Process started (pid {pid})")),
+        Some(&started),
     ));
 
     // Monitor process in background
     let item_id = item.id.clone();
     let item_title = item.title.clone();
+    let verify_command = opts.verify_command.clone();
+    let verify_env = opts.env.clone();
+    let wt_path = wt_path.to_string();
     tokio::spawn(async move {
-        let result = child.wait_with_output().await;
+        let result = child.wait().await;
+        // The plan is the run's last output line, so let it reach the log
+        if let Some(progress) = progress {
+            let _ = progress.await;
+        }
         match result {
-            Ok(output) if output.status.success() => {
+            Ok(status) if status.success() && plan_only => {
+                let log = std::fs::read_to_string(&log_file_path).unwrap_or_default();
+                let saved = plan::result_from_log(&log)
+                    .context("The run finished without a plan")
+                    .and_then(|text| plan::save_plan(agent_name, &text));
+                if let Err(e) = saved {
+                    let _ = append_event(&new_event(
+                        agent_name,
+                        "error",
+                        Some(&item_id),
+                        Some(&item_title),
+                        Some(&e.to_string()),
+                    ));
+                    let _ = action_tx.send(Action::AgentProcessExited(agent_name, false));
+                    return;
+                }
+                let _ = append_event(&new_event(
+                    agent_name,
+                    "plan-ready",
+                    Some(&item_id),
+                    Some(&item_title),
+                    Some("Waiting for approval"),
+                ));
+                let _ = action_tx.send(Action::AgentPlanReady(agent_name));
+            }
+            Ok(status) if status.success() => {
+                if let Some(cmd) = verify_command {
+                    let _ = action_tx.send(Action::AgentOutput(
+                        agent_name,
+                        format!("── verify: {cmd} ──"),
+                    ));
+                    if let Err(output) = run_verify(&cmd, &wt_path, &verify_env).await {
+                        let _ = append_event(&new_event(
+                            agent_name,
+                            "verify-failed",
+                            Some(&item_id),
+                            Some(&item_title),
+                            Some(&format!("`{cmd}` failed")),
+                        ));
+                        for line in output.lines() {
+                            let _ = action_tx.send(Action::AgentOutput(agent_name, line.into()));
+                        }
+                        let _ = action_tx.send(Action::AgentVerifyFailed(agent_name, output));
+                        return;
+                    }
+                    let _ = append_event(&new_event(
+                        agent_name,
+                        "verified",
+                        Some(&item_id),
+                        Some(&item_title),
+                        Some(&format!("`{cmd}` passed")),
+                    ));
+                }
                 let _ = append_event(&new_event(
                     agent_name,
                     "done",
@@ -150,8 +441,14 @@ async fn provision_and_spawn(
                 ));
                 let _ = action_tx.send(Action::AgentProcessExited(agent_name, true));
             }
-            Ok(output) => {
-                let msg = format!("Exit code: {}", output.status);
+            Ok(status) => {
+                tracing::warn!(
+                    agent = agent_name.as_str(),
+                    item = %item_id,
+                    %status,
+                    "claude exited with an error"
+                );
+                let msg = format!("Exit code: {status}");
                 let _ = append_event(&new_event(
                     agent_name,
                     "error",
@@ -162,6 +459,12 @@ async fn provision_and_spawn(
                 let _ = action_tx.send(Action::AgentProcessExited(agent_name, false));
             }
             Err(e) => {
+                tracing::warn!(
+                    agent = agent_name.as_str(),
+                    item = %item_id,
+                    error = %e,
+                    "waiting on claude failed"
+                );
                 let msg = format!("Process error: {e}");
                 let _ = append_event(&new_event(
                     agent_name,
@@ -178,7 +481,109 @@ async fn provision_and_spawn(
     Ok(pid)
 }
 
+/// Parse claude's `stream-json` stdout: raw lines go to the log file, readable
+/// text to the app, and recognized steps to the activity log.
+async fn stream_progress<R: AsyncRead + Unpin>(
+    reader: R,
+    agent_name: AgentName,
+    item_id: String,
+    item_title: String,
+    mut log_file: std::fs::File,
+    action_tx: mpsc::UnboundedSender<Action>,
+) {
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let _ = writeln!(log_file, "{line}");
+        let parsed = parse_stream_line(&line);
+
+        for step in &parsed.progress {
+            let _ = append_event(&new_event(
+                agent_name,
+                step.event_type(),
+                Some(&item_id),
+                Some(&item_title),
+                step.message(),
+            ));
+        }
+
+        if let Some(usage) = parsed.usage {
+            let _ = append_event(&new_event(
+                agent_name,
+                "usage",
+                Some(&item_id),
+                Some(&item_title),
+                Some(&usage.to_string()),
+            ));
+            let _ = action_tx.send(Action::AgentUsage(agent_name, item_id.clone(), usage));
+        }
+
+        if let Some(display) = parsed.display {
+            for text in display.lines() {
+                if action_tx
+                    .send(Action::AgentOutput(agent_name, text.to_string()))
+                    .is_err()
+                {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Forward each line of a child stream to the log file and the app.
+async fn stream_output<R: AsyncRead + Unpin>(
+    reader: R,
+    agent_name: AgentName,
+    mut log_file: std::fs::File,
+    action_tx: mpsc::UnboundedSender<Action>,
+) {
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let _ = writeln!(log_file, "{line}");
+        if action_tx.send(Action::AgentOutput(agent_name, line)).is_err() {
+            break;
+        }
+    }
+}
+
+/// Keep the agent section out of the agent's commits and `git status`: a
+/// tracked CLAUDE.md is marked skip-worktree in the worktree's own index, an
+/// untracked one goes in the repository's exclude file.
+async fn hide_claude_md(wt_path: &str) -> Result<()> {
+    if run_git(wt_path, &["ls-files", "--error-unmatch", "CLAUDE.md"])
+        .await
+        .is_ok()
+    {
+        return run_git(wt_path, &["update-index", "--skip-worktree", "CLAUDE.md"]).await;
+    }
+    exclude_untracked(wt_path, "/CLAUDE.md").await
+}
+
+/// Add `pattern` to the repository's exclude file, shared by its worktrees.
+async fn exclude_untracked(wt_path: &str, pattern: &str) -> Result<()> {
+    let output = tokio::process::Command::new("git")
+        .args(["rev-parse", "--git-path", "info/exclude"])
+        .current_dir(wt_path)
+        .output()
+        .await
+        .context("Failed to run git rev-parse")?;
+    let exclude = Path::new(wt_path).join(String::from_utf8_lossy(&output.stdout).trim());
+    let current = std::fs::read_to_string(&exclude).unwrap_or_default();
+    if !current.lines().any(|line| line == pattern) {
+        if let Some(parent) = exclude.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&exclude)?;
+        writeln!(file, "{pattern}")?;
+    }
+    Ok(())
+}
+
 async fn run_git(cwd: &str, args: &[&str]) -> Result<()> {
+    tracing::debug!(cwd, "git {}", args.join(" "));
     let output = tokio::time::timeout(
         GIT_TIMEOUT,
         tokio::process::Command::new("git")
@@ -192,6 +597,7 @@ async fn run_git(cwd: &str, args: &[&str]) -> Result<()> {
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
+        tracing::debug!(cwd, %stderr, "git {} failed", args.join(" "));
         anyhow::bail!("git {} failed: {}", args.join(" "), stderr);
     }
     Ok(())
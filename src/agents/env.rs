@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use crate::config::AgentsConfig;
+use crate::model::work_item::WorkItem;
+
+/// Build the environment for an agent run. Later layers override earlier ones:
+/// global `env`, then `project_env` for the project directory, then `label_env`
+/// for each of the item's labels (matched case-insensitively).
+pub fn resolve_env(
+    cfg: &AgentsConfig,
+    project_dir: &str,
+    item: &WorkItem,
+) -> BTreeMap<String, String> {
+    let mut env: BTreeMap<String, String> = cfg
+        .env
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+
+    if let Some(vars) = cfg.project_env.get(project_dir) {
+        env.extend(vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+
+    for label in &item.labels {
+        let vars = cfg
+            .label_env
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(label))
+            .map(|(_, vars)| vars);
+        if let Some(vars) = vars {
+            env.extend(vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+    }
+
+    env
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn item_with_labels(labels: &[&str]) -> WorkItem {
+        WorkItem::test("ENG-1")
+            .with_title("Test")
+            .with_labels(labels)
+    }
+
+    #[test]
+    fn empty_config_yields_empty_env() {
+        let env = resolve_env(&AgentsConfig::default(), "/proj", &item_with_labels(&[]));
+        assert!(env.is_empty());
+    }
+
+    #[test]
+    fn project_overrides_global_and_label_overrides_project() {
+        let cfg = AgentsConfig {
+            env: vars(&[("DATABASE_URL", "global"), ("RUST_LOG", "info")]),
+            project_env: HashMap::from([(
+                "/proj".to_string(),
+                vars(&[("DATABASE_URL", "project"), ("API_URL", "http://localhost")]),
+            )]),
+            label_env: HashMap::from([("Backend".to_string(), vars(&[("DATABASE_URL", "label")]))]),
+            ..Default::default()
+        };
+
+        let env = resolve_env(&cfg, "/proj", &item_with_labels(&["backend"]));
+        assert_eq!(env["DATABASE_URL"], "label");
+        assert_eq!(env["API_URL"], "http://localhost");
+        assert_eq!(env["RUST_LOG"], "info");
+    }
+
+    #[test]
+    fn other_projects_and_labels_are_ignored() {
+        let cfg = AgentsConfig {
+            project_env: HashMap::from([("/other".to_string(), vars(&[("A", "1")]))]),
+            label_env: HashMap::from([("frontend".to_string(), vars(&[("B", "2")]))]),
+            ..Default::default()
+        };
+
+        let env = resolve_env(&cfg, "/proj", &item_with_labels(&["backend"]));
+        assert!(env.is_empty());
+    }
+}
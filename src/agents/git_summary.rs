@@ -0,0 +1,100 @@
+use anyhow::Result;
+
+use super::hygiene::git_output;
+
+/// What an agent's worktree branch holds beyond its base, shown above the
+/// activity log.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitSummary {
+    pub branch: String,
+    /// `git log --oneline` lines, newest first
+    pub commits: Vec<String>,
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// Remote branches already holding HEAD, e.g. `origin/agent/ember/eng-1`
+    pub pushed_to: Vec<String>,
+}
+
+impl GitSummary {
+    /// e.g. `3 files +42 −7`
+    pub fn stat(&self) -> String {
+        let files = if self.files_changed == 1 {
+            "file"
+        } else {
+            "files"
+        };
+        format!(
+            "{} {files} +{} \u{2212}{}",
+            self.files_changed, self.insertions, self.deletions
+        )
+    }
+}
+
+pub async fn summarize(wt_path: &str, base: &str) -> Result<GitSummary> {
+    let branch = git_output(wt_path, &["rev-parse", "--abbrev-ref", "HEAD"]).await?;
+    let range = format!("origin/{base}..HEAD");
+    let log = git_output(wt_path, &["log", "--oneline", &range]).await?;
+    let stat = git_output(
+        wt_path,
+        &["diff", "--shortstat", &format!("origin/{base}...HEAD")],
+    )
+    .await?;
+    let remotes = git_output(wt_path, &["branch", "-r", "--contains", "HEAD"]).await?;
+
+    let (files_changed, insertions, deletions) = parse_shortstat(&stat);
+    let commits: Vec<String> = log.lines().map(String::from).collect();
+    // With nothing ahead of the base, HEAD is on origin/<base> itself
+    let pushed_to = if commits.is_empty() {
+        Vec::new()
+    } else {
+        remotes
+            .lines()
+            .map(str::trim)
+            .filter(|r| !r.is_empty() && !r.contains(" -> "))
+            .map(String::from)
+            .collect()
+    };
+    Ok(GitSummary {
+        branch: branch.trim().to_string(),
+        commits,
+        files_changed,
+        insertions,
+        deletions,
+        pushed_to,
+    })
+}
+
+/// Files, insertions and deletions from `git diff --shortstat`, e.g.
+/// ` 3 files changed, 42 insertions(+), 7 deletions(-)`.
+fn parse_shortstat(stat: &str) -> (usize, usize, usize) {
+    let (mut files, mut insertions, mut deletions) = (0, 0, 0);
+    for part in stat.split(',') {
+        let mut words = part.split_whitespace();
+        let Some(count) = words.next().and_then(|n| n.parse().ok()) else {
+            continue;
+        };
+        match words.next() {
+            Some(w) if w.starts_with("file") => files = count,
+            Some(w) if w.starts_with("insertion") => insertions = count,
+            Some(w) if w.starts_with("deletion") => deletions = count,
+            _ => {}
+        }
+    }
+    (files, insertions, deletions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_shortstat() {
+        assert_eq!(
+            parse_shortstat(" 3 files changed, 42 insertions(+), 7 deletions(-)\n"),
+            (3, 42, 7)
+        );
+        assert_eq!(parse_shortstat(" 1 file changed, 1 deletion(-)"), (1, 0, 1));
+        assert_eq!(parse_shortstat(""), (0, 0, 0));
+    }
+}
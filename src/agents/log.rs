@@ -1,10 +1,11 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::config::data_dir;
-use crate::model::agent::AgentName;
+use crate::model::agent::{AgentName, AgentStats, ItemRecord};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentEvent {
@@ -19,12 +20,149 @@ pub struct AgentEvent {
     pub message: Option<String>,
 }
 
-fn log_path() -> PathBuf {
-    data_dir().join("agent-activity.jsonl")
+/// Groups of event types the activity view can narrow to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EventKind {
+    #[default]
+    All,
+    Errors,
+    Commits,
+    Messages,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 4] = [Self::All, Self::Errors, Self::Commits, Self::Messages];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Errors => "errors",
+            Self::Commits => "commits",
+            Self::Messages => "messages",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.as_str() == name)
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&k| k == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    fn matches(self, event: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Errors => matches!(
+                event,
+                "error"
+                    | "ci-failed"
+                    | "verify-failed"
+                    | "max-retries"
+                    | "stalled"
+                    | "hygiene-flagged"
+                    | "budget-exceeded"
+            ),
+            Self::Commits => matches!(event, "commit" | "pushed" | "pr-opened" | "pr-merged"),
+            Self::Messages => matches!(event, "user-message" | "answered" | "question"),
+        }
+    }
+}
+
+/// Filters for `query_events`; unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct EventQuery {
+    pub agent: Option<AgentName>,
+    /// Event type, e.g. `dispatched` or `error`
+    pub event: Option<String>,
+    pub work_item_id: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub kind: EventKind,
+    /// Case-insensitive text to find in the event type, item or message
+    pub text: Option<String>,
+    /// Keep only the most recent matches
+    pub limit: Option<usize>,
+}
+
+impl EventQuery {
+    fn matches(&self, e: &AgentEvent) -> bool {
+        if self.agent.is_some_and(|a| e.agent != a)
+            || self.event.as_ref().is_some_and(|t| e.event != *t)
+            || self
+                .work_item_id
+                .as_ref()
+                .is_some_and(|id| e.work_item_id.as_ref() != Some(id))
+            || !self.kind.matches(&e.event)
+        {
+            return false;
+        }
+        if let Some(text) = &self.text {
+            let text = text.to_lowercase();
+            let found = [
+                Some(&e.event),
+                e.work_item_id.as_ref(),
+                e.work_item_title.as_ref(),
+                e.message.as_ref(),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|field| field.to_lowercase().contains(&text));
+            if !found {
+                return false;
+            }
+        }
+        if self.since.is_none() && self.until.is_none() {
+            return true;
+        }
+        let Ok(ts) = DateTime::parse_from_rfc3339(&e.timestamp) else {
+            return false;
+        };
+        self.since.is_none_or(|s| ts >= s) && self.until.is_none_or(|u| ts <= u)
+    }
+
+    /// Whether the month segment `YYYY-MM` can hold matching events.
+    fn covers_month(&self, month: &str) -> bool {
+        self.since
+            .is_none_or(|s| month >= s.format("%Y-%m").to_string().as_str())
+            && self
+                .until
+                .is_none_or(|u| month <= u.format("%Y-%m").to_string().as_str())
+    }
+}
+
+/// Events live in one file per agent per month,
+/// `events/<agent>/<YYYY-MM>.jsonl`, so queries only open the segments that
+/// can match instead of scanning the whole history.
+fn events_dir(data: &Path) -> PathBuf {
+    data.join("events")
+}
+
+/// The single log used before events were segmented.
+fn legacy_log_path(data: &Path) -> PathBuf {
+    data.join("agent-activity.jsonl")
+}
+
+fn segment_path(data: &Path, event: &AgentEvent) -> PathBuf {
+    // Timestamps are RFC 3339 in UTC, so the prefix is the month
+    let month = event.timestamp.get(..7).unwrap_or("unknown");
+    events_dir(data)
+        .join(event.agent.as_str())
+        .join(format!("{month}.jsonl"))
 }
 
 pub fn append_event(event: &AgentEvent) -> Result<()> {
-    let path = log_path();
+    append_event_in(&data_dir(), event)
+}
+
+fn append_event_in(data: &Path, event: &AgentEvent) -> Result<()> {
+    migrate_legacy_log(data)?;
+    append_to_segment(data, event)
+}
+
+fn append_to_segment(data: &Path, event: &AgentEvent) -> Result<()> {
+    let path = segment_path(data, event);
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)?;
     }
@@ -38,49 +176,113 @@ pub fn append_event(event: &AgentEvent) -> Result<()> {
 }
 
 pub fn read_events(agent: Option<AgentName>, limit: Option<usize>) -> Vec<AgentEvent> {
-    let path = log_path();
-    if !path.exists() {
-        return Vec::new();
-    }
-    let contents = match std::fs::read_to_string(&path) {
-        Ok(c) => c,
-        Err(_) => return Vec::new(),
+    query_events(&EventQuery {
+        agent,
+        limit,
+        ..Default::default()
+    })
+}
+
+/// Matching events, oldest first.
+pub fn query_events(query: &EventQuery) -> Vec<AgentEvent> {
+    query_events_in(&data_dir(), query)
+}
+
+fn query_events_in(data: &Path, query: &EventQuery) -> Vec<AgentEvent> {
+    let _ = migrate_legacy_log(data);
+
+    let mut agents: Vec<AgentName> = match query.agent {
+        Some(agent) => vec![agent],
+        // Every agent with a log, scaled-out workers included
+        None => std::fs::read_dir(events_dir(data))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+            .collect(),
     };
+    agents.sort();
+    let mut segments: Vec<(String, PathBuf)> = Vec::new();
+    for agent in agents {
+        let Ok(entries) = std::fs::read_dir(events_dir(data).join(agent.as_str())) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(month) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if query.covers_month(month) {
+                segments.push((month.to_string(), path));
+            }
+        }
+    }
+    // Newest months first, so a limited query can stop early
+    segments.sort_by(|a, b| b.0.cmp(&a.0));
 
-    let mut events: Vec<AgentEvent> = contents
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .filter_map(|line| serde_json::from_str(line).ok())
-        .filter(|e: &AgentEvent| agent.map_or(true, |a| e.agent == a))
-        .collect();
+    let mut events = Vec::new();
+    let mut i = 0;
+    while i < segments.len() {
+        let month = &segments[i].0;
+        while i < segments.len() && segments[i].0 == *month {
+            if let Ok(contents) = std::fs::read_to_string(&segments[i].1) {
+                events.extend(
+                    contents
+                        .lines()
+                        .filter(|line| !line.trim().is_empty())
+                        .filter_map(|line| serde_json::from_str::<AgentEvent>(line).ok())
+                        .filter(|e| query.matches(e)),
+                );
+            }
+            i += 1;
+        }
+        if query.limit.is_some_and(|limit| events.len() >= limit) {
+            break;
+        }
+    }
 
-    if let Some(limit) = limit {
+    // Interleave agents; stable, so same-instant events keep file order
+    events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    if let Some(limit) = query.limit {
         let len = events.len();
         if len > limit {
             events = events.split_off(len - limit);
         }
     }
-
     events
 }
 
 pub fn clear_events(agent: AgentName) -> Result<()> {
-    let path = log_path();
-    if !path.exists() {
+    clear_events_in(&data_dir(), agent)
+}
+
+fn clear_events_in(data: &Path, agent: AgentName) -> Result<()> {
+    migrate_legacy_log(data)?;
+    let dir = events_dir(data).join(agent.as_str());
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)?;
+    }
+    Ok(())
+}
+
+/// Split the old single-file log into segments. The file is renamed first,
+/// so only one process migrates it; it is kept afterwards as a backup.
+fn migrate_legacy_log(data: &Path) -> Result<()> {
+    let legacy = legacy_log_path(data);
+    if !legacy.exists() {
+        return Ok(());
+    }
+    let claimed = legacy.with_extension("jsonl.migrated");
+    if std::fs::rename(&legacy, &claimed).is_err() {
         return Ok(());
     }
-    let contents = std::fs::read_to_string(&path)?;
-    let remaining: Vec<&str> = contents
+    let contents = std::fs::read_to_string(&claimed)?;
+    for event in contents
         .lines()
-        .filter(|line| {
-            if let Ok(event) = serde_json::from_str::<AgentEvent>(line) {
-                event.agent != agent
-            } else {
-                true // keep unparseable lines
-            }
-        })
-        .collect();
-    std::fs::write(&path, remaining.join("\n") + if remaining.is_empty() { "" } else { "\n" })?;
+        .filter_map(|line| serde_json::from_str::<AgentEvent>(line).ok())
+    {
+        append_to_segment(data, &event)?;
+    }
     Ok(())
 }
 
@@ -100,3 +302,185 @@ pub fn new_event(
         message: message.map(String::from),
     }
 }
+
+/// Lifetime stats rebuilt from one agent's events, oldest first, for stores
+/// written before agents kept a tally.
+pub fn replay_stats(events: &[AgentEvent]) -> AgentStats {
+    let mut stats = AgentStats::default();
+    // Start of the current attempt and retries so far, while an item is open
+    let mut open: Option<(Option<DateTime<Utc>>, u32)> = None;
+    for event in events {
+        let at = DateTime::parse_from_rfc3339(&event.timestamp)
+            .ok()
+            .map(|t| t.with_timezone(&Utc));
+        let outcome = match event.event.as_str() {
+            "dispatched" => {
+                let retries = open.map_or(0, |(_, retries)| retries);
+                open = Some((at, retries));
+                continue;
+            }
+            "retry" => {
+                if let Some((_, retries)) = open.as_mut() {
+                    *retries += 1;
+                }
+                continue;
+            }
+            "done" => "done",
+            "max-retries" => "failed",
+            // Done items are released too; only count ones left unfinished
+            "released" | "cleared" => "released",
+            _ => continue,
+        };
+        let Some((started, retries)) = open.take() else {
+            continue;
+        };
+        stats.record(&ItemRecord {
+            work_item_id: event.work_item_id.clone().unwrap_or_default(),
+            work_item_title: event.work_item_title.clone().unwrap_or_default(),
+            outcome: outcome.into(),
+            finished_at: event.timestamp.clone(),
+            duration_secs: started.zip(at).map(|(from, to)| (to - from).num_seconds()),
+            retries,
+        });
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(agent: AgentName, timestamp: &str, kind: &str, item: &str) -> AgentEvent {
+        AgentEvent {
+            timestamp: timestamp.into(),
+            agent,
+            event: kind.into(),
+            work_item_id: Some(item.into()),
+            work_item_title: None,
+            message: None,
+        }
+    }
+
+    #[test]
+    fn queries_segments_and_migrates_legacy_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = dir.path();
+        let legacy = serde_json::to_string(&event(
+            AgentName::Ember,
+            "2026-01-05T09:00:00+00:00",
+            "dispatched",
+            "ENG-1",
+        ))
+        .unwrap();
+        std::fs::write(legacy_log_path(data), legacy + "\n").unwrap();
+
+        append_event_in(
+            data,
+            &event(
+                AgentName::Flow,
+                "2026-02-01T10:00:00+00:00",
+                "dispatched",
+                "ENG-2",
+            ),
+        )
+        .unwrap();
+        append_event_in(
+            data,
+            &event(
+                AgentName::Ember,
+                "2026-02-03T11:00:00+00:00",
+                "error",
+                "ENG-1",
+            ),
+        )
+        .unwrap();
+        assert!(!legacy_log_path(data).exists());
+        assert!(events_dir(data).join("ember/2026-01.jsonl").exists());
+
+        let all = query_events_in(data, &EventQuery::default());
+        let order: Vec<&str> = all.iter().map(|e| e.timestamp.get(..10).unwrap()).collect();
+        assert_eq!(order, ["2026-01-05", "2026-02-01", "2026-02-03"]);
+
+        let q = EventQuery {
+            work_item_id: Some("ENG-1".into()),
+            since: Some("2026-02-01T00:00:00Z".parse().unwrap()),
+            ..Default::default()
+        };
+        let found = query_events_in(data, &q);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].event, "error");
+
+        let latest = EventQuery {
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(query_events_in(data, &latest)[0].event, "error");
+
+        clear_events_in(data, AgentName::Ember).unwrap();
+        let left = query_events_in(data, &EventQuery::default());
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].agent, AgentName::Flow);
+    }
+
+    #[test]
+    fn filters_by_kind_and_text() {
+        let events = [
+            event(AgentName::Tempest, "t", "error", "ENG-1"),
+            event(AgentName::Tempest, "t", "commit", "ENG-2"),
+            event(AgentName::Tempest, "t", "user-message", "ENG-3"),
+        ];
+        let found = |q: EventQuery| -> Vec<&str> {
+            events
+                .iter()
+                .filter(|e| q.matches(e))
+                .map(|e| e.event.as_str())
+                .collect()
+        };
+
+        let kind = |kind| EventQuery {
+            kind,
+            ..Default::default()
+        };
+        assert_eq!(found(kind(EventKind::Errors)), ["error"]);
+        assert_eq!(found(kind(EventKind::Commits)), ["commit"]);
+        assert_eq!(found(kind(EventKind::Messages)), ["user-message"]);
+
+        let text = EventQuery {
+            text: Some("eng-2".into()),
+            ..Default::default()
+        };
+        assert_eq!(found(text), ["commit"]);
+        assert_eq!(EventKind::Messages.next(), EventKind::All);
+    }
+
+    #[test]
+    fn replays_stats_from_events() {
+        let events: Vec<AgentEvent> = [
+            ("2026-03-01T10:00:00+00:00", "dispatched", "ENG-1"),
+            ("2026-03-01T10:05:00+00:00", "retry", "ENG-1"),
+            ("2026-03-01T10:06:00+00:00", "dispatched", "ENG-1"),
+            ("2026-03-01T10:16:00+00:00", "done", "ENG-1"),
+            ("2026-03-01T10:17:00+00:00", "released", "ENG-1"),
+            ("2026-03-01T11:00:00+00:00", "dispatched", "ENG-2"),
+            ("2026-03-01T11:30:00+00:00", "max-retries", "ENG-2"),
+            ("2026-03-01T12:00:00+00:00", "dispatched", "ENG-3"),
+            ("2026-03-01T12:10:00+00:00", "cleared", "ENG-3"),
+        ]
+        .into_iter()
+        .map(|(at, kind, item)| event(AgentName::Tempest, at, kind, item))
+        .collect();
+
+        let stats = replay_stats(&events);
+        assert_eq!(
+            stats,
+            AgentStats {
+                done: 1,
+                failed: 1,
+                released: 1,
+                retries: 1,
+                // The last attempt on ENG-1
+                done_secs: 600,
+            }
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use crate::model::agent::AgentName;
+use crate::model::work_item::WorkItem;
+
+/// Pick an idle agent for `item`: an idle specialist whose keywords match
+/// first, otherwise the next idle agent after `last` in round-robin order.
+pub fn pick_agent(
+    item: &WorkItem,
+    specialties: &HashMap<String, Vec<String>>,
+    idle: &[AgentName],
+    last: Option<AgentName>,
+) -> Option<AgentName> {
+    let specialist = idle.iter().copied().find(|name| {
+        specialties
+            .get(name.as_str())
+            .or_else(|| specialties.get(name.persona().as_str()))
+            .is_some_and(|keywords| matches_specialty(item, keywords))
+    });
+    specialist.or_else(|| round_robin(idle, last))
+}
+
+/// Whether any keyword names one of the item's labels, its source, or a word
+/// in its title. Case-insensitive.
+pub fn matches_specialty(item: &WorkItem, keywords: &[String]) -> bool {
+    let title = item.title.to_lowercase();
+    let words: Vec<&str> = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    keywords.iter().any(|keyword| {
+        let keyword = keyword.to_lowercase();
+        item.labels.iter().any(|l| l.to_lowercase() == keyword)
+            || item.source.to_lowercase() == keyword
+            || words.contains(&keyword.as_str())
+    })
+}
+
+fn round_robin(idle: &[AgentName], last: Option<AgentName>) -> Option<AgentName> {
+    let after_last = idle.iter().filter(|n| last.is_none_or(|l| **n > l)).min();
+    after_last.or_else(|| idle.iter().min()).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::agent::Persona;
+
+    fn item(title: &str, source: &str, labels: &[&str]) -> WorkItem {
+        WorkItem::test("ENG-1")
+            .with_title(title)
+            .with_source(source)
+            .with_labels(labels)
+    }
+
+    fn specialties() -> HashMap<String, Vec<String>> {
+        HashMap::from([
+            ("ember".into(), vec!["bug".into(), "sentry".into()]),
+            ("terra".into(), vec!["refactor".into()]),
+            ("tempest".into(), vec!["test".into()]),
+        ])
+    }
+
+    #[test]
+    fn routes_by_label_source_and_title() {
+        let idle = AgentName::ALL.to_vec();
+        let s = specialties();
+        let bug = item("Crash on save", "linear", &["Bug"]);
+        assert_eq!(pick_agent(&bug, &s, &idle, None), Some(AgentName::Ember));
+        let incident = item("NullPointer in api", "sentry", &[]);
+        assert_eq!(
+            pick_agent(&incident, &s, &idle, None),
+            Some(AgentName::Ember)
+        );
+        let refactor = item("Refactor the store module", "github", &[]);
+        assert_eq!(
+            pick_agent(&refactor, &s, &idle, None),
+            Some(AgentName::Terra)
+        );
+    }
+
+    #[test]
+    fn title_matches_whole_words_only() {
+        let s = specialties();
+        let idle = vec![AgentName::Flow, AgentName::Tempest];
+        let latest = item("Show latest builds", "github", &[]);
+        assert_eq!(pick_agent(&latest, &s, &idle, None), Some(AgentName::Flow));
+    }
+
+    #[test]
+    fn falls_back_to_round_robin() {
+        let s = specialties();
+        let idle = vec![AgentName::Ember, AgentName::Flow, AgentName::Terra];
+        let plain = item("Update copy", "trello", &[]);
+        assert_eq!(
+            pick_agent(&plain, &s, &idle, Some(AgentName::Ember)),
+            Some(AgentName::Flow)
+        );
+        assert_eq!(
+            pick_agent(&plain, &s, &idle, Some(AgentName::Flow)),
+            Some(AgentName::Terra)
+        );
+        assert_eq!(
+            pick_agent(&plain, &s, &idle, Some(AgentName::Terra)),
+            Some(AgentName::Ember)
+        );
+        assert_eq!(pick_agent(&plain, &s, &[], None), None);
+    }
+
+    #[test]
+    fn scaled_workers_share_their_persona_specialty() {
+        let ember2 = AgentName::new(Persona::Ember, 2).unwrap();
+        let bug = item("Crash", "linear", &["bug"]);
+        let idle = vec![AgentName::Flow, ember2];
+        assert_eq!(pick_agent(&bug, &specialties(), &idle, None), Some(ember2));
+        let plain = item("Update copy", "trello", &[]);
+        assert_eq!(
+            pick_agent(&plain, &specialties(), &idle, Some(AgentName::Flow)),
+            Some(ember2)
+        );
+    }
+
+    #[test]
+    fn busy_specialist_falls_back() {
+        let bug = item("Crash", "linear", &["bug"]);
+        let idle = vec![AgentName::Tempest];
+        assert_eq!(
+            pick_agent(&bug, &specialties(), &idle, None),
+            Some(AgentName::Tempest)
+        );
+    }
+}
@@ -1,27 +1,56 @@
 use anyhow::{Context, Result};
 use std::process::Stdio;
 
+use crate::agents::thread::{render_thread, Turn};
+use crate::config::{ChatBackend, ChatConfig};
 use crate::model::agent::AgentName;
 use crate::model::personality::personality;
 
+const OLLAMA_DEFAULT_ENDPOINT: &str = "http://localhost:11434";
+const OLLAMA_DEFAULT_MODEL: &str = "llama3.2";
+const OPENAI_DEFAULT_ENDPOINT: &str = "https://api.openai.com";
+
 /// Send a message to an agent and get a response.
-/// Spawns a short-lived claude process with the message as prompt.
-/// If the agent has a worktree, runs in that directory.
+/// Uses the configured chat backend; without one, spawns a short-lived claude
+/// process with the message as prompt. If the agent has a worktree, claude runs
+/// in that directory. Earlier turns of the thread are included as context.
 pub async fn message_agent(
     agent_name: AgentName,
     message: &str,
     work_dir: &str,
     task_context: Option<&str>,
+    history: &[Turn],
+    chat: Option<&ChatConfig>,
 ) -> Result<String> {
+    let prompt = build_message_prompt(agent_name, message, task_context, history);
+    complete(&prompt, work_dir, chat).await
+}
+
+/// Run a one-off prompt through the configured chat backend.
+pub async fn complete(prompt: &str, work_dir: &str, chat: Option<&ChatConfig>) -> Result<String> {
+    match chat {
+        Some(cfg) if cfg.backend == ChatBackend::Ollama => ollama_chat(cfg, prompt).await,
+        Some(cfg) if cfg.backend == ChatBackend::OpenAi => openai_chat(cfg, prompt).await,
+        _ => claude_chat(prompt, work_dir).await,
+    }
+}
+
+fn build_message_prompt(
+    agent_name: AgentName,
+    message: &str,
+    task_context: Option<&str>,
+    history: &[Turn],
+) -> String {
     let p = personality(agent_name);
+    let conversation = conversation_section(agent_name, history);
 
-    let prompt = if let Some(ctx) = task_context {
+    if let Some(ctx) = task_context {
         format!(
             r#"You are {name}, an agent in a team dashboard CLI called "work".
 Your personality: {tagline} — {focus}
 
 You are currently working on: {ctx}
-
+{conversation}
 The user has sent you this message:
 {message}
 
@@ -33,13 +62,14 @@ Keep responses under 200 words."#,
             tagline = p.tagline,
             focus = p.focus,
             ctx = ctx,
+            conversation = conversation,
             message = message,
         )
     } else {
         format!(
             r#"You are {name}, an agent in a team dashboard CLI called "work".
 Your personality: {tagline} — {focus}
-
+{conversation}
 The user has sent you this message:
 {message}
 
@@ -48,12 +78,25 @@ Keep responses under 200 words."#,
             name = agent_name.display_name(),
             tagline = p.tagline,
             focus = p.focus,
+            conversation = conversation,
             message = message,
         )
-    };
+    }
+}
 
+fn conversation_section(agent_name: AgentName, history: &[Turn]) -> String {
+    if history.is_empty() {
+        return String::new();
+    }
+    format!(
+        "\nConversation so far:\n{}\n",
+        render_thread(agent_name, history)
+    )
+}
+
+async fn claude_chat(prompt: &str, work_dir: &str) -> Result<String> {
     let output = tokio::process::Command::new("claude")
-        .args(["-p", &prompt, "--output-format", "text"])
+        .args(["-p", prompt, "--output-format", "text"])
         .current_dir(work_dir)
         .stdin(Stdio::null())
         .stdout(Stdio::piped())
@@ -71,6 +114,78 @@ Keep responses under 200 words."#,
     }
 }
 
+async fn ollama_chat(cfg: &ChatConfig, prompt: &str) -> Result<String> {
+    let endpoint = cfg.endpoint.as_deref().unwrap_or(OLLAMA_DEFAULT_ENDPOINT);
+    let body = serde_json::json!({
+        "model": cfg.model.as_deref().unwrap_or(OLLAMA_DEFAULT_MODEL),
+        "messages": [{ "role": "user", "content": prompt }],
+        "stream": false,
+    });
+
+    let resp = reqwest::Client::new()
+        .post(format!("{}/api/chat", endpoint.trim_end_matches('/')))
+        .json(&body)
+        .send()
+        .await
+        .context("Ollama request failed")?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        anyhow::bail!("Ollama returned {status}: {text}");
+    }
+
+    let json: serde_json::Value = resp.json().await.context("Failed to parse Ollama response")?;
+    parse_ollama_response(&json)
+}
+
+async fn openai_chat(cfg: &ChatConfig, prompt: &str) -> Result<String> {
+    let endpoint = cfg.endpoint.as_deref().unwrap_or(OPENAI_DEFAULT_ENDPOINT);
+    let model = cfg
+        .model
+        .as_deref()
+        .context("chat.model is required for the openai backend")?;
+    let body = serde_json::json!({
+        "model": model,
+        "messages": [{ "role": "user", "content": prompt }],
+    });
+
+    let mut req = reqwest::Client::new()
+        .post(format!("{}/v1/chat/completions", endpoint.trim_end_matches('/')))
+        .json(&body);
+    if let Some(key) = &cfg.api_key {
+        req = req.bearer_auth(key);
+    }
+
+    let resp = req.send().await.context("Chat completion request failed")?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        anyhow::bail!("Chat endpoint returned {status}: {text}");
+    }
+
+    let json: serde_json::Value = resp
+        .json()
+        .await
+        .context("Failed to parse chat completion response")?;
+    parse_openai_response(&json)
+}
+
+fn parse_ollama_response(json: &serde_json::Value) -> Result<String> {
+    json.pointer("/message/content")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .context("No message content in Ollama response")
+}
+
+fn parse_openai_response(json: &serde_json::Value) -> Result<String> {
+    json.pointer("/choices/0/message/content")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .context("No message content in chat completion response")
+}
+
 /// Build a prompt for an agent to apply feedback and make changes.
 /// This spawns claude with --dangerously-skip-permissions so it can edit files.
 pub async fn apply_feedback(
@@ -78,13 +193,14 @@ pub async fn apply_feedback(
     feedback: &str,
     work_dir: &str,
     task_context: &str,
+    history: &[Turn],
 ) -> Result<String> {
     let p = personality(agent_name);
 
     let prompt = format!(
         r#"You are {name}, an agent working on: {ctx}
 Your personality: {tagline} — {focus}
-
+{conversation}
 The user has given you this feedback:
 {feedback}
 
@@ -94,6 +210,7 @@ After making changes, briefly summarize what you did."#,
         tagline = p.tagline,
         focus = p.focus,
         ctx = task_context,
+        conversation = conversation_section(agent_name, history),
         feedback = feedback,
     );
 
@@ -115,3 +232,68 @@ After making changes, briefly summarize what you did."#,
         anyhow::bail!("Feedback application failed: {stderr}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::thread::Role;
+
+    #[test]
+    fn message_prompt_includes_task_context() {
+        let prompt =
+            build_message_prompt(AgentName::Flow, "how's it going?", Some("Fix login"), &[]);
+        assert!(prompt.contains("You are Flow"));
+        assert!(prompt.contains("You are currently working on: Fix login"));
+        assert!(prompt.contains("how's it going?"));
+    }
+
+    #[test]
+    fn message_prompt_without_task_context() {
+        let prompt = build_message_prompt(AgentName::Terra, "hello", None, &[]);
+        assert!(!prompt.contains("currently working on"));
+        assert!(!prompt.contains("Conversation so far"));
+        assert!(prompt.contains("hello"));
+    }
+
+    #[test]
+    fn message_prompt_includes_thread() {
+        let history = vec![
+            Turn::new(Role::User, "what did you change?", Some("ENG-1")),
+            Turn::new(Role::Agent, "I switched to a BTreeMap", Some("ENG-1")),
+        ];
+        let prompt = build_message_prompt(
+            AgentName::Ember,
+            "why did you choose that approach?",
+            Some("Fix ordering"),
+            &history,
+        );
+        assert!(prompt.contains("Conversation so far:"));
+        assert!(prompt.contains("Ember: I switched to a BTreeMap"));
+        assert!(prompt.contains("why did you choose that approach?"));
+    }
+
+    #[test]
+    fn parses_ollama_response() {
+        let json = serde_json::json!({
+            "model": "llama3.2",
+            "message": { "role": "assistant", "content": "  All good.\n" },
+            "done": true
+        });
+        assert_eq!(parse_ollama_response(&json).unwrap(), "All good.");
+    }
+
+    #[test]
+    fn parses_openai_response() {
+        let json = serde_json::json!({
+            "choices": [{ "index": 0, "message": { "role": "assistant", "content": "Done." } }]
+        });
+        assert_eq!(parse_openai_response(&json).unwrap(), "Done.");
+    }
+
+    #[test]
+    fn missing_content_is_an_error() {
+        let json = serde_json::json!({ "error": "model not found" });
+        assert!(parse_ollama_response(&json).is_err());
+        assert!(parse_openai_response(&json).is_err());
+    }
+}
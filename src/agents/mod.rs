@@ -1,8 +1,31 @@
 pub mod branch;
+pub mod capacity;
+pub mod ci;
 pub mod claude_md;
 pub mod claude_prompt;
+pub mod cost;
+pub mod diff;
 pub mod dispatch;
+pub mod env;
+pub mod git_summary;
+pub mod hygiene;
 pub mod log;
+pub mod matching;
 pub mod message;
+pub mod plan;
+pub mod pr;
+pub mod progress;
+pub mod queue;
+pub mod questions;
+pub mod report;
+pub mod repos;
 pub mod retry;
+pub mod schedule;
+pub mod review;
+pub mod split;
 pub mod store;
+pub mod thread;
+pub mod transcript;
+pub mod triage;
+pub mod verify;
+pub mod worktree;
@@ -0,0 +1,88 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::config::data_dir;
+use crate::model::agent::AgentName;
+
+/// Plans from plan-only runs wait here for approval. Kept outside the
+/// worktree so the plan never ends up in the agent's commits.
+fn plan_path(agent: AgentName) -> PathBuf {
+    data_dir()
+        .join("plans")
+        .join(format!("{}.md", agent.as_str()))
+}
+
+pub fn save_plan(agent: AgentName, plan: &str) -> Result<()> {
+    let path = plan_path(agent);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, plan)?;
+    Ok(())
+}
+
+pub fn load_plan(agent: AgentName) -> Option<String> {
+    std::fs::read_to_string(plan_path(agent)).ok()
+}
+
+pub fn clear_plan(agent: AgentName) {
+    let _ = std::fs::remove_file(plan_path(agent));
+}
+
+/// Record the user's edit to a plan under review; the implementation run
+/// sees the plan and the notes together.
+pub fn add_note(agent: AgentName, note: &str) -> Result<()> {
+    let plan = load_plan(agent).unwrap_or_default();
+    save_plan(agent, &with_note(&plan, note))
+}
+
+const NOTES_HEADING: &str = "## Reviewer Notes";
+
+fn with_note(plan: &str, note: &str) -> String {
+    let mut plan = plan.trim_end().to_string();
+    if !plan.contains(NOTES_HEADING) {
+        plan.push_str(&format!("\n\n{NOTES_HEADING}"));
+    }
+    plan.push_str(&format!("\n- {}\n", note.trim()));
+    plan
+}
+
+/// The final answer of a run, from the `result` line of its `stream-json` log.
+pub fn result_from_log(log: &str) -> Option<String> {
+    log.lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find(|json| json.get("type").and_then(|t| t.as_str()) == Some("result"))
+        .and_then(|json| json.get("result")?.as_str().map(String::from))
+        .filter(|text| !text.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn result_is_read_from_last_result_line() {
+        let log = [
+            r#"{"type":"system","model":"claude"}"#,
+            r#"{"type":"assistant","message":{"content":[]}}"#,
+            r#"{"type":"result","subtype":"success","result":"1. Add the flag\n2. Test it"}"#,
+        ]
+        .join("\n");
+        assert_eq!(
+            result_from_log(&log).as_deref(),
+            Some("1. Add the flag\n2. Test it")
+        );
+        assert_eq!(result_from_log("not json"), None);
+    }
+
+    #[test]
+    fn notes_collect_under_one_heading() {
+        let plan = with_note("1. Add the flag\n", "Keep the old flag");
+        let plan = with_note(&plan, "No migrations");
+        assert_eq!(
+            plan,
+            "1. Add the flag\n\n## Reviewer Notes\n- Keep the old flag\n- No migrations\n"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::model::agent::AgentName;
+
+/// A pull request opened for an agent run, tracked until it merges so the
+/// item can then be moved to done.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingPr {
+    pub agent: AgentName,
+    pub url: String,
+    pub work_item_id: String,
+    pub work_item_title: String,
+    /// Provider name and source ID, for `move_to_done` once merged
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrState {
+    Open,
+    Merged,
+    Closed,
+}
+
+/// Remote branch for an item's PR. The agent's local branch is reused across
+/// dispatches, so each PR gets its own remote name.
+pub fn pr_branch_name(agent: AgentName, item_id: &str) -> String {
+    let slug: String = item_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("agent/{}/{}", agent.as_str(), slug.trim_matches('-'))
+}
+
+/// Push the worktree's HEAD to `pr_branch` and open a pull request with `gh`.
+/// Returns the PR URL.
+pub async fn push_and_open_pr(
+    wt_path: &str,
+    pr_branch: &str,
+    base: &str,
+    title: &str,
+    body: &str,
+) -> Result<String> {
+    run(
+        "git",
+        wt_path,
+        &[
+            "push",
+            "--force",
+            "origin",
+            &format!("HEAD:refs/heads/{pr_branch}"),
+        ],
+    )
+    .await?;
+
+    let stdout = run(
+        "gh",
+        wt_path,
+        &[
+            "pr", "create", "--head", pr_branch, "--base", base, "--title", title, "--body", body,
+        ],
+    )
+    .await?;
+
+    // gh prints the PR URL as the last line
+    stdout
+        .lines()
+        .rev()
+        .find(|l| l.starts_with("http"))
+        .map(|l| l.trim().to_string())
+        .context("gh pr create did not print a PR URL")
+}
+
+pub async fn pr_state(url: &str) -> Result<PrState> {
+    let output = tokio::process::Command::new("gh")
+        .args(["pr", "view", url, "--json", "state"])
+        .output()
+        .await
+        .context("Failed to run gh pr view")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh pr view failed: {stderr}");
+    }
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).context("Failed to parse gh pr view output")?;
+    parse_pr_state(&json).context("Unknown PR state")
+}
+
+pub fn parse_pr_state(json: &serde_json::Value) -> Option<PrState> {
+    match json.get("state")?.as_str()? {
+        "OPEN" => Some(PrState::Open),
+        "MERGED" => Some(PrState::Merged),
+        "CLOSED" => Some(PrState::Closed),
+        _ => None,
+    }
+}
+
+async fn run(program: &str, cwd: &str, args: &[&str]) -> Result<String> {
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {program}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{program} {} failed: {stderr}", args.join(" "));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pr_branch_is_per_item() {
+        assert_eq!(
+            pr_branch_name(AgentName::Ember, "ENG-42"),
+            "agent/ember/eng-42"
+        );
+        assert_eq!(pr_branch_name(AgentName::Flow, "#17"), "agent/flow/17");
+    }
+
+    #[test]
+    fn parses_pr_states() {
+        let state = |s: &str| parse_pr_state(&serde_json::json!({ "state": s }));
+        assert_eq!(state("OPEN"), Some(PrState::Open));
+        assert_eq!(state("MERGED"), Some(PrState::Merged));
+        assert_eq!(state("CLOSED"), Some(PrState::Closed));
+        assert_eq!(state("DRAFT"), None);
+    }
+}
@@ -0,0 +1,248 @@
+use serde_json::Value;
+
+use super::cost::Usage;
+
+/// A meaningful step parsed from claude's `stream-json` output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Progress {
+    FileEdited(String),
+    TestsRun(String),
+    Commit(String),
+    Pushed,
+}
+
+impl Progress {
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Progress::FileEdited(_) => "file-edited",
+            Progress::TestsRun(_) => "tests-run",
+            Progress::Commit(_) => "commit",
+            Progress::Pushed => "pushed",
+        }
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            Progress::FileEdited(path) => Some(path),
+            Progress::TestsRun(cmd) | Progress::Commit(cmd) => Some(cmd),
+            Progress::Pushed => None,
+        }
+    }
+}
+
+/// One parsed line of `stream-json` output.
+#[derive(Debug, Default)]
+pub struct StreamLine {
+    /// Human-readable text for the live output pane, if the line is worth showing
+    pub display: Option<String>,
+    pub progress: Vec<Progress>,
+    /// Cost and tokens for the whole run, reported on the final `result` line
+    pub usage: Option<Usage>,
+}
+
+const EDIT_TOOLS: &[&str] = &["Edit", "MultiEdit", "Write", "NotebookEdit"];
+const TEST_COMMANDS: &[&str] = &[
+    "cargo test",
+    "npm test",
+    "npm run test",
+    "yarn test",
+    "pnpm test",
+    "pytest",
+    "go test",
+    "make test",
+];
+
+/// Parse a line of claude's `stream-json` output. Non-JSON lines pass through as-is.
+pub fn parse_stream_line(line: &str) -> StreamLine {
+    let json: Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(_) => {
+            return StreamLine {
+                display: Some(line.to_string()),
+                ..Default::default()
+            }
+        }
+    };
+
+    match json.get("type").and_then(|t| t.as_str()) {
+        Some("assistant") => parse_assistant(&json),
+        Some("system") => StreamLine {
+            display: json
+                .get("model")
+                .and_then(|m| m.as_str())
+                .map(|m| format!("Session started ({m})")),
+            ..Default::default()
+        },
+        Some("result") => {
+            let summary = json
+                .get("result")
+                .and_then(|r| r.as_str())
+                .and_then(|r| r.lines().next())
+                .unwrap_or("");
+            let subtype = json
+                .get("subtype")
+                .and_then(|s| s.as_str())
+                .unwrap_or("done");
+            StreamLine {
+                display: Some(
+                    format!("Finished ({subtype}) {summary}")
+                        .trim_end()
+                        .to_string(),
+                ),
+                usage: Usage::from_result(&json),
+                ..Default::default()
+            }
+        }
+        // Tool results echoed back as user turns are noise in the live view
+        _ => StreamLine::default(),
+    }
+}
+
+fn parse_assistant(json: &Value) -> StreamLine {
+    let mut display = Vec::new();
+    let mut progress = Vec::new();
+
+    let content = json
+        .pointer("/message/content")
+        .and_then(|c| c.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    for block in &content {
+        match block.get("type").and_then(|t| t.as_str()) {
+            Some("text") => {
+                if let Some(text) = block.get("text").and_then(|t| t.as_str()) {
+                    if !text.trim().is_empty() {
+                        display.push(text.trim().to_string());
+                    }
+                }
+            }
+            Some("tool_use") => {
+                let name = block.get("name").and_then(|n| n.as_str()).unwrap_or("tool");
+                let input = block.get("input").cloned().unwrap_or(Value::Null);
+                let file_path = input.get("file_path").and_then(|p| p.as_str());
+                let command = input.get("command").and_then(|c| c.as_str());
+
+                let detail = file_path
+                    .or(command)
+                    .or_else(|| input.get("pattern").and_then(|p| p.as_str()))
+                    .unwrap_or("");
+                display.push(format!("\u{2192} {name} {detail}").trim_end().to_string());
+
+                if EDIT_TOOLS.contains(&name) {
+                    if let Some(path) = file_path {
+                        progress.push(Progress::FileEdited(path.to_string()));
+                    }
+                }
+                if name == "Bash" {
+                    if let Some(cmd) = command {
+                        progress.extend(classify_command(cmd));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    StreamLine {
+        display: if display.is_empty() {
+            None
+        } else {
+            Some(display.join("\n"))
+        },
+        progress,
+        usage: None,
+    }
+}
+
+fn classify_command(cmd: &str) -> Vec<Progress> {
+    let summary: String = cmd.lines().next().unwrap_or("").chars().take(120).collect();
+    let mut progress = Vec::new();
+    if TEST_COMMANDS.iter().any(|t| cmd.contains(t)) {
+        progress.push(Progress::TestsRun(summary.clone()));
+    }
+    if cmd.contains("git commit") {
+        progress.push(Progress::Commit(summary));
+    }
+    if cmd.contains("git push") {
+        progress.push(Progress::Pushed);
+    }
+    progress
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool_use(name: &str, input: Value) -> String {
+        serde_json::json!({
+            "type": "assistant",
+            "message": { "content": [{ "type": "tool_use", "name": name, "input": input }] }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn plain_text_passes_through() {
+        let line = parse_stream_line("not json at all");
+        assert_eq!(line.display.as_deref(), Some("not json at all"));
+        assert!(line.progress.is_empty());
+    }
+
+    #[test]
+    fn edit_tool_records_file() {
+        let line = parse_stream_line(&tool_use(
+            "Edit",
+            serde_json::json!({ "file_path": "src/app.rs", "old_string": "a", "new_string": "b" }),
+        ));
+        assert_eq!(
+            line.progress,
+            vec![Progress::FileEdited("src/app.rs".into())]
+        );
+        assert_eq!(line.display.as_deref(), Some("\u{2192} Edit src/app.rs"));
+    }
+
+    #[test]
+    fn bash_commands_are_classified() {
+        let line = parse_stream_line(&tool_use(
+            "Bash",
+            serde_json::json!({ "command": "cargo test && git commit -m 'Fix' && git push origin HEAD:main" }),
+        ));
+        let types: Vec<&str> = line.progress.iter().map(|p| p.event_type()).collect();
+        assert_eq!(types, vec!["tests-run", "commit", "pushed"]);
+    }
+
+    #[test]
+    fn unrelated_bash_is_not_progress() {
+        let line = parse_stream_line(&tool_use(
+            "Bash",
+            serde_json::json!({ "command": "ls -la" }),
+        ));
+        assert!(line.progress.is_empty());
+        assert!(line.display.is_some());
+    }
+
+    #[test]
+    fn assistant_text_is_displayed() {
+        let json = serde_json::json!({
+            "type": "assistant",
+            "message": { "content": [{ "type": "text", "text": "Reading the code first.\n" }] }
+        });
+        let line = parse_stream_line(&json.to_string());
+        assert_eq!(line.display.as_deref(), Some("Reading the code first."));
+    }
+
+    #[test]
+    fn result_line_summarizes() {
+        let json = serde_json::json!({ "type": "result", "subtype": "success", "result": "All done\nmore" });
+        let line = parse_stream_line(&json.to_string());
+        assert_eq!(line.display.as_deref(), Some("Finished (success) All done"));
+    }
+
+    #[test]
+    fn tool_results_are_hidden() {
+        let json = serde_json::json!({ "type": "user", "message": { "content": [{ "type": "tool_result" }] } });
+        let line = parse_stream_line(&json.to_string());
+        assert!(line.display.is_none());
+    }
+}
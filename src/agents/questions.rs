@@ -0,0 +1,184 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// A question a running agent asked the user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Question {
+    pub id: String,
+    pub question: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Answer {
+    pub id: String,
+    pub answer: String,
+}
+
+/// Directory in the worktree the agent and the app trade questions and
+/// answers through. Inside the worktree so a plan-only run, which can't write
+/// anywhere else, can still ask; dispatch keeps it out of git.
+pub const CHANNEL_DIR: &str = ".work";
+
+/// How long `work ask` waits for an answer before handing control back, so
+/// the agent's shell call doesn't time out.
+pub const ASK_WAIT: Duration = Duration::from_secs(100);
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+fn questions_path(wt_path: &Path) -> PathBuf {
+    wt_path.join(CHANNEL_DIR).join("questions.jsonl")
+}
+
+fn answers_path(wt_path: &Path) -> PathBuf {
+    wt_path.join(CHANNEL_DIR).join("answers.jsonl")
+}
+
+/// Start a run with no questions or answers left over from the last one.
+pub fn reset(wt_path: &Path) -> Result<()> {
+    std::fs::create_dir_all(wt_path.join(CHANNEL_DIR))?;
+    std::fs::write(questions_path(wt_path), "")?;
+    std::fs::write(answers_path(wt_path), "")?;
+    Ok(())
+}
+
+/// Questions the agent asked that have no answer yet, oldest first.
+pub fn pending_questions(wt_path: &Path) -> Vec<Question> {
+    let answered: Vec<Answer> = read_lines(&answers_path(wt_path));
+    read_lines::<Question>(&questions_path(wt_path))
+        .into_iter()
+        .filter(|q| !answered.iter().any(|a| a.id == q.id))
+        .collect()
+}
+
+pub fn answer(wt_path: &Path, id: &str, text: &str) -> Result<()> {
+    append_line(
+        &answers_path(wt_path),
+        &Answer {
+            id: id.to_string(),
+            answer: text.to_string(),
+        },
+    )
+}
+
+/// Record a question for the user and return its id.
+pub fn ask(wt_path: &Path, question: &str) -> Result<String> {
+    let asked: Vec<Question> = read_lines(&questions_path(wt_path));
+    let id = format!("q{}", asked.len() + 1);
+    append_line(
+        &questions_path(wt_path),
+        &Question {
+            id: id.clone(),
+            question: question.to_string(),
+        },
+    )?;
+    Ok(id)
+}
+
+pub fn read_answer(wt_path: &Path, id: &str) -> Option<String> {
+    read_lines::<Answer>(&answers_path(wt_path))
+        .into_iter()
+        .find(|a| a.id == id)
+        .map(|a| a.answer)
+}
+
+/// Poll for the answer to `id`, giving up after `wait`.
+pub async fn wait_for_answer(wt_path: &Path, id: &str, wait: Duration) -> Option<String> {
+    let deadline = tokio::time::Instant::now() + wait;
+    loop {
+        if let Some(answer) = read_answer(wt_path, id) {
+            return Some(answer);
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return None;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+fn read_lines<T: for<'de> Deserialize<'de>>(path: &Path) -> Vec<T> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn append_line(path: &Path, value: &impl Serialize) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", serde_json::to_string(value)?)?;
+    Ok(())
+}
+
+/// Prompt section telling the agent how to ask and wait for an answer.
+pub fn prompt_section() -> String {
+    let work = std::env::current_exe()
+        .map(|exe| exe.display().to_string())
+        .unwrap_or_else(|_| "work".into());
+    format!(
+        r#"
+## Asking the User
+If you are blocked on a decision you should not make alone (ambiguous requirements, destructive or irreversible operations, anything needing approval), ask instead of guessing:
+1. Run `{work} ask "Drop the legacy table or keep it?"` from the worktree. It waits for the user's reply and prints it.
+2. If it prints that there is no answer yet, run the `{work} ask --wait <id>` command it suggests until the answer arrives.
+3. Continue with the answer.
+Only ask when truly blocked; otherwise make a reasonable decision.
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answered_questions_are_not_pending() {
+        let wt = tempfile::tempdir().unwrap();
+        reset(wt.path()).unwrap();
+        std::fs::write(
+            questions_path(wt.path()),
+            "{\"id\":\"q1\",\"question\":\"Keep the old API?\"}\nnot json\n{\"id\":\"q2\",\"question\":\"Which port?\"}\n",
+        )
+        .unwrap();
+
+        assert_eq!(pending_questions(wt.path()).len(), 2);
+
+        answer(wt.path(), "q1", "Yes").unwrap();
+        let pending = pending_questions(wt.path());
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, "q2");
+    }
+
+    #[tokio::test]
+    async fn asking_waits_for_the_answer() {
+        let wt = tempfile::tempdir().unwrap();
+        assert_eq!(ask(wt.path(), "Keep the old API?").unwrap(), "q1");
+        assert_eq!(ask(wt.path(), "Which port?").unwrap(), "q2");
+        assert!(wt.path().join(CHANNEL_DIR).join("questions.jsonl").exists());
+        assert_eq!(wait_for_answer(wt.path(), "q2", Duration::ZERO).await, None);
+
+        answer(wt.path(), "q2", "Use port 8080").unwrap();
+        assert_eq!(
+            wait_for_answer(wt.path(), "q2", Duration::ZERO)
+                .await
+                .as_deref(),
+            Some("Use port 8080")
+        );
+        assert_eq!(pending_questions(wt.path())[0].id, "q1");
+    }
+
+    #[test]
+    fn prompt_explains_work_ask() {
+        let section = prompt_section();
+        assert!(section.contains(" ask \"Drop the legacy table or keep it?\""));
+        assert!(!section.contains("sleep"));
+    }
+}
@@ -0,0 +1,178 @@
+use crate::config::AutoRules;
+use crate::model::work_item::WorkItem;
+use crate::providers::is_review_request;
+
+/// Place of a priority name on the Low (1) to Urgent (4) scale, in any
+/// case. Jira's names map onto it, e.g. Highest and Critical rank as Urgent.
+/// None for a name off the scale.
+pub fn priority_level(name: &str) -> Option<u8> {
+    match name.to_ascii_lowercase().as_str() {
+        "urgent" | "highest" | "critical" | "blocker" => Some(4),
+        "high" | "major" => Some(3),
+        "medium" | "normal" => Some(2),
+        "low" | "lowest" | "minor" | "trivial" => Some(1),
+        _ => None,
+    }
+}
+
+/// Rank of an item's priority; higher goes first. No priority and names off
+/// the scale rank lowest.
+pub fn priority_rank(priority: Option<&str>) -> u8 {
+    priority.and_then(priority_level).unwrap_or(0)
+}
+
+/// Dispatch order score: priority first, with `boost_labels` breaking ties
+/// within a priority.
+pub fn dispatch_score(item: &WorkItem, boost_labels: &[String]) -> u8 {
+    let boosted = item
+        .labels
+        .iter()
+        .any(|l| boost_labels.iter().any(|b| b.eq_ignore_ascii_case(l)));
+    priority_rank(item.priority.as_deref()) * 2 + u8::from(boosted)
+}
+
+/// Where an item with `score` joins a queue whose entries score `queued`:
+/// after everything at least as important, so manual reordering and arrival
+/// order are kept among equals.
+pub fn insert_position(queued: &[u8], score: u8) -> usize {
+    queued
+        .iter()
+        .rposition(|&s| s >= score)
+        .map_or(0, |i| i + 1)
+}
+
+/// Whether auto mode may pick up `item` under `rules`. Pull requests
+/// waiting on the user's review never are: they're someone else's branch.
+pub fn is_auto_eligible(item: &WorkItem, rules: &AutoRules) -> bool {
+    if is_review_request(item) {
+        return false;
+    }
+    let has_any = |wanted: &[String]| {
+        item.labels
+            .iter()
+            .any(|l| wanted.iter().any(|w| w.eq_ignore_ascii_case(l)))
+    };
+    if !rules.require_labels.is_empty() && !has_any(&rules.require_labels) {
+        return false;
+    }
+    if has_any(&rules.exclude_labels) {
+        return false;
+    }
+    if let Some(max) = &rules.max_priority {
+        if priority_rank(item.priority.as_deref()) > priority_rank(Some(max)) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Sort items for auto mode, most important first; list order breaks ties.
+pub fn auto_order(items: &mut [&WorkItem], boost_labels: &[String]) {
+    items.sort_by_key(|item| std::cmp::Reverse(dispatch_score(item, boost_labels)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, priority: Option<&str>, labels: &[&str]) -> WorkItem {
+        let mut item = WorkItem::test(id)
+            .with_title(id)
+            .with_source("local")
+            .with_labels(labels);
+        item.priority = priority.map(String::from);
+        item
+    }
+
+    #[test]
+    fn scores_priority_then_labels() {
+        let boost = vec!["customer".to_string()];
+        let high = item("A", Some("High"), &[]);
+        let high_boosted = item("B", Some("High"), &["Customer"]);
+        let urgent = item("C", Some("Urgent"), &[]);
+        assert!(dispatch_score(&high_boosted, &boost) > dispatch_score(&high, &boost));
+        assert!(dispatch_score(&urgent, &boost) > dispatch_score(&high_boosted, &boost));
+        assert_eq!(dispatch_score(&item("D", None, &[]), &boost), 0);
+        assert_eq!(
+            dispatch_score(&item("E", Some("high"), &[]), &boost),
+            dispatch_score(&high, &boost)
+        );
+    }
+
+    #[test]
+    fn ranks_jira_priorities_on_the_same_scale() {
+        assert_eq!(
+            priority_rank(Some("Highest")),
+            priority_rank(Some("Urgent"))
+        );
+        assert_eq!(
+            priority_rank(Some("critical")),
+            priority_rank(Some("Urgent"))
+        );
+        assert_eq!(priority_rank(Some("Lowest")), priority_rank(Some("Low")));
+        assert!(priority_rank(Some("Highest")) > priority_rank(Some("High")));
+        assert_eq!(priority_level("Hihg"), None);
+        assert_eq!(priority_rank(Some("Hihg")), priority_rank(None));
+
+        let rules = AutoRules {
+            max_priority: Some("High".into()),
+            ..AutoRules::default()
+        };
+        assert!(!is_auto_eligible(&item("A", Some("Highest"), &[]), &rules));
+        let highest = item("B", Some("Highest"), &[]);
+        let medium = item("C", Some("Medium"), &[]);
+        let mut items = vec![&medium, &highest];
+        auto_order(&mut items, &[]);
+        assert_eq!(items[0].id, "B");
+    }
+
+    #[test]
+    fn inserts_after_equal_or_higher() {
+        assert_eq!(insert_position(&[], 3), 0);
+        assert_eq!(insert_position(&[6, 4, 2], 4), 2);
+        assert_eq!(insert_position(&[6, 4, 2], 8), 0);
+        assert_eq!(insert_position(&[6, 4, 2], 0), 3);
+    }
+
+    #[test]
+    fn auto_rules_filter_by_label_and_priority() {
+        let rules = AutoRules {
+            require_labels: vec!["agent-ok".into()],
+            exclude_labels: vec!["needs-design".into()],
+            max_priority: Some("High".into()),
+        };
+        assert!(is_auto_eligible(
+            &item("A", Some("High"), &["Agent-OK"]),
+            &rules
+        ));
+        assert!(!is_auto_eligible(&item("B", Some("Low"), &[]), &rules));
+        assert!(!is_auto_eligible(
+            &item("C", Some("Low"), &["agent-ok", "needs-design"]),
+            &rules
+        ));
+        assert!(!is_auto_eligible(
+            &item("D", Some("Urgent"), &["agent-ok"]),
+            &rules
+        ));
+        assert!(is_auto_eligible(
+            &item("E", None, &[]),
+            &AutoRules::default()
+        ));
+
+        let mut pr = item("#7", None, &[]);
+        pr.source = "GitHub".into();
+        pr.url = Some("https://github.com/acme/app/pull/7".into());
+        assert!(!is_auto_eligible(&pr, &AutoRules::default()));
+    }
+
+    #[test]
+    fn auto_order_is_stable_within_priority() {
+        let a = item("A", Some("Low"), &[]);
+        let b = item("B", Some("Urgent"), &[]);
+        let c = item("C", Some("Low"), &[]);
+        let mut items = vec![&a, &b, &c];
+        auto_order(&mut items, &[]);
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, ["B", "A", "C"]);
+    }
+}
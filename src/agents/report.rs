@@ -0,0 +1,91 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+
+use super::questions::CHANNEL_DIR;
+
+/// Longest status line shown; agents are asked for a short phrase.
+const MAX_LEN: usize = 80;
+
+/// The line a running agent last wrote about what it is doing, e.g.
+/// "writing tests for auth module".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusReport {
+    pub text: String,
+    /// When the file was last written
+    pub at: DateTime<Utc>,
+}
+
+/// Next to the questions in the worktree's channel directory, so plan-only
+/// runs can write it too; dispatch keeps the directory out of git.
+fn report_path(wt_path: &Path) -> PathBuf {
+    wt_path.join(CHANNEL_DIR).join("status.txt")
+}
+
+/// Start a run without the last run's status.
+pub fn reset(wt_path: &Path) -> Result<()> {
+    let path = report_path(wt_path);
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+pub fn read_report(wt_path: &Path) -> Option<StatusReport> {
+    read_at(&report_path(wt_path))
+}
+
+/// The last non-empty line, so agents that append rather than overwrite
+/// still show their latest status.
+fn read_at(path: &Path) -> Option<StatusReport> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    let line = contents
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|l| !l.is_empty())?;
+    let at = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    Some(StatusReport {
+        text: line.chars().take(MAX_LEN).collect(),
+        at: at.into(),
+    })
+}
+
+/// Prompt section asking the agent to keep its status line current. The
+/// path is relative to the worktree, where the agent runs.
+pub fn prompt_section() -> String {
+    format!(
+        r#"
+## Reporting Status
+Whenever you move on to a new step, overwrite `{path}` with one short line saying what you are doing, e.g. `echo "writing tests for auth module" > {path}`. The user sees this line while you work.
+"#,
+        path = report_path(Path::new("")).display(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_latest_line() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(read_report(dir.path()), None);
+        std::fs::create_dir_all(dir.path().join(CHANNEL_DIR)).unwrap();
+        let path = report_path(dir.path());
+
+        std::fs::write(
+            &path,
+            "reading the auth module\nwriting tests for auth module\n\n",
+        )
+        .unwrap();
+        assert_eq!(
+            read_report(dir.path()).unwrap().text,
+            "writing tests for auth module"
+        );
+        assert!(prompt_section().contains("> .work/status.txt`"));
+
+        std::fs::write(&path, "x".repeat(200)).unwrap();
+        assert_eq!(read_at(&path).unwrap().text.len(), MAX_LEN);
+    }
+}
@@ -0,0 +1,132 @@
+use super::branch::default_branch;
+use crate::config::{AgentsConfig, RepoConfig};
+use crate::model::work_item::WorkItem;
+
+/// Repository an item's worktree should be created in: the first configured
+/// repo whose source, label or team rules match, else `default_root`.
+pub fn resolve_repo(cfg: &AgentsConfig, item: &WorkItem, default_root: &str) -> String {
+    cfg.repos
+        .iter()
+        .find(|repo| repo_matches(repo, item))
+        .map_or_else(|| default_root.to_string(), |repo| repo.path.clone())
+}
+
+/// Branch agents in `repo_root` start from and land on: the repo's
+/// `base_branch`, then the global one, then origin's default branch, then
+/// `main`.
+pub async fn base_branch(cfg: &AgentsConfig, repo_root: &str) -> String {
+    if let Some(base) = configured_base(cfg, repo_root) {
+        return base.to_string();
+    }
+    default_branch(repo_root)
+        .await
+        .unwrap_or_else(|| "main".to_string())
+}
+
+fn configured_base<'a>(cfg: &'a AgentsConfig, repo_root: &str) -> Option<&'a str> {
+    cfg.repos
+        .iter()
+        .find(|repo| repo.path == repo_root)
+        .and_then(|repo| repo.base_branch.as_deref())
+        .or(cfg.base_branch.as_deref())
+}
+
+/// Every repository agents may work in, default first.
+pub fn all_repo_roots(cfg: &AgentsConfig, default_root: &str) -> Vec<String> {
+    let mut roots = vec![default_root.to_string()];
+    for repo in &cfg.repos {
+        if !roots.contains(&repo.path) {
+            roots.push(repo.path.clone());
+        }
+    }
+    roots
+}
+
+fn repo_matches(repo: &RepoConfig, item: &WorkItem) -> bool {
+    let eq = |a: &str, b: &str| a.eq_ignore_ascii_case(b);
+    repo.sources.iter().any(|s| eq(s, &item.source))
+        || repo
+            .labels
+            .iter()
+            .any(|l| item.labels.iter().any(|il| eq(l, il)))
+        || item
+            .team
+            .as_deref()
+            .is_some_and(|team| repo.teams.iter().any(|t| eq(t, team)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(source: &str, labels: &[&str], team: Option<&str>) -> WorkItem {
+        let mut item = WorkItem::test("X-1")
+            .with_title("Task")
+            .with_source(source)
+            .with_labels(labels);
+        item.team = team.map(String::from);
+        item
+    }
+
+    fn config() -> AgentsConfig {
+        AgentsConfig {
+            repos: vec![
+                RepoConfig {
+                    path: "/code/api".into(),
+                    sources: vec!["sentry".into()],
+                    labels: vec!["backend".into()],
+                    teams: vec![],
+                    base_branch: Some("develop".into()),
+                },
+                RepoConfig {
+                    path: "/code/web".into(),
+                    sources: vec![],
+                    labels: vec![],
+                    teams: vec!["Frontend".into()],
+                    base_branch: None,
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn routes_by_source_label_and_team() {
+        let cfg = config();
+        assert_eq!(
+            resolve_repo(&cfg, &item("sentry", &[], None), "/code/main"),
+            "/code/api"
+        );
+        assert_eq!(
+            resolve_repo(&cfg, &item("linear", &["Backend"], None), "/code/main"),
+            "/code/api"
+        );
+        assert_eq!(
+            resolve_repo(&cfg, &item("jira", &[], Some("frontend")), "/code/main"),
+            "/code/web"
+        );
+    }
+
+    #[test]
+    fn unmatched_items_use_default_repo() {
+        let cfg = config();
+        assert_eq!(
+            resolve_repo(&cfg, &item("trello", &["docs"], None), "/code/main"),
+            "/code/main"
+        );
+        assert_eq!(
+            all_repo_roots(&cfg, "/code/main"),
+            vec!["/code/main", "/code/api", "/code/web"]
+        );
+    }
+
+    #[test]
+    fn base_branch_prefers_repo_then_global() {
+        let mut cfg = config();
+        assert_eq!(configured_base(&cfg, "/code/api"), Some("develop"));
+        assert_eq!(configured_base(&cfg, "/code/web"), None);
+        cfg.base_branch = Some("trunk".into());
+        assert_eq!(configured_base(&cfg, "/code/web"), Some("trunk"));
+        assert_eq!(configured_base(&cfg, "/code/api"), Some("develop"));
+    }
+}
@@ -1 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+use super::log::AgentEvent;
+use super::progress::parse_stream_line;
+use crate::model::agent::AgentName;
+
 pub const MAX_RETRIES: u32 = 3;
+
+/// Lines of the failed run's output carried into the retry prompt.
+const LOG_TAIL_LINES: usize = 30;
+
+/// An item that failed every retry. Auto mode skips it until it is requeued
+/// or dispatched by hand.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedItem {
+    pub work_item_id: String,
+    pub work_item_title: String,
+    /// Agent that made the last attempt
+    pub agent: AgentName,
+    pub failed_at: String,
+    /// Errors logged across its attempts, oldest first
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+/// Messages of the `error` events, oldest first, without repeats in a row.
+pub fn error_history(events: &[AgentEvent]) -> Vec<String> {
+    let mut errors: Vec<String> = Vec::new();
+    for message in events
+        .iter()
+        .filter(|e| e.event == "error")
+        .filter_map(|e| e.message.as_deref())
+    {
+        if errors.last().map(String::as_str) != Some(message) {
+            errors.push(message.to_string());
+        }
+    }
+    errors
+}
+
+/// Describe why a run failed: the last error logged for it plus the tail of
+/// its output, so the retry prompt can address the cause.
+pub fn failure_context(reason: &str, output_tail: &[String]) -> String {
+    if output_tail.is_empty() {
+        return reason.to_string();
+    }
+    format!("{reason}\n\nLast output:\n{}", output_tail.join("\n"))
+}
+
+/// Message of the most recent `error` event, if any.
+pub fn last_error(events: &[AgentEvent]) -> Option<&str> {
+    events
+        .iter()
+        .rev()
+        .find(|e| e.event == "error")
+        .and_then(|e| e.message.as_deref())
+}
+
+/// Readable tail of an agent's run log. The log holds claude's raw
+/// stream-json, so each line is rendered the same way as the output pane.
+pub fn read_log_tail(agent: AgentName) -> Vec<String> {
+    let path = crate::config::data_dir()
+        .join("logs")
+        .join(format!("agent-{}.log", agent.as_str()));
+    let contents = std::fs::read_to_string(path).unwrap_or_default();
+    render_log_tail(&contents, LOG_TAIL_LINES)
+}
+
+fn render_log_tail(contents: &str, n: usize) -> Vec<String> {
+    let lines: Vec<String> = contents
+        .lines()
+        .filter_map(|line| parse_stream_line(line).display)
+        .flat_map(|text| text.lines().map(String::from).collect::<Vec<_>>())
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    lines[lines.len().saturating_sub(n)..].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::log::new_event;
+
+    #[test]
+    fn context_includes_reason_and_output() {
+        let ctx = failure_context("Exit code: 1", &["error[E0308]: mismatched types".into()]);
+        assert!(ctx.starts_with("Exit code: 1"));
+        assert!(ctx.contains("Last output:\nerror[E0308]"));
+        assert_eq!(failure_context("Timed out", &[]), "Timed out");
+    }
+
+    #[test]
+    fn finds_last_error_message() {
+        let events = vec![
+            new_event(AgentName::Ember, "error", None, None, Some("first")),
+            new_event(AgentName::Ember, "working", None, None, None),
+            new_event(AgentName::Ember, "error", None, None, Some("second")),
+        ];
+        assert_eq!(last_error(&events), Some("second"));
+        assert_eq!(last_error(&events[1..2]), None);
+    }
+
+    #[test]
+    fn error_history_drops_repeats() {
+        let events = vec![
+            new_event(AgentName::Ember, "error", None, None, Some("Timed out")),
+            new_event(AgentName::Ember, "retry", None, None, Some("Retry 1/3")),
+            new_event(AgentName::Ember, "error", None, None, Some("Timed out")),
+            new_event(AgentName::Ember, "error", None, None, Some("Exit code: 1")),
+        ];
+        assert_eq!(error_history(&events), ["Timed out", "Exit code: 1"]);
+    }
+
+    #[test]
+    fn log_tail_renders_plain_lines() {
+        let contents = "one\n\ntwo\nthree\n";
+        assert_eq!(render_log_tail(contents, 2), vec!["two", "three"]);
+    }
+}
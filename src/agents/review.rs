@@ -0,0 +1,28 @@
+use anyhow::Result;
+
+use super::hygiene::git_output;
+
+/// Diff of the agent's branch against its base, shown in the review pane.
+pub async fn branch_diff(wt_path: &str, base: &str) -> Result<String> {
+    git_output(
+        wt_path,
+        &[
+            "diff",
+            "--stat",
+            "--patch",
+            &format!("origin/{base}...HEAD"),
+        ],
+    )
+    .await
+}
+
+/// Land an approved branch: rebase onto the latest base and push it.
+pub async fn approve(wt_path: &str, base: &str) -> Result<()> {
+    git_output(wt_path, &["fetch", "origin", base]).await?;
+    if let Err(e) = git_output(wt_path, &["rebase", &format!("origin/{base}")]).await {
+        let _ = git_output(wt_path, &["rebase", "--abort"]).await;
+        anyhow::bail!("Rebase onto origin/{base} failed, send feedback to resolve: {e}");
+    }
+    git_output(wt_path, &["push", "origin", &format!("HEAD:{base}")]).await?;
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use chrono::{Datelike, Duration, NaiveDateTime, Timelike};
+
+use crate::config::AutoWindow;
+
+/// Whether any window is open at `now`, local time.
+pub fn is_open(windows: &[AutoWindow], now: NaiveDateTime) -> bool {
+    windows.iter().any(|w| window_open(w, now))
+}
+
+fn window_open(window: &AutoWindow, now: NaiveDateTime) -> bool {
+    let time = now.time();
+    let on =
+        |date: chrono::NaiveDate| window.days.is_empty() || window.days.contains(&date.weekday());
+    if window.start <= window.end {
+        on(now.date()) && time >= window.start && time < window.end
+    } else {
+        // Overnight: the evening belongs to the day it starts on
+        let yesterday = now.date() - Duration::days(1);
+        (on(now.date()) && time >= window.start) || (on(yesterday) && time < window.end)
+    }
+}
+
+/// When auto mode next switches on or off, within the coming week.
+pub fn next_change(windows: &[AutoWindow], now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let open = is_open(windows, now);
+    let now = now.with_nanosecond(0)?;
+    let mut boundaries: Vec<NaiveDateTime> = (0..=7)
+        .flat_map(|offset| {
+            let date = now.date() + Duration::days(offset);
+            windows
+                .iter()
+                .flat_map(move |w| [date.and_time(w.start), date.and_time(w.end)])
+        })
+        .filter(|&t| t > now)
+        .collect();
+    boundaries.sort();
+    boundaries
+        .into_iter()
+        .find(|&t| is_open(windows, t) != open)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveTime, Weekday};
+
+    fn window(days: &[Weekday], start: u32, end: u32) -> AutoWindow {
+        AutoWindow {
+            days: days.to_vec(),
+            start: NaiveTime::from_hms_opt(start, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(end, 0, 0).unwrap(),
+        }
+    }
+
+    fn at(date: &str, hour: u32) -> NaiveDateTime {
+        let date: chrono::NaiveDate = date.parse().unwrap();
+        date.and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn opens_in_office_hours_and_overnight() {
+        let weekdays = [
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+        ];
+        let office = [window(&weekdays, 9, 18)];
+        // 2026-10-16 is a Friday
+        assert!(is_open(&office, at("2026-10-16", 9)));
+        assert!(!is_open(&office, at("2026-10-16", 18)));
+        assert!(!is_open(&office, at("2026-10-17", 12)));
+        assert_eq!(
+            next_change(&office, at("2026-10-16", 12)),
+            Some(at("2026-10-16", 18))
+        );
+        assert_eq!(
+            next_change(&office, at("2026-10-16", 20)),
+            Some(at("2026-10-19", 9))
+        );
+
+        let nights = [window(&[Weekday::Fri], 22, 6)];
+        assert!(is_open(&nights, at("2026-10-16", 23)));
+        assert!(is_open(&nights, at("2026-10-17", 5)));
+        assert!(!is_open(&nights, at("2026-10-18", 5)));
+        assert_eq!(
+            next_change(&nights, at("2026-10-16", 23)),
+            Some(at("2026-10-17", 6))
+        );
+    }
+}
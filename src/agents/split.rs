@@ -0,0 +1,97 @@
+use anyhow::{bail, Result};
+use serde::Deserialize;
+
+use crate::model::work_item::WorkItem;
+use crate::util::json::parse_json_array;
+
+/// One piece of a larger item, proposed by `/split`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Subtask {
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+pub fn build_split_prompt(item: &WorkItem) -> String {
+    format!(
+        r#"Break the work item below into subtasks small enough for an autonomous coding agent to finish each one in a single session. Read the code in the current directory if it helps to find the natural seams.
+- Each subtask must be independently shippable and testable
+- Order them so earlier subtasks don't depend on later ones
+- Use between 2 and 8 subtasks
+
+Reply with ONLY a JSON array of objects with keys "title" (short, imperative) and "description" (what to change and how to verify it). No prose.
+
+# {} ({})
+{}"#,
+        item.title,
+        item.id,
+        item.description
+            .as_deref()
+            .unwrap_or("No description provided.")
+    )
+}
+
+/// Parse the model's reply, dropping subtasks without a title.
+pub fn parse_subtasks(reply: &str) -> Result<Vec<Subtask>> {
+    let subtasks: Vec<Subtask> = parse_json_array(reply, "split")?;
+    let subtasks: Vec<Subtask> = subtasks
+        .into_iter()
+        .filter(|s| !s.title.trim().is_empty())
+        .collect();
+    if subtasks.is_empty() {
+        bail!("The split reply had no subtasks");
+    }
+    Ok(subtasks)
+}
+
+/// Description for a created subtask, pointing back at its parent.
+pub fn subtask_description(subtask: &Subtask, parent: &WorkItem) -> String {
+    let mut parent_ref = format!("Part of {}: {}", parent.id, parent.title);
+    if let Some(url) = &parent.url {
+        parent_ref.push_str(&format!(" ({url})"));
+    }
+    match subtask.description.as_deref().map(str::trim) {
+        Some(desc) if !desc.is_empty() => format!("{desc}\n\n{parent_ref}"),
+        _ => parent_ref,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parent() -> WorkItem {
+        WorkItem::test("ENG-7")
+            .with_source_id("uuid-7")
+            .with_title("Rework billing")
+            .with_description("Move billing to the new API")
+            .with_url("https://linear.app/eng-7")
+    }
+
+    #[test]
+    fn drops_untitled_subtasks() {
+        let reply = r#"[
+  {"title": "Add the API client", "description": "Wrap the new endpoints"},
+  {"title": "Switch invoices over"},
+  {"title": " "}
+]"#;
+        let subtasks = parse_subtasks(reply).unwrap();
+        assert_eq!(subtasks.len(), 2);
+        assert_eq!(subtasks[1].title, "Switch invoices over");
+        assert!(parse_subtasks("[]").is_err());
+        assert!(parse_subtasks("Sorry, no").is_err());
+    }
+
+    #[test]
+    fn description_links_to_parent() {
+        let subtask = Subtask {
+            title: "Add the API client".into(),
+            description: Some("Wrap the new endpoints".into()),
+        };
+        assert_eq!(
+            subtask_description(&subtask, &parent()),
+            "Wrap the new endpoints\n\nPart of ENG-7: Rework billing (https://linear.app/eng-7)"
+        );
+        assert!(build_split_prompt(&parent()).contains("# Rework billing (ENG-7)"));
+    }
+}
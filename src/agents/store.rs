@@ -1,18 +1,49 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::sync::mpsc;
 
+use super::ci::{CiStatus, CiWatch};
+use super::cost::{today, CostLedger, Usage};
+use super::log::{read_events, replay_stats, AgentEvent};
+use super::pr::PendingPr;
+use super::retry::FailedItem;
+use crate::app::Action;
 use crate::config::data_dir;
-use crate::model::agent::{Agent, AgentName, AgentStatus};
+use crate::model::agent::{Agent, AgentName, AgentStats, AgentStatus, ItemRecord};
 
 /// Max seconds an agent can stay in Provisioning before being marked Error.
 const PROVISIONING_TIMEOUT_SECS: i64 = 60;
+/// Finished items kept per agent
+const HISTORY_LEN: usize = 20;
+/// How often `watch` checks agents.json for writes
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+/// Schema version written to agents.json. Bump it and append to
+/// `MIGRATIONS` whenever stored data changes shape.
+const STORE_VERSION: u64 = 2;
+
+/// `MIGRATIONS[n]` upgrades a version `n` store to `n + 1`.
+const MIGRATIONS: &[fn(&mut serde_json::Value)] =
+    &[migrate_v0_name_and_status, migrate_v1_backfill_stats];
 
 #[derive(Debug, Serialize, Deserialize)]
 struct StoreData {
+    /// Files from before versioning have none and load as 0
+    #[serde(default)]
+    version: u64,
     agents: HashMap<String, Agent>,
+    #[serde(default)]
+    pending_prs: Vec<PendingPr>,
+    #[serde(default)]
+    ci_watches: Vec<CiWatch>,
+    #[serde(default)]
+    costs: CostLedger,
+    #[serde(default)]
+    failed_items: Vec<FailedItem>,
 }
 
 impl Default for StoreData {
@@ -21,74 +52,266 @@ impl Default for StoreData {
         for name in AgentName::ALL {
             agents.insert(name.as_str().to_string(), Agent::new(name));
         }
-        StoreData { agents }
+        StoreData {
+            version: STORE_VERSION,
+            agents,
+            pending_prs: Vec::new(),
+            ci_watches: Vec::new(),
+            costs: CostLedger::default(),
+            failed_items: Vec::new(),
+        }
+    }
+}
+
+/// Exclusive advisory lock on `agents.json.lock`, held for one
+/// read-modify-write so the TUI, `work serve` and other instances never
+/// overwrite each other's changes. Released on drop.
+struct StoreLock(std::fs::File);
+
+impl StoreLock {
+    fn acquire(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+            return Err(std::io::Error::last_os_error())
+                .with_context(|| format!("Failed to lock {}", path.display()));
+        }
+        Ok(Self(file))
+    }
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        unsafe {
+            libc::flock(self.0.as_raw_fd(), libc::LOCK_UN);
+        }
+    }
+}
+
+/// An idle scaled-out worker that is no longer configured. The first
+/// instance of each persona always stays.
+fn retired(agent: &Agent, workers: &[AgentName]) -> bool {
+    agent.name.instance() > 1
+        && agent.status == AgentStatus::Idle
+        && agent.backlog.is_empty()
+        && !workers.contains(&agent.name)
+}
+
+/// Load the store, upgrading older files. A file that can't be parsed is an
+/// error rather than a reset, so agents and history are never silently lost.
+fn read_data(path: &Path) -> Result<StoreData> {
+    if !path.exists() {
+        return Ok(StoreData::default());
+    }
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_data(&contents).with_context(|| {
+        format!(
+            "{} is unreadable; fix or move it aside to start fresh",
+            path.display()
+        )
+    })
+}
+
+fn parse_data(contents: &str) -> Result<StoreData> {
+    let mut value: serde_json::Value = serde_json::from_str(contents)?;
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version > STORE_VERSION {
+        bail!("written by a newer version of work (store v{version}, this build reads v{STORE_VERSION})");
+    }
+    for migrate in &MIGRATIONS[version as usize..] {
+        migrate(&mut value);
+    }
+    let mut data: StoreData = serde_json::from_value(value)?;
+    data.version = STORE_VERSION;
+    // Agents added since the file was written start out idle
+    for name in AgentName::ALL {
+        data.agents
+            .entry(name.as_str().to_string())
+            .or_insert_with(|| Agent::new(name));
+    }
+    Ok(data)
+}
+
+/// v0 → v1: unversioned stores could hold hand-edited or partial agent
+/// entries; fill in the name from the map key and default the status.
+fn migrate_v0_name_and_status(value: &mut serde_json::Value) {
+    if let Some(agents) = value.get_mut("agents").and_then(|a| a.as_object_mut()) {
+        for (key, agent) in agents.iter_mut() {
+            if let Some(agent) = agent.as_object_mut() {
+                agent
+                    .entry("name")
+                    .or_insert_with(|| serde_json::Value::String(key.clone()));
+                agent
+                    .entry("status")
+                    .or_insert_with(|| serde_json::Value::String("idle".into()));
+            }
+        }
+    }
+}
+
+/// v1 → v2: agents gained lifetime stats. Rebuild them from the kept
+/// history, or from the dispatch log where that reaches further back.
+fn migrate_v1_backfill_stats(value: &mut serde_json::Value) {
+    backfill_stats(value, |name| read_events(Some(name), None));
+}
+
+fn backfill_stats(value: &mut serde_json::Value, events: impl Fn(AgentName) -> Vec<AgentEvent>) {
+    let Some(agents) = value.get_mut("agents").and_then(|a| a.as_object_mut()) else {
+        return;
+    };
+    for (key, agent) in agents.iter_mut() {
+        let Some(agent) = agent.as_object_mut() else {
+            continue;
+        };
+        if agent.contains_key("stats") {
+            continue;
+        }
+        let history: Vec<ItemRecord> = agent
+            .get("history")
+            .and_then(|h| serde_json::from_value(h.clone()).ok())
+            .unwrap_or_default();
+        let mut stats = AgentStats::default();
+        for record in &history {
+            stats.record(record);
+        }
+        if let Ok(name) = key.parse::<AgentName>() {
+            let logged = replay_stats(&events(name));
+            if logged.finished() > stats.finished() {
+                stats = logged;
+            }
+        }
+        if let Ok(stats) = serde_json::to_value(stats) {
+            agent.insert("stats".into(), stats);
+        }
+    }
+}
+
+fn store_path() -> PathBuf {
+    data_dir().join("agents.json")
+}
+
+fn file_modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Send `PollAgents` whenever agents.json changes on disk, so writes by
+/// `work serve`, another TUI or our own background tasks show up without
+/// reloading on every tick.
+pub async fn watch(tx: mpsc::UnboundedSender<Action>) {
+    let path = store_path();
+    let mut last = file_modified(&path);
+    let mut interval = tokio::time::interval(WATCH_INTERVAL);
+    loop {
+        interval.tick().await;
+        let modified = file_modified(&path);
+        if modified != last {
+            last = modified;
+            if tx.send(Action::PollAgents).is_err() {
+                break;
+            }
+        }
     }
 }
 
 pub struct AgentStore {
     path: PathBuf,
     data: StoreData,
+    /// Modification time of the file as of our last read or write
+    seen: Option<SystemTime>,
 }
 
 impl AgentStore {
     pub fn new() -> Result<Self> {
-        let path = data_dir().join("agents.json");
-        let data = if path.exists() {
-            let contents = std::fs::read_to_string(&path)
-                .with_context(|| format!("Failed to read {}", path.display()))?;
-            serde_json::from_str(&contents).unwrap_or_default()
-        } else {
-            StoreData::default()
-        };
-        let mut store = Self { path, data };
-        store.clean_stale_processes();
+        Self::open(store_path())
+    }
+
+    pub(crate) fn open(path: PathBuf) -> Result<Self> {
+        let data = read_data(&path)?;
+        let seen = file_modified(&path);
+        let mut store = Self { path, data, seen };
+        store.check_processes();
         Ok(store)
     }
 
-    fn save(&self) -> Result<()> {
+    fn lock_path(&self) -> PathBuf {
+        self.path.with_extension("json.lock")
+    }
+
+    /// Apply `f` to the latest state on disk and save it, all under the
+    /// store lock, so changes made by other processes since our last
+    /// reload are kept rather than clobbered.
+    fn mutate<T>(&mut self, f: impl FnOnce(&mut StoreData) -> Result<T>) -> Result<T> {
+        let _lock = StoreLock::acquire(&self.lock_path())?;
+        self.data = read_data(&self.path)?;
+        let result = f(&mut self.data)?;
+        self.save()?;
+        Ok(result)
+    }
+
+    /// Write to a temp file and rename over agents.json, so readers and
+    /// crashes never see a half-written file. Callers hold the store lock.
+    fn save(&mut self) -> Result<()> {
         if let Some(parent) = self.path.parent() {
             std::fs::create_dir_all(parent)?;
         }
         let json = serde_json::to_string_pretty(&self.data)?;
-        std::fs::write(&self.path, json)?;
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to replace {}", self.path.display()))?;
+        self.seen = file_modified(&self.path);
         Ok(())
     }
 
-    fn clean_stale_processes(&mut self) {
-        let now = Utc::now();
-        for agent in self.data.agents.values_mut() {
-            // Detect dead processes
-            if let Some(pid) = agent.pid {
-                if !is_process_alive(pid) {
-                    agent.status = AgentStatus::Error;
-                    agent.error = Some("Process exited unexpectedly".into());
-                    agent.pid = None;
-                }
-            }
-            // Detect stuck provisioning (no PID, been provisioning too long)
-            if agent.status == AgentStatus::Provisioning && agent.pid.is_none() {
-                if let Some(ref started) = agent.started_at {
-                    if let Ok(started_at) = chrono::DateTime::parse_from_rfc3339(started) {
-                        let elapsed = now.signed_duration_since(started_at);
-                        if elapsed.num_seconds() > PROVISIONING_TIMEOUT_SECS {
-                            agent.status = AgentStatus::Error;
-                            agent.error = Some(format!(
-                                "Provisioning timed out after {}s",
-                                elapsed.num_seconds()
-                            ));
-                        }
-                    }
-                }
-            }
+    /// Mark agents whose process is gone as errored. Returns whether any
+    /// agent changed.
+    pub fn check_processes(&mut self) -> bool {
+        // Only take the lock and write when something actually went stale
+        if !clean_stale_processes(&mut self.data) {
+            return false;
         }
-        let _ = self.save();
+        let _ = self.mutate(|data| {
+            clean_stale_processes(data);
+            Ok(())
+        });
+        true
     }
 
+    /// Every agent, grouped by persona: ember, ember-2, flow, ...
     pub fn get_all(&self) -> Vec<&Agent> {
-        AgentName::ALL
-            .iter()
-            .filter_map(|name| self.data.agents.get(name.as_str()))
-            .collect()
+        let mut agents: Vec<&Agent> = self.data.agents.values().collect();
+        agents.sort_by_key(|a| a.name);
+        agents
+    }
+
+    /// Match the agents to the configured workers: missing ones are added
+    /// idle, and idle extra instances no longer configured are dropped with
+    /// their history. Busy ones stay until a later start finds them idle.
+    pub fn set_workers(&mut self, workers: &[AgentName]) -> Result<()> {
+        let changed = |data: &StoreData| {
+            workers.iter().any(|n| !data.agents.contains_key(n.as_str()))
+                || data.agents.values().any(|a| retired(a, workers))
+        };
+        if !changed(&self.data) {
+            return Ok(());
+        }
+        self.mutate(|data| {
+            for &name in workers {
+                data.agents
+                    .entry(name.as_str().to_string())
+                    .or_insert_with(|| Agent::new(name));
+            }
+            data.agents.retain(|_, a| !retired(a, workers));
+            Ok(())
+        })
     }
 
     pub fn get_agent(&self, name: AgentName) -> Option<&Agent> {
@@ -96,24 +319,19 @@ impl AgentStore {
     }
 
     pub fn update_agent(&mut self, name: AgentName, f: impl FnOnce(&mut Agent)) -> Result<()> {
-        if let Some(agent) = self.data.agents.get_mut(name.as_str()) {
-            f(agent);
-            self.save()?;
-        }
-        Ok(())
+        self.mutate(|data| {
+            if let Some(agent) = data.agents.get_mut(name.as_str()) {
+                f(agent);
+            }
+            Ok(())
+        })
     }
 
     pub fn next_free_agent(&self) -> Option<AgentName> {
-        AgentName::ALL
-            .iter()
-            .find(|name| {
-                self.data
-                    .agents
-                    .get(name.as_str())
-                    .map(|a| a.status == AgentStatus::Idle)
-                    .unwrap_or(false)
-            })
-            .copied()
+        self.get_all()
+            .into_iter()
+            .find(|a| a.status == AgentStatus::Idle)
+            .map(|a| a.name)
     }
 
     pub fn mark_provisioning(
@@ -123,15 +341,34 @@ impl AgentStore {
         work_item_title: &str,
         branch: &str,
         worktree_path: &str,
+        repo_root: &str,
     ) -> Result<()> {
-        self.update_agent(name, |agent| {
+        self.mutate(|data| {
+            let Some(agent) = data.agents.get_mut(name.as_str()) else {
+                return Ok(());
+            };
+            // Another process may have dispatched this agent since our last reload
+            if matches!(
+                agent.status,
+                AgentStatus::Provisioning | AgentStatus::Working | AgentStatus::Stalled
+            ) {
+                bail!(
+                    "{} is already working on {}",
+                    name.as_str(),
+                    agent.work_item_id.as_deref().unwrap_or("another item")
+                );
+            }
             agent.status = AgentStatus::Provisioning;
             agent.work_item_id = Some(work_item_id.into());
             agent.work_item_title = Some(work_item_title.into());
             agent.branch = Some(branch.into());
             agent.worktree_path = Some(worktree_path.into());
+            agent.repo_root = Some(repo_root.into());
             agent.started_at = Some(chrono::Utc::now().to_rfc3339());
             agent.error = None;
+            agent.last_failure = None;
+            agent.flagged = false;
+            Ok(())
         })
     }
 
@@ -142,6 +379,26 @@ impl AgentStore {
         })
     }
 
+    pub fn mark_stalled(&mut self, name: AgentName) -> Result<()> {
+        self.update_agent(name, |agent| {
+            agent.status = AgentStatus::Stalled;
+        })
+    }
+
+    pub fn mark_plan(&mut self, name: AgentName) -> Result<()> {
+        self.update_agent(name, |agent| {
+            agent.status = AgentStatus::Plan;
+            agent.pid = None;
+        })
+    }
+
+    pub fn mark_review(&mut self, name: AgentName) -> Result<()> {
+        self.update_agent(name, |agent| {
+            agent.status = AgentStatus::Review;
+            agent.pid = None;
+        })
+    }
+
     pub fn mark_done(&mut self, name: AgentName) -> Result<()> {
         self.update_agent(name, |agent| {
             agent.status = AgentStatus::Done;
@@ -157,6 +414,44 @@ impl AgentStore {
         })
     }
 
+    /// Mark the agent errored and remember why, for the retry prompt.
+    pub fn mark_failed(&mut self, name: AgentName, error: &str, failure: &str) -> Result<()> {
+        self.update_agent(name, |agent| {
+            agent.status = AgentStatus::Error;
+            agent.error = Some(error.into());
+            agent.last_failure = Some(failure.into());
+            agent.pid = None;
+        })
+    }
+
+    /// Failure context for the next dispatch, without changing status.
+    pub fn set_last_failure(&mut self, name: AgentName, failure: &str) -> Result<()> {
+        self.update_agent(name, |agent| {
+            agent.last_failure = Some(failure.into());
+        })
+    }
+
+    /// Errored agents auto-retry may dispatch again: not those flagged for
+    /// review.
+    pub fn retry_candidates(&self) -> Vec<AgentName> {
+        self.get_all()
+            .iter()
+            .filter(|a| a.status == AgentStatus::Error && !a.flagged)
+            .map(|a| a.name)
+            .collect()
+    }
+
+    /// Mark the agent errored for a person to review, out of auto-retry's way.
+    pub fn mark_flagged(&mut self, name: AgentName, error: &str) -> Result<()> {
+        self.update_agent(name, |agent| {
+            agent.status = AgentStatus::Error;
+            agent.error = Some(error.into());
+            agent.last_failure = Some(error.into());
+            agent.flagged = true;
+            agent.pid = None;
+        })
+    }
+
     pub fn increment_retry(&mut self, name: AgentName) -> Result<u32> {
         let mut count = 0;
         self.update_agent(name, |agent| {
@@ -166,22 +461,348 @@ impl AgentStore {
         Ok(count)
     }
 
+    /// Reset the agent to Idle, recording the item it held in its history.
+    /// The backlog carries over.
     pub fn release(&mut self, name: AgentName) -> Result<()> {
         self.update_agent(name, |agent| {
-            *agent = Agent::new(name);
+            let mut fresh = Agent::new(name);
+            fresh.backlog = std::mem::take(&mut agent.backlog);
+            fresh.history = std::mem::take(&mut agent.history);
+            fresh.stats = agent.stats;
+            if let (Some(id), Some(title)) = (agent.work_item_id.take(), agent.work_item_title.take())
+            {
+                let outcome = match agent.status {
+                    AgentStatus::Done => "done",
+                    AgentStatus::Error => "failed",
+                    _ => "released",
+                };
+                let now = Utc::now();
+                let record = ItemRecord {
+                    work_item_id: id,
+                    work_item_title: title,
+                    outcome: outcome.into(),
+                    finished_at: now.to_rfc3339(),
+                    duration_secs: agent.runtime(now).map(|d| d.num_seconds()),
+                    retries: agent.retry_count,
+                };
+                fresh.stats.record(&record);
+                fresh.history.push(record);
+                let excess = fresh.history.len().saturating_sub(HISTORY_LEN);
+                fresh.history.drain(..excess);
+            }
+            *agent = fresh;
+        })
+    }
+
+    /// Append an item to the agent's backlog. Returns its position (1-based).
+    pub fn push_backlog(&mut self, name: AgentName, item_id: &str) -> Result<usize> {
+        let mut len = 0;
+        self.update_agent(name, |agent| {
+            if !agent.backlog.iter().any(|id| id == item_id) {
+                agent.backlog.push(item_id.into());
+            }
+            len = agent.backlog.len();
+        })?;
+        Ok(len)
+    }
+
+    pub fn pop_backlog(&mut self, name: AgentName) -> Option<String> {
+        let mut next = None;
+        self.update_agent(name, |agent| {
+            if !agent.backlog.is_empty() {
+                next = Some(agent.backlog.remove(0));
+            }
+        })
+        .ok()?;
+        next
+    }
+
+    pub fn pending_prs(&self) -> &[PendingPr] {
+        &self.data.pending_prs
+    }
+
+    pub fn add_pending_pr(&mut self, pr: PendingPr) -> Result<()> {
+        self.mutate(|data| {
+            data.pending_prs.retain(|p| p.url != pr.url);
+            data.pending_prs.push(pr);
+            Ok(())
+        })
+    }
+
+    pub fn remove_pending_pr(&mut self, url: &str) -> Result<()> {
+        self.mutate(|data| {
+            data.pending_prs.retain(|p| p.url != url);
+            Ok(())
+        })
+    }
+
+    pub fn ci_watches(&self) -> &[CiWatch] {
+        &self.data.ci_watches
+    }
+
+    /// Latest CI watch for the agent's most recent push.
+    pub fn ci_watch(&self, name: AgentName) -> Option<&CiWatch> {
+        self.data.ci_watches.iter().find(|w| w.agent == name)
+    }
+
+    /// Start watching a pushed commit, replacing the agent's previous watch.
+    pub fn watch_ci(&mut self, watch: CiWatch) -> Result<()> {
+        self.mutate(|data| {
+            data.ci_watches.retain(|w| w.agent != watch.agent);
+            data.ci_watches.push(watch);
+            Ok(())
+        })
+    }
+
+    pub fn update_ci(
+        &mut self,
+        name: AgentName,
+        sha: &str,
+        status: CiStatus,
+        failed: Vec<String>,
+    ) -> Result<()> {
+        self.mutate(|data| {
+            if let Some(watch) = data
+                .ci_watches
+                .iter_mut()
+                .find(|w| w.agent == name && w.sha == sha)
+            {
+                watch.status = status;
+                watch.failed = failed;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn mark_ci_followed_up(&mut self, name: AgentName) -> Result<()> {
+        self.mutate(|data| {
+            if let Some(watch) = data.ci_watches.iter_mut().find(|w| w.agent == name) {
+                watch.followed_up = true;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn failed_items(&self) -> &[FailedItem] {
+        &self.data.failed_items
+    }
+
+    pub fn is_failed(&self, item_id: &str) -> bool {
+        self.data
+            .failed_items
+            .iter()
+            .any(|f| f.work_item_id == item_id)
+    }
+
+    pub fn add_failed_item(&mut self, item: FailedItem) -> Result<()> {
+        self.mutate(|data| {
+            data.failed_items
+                .retain(|f| f.work_item_id != item.work_item_id);
+            data.failed_items.push(item);
+            Ok(())
+        })
+    }
+
+    /// Take an item off the failed list. Returns whether it was on it.
+    pub fn requeue_failed(&mut self, item_id: &str) -> Result<bool> {
+        self.mutate(|data| {
+            let before = data.failed_items.len();
+            data.failed_items.retain(|f| f.work_item_id != item_id);
+            Ok(data.failed_items.len() < before)
+        })
+    }
+
+    pub fn costs(&self) -> &CostLedger {
+        &self.data.costs
+    }
+
+    pub fn record_usage(&mut self, name: AgentName, item_id: &str, usage: &Usage) -> Result<()> {
+        self.mutate(|data| {
+            data.costs.record(name, item_id, usage, &today());
+            Ok(())
         })
     }
 
     pub fn reload(&mut self) -> Result<()> {
-        if self.path.exists() {
-            let contents = std::fs::read_to_string(&self.path)?;
-            self.data = serde_json::from_str(&contents).unwrap_or_default();
-        }
-        self.clean_stale_processes();
+        self.data = read_data(&self.path)?;
+        self.seen = file_modified(&self.path);
+        self.check_processes();
         Ok(())
     }
+
+    /// Reload only if another writer touched the file since we last read or
+    /// wrote it. Returns whether anything was reloaded.
+    pub fn reload_if_changed(&mut self) -> Result<bool> {
+        if file_modified(&self.path) == self.seen {
+            return Ok(false);
+        }
+        self.reload()?;
+        Ok(true)
+    }
 }
 
-fn is_process_alive(pid: u32) -> bool {
+/// Mark agents whose process died, or that never got one, as errored.
+/// Returns whether any agent changed.
+fn clean_stale_processes(data: &mut StoreData) -> bool {
+    let now = Utc::now();
+    let mut changed = false;
+    for agent in data.agents.values_mut() {
+        // Detect dead processes
+        if let Some(pid) = agent.pid {
+            if !is_process_alive(pid) {
+                agent.status = AgentStatus::Error;
+                agent.error = Some("Process exited unexpectedly".into());
+                agent.pid = None;
+                changed = true;
+            }
+        }
+        // Detect stuck provisioning (no PID, been provisioning too long)
+        if agent.status == AgentStatus::Provisioning && agent.pid.is_none() {
+            if let Some(ref started) = agent.started_at {
+                if let Ok(started_at) = chrono::DateTime::parse_from_rfc3339(started) {
+                    let elapsed = now.signed_duration_since(started_at);
+                    if elapsed.num_seconds() > PROVISIONING_TIMEOUT_SECS {
+                        agent.status = AgentStatus::Error;
+                        agent.error = Some(format!(
+                            "Provisioning timed out after {}s",
+                            elapsed.num_seconds()
+                        ));
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+    changed
+}
+
+pub fn is_process_alive(pid: u32) -> bool {
     unsafe { libc::kill(pid as i32, 0) == 0 }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::agent::Persona;
+
+    #[test]
+    fn concurrent_stores_keep_each_others_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agents.json");
+        let mut tui = AgentStore::open(path.clone()).unwrap();
+        let mut daemon = AgentStore::open(path.clone()).unwrap();
+
+        tui.mark_provisioning(AgentName::Ember, "ENG-1", "Fix login", "b", "/wt", "/repo")
+            .unwrap();
+        // The daemon hasn't reloaded, but its write starts from the file
+        daemon
+            .set_last_failure(AgentName::Flow, "tests failed")
+            .unwrap();
+        assert_eq!(
+            daemon.get_agent(AgentName::Ember).unwrap().status,
+            AgentStatus::Provisioning
+        );
+
+        // A second dispatch of a busy agent is refused instead of clobbering it
+        let err = daemon
+            .mark_provisioning(AgentName::Ember, "ENG-2", "Other", "b", "/wt", "/repo")
+            .unwrap_err();
+        assert!(err.to_string().contains("ENG-1"));
+
+        assert!(tui.reload_if_changed().unwrap());
+        assert!(!tui.reload_if_changed().unwrap());
+        let flow = tui.get_agent(AgentName::Flow).unwrap();
+        assert_eq!(flow.last_failure.as_deref(), Some("tests failed"));
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn flagged_runs_are_left_for_review() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = AgentStore::open(dir.path().join("agents.json")).unwrap();
+        store.mark_error(AgentName::Ember, "Process failed").unwrap();
+        store
+            .mark_flagged(AgentName::Flow, "Flagged for review: .env added")
+            .unwrap();
+        assert_eq!(store.retry_candidates(), [AgentName::Ember]);
+
+        // Dispatching it again by hand clears the flag
+        store
+            .mark_provisioning(AgentName::Flow, "ENG-1", "Fix login", "b", "/wt", "/repo")
+            .unwrap();
+        assert!(!store.get_agent(AgentName::Flow).unwrap().flagged);
+    }
+
+    #[test]
+    fn scales_workers_up_and_down() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = AgentStore::open(dir.path().join("agents.json")).unwrap();
+        let flow2 = AgentName::new(Persona::Flow, 2).unwrap();
+        let flow3 = AgentName::new(Persona::Flow, 3).unwrap();
+        let mut workers = AgentName::ALL.to_vec();
+        workers.extend([flow2, flow3]);
+        store.set_workers(&workers).unwrap();
+        let names: Vec<&str> = store.get_all().iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["ember", "flow", "flow-2", "flow-3", "tempest", "terra"]);
+
+        // A busy worker outlives its removal from the config
+        store
+            .mark_provisioning(flow3, "ENG-1", "Fix login", "agent/flow-3", "/wt", "/repo")
+            .unwrap();
+        store.set_workers(&AgentName::ALL).unwrap();
+        assert!(store.get_agent(flow2).is_none());
+        assert!(store.get_agent(flow3).is_some());
+        assert!(store.get_agent(AgentName::Flow).is_some());
+    }
+
+    #[test]
+    fn migrates_unversioned_stores() {
+        let data = parse_data(
+            r#"{"agents": {"ember": {"status": "working", "pid": 42, "work_item_id": "ENG-1"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(data.version, STORE_VERSION);
+        let ember = &data.agents["ember"];
+        assert_eq!(ember.name, AgentName::Ember);
+        assert_eq!(ember.work_item_id.as_deref(), Some("ENG-1"));
+        assert_eq!(data.agents.len(), AgentName::ALL.len());
+
+        assert!(parse_data(r#"{"version": 99, "agents": {}}"#).is_err());
+        assert!(parse_data("{\"agents\": ").is_err());
+    }
+
+    #[test]
+    fn backfills_stats_from_history_or_the_log() {
+        let mut value = serde_json::json!({"version": 1, "agents": {
+            "ember": {"name": "ember", "status": "idle", "history": [
+                {"work_item_id": "ENG-1", "work_item_title": "A", "outcome": "done",
+                 "finished_at": "2026-03-01T10:00:00+00:00", "duration_secs": 600, "retries": 1},
+                {"work_item_id": "ENG-2", "work_item_title": "B", "outcome": "failed",
+                 "finished_at": "2026-03-01T11:00:00+00:00"}
+            ]},
+            "tempest": {"name": "tempest", "status": "idle"},
+            "flow": {"name": "flow", "status": "idle", "stats": {
+                "done": 7, "failed": 0, "released": 0, "retries": 0, "done_secs": 0
+            }}
+        }});
+        let log = |name: AgentName| match name {
+            AgentName::Tempest => ["dispatched", "done", "dispatched", "max-retries"]
+                .into_iter()
+                .map(|kind| crate::agents::log::new_event(name, kind, Some("ENG-9"), None, None))
+                .collect(),
+            _ => Vec::new(),
+        };
+        backfill_stats(&mut value, log);
+        let data: StoreData = serde_json::from_value(value).unwrap();
+
+        let ember = data.agents["ember"].stats;
+        assert_eq!(
+            (ember.done, ember.failed, ember.retries, ember.done_secs),
+            (1, 1, 1, 600)
+        );
+        let tempest = data.agents["tempest"].stats;
+        assert_eq!((tempest.done, tempest.failed), (1, 1));
+        // Stats already kept are left alone
+        assert_eq!(data.agents["flow"].stats.done, 7);
+    }
+}
@@ -0,0 +1,149 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::data_dir;
+use crate::model::agent::AgentName;
+
+/// Turns of earlier conversation included with each new message.
+pub const CONTEXT_TURNS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Agent,
+}
+
+/// One message in an agent's conversation with the user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Turn {
+    pub role: Role,
+    pub content: String,
+    /// Item the agent held when the turn happened; threads are kept per item
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub work_item_id: Option<String>,
+    pub timestamp: String,
+}
+
+impl Turn {
+    pub fn new(role: Role, content: &str, work_item_id: Option<&str>) -> Self {
+        Turn {
+            role,
+            content: content.to_string(),
+            work_item_id: work_item_id.map(String::from),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+fn thread_path(agent: AgentName) -> PathBuf {
+    data_dir()
+        .join("threads")
+        .join(format!("{}.jsonl", agent.as_str()))
+}
+
+pub fn append_turn(agent: AgentName, turn: &Turn) -> Result<()> {
+    append_turn_at(&thread_path(agent), turn)
+}
+
+/// The latest turns about `work_item_id` (or with no item, when idle).
+pub fn load_thread(agent: AgentName, work_item_id: Option<&str>) -> Vec<Turn> {
+    load_thread_at(&thread_path(agent), work_item_id)
+}
+
+pub fn clear_thread(agent: AgentName) -> Result<()> {
+    let path = thread_path(agent);
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+fn append_turn_at(path: &Path, turn: &Turn) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", serde_json::to_string(turn)?)?;
+    Ok(())
+}
+
+fn load_thread_at(path: &Path, work_item_id: Option<&str>) -> Vec<Turn> {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let mut turns: Vec<Turn> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .filter(|t: &Turn| t.work_item_id.as_deref() == work_item_id)
+        .collect();
+    if turns.len() > CONTEXT_TURNS {
+        turns = turns.split_off(turns.len() - CONTEXT_TURNS);
+    }
+    turns
+}
+
+/// Render earlier turns for inclusion in a prompt.
+pub fn render_thread(agent: AgentName, turns: &[Turn]) -> String {
+    turns
+        .iter()
+        .map(|t| {
+            let speaker = match t.role {
+                Role::User => "User",
+                Role::Agent => agent.display_name(),
+            };
+            format!("{speaker}: {}", t.content)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn threads_are_kept_per_item() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ember.jsonl");
+
+        append_turn_at(&path, &Turn::new(Role::User, "hi", None)).unwrap();
+        append_turn_at(&path, &Turn::new(Role::User, "status?", Some("ENG-1"))).unwrap();
+        append_turn_at(&path, &Turn::new(Role::Agent, "tests pass", Some("ENG-1"))).unwrap();
+
+        let turns = load_thread_at(&path, Some("ENG-1"));
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[1].role, Role::Agent);
+        assert_eq!(load_thread_at(&path, None).len(), 1);
+        assert!(load_thread_at(&path, Some("ENG-2")).is_empty());
+    }
+
+    #[test]
+    fn thread_keeps_latest_turns() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("flow.jsonl");
+        for i in 0..CONTEXT_TURNS + 5 {
+            append_turn_at(&path, &Turn::new(Role::User, &i.to_string(), None)).unwrap();
+        }
+        let turns = load_thread_at(&path, None);
+        assert_eq!(turns.len(), CONTEXT_TURNS);
+        assert_eq!(turns[0].content, "5");
+    }
+
+    #[test]
+    fn renders_speakers() {
+        let turns = vec![
+            Turn::new(Role::User, "why this approach?", None),
+            Turn::new(Role::Agent, "fewer allocations", None),
+        ];
+        assert_eq!(
+            render_thread(AgentName::Terra, &turns),
+            "User: why this approach?\n\nTerra: fewer allocations"
+        );
+    }
+}
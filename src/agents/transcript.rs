@@ -0,0 +1,136 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use super::log::AgentEvent;
+use crate::config::data_dir;
+use crate::model::agent::AgentName;
+use crate::model::chat::{ChatMessage, ChatSender};
+
+/// Render a markdown transcript of one agent run: activity log plus the chat
+/// exchanged with the agent while it worked.
+pub fn build_transcript(
+    item_id: &str,
+    item_title: &str,
+    agent: AgentName,
+    events: &[AgentEvent],
+    chat: &[&ChatMessage],
+) -> String {
+    let mut out = format!(
+        "# {item_id}: {item_title}\n\n- Agent: {}\n",
+        agent.display_name()
+    );
+    if let Some(first) = events.first() {
+        out.push_str(&format!("- Started: {}\n", first.timestamp));
+    }
+    if let Some(last) = events.last() {
+        out.push_str(&format!("- Finished: {}\n", last.timestamp));
+    }
+
+    out.push_str("\n## Activity\n\n");
+    for event in events {
+        let time = event.timestamp.get(11..19).unwrap_or(&event.timestamp);
+        out.push_str(&format!("- `{time}` **{}**", event.event));
+        if let Some(msg) = &event.message {
+            out.push_str(&format!(" {msg}"));
+        }
+        out.push('\n');
+    }
+
+    if !chat.is_empty() {
+        out.push_str("\n## Chat\n");
+        for msg in chat {
+            let who = match &msg.sender {
+                ChatSender::User => "you".to_string(),
+                ChatSender::Agent(name) => name.display_name().to_string(),
+                ChatSender::System => "system".to_string(),
+            };
+            out.push_str(&format!(
+                "\n**{who}** ({}):\n\n{}\n",
+                msg.timestamp, msg.text
+            ));
+        }
+    }
+
+    out
+}
+
+fn transcripts_dir() -> PathBuf {
+    data_dir().join("transcripts")
+}
+
+/// Write a transcript under `~/.localpipeline/transcripts/` and return its path.
+pub fn save_transcript(item_id: &str, content: &str) -> Result<PathBuf> {
+    let dir = transcripts_dir();
+    std::fs::create_dir_all(&dir)?;
+    let safe_id: String = item_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+    let path = dir.join(format!("{safe_id}-{stamp}.md"));
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::log::new_event;
+
+    #[test]
+    fn transcript_includes_events_and_chat() {
+        let events = vec![
+            new_event(
+                AgentName::Flow,
+                "dispatched",
+                Some("ENG-1"),
+                Some("Fix login"),
+                None,
+            ),
+            new_event(
+                AgentName::Flow,
+                "commit",
+                Some("ENG-1"),
+                Some("Fix login"),
+                Some("git commit -m 'Fix'"),
+            ),
+        ];
+        let question = ChatMessage::user("@flow why this approach?");
+        let answer =
+            ChatMessage::agent(AgentName::Flow, "It keeps the session logic in one place.");
+
+        let md = build_transcript(
+            "ENG-1",
+            "Fix login",
+            AgentName::Flow,
+            &events,
+            &[&question, &answer],
+        );
+
+        assert!(md.starts_with("# ENG-1: Fix login"));
+        assert!(md.contains("- Agent: Flow"));
+        assert!(md.contains("**dispatched**"));
+        assert!(md.contains("**commit** git commit -m 'Fix'"));
+        assert!(md.contains("**you**"));
+        assert!(md.contains("It keeps the session logic in one place."));
+    }
+
+    #[test]
+    fn transcript_without_chat_omits_section() {
+        let events = vec![new_event(
+            AgentName::Ember,
+            "done",
+            Some("X-1"),
+            Some("T"),
+            None,
+        )];
+        let md = build_transcript("X-1", "T", AgentName::Ember, &events, &[]);
+        assert!(!md.contains("## Chat"));
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config::data_dir;
+use crate::model::work_item::WorkItem;
+use crate::util::json::parse_json_array;
+
+/// LLM grooming advice for one backlog item.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct Suggestion {
+    pub id: String,
+    /// Urgent, High, Medium or Low
+    #[serde(default)]
+    pub priority: Option<String>,
+    /// ID of an item this one duplicates
+    #[serde(default)]
+    pub duplicate_of: Option<String>,
+    #[serde(default)]
+    pub agent_suitable: Option<bool>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl Suggestion {
+    /// Compact annotation for the item list, e.g. "→High ≈ENG-3 manual".
+    pub fn annotation(&self, current_priority: Option<&str>) -> String {
+        let mut parts = Vec::new();
+        if let Some(p) = &self.priority {
+            if current_priority != Some(p.as_str()) {
+                parts.push(format!("\u{2192}{p}"));
+            }
+        }
+        if let Some(dup) = &self.duplicate_of {
+            parts.push(format!("\u{2248}{dup}"));
+        }
+        if self.agent_suitable == Some(false) {
+            parts.push("manual".to_string());
+        }
+        parts.join(" ")
+    }
+}
+
+/// Accepted triage advice, persisted next to agents.json so it survives
+/// restarts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TriageOverrides {
+    /// Priorities by item id, reapplied when items reload
+    #[serde(default)]
+    priorities: BTreeMap<String, String>,
+    /// Items that need a human; auto mode skips them
+    #[serde(default)]
+    manual_only: BTreeSet<String>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+fn overrides_path() -> PathBuf {
+    data_dir().join("triage-overrides.json")
+}
+
+impl TriageOverrides {
+    pub fn load() -> Self {
+        Self::load_at(overrides_path())
+    }
+
+    fn load_at(path: PathBuf) -> Self {
+        let overrides: Self = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            ..overrides
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        }
+        Ok(())
+    }
+
+    /// Keep the priority and manual flag of each accepted suggestion.
+    pub fn accept(&mut self, suggestions: &[Suggestion]) -> Result<()> {
+        for suggestion in suggestions {
+            if let Some(priority) = &suggestion.priority {
+                self.priorities
+                    .insert(suggestion.id.clone(), priority.clone());
+            }
+            if suggestion.agent_suitable == Some(false) {
+                self.manual_only.insert(suggestion.id.clone());
+            }
+        }
+        self.save()
+    }
+
+    pub fn priority(&self, id: &str) -> Option<&str> {
+        self.priorities.get(id).map(String::as_str)
+    }
+
+    pub fn is_manual_only(&self, id: &str) -> bool {
+        self.manual_only.contains(id)
+    }
+}
+
+pub fn build_triage_prompt(items: &[WorkItem]) -> String {
+    let backlog: Vec<String> = items
+        .iter()
+        .map(|item| {
+            let mut line = format!(
+                "- {} [{}] {} (priority: {}, labels: {})",
+                item.id,
+                item.source,
+                item.title,
+                item.priority.as_deref().unwrap_or("none"),
+                if item.labels.is_empty() {
+                    "none".to_string()
+                } else {
+                    item.labels.join(", ")
+                }
+            );
+            if let Some(desc) = &item.description {
+                let short: String = desc.chars().take(300).collect();
+                line.push_str(&format!("\n  {}", short.replace('\n', " ")));
+            }
+            line
+        })
+        .collect();
+
+    format!(
+        r#"You are grooming a software team's backlog. For each item below, suggest:
+- "priority": one of Urgent, High, Medium, Low
+- "duplicate_of": the ID of another item in this list it duplicates, or null
+- "agent_suitable": true if an autonomous coding agent could complete it from the description alone, false if it needs a human (design decisions, access, vague scope)
+- "reason": one short sentence
+
+Reply with ONLY a JSON array of objects with keys "id", "priority", "duplicate_of", "agent_suitable", "reason". No prose.
+
+Backlog:
+{}"#,
+        backlog.join("\n")
+    )
+}
+
+pub fn parse_triage(reply: &str) -> Result<Vec<Suggestion>> {
+    parse_json_array(reply, "triage")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, title: &str) -> WorkItem {
+        WorkItem::test(id)
+            .with_title(title)
+            .with_description("Line one\nline two")
+            .with_priority("Low")
+            .with_labels(&["bug"])
+    }
+
+    #[test]
+    fn prompt_lists_items() {
+        let prompt =
+            build_triage_prompt(&[item("ENG-1", "Login fails"), item("ENG-2", "Can't log in")]);
+        assert!(prompt.contains("- ENG-1 [Linear] Login fails (priority: Low, labels: bug)"));
+        assert!(prompt.contains("Line one line two"));
+        assert!(prompt.contains("ENG-2"));
+    }
+
+    #[test]
+    fn annotation_skips_unchanged_priority() {
+        let s = Suggestion {
+            id: "ENG-2".into(),
+            priority: Some("High".into()),
+            duplicate_of: Some("ENG-1".into()),
+            agent_suitable: Some(false),
+            reason: None,
+        };
+        assert_eq!(
+            s.annotation(Some("Low")),
+            "\u{2192}High \u{2248}ENG-1 manual"
+        );
+        assert_eq!(s.annotation(Some("High")), "\u{2248}ENG-1 manual");
+    }
+
+    #[test]
+    fn accepted_advice_survives_a_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("triage-overrides.json");
+        let mut overrides = TriageOverrides::load_at(path.clone());
+        overrides
+            .accept(&[
+                Suggestion {
+                    id: "ENG-1".into(),
+                    priority: Some("High".into()),
+                    ..Default::default()
+                },
+                Suggestion {
+                    id: "ENG-2".into(),
+                    agent_suitable: Some(false),
+                    ..Default::default()
+                },
+            ])
+            .unwrap();
+
+        let reloaded = TriageOverrides::load_at(path);
+        assert_eq!(reloaded.priority("ENG-1"), Some("High"));
+        assert_eq!(reloaded.priority("ENG-2"), None);
+        assert!(reloaded.is_manual_only("ENG-2"));
+        assert!(!reloaded.is_manual_only("ENG-1"));
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+use std::process::Stdio;
+
+/// Lines of failing output kept for the activity log and retry prompt.
+const FAILURE_TAIL_LINES: usize = 40;
+
+/// Run the configured verification command in the worktree, with the same
+/// env the agent ran with. On failure, returns the tail of its combined output.
+pub async fn run_verify(
+    command: &str,
+    wt_path: &str,
+    env: &BTreeMap<String, String>,
+) -> Result<(), String> {
+    let output = tokio::process::Command::new("sh")
+        .args(["-c", command])
+        .current_dir(wt_path)
+        .envs(env)
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run `{command}`: {e}"))?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let mut combined = String::from_utf8_lossy(&output.stdout).to_string();
+    combined.push_str(&String::from_utf8_lossy(&output.stderr));
+    Err(format!(
+        "`{command}` failed ({})\n{}",
+        output.status,
+        tail(&combined, FAILURE_TAIL_LINES)
+    ))
+}
+
+/// Last `n` lines of `text`.
+pub fn tail(text: &str, n: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    lines[lines.len().saturating_sub(n)..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tail_keeps_last_lines() {
+        assert_eq!(tail("a\nb\nc\nd", 2), "c\nd");
+        assert_eq!(tail("a\nb", 5), "a\nb");
+        assert_eq!(tail("", 3), "");
+    }
+
+    #[tokio::test]
+    async fn passing_command_is_ok() {
+        assert!(run_verify("true", ".", &BTreeMap::new()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn command_sees_the_agent_env() {
+        let env = BTreeMap::from([("DATABASE_URL".to_string(), "postgres://test".to_string())]);
+        assert!(
+            run_verify("test \"$DATABASE_URL\" = postgres://test", ".", &env)
+                .await
+                .is_ok()
+        );
+        assert!(
+            run_verify("test -n \"$DATABASE_URL\"", ".", &BTreeMap::new())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn failing_command_returns_output_tail() {
+        let err = run_verify("echo broken test; exit 3", ".", &BTreeMap::new())
+            .await
+            .unwrap_err();
+        assert!(err.contains("`echo broken test; exit 3` failed"));
+        assert!(err.contains("broken test"));
+    }
+}
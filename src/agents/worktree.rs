@@ -0,0 +1,98 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+use super::branch::worktree_path;
+use crate::model::agent::{Agent, AgentName};
+
+/// Where the agent's worktree is: the recorded one while it works on
+/// something, else the one left from its last run, if that still exists.
+pub fn agent_worktree(agent: &Agent, default_repo_root: &str) -> Option<String> {
+    if let Some(path) = &agent.worktree_path {
+        return Some(path.clone());
+    }
+    let repo_root = agent.repo_root.as_deref().unwrap_or(default_repo_root);
+    let path = worktree_path(repo_root, agent.name);
+    Path::new(&path).exists().then_some(path)
+}
+
+/// Remove an agent's worktree and prune git's record of it.
+pub async fn remove_worktree(repo_root: &str, agent: AgentName) -> Result<()> {
+    let wt_path = worktree_path(repo_root, agent);
+    if Path::new(&wt_path).exists() {
+        let removed = tokio::process::Command::new("git")
+            .args(["worktree", "remove", "--force", &wt_path])
+            .current_dir(repo_root)
+            .output()
+            .await
+            .is_ok_and(|o| o.status.success());
+        if !removed {
+            tokio::fs::remove_dir_all(&wt_path).await?;
+        }
+    }
+    tokio::process::Command::new("git")
+        .args(["worktree", "prune"])
+        .current_dir(repo_root)
+        .output()
+        .await?;
+    Ok(())
+}
+
+/// Disk used by the agent's worktree, or `None` if it doesn't exist.
+pub fn worktree_size(repo_root: &str, agent: AgentName) -> Option<u64> {
+    let path = worktree_path(repo_root, agent);
+    let path = Path::new(&path);
+    path.exists().then(|| dir_size(path))
+}
+
+/// Total size of files under `path`. Symlinks are not followed.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map_or(0, |m| m.len()),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Human-readable size, e.g. `512 B`, `3.4 MB`, `1.2 GB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_nested_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), vec![0u8; 100]).unwrap();
+        std::fs::create_dir_all(dir.path().join("target/debug")).unwrap();
+        std::fs::write(dir.path().join("target/debug/bin"), vec![0u8; 2048]).unwrap();
+        assert_eq!(dir_size(dir.path()), 2148);
+        assert_eq!(dir_size(&dir.path().join("missing")), 0);
+    }
+
+    #[test]
+    fn formats_sizes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(3 * 1024 * 1024 + 400 * 1024), "3.4 MB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5.0 GB");
+    }
+}
@@ -1,70 +1,298 @@
-use std::time::Instant;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 use tokio::sync::mpsc;
 
-use crate::agents::dispatch;
-use crate::agents::log::{append_event, clear_events, new_event, read_events, AgentEvent};
+use crate::agents::capacity;
+use crate::agents::ci::{self, CiStatus, CiWatch};
+use crate::agents::cost::{today, Usage};
+use crate::agents::diff;
+use crate::agents::dispatch::{self, DispatchOptions};
+use crate::agents::env::resolve_env;
+use crate::agents::git_summary::{self, GitSummary};
+use crate::agents::hygiene;
+use crate::agents::log::{
+    append_event, clear_events, new_event, query_events, read_events, AgentEvent, EventKind,
+    EventQuery,
+};
+use crate::agents::matching;
 use crate::agents::message;
-use crate::agents::retry::MAX_RETRIES;
-use crate::agents::store::AgentStore;
-use crate::config::{self, AppConfig, BoardMapping};
+use crate::agents::plan;
+use crate::agents::pr::{self, PendingPr, PrState};
+use crate::agents::queue;
+use crate::agents::questions::{self, Question};
+use crate::agents::report::{self, StatusReport};
+use crate::agents::repos::{base_branch, resolve_repo};
+use crate::agents::retry::{
+    error_history, failure_context, last_error, read_log_tail, FailedItem, MAX_RETRIES,
+};
+use crate::agents::review;
+use crate::agents::schedule;
+use crate::agents::split::{self, Subtask};
+use crate::agents::store::{is_process_alive, AgentStore};
+use crate::agents::thread::{append_turn, clear_thread, load_thread, Role, Turn};
+use crate::agents::triage::{self, Suggestion, TriageOverrides};
+use crate::agents::transcript::{build_transcript, save_transcript};
+use crate::agents::worktree;
+use crate::config::{
+    self, AgentsConfig, AppConfig, BoardMapping, ChatConfig, HooksConfig, NotificationsConfig,
+    ViewConfig, Workflow,
+};
+use crate::command::{self, SlashCommand, SplitAction, TriageAction};
+use crate::daemon::{self, Reply, Request};
 use crate::event::KeyAction;
-use crate::model::agent::{AgentName, AgentStatus};
+use crate::focus::{self, Focus, Pomodoro};
+use crate::links::{ItemLinks, ItemRef};
+use crate::logging;
+use crate::model::agent::{Agent, AgentName, AgentStatus, Persona};
+use crate::model::calendar::Calendar;
 use crate::model::chat::ChatMessage;
-use crate::model::work_item::WorkItem;
-use crate::providers::{self, BoardInfo, Provider};
+use crate::model::failure::ErrorCenter;
+use crate::model::filter::ItemFilter;
+use crate::model::work_item::{self, WorkItem};
+use crate::hooks::{spawn_hook, Hook};
+use crate::input::{InputHistory, InputLine};
+use crate::notify::{notify, NotifyEvent};
+use crate::status;
+use crate::webhook::ItemUpdate;
+use crate::providers::{self, BoardInfo, ErrorKind, Provider, ProviderError};
+use crate::undo::{UndoStack, Undoable};
+use crate::util::{browser, clipboard};
 
 #[derive(Debug, Clone)]
 pub enum Action {
     Key(KeyAction),
+    /// Slow tick: check agent processes, retry and auto-dispatch
     Tick,
+    /// Fast tick: advance spinners and elapsed timers
+    RenderTick,
+    Resize,
+    /// Bracketed paste; may span several lines
+    Paste(String),
     WorkItemsLoaded(Vec<WorkItem>),
-    FetchError(String),
-    #[allow(dead_code)]
+    /// Incremental change pushed by a provider webhook
+    ItemUpdated(ItemUpdate),
+    /// A provider call made in the background failed
+    FetchError {
+        source: String,
+        action: &'static str,
+        error: String,
+        kind: Option<ErrorKind>,
+    },
+    /// One provider's board listing finished, in whatever order they arrive
+    BoardsLoaded(String, Result<Vec<BoardInfo>, String>),
+    /// agents.json changed on disk
     PollAgents,
     AgentProcessExited(AgentName, bool),
+    /// A plan-only run finished and saved its plan
+    AgentPlanReady(AgentName),
+    /// Claude exited cleanly but the verification command failed; carries its output
+    AgentVerifyFailed(AgentName, String),
+    /// Usage reported at the end of a run, for the given work item
+    AgentUsage(AgentName, String, Usage),
+    WorktreePruned(AgentName),
+    DiskUsage(AgentName, Option<u64>),
+    GitSummary(AgentName, Option<GitSummary>),
+    /// An agent's diff, loaded for its detail view
+    BranchDiffLoaded(Box<diff::BranchDiff>),
+    AgentOutput(AgentName, String),
     AgentResponse(AgentName, String),
     AgentResponseError(AgentName, String),
-    TaskCreated(WorkItem),
+    TaskCreated(Box<WorkItem>),
+    TriageReady(Vec<Suggestion>),
+    TriageFailed(String),
+    /// Subtasks proposed for the item with the given ID
+    SplitReady(String, Vec<Subtask>),
+    SplitFailed(String),
     TaskCreateError(String),
+    /// Whether `work daemon` is listening, checked every few seconds
+    DaemonProbed(Option<daemon::Client>),
+    /// Ctrl+Z: hand the terminal to a shell in the project until it exits
+    Suspend,
     Quit,
 }
 
+/// Lines of live agent output kept per agent.
+const OUTPUT_SCROLLBACK: usize = 2000;
+const PR_POLL_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_AGENT_BACKLOG: usize = 3;
+/// How long a dispatch waits on the provider for an item's links and comments.
+const CONTEXT_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A destructive action waiting on a y/n answer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Confirm {
+    ClearAgent(AgentName),
+    ClearLogs(AgentName),
+    AcceptAllTriage(usize),
+    /// Quitting would orphan this many running agents
+    Quit(usize),
+}
+
+impl Confirm {
+    pub fn prompt(&self) -> String {
+        match self {
+            Confirm::ClearAgent(name) => format!(
+                "Stop {} and discard its current work?",
+                name.display_name()
+            ),
+            Confirm::ClearLogs(name) => {
+                format!("Wipe {}'s activity log and chat thread?", name.display_name())
+            }
+            Confirm::AcceptAllTriage(n) => format!("Accept all {n} triage suggestions?"),
+            Confirm::Quit(1) => "An agent is still running. Stop it and quit?".into(),
+            Confirm::Quit(n) => format!("{n} agents are still running. Stop them and quit?"),
+        }
+    }
+}
+
+/// Progress of one provider's board listing in the picker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoardLoad {
+    Loading,
+    Loaded,
+    Failed(String),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ViewMode {
     BoardSelection,
     Items,
     Agents,
     AgentDetail(AgentName),
+    /// Items on a grid by due date
+    Calendar,
 }
 
 pub struct App {
     pub items: Vec<WorkItem>,
     pub selected_item: usize,
+    /// First visible row of the item list. Rendering moves it to keep the
+    /// selection on screen, hence the `Cell`.
+    pub item_scroll: Cell<usize>,
+    /// Set with `/filter` or a view; hides non-matching items from the list
+    pub item_filter: ItemFilter,
+    /// Named filters from `[[views]]`, on keys 5 to 9
+    pub views: Vec<ViewConfig>,
+    /// Name of the view whose filter is applied, until `/filter` replaces it
+    pub active_view: Option<String>,
+    /// Pending `/triage` suggestions by item ID, shown on the item list
+    pub triage: HashMap<String, Suggestion>,
+    /// Subtasks proposed by `/split`, with the ID of the item they divide
+    split: Option<(String, Vec<Subtask>)>,
+    /// Items mirrored across providers with `/mirror`
+    pub item_links: ItemLinks,
+    /// Cursor of the due-date calendar
+    pub calendar: Calendar,
+    /// The item the user is working on, pinned with `p`
+    pub focus: Option<Focus>,
+    /// Running timer on the pinned item, toggled with `t`
+    pub pomodoro: Option<Pomodoro>,
+    pomodoro_length: chrono::Duration,
+    /// Accepted triage priorities and items that need a human
+    triage_overrides: TriageOverrides,
     pub view_mode: ViewMode,
+    /// On narrow terminals the Items view shows one panel; Tab flips it
+    /// between the list and the selected item's detail
+    pub show_item_detail: bool,
     pub selected_agent: usize,
     pub agent_log_scroll: usize,
+    /// Narrows the agent activity log; reset when another agent is opened
+    pub agent_event_kind: EventKind,
+    pub agent_event_search: Option<String>,
+    pub agent_output: HashMap<AgentName, VecDeque<String>>,
+    /// Lines scrolled up from the tail of the output pane; 0 follows live output
+    pub agent_output_scroll: usize,
+    /// Diff of the branch under review, or the plan awaiting approval, shown
+    /// in place of the output pane
+    pub review_diff: Option<(AgentName, Vec<String>)>,
+    pub review_scroll: usize,
+    /// The agent's commits and uncommitted edits against its base, opened
+    /// with `d` from its detail view over everything else
+    pub branch_diff: Option<diff::BranchDiff>,
+    pub branch_diff_scroll: usize,
     pub auto_mode: bool,
     pub loading: bool,
     pub flash_message: Option<(String, Instant)>,
+    /// Animation frame counter, advanced on every render tick
+    pub frame: usize,
+    needs_redraw: bool,
+    /// Sticky banner for urgent incidents; dismissed with Esc
+    pub incident_alert: Option<String>,
+    escalate_incidents: bool,
     pub store: AgentStore,
     pub repo_root: String,
+    chat_config: Option<ChatConfig>,
+    agents_config: AgentsConfig,
+    notifications: NotificationsConfig,
+    hooks: HooksConfig,
+    last_pr_poll: Instant,
     pub should_quit: bool,
     pub action_tx: mpsc::UnboundedSender<Action>,
     pub available_boards: Vec<BoardInfo>,
     pub selected_board: usize,
+    pub board_loads: Vec<(String, BoardLoad)>,
     pub project_dir: String,
     providers: Vec<Box<dyn Provider>>,
     dispatched_item_ids: std::collections::HashSet<String>,
+    /// Whether the auto schedule was open at the last tick, so auto mode only
+    /// flips at a window's edge and a manual toggle holds until the next one
+    schedule_open: Option<bool>,
+    /// Manually dispatched items waiting for a free agent or slot
+    dispatch_queue: VecDeque<String>,
+    /// Agent that got the most recent dispatch, for round-robin fallback
+    last_assigned: Option<AgentName>,
+    /// Agents whose worktree is being removed; not dispatchable until done
+    pruning: std::collections::HashSet<AgentName>,
+    /// Worktree disk usage, refreshed when an agent's detail view opens
+    pub disk_usage: HashMap<AgentName, u64>,
+    /// Branch, commits and push state of the agent's worktree, refreshed when
+    /// its detail view opens and when its run ends
+    pub git_summary: HashMap<AgentName, GitSummary>,
+    /// Unanswered questions from running agents, refreshed on tick
+    pub pending_questions: HashMap<AgentName, Vec<Question>>,
+    /// What running agents last said they are doing, refreshed on tick
+    pub status_reports: HashMap<AgentName, StatusReport>,
 
     // Input & chat state
     pub input_active: bool,
-    pub input_buffer: String,
-    pub input_cursor: usize,
+    pub input: InputLine,
+    input_history: InputHistory,
     pub chat_messages: Vec<ChatMessage>,
-    #[allow(dead_code)]
+    /// Rows scrolled up from the newest chat line; 0 follows the conversation
     pub chat_scroll: usize,
+    pub chat_height: u16,
+    /// Chat expanded over the main panels, toggled with `z`
+    pub chat_focus: bool,
+    /// Recent `tracing` output drawn over the screen, toggled with L
+    pub debug_log: bool,
+    /// Lines scrolled up from the newest
+    pub debug_log_scroll: usize,
+    /// Provider failures that stay until dismissed or resolved
+    pub errors: ErrorCenter,
+    /// Providers left alone after a rate limit or network failure, until
+    /// when; their items are kept and refetched once it passes
+    retry_at: HashMap<String, Instant>,
+    /// Error center drawn over the screen, toggled with !
+    pub error_center: bool,
+    pub selected_error: usize,
+    /// Selected failure shows its whole message
+    pub error_expanded: bool,
+    /// Recent done/dispatch actions `u` can revert
+    pub undo: UndoStack,
+    /// Pending y/n prompt; while set it takes every key
+    pub confirm: Option<Confirm>,
+    confirm_destructive: bool,
     pub waiting_for_response: bool,
+    /// Agent daemon running the pool; while set, dispatches go to it and
+    /// this instance only shows its agents
+    pub daemon: Option<daemon::Client>,
+    /// Directory to open a shell in, a worktree or the project on Ctrl+Z;
+    /// the main loop hands it the terminal
+    pub shell_request: Option<String>,
 }
 
 impl App {
@@ -91,11 +319,10 @@ impl App {
             .to_string_lossy()
             .to_string();
 
-        let mut providers = providers::create_providers(config);
+        let mut providers = providers::create_cached_providers(config, true);
 
         // Check board mappings for current directory
-        let mappings = config::load_board_mappings();
-        let has_mapping = if let Some(mapping) = mappings.get(&project_dir) {
+        let has_mapping = if let Some(mapping) = config::board_mapping(&project_dir) {
             // Apply board filter to the matching provider
             for provider in &mut providers {
                 if provider.name() == mapping.source {
@@ -113,29 +340,91 @@ impl App {
             ViewMode::BoardSelection
         };
 
+        let agents_config = config.agents.clone().unwrap_or_default();
+        let mut store = store;
+        if let Err(e) = store.set_workers(&agents_config.worker_names()) {
+            tracing::warn!(error = %e, "updating agent workers failed");
+        }
+
         Self {
             items: Vec::new(),
             selected_item: 0,
+            item_scroll: Cell::new(0),
+            item_filter: ItemFilter::default(),
+            views: config.views.clone(),
+            active_view: None,
+            triage: HashMap::new(),
+            split: None,
+            item_links: ItemLinks::load(),
+            calendar: Calendar::default(),
+            focus: focus::load_focus(),
+            pomodoro: None,
+            pomodoro_length: config.ui.clone().unwrap_or_default().pomodoro_length(),
+            triage_overrides: TriageOverrides::load(),
             view_mode,
+            show_item_detail: false,
             selected_agent: 0,
             agent_log_scroll: 0,
+            agent_event_kind: EventKind::All,
+            agent_event_search: None,
+            agent_output: HashMap::new(),
+            agent_output_scroll: 0,
             auto_mode: false,
             loading: !has_mapping,
             flash_message: None,
+            frame: 0,
+            review_diff: None,
+            review_scroll: 0,
+            branch_diff: None,
+            branch_diff_scroll: 0,
+            needs_redraw: true,
+            incident_alert: None,
+            escalate_incidents: config
+                .agents
+                .as_ref()
+                .is_some_and(|a| a.escalate_incidents),
             store,
             repo_root,
+            chat_config: config.chat.clone(),
+            agents_config,
+            notifications: config.notifications.clone().unwrap_or_default(),
+            hooks: config.hooks.clone().unwrap_or_default(),
+            last_pr_poll: Instant::now(),
             should_quit: false,
             action_tx,
             available_boards: Vec::new(),
             selected_board: 0,
+            board_loads: Vec::new(),
             project_dir,
             providers,
             dispatched_item_ids: std::collections::HashSet::new(),
+            schedule_open: None,
+            dispatch_queue: VecDeque::new(),
+            last_assigned: None,
+            pruning: std::collections::HashSet::new(),
+            disk_usage: HashMap::new(),
+            git_summary: HashMap::new(),
+            pending_questions: HashMap::new(),
+            status_reports: HashMap::new(),
             input_active: false,
-            input_buffer: String::new(),
-            input_cursor: 0,
+            input: InputLine::default(),
+            input_history: InputHistory::load(),
             chat_messages: Vec::new(),
             chat_scroll: 0,
+            chat_height: config.ui.clone().unwrap_or_default().chat_height(),
+            chat_focus: false,
+            debug_log: false,
+            debug_log_scroll: 0,
+            errors: ErrorCenter::default(),
+            retry_at: HashMap::new(),
+            error_center: false,
+            selected_error: 0,
+            error_expanded: false,
+            undo: UndoStack::default(),
+            confirm: None,
+            confirm_destructive: config.ui.clone().unwrap_or_default().confirm_destructive(),
+            daemon: None,
+            shell_request: None,
             waiting_for_response: false,
         }
     }
@@ -145,12 +434,47 @@ impl App {
         if let Some((_, t)) = &self.flash_message {
             if t.elapsed().as_secs() >= 3 {
                 self.flash_message = None;
+                self.needs_redraw = true;
             }
         }
 
+        // Render ticks only redraw while something is animating, and store
+        // polls only when the reload brought in changes. Frequent background
+        // actions redraw only if they changed what is on screen.
+        let before = match action {
+            Action::RenderTick | Action::PollAgents => None,
+            Action::Tick | Action::AgentOutput(..) | Action::DiskUsage(..) => {
+                Some(self.screen_fingerprint())
+            }
+            _ => {
+                self.needs_redraw = true;
+                None
+            }
+        };
+
         match action {
+            Action::RenderTick => {
+                self.frame = self.frame.wrapping_add(1);
+                if self.is_animating() {
+                    self.needs_redraw = true;
+                }
+            }
+            Action::Resize => {}
+            Action::Suspend => self.shell_request = Some(self.project_dir.clone()),
+            Action::Paste(text) => {
+                // Pasting opens the command bar if it isn't already
+                self.input_active = true;
+                self.input.insert_str(&text);
+            }
             Action::Key(key) => {
-                if self.input_active {
+                if let Some(confirm) = self.confirm.take() {
+                    match key {
+                        KeyAction::Char('y' | 'Y') => self.run_confirmed(confirm).await,
+                        KeyAction::Char('n' | 'N') | KeyAction::Escape | KeyAction::Quit => {}
+                        // Anything else leaves the prompt up
+                        _ => self.confirm = Some(confirm),
+                    }
+                } else if self.input_active {
                     self.handle_input_key(key).await;
                 } else {
                     self.handle_key(key).await;
@@ -158,40 +482,226 @@ impl App {
             }
             Action::Tick => self.handle_tick().await,
             Action::WorkItemsLoaded(items) => {
-                self.items = items;
+                tracing::debug!(items = items.len(), "items loaded");
+                let mut removed = Vec::new();
+                self.keep_selection(|app| {
+                    removed = work_item::reconcile(&mut app.items, items);
+                    for item in &removed {
+                        app.forget_item(&item.id);
+                    }
+                });
+                for item in &mut self.items {
+                    if let Some(priority) = self.triage_overrides.priority(&item.id) {
+                        item.priority = Some(priority.to_string());
+                    }
+                }
                 self.loading = false;
-                if self.selected_item >= self.items.len() && !self.items.is_empty() {
-                    self.selected_item = self.items.len() - 1;
+                let _ = status::save_snapshot(&self.items);
+                // Closed upstream: the mirrors go with them
+                for item in &removed {
+                    self.close_linked(&item.source, &item.id).await;
+                }
+                self.escalate_incidents().await;
+            }
+            Action::ItemUpdated(update) => {
+                let removed = match &update {
+                    ItemUpdate::Remove { source, id, .. } => Some((source.clone(), id.clone())),
+                    ItemUpdate::Upsert(_) => None,
+                };
+                self.keep_selection(|app| update.apply(&mut app.items));
+                let _ = status::save_snapshot(&self.items);
+                if let Some((source, id)) = removed {
+                    self.forget_item(&id);
+                    self.close_linked(&source, &id).await;
+                }
+                self.escalate_incidents().await;
+            }
+            Action::BoardsLoaded(source, result) => {
+                let load = match result {
+                    Ok(boards) => {
+                        self.errors.resolve(&source, "list boards");
+                        // Stay on the same retry row as boards arrive above it
+                        if self.selected_board >= self.available_boards.len()
+                            && !self.failed_board_sources().is_empty()
+                        {
+                            self.selected_board += boards.len();
+                        }
+                        self.available_boards.extend(boards);
+                        BoardLoad::Loaded
+                    }
+                    Err(e) => {
+                        self.errors.record(&source, "list boards", e.clone(), None);
+                        BoardLoad::Failed(e)
+                    }
+                };
+                if let Some(entry) = self.board_loads.iter_mut().find(|(s, _)| *s == source) {
+                    entry.1 = load;
                 }
             }
-            Action::FetchError(msg) => {
+            Action::FetchError {
+                source,
+                action,
+                error,
+                kind,
+            } => {
+                tracing::error!(source = source.as_str(), action, error = %error, "fetch failed");
                 self.loading = false;
-                self.flash_message = Some((format!("Fetch error: {msg}"), Instant::now()));
+                notify(
+                    &self.notifications,
+                    NotifyEvent::FetchError,
+                    format!("{source}: {action} failed"),
+                    error.clone(),
+                );
+                if kind == Some(ErrorKind::Auth) {
+                    // Nothing fixes itself here, so say so even for background fetches
+                    self.flash_message = Some((
+                        format!("{source} rejected its token — press ! for the fix"),
+                        Instant::now(),
+                    ));
+                }
+                self.errors.record(&source, action, error, kind);
             }
             Action::PollAgents => {
-                let _ = self.store.reload();
+                if self.store.reload_if_changed().unwrap_or(false) {
+                    self.needs_redraw = true;
+                }
             }
+            Action::DaemonProbed(client) => match (&self.daemon, client) {
+                (None, Some(client)) => {
+                    self.adopt_daemon(client).await;
+                    self.flash_message = Some((
+                        "Agent daemon started — it runs the pool now".into(),
+                        Instant::now(),
+                    ));
+                }
+                (Some(_), None) => self.lose_daemon(),
+                _ => {}
+            },
             Action::AgentProcessExited(name, success) => {
+                tracing::info!(agent = name.as_str(), success, "agent exited");
                 let _ = self.store.reload();
-                if success {
-                    // Move work item to done in source system
-                    if let Some(agent) = self.store.get_agent(name) {
-                        if let Some(item_id) = agent.work_item_id.clone() {
-                            if let Some(item) = self.items.iter().find(|i| i.id == item_id) {
+                if self.view_mode == ViewMode::AgentDetail(name) {
+                    self.load_git_summary(name);
+                }
+                if success && self.flag_unsafe_changes(name).await {
+                    // Left in Error for review; the item stays where it is
+                } else if success {
+                    self.export_transcript(name).await;
+                    // Worded before landing changes the agent
+                    let body = match self.agents_config.workflow {
+                        Workflow::Review => "Ready for review".to_string(),
+                        Workflow::Pr => "Pull request opened".to_string(),
+                        Workflow::Push => self.store.get_agent(name).map_or_else(
+                            || "Pushed".to_string(),
+                            |a| format!("Pushed to {}", a.base()),
+                        ),
+                    };
+                    match self.agents_config.workflow {
+                        // Nothing lands until the branch is approved
+                        Workflow::Review => self.request_review(name).await,
+                        Workflow::Pr => {
+                            // The item moves to done once the PR merges
+                            self.open_pr(name).await;
+                            self.watch_ci(name).await;
+                            let _ = self.store.mark_done(name);
+                        }
+                        Workflow::Push => {
+                            // Move work item to done in source system
+                            let item_id =
+                                self.store.get_agent(name).and_then(|a| a.work_item_id.clone());
+                            if let Some(item) =
+                                item_id.and_then(|id| self.items.iter().find(|i| i.id == id))
+                            {
                                 self.move_item_to_done(item.clone()).await;
                             }
+                            self.watch_ci(name).await;
+                            let _ = self.store.mark_done(name);
                         }
                     }
-                    let _ = self.store.mark_done(name);
-                } else {
-                    let _ = self.store.mark_error(name, "Process failed");
+                    self.announce(name, NotifyEvent::AgentDone, body);
+                } else if self
+                    .store
+                    .get_agent(name)
+                    .is_some_and(|a| a.status != AgentStatus::Error)
+                {
+                    // Keep a more specific error (e.g. a timeout kill) if one is set
+                    let events = read_events(Some(name), Some(20));
+                    let reason = last_error(&events).unwrap_or("Process failed");
+                    let context = failure_context(reason, &read_log_tail(name));
+                    let _ = self.store.mark_failed(name, "Process failed", &context);
+                    self.announce(name, NotifyEvent::AgentError, reason.to_string());
+                }
+            }
+            Action::AgentPlanReady(name) => {
+                let _ = self.store.reload();
+                let _ = self.store.mark_plan(name);
+                if self.view_mode == ViewMode::AgentDetail(name) {
+                    self.load_plan(name);
+                }
+                self.flash_message = Some((
+                    format!("{}'s plan is ready for approval", name.display_name()),
+                    Instant::now(),
+                ));
+            }
+            Action::AgentVerifyFailed(name, output) => {
+                let _ = self.store.reload();
+                let _ = self.store.mark_failed(name, "Verification failed", &output);
+                self.announce(name, NotifyEvent::AgentError, "Verification failed".into());
+            }
+            Action::AgentUsage(name, item_id, usage) => {
+                let _ = self.store.record_usage(name, &item_id, &usage);
+                self.enforce_budget();
+            }
+            Action::WorktreePruned(name) => {
+                self.pruning.remove(&name);
+                self.disk_usage.remove(&name);
+            }
+            Action::DiskUsage(name, size) => match size {
+                Some(bytes) => {
+                    self.disk_usage.insert(name, bytes);
+                }
+                None => {
+                    self.disk_usage.remove(&name);
+                }
+            },
+            Action::GitSummary(name, summary) => match summary {
+                Some(summary) => {
+                    self.git_summary.insert(name, summary);
+                }
+                None => {
+                    self.git_summary.remove(&name);
+                }
+            },
+            Action::BranchDiffLoaded(loaded) => {
+                // Only if the view is still open on that agent
+                if self
+                    .branch_diff
+                    .as_ref()
+                    .is_some_and(|open| open.agent == loaded.agent)
+                {
+                    self.branch_diff = Some(*loaded);
+                    self.scroll_branch_diff_to(self.branch_diff_scroll);
+                }
+            }
+            Action::AgentOutput(name, line) => {
+                let buf = self.agent_output.entry(name).or_default();
+                buf.push_back(line);
+                if buf.len() > OUTPUT_SCROLLBACK {
+                    buf.pop_front();
                 }
             }
             Action::AgentResponse(name, response) => {
                 self.waiting_for_response = false;
+                let item_id = self.store.get_agent(name).and_then(|a| a.work_item_id.clone());
+                let _ = append_turn(name, &Turn::new(Role::Agent, &response, item_id.as_deref()));
                 self.chat_messages.push(ChatMessage::agent(name, response));
+                // Feedback may have changed the branch under review
+                if self.review_diff.as_ref().is_some_and(|(n, _)| *n == name) {
+                    self.load_review_diff(name).await;
+                }
             }
             Action::AgentResponseError(name, error) => {
+                tracing::warn!(agent = name.as_str(), %error, "chat request failed");
                 self.waiting_for_response = false;
                 self.chat_messages.push(ChatMessage::system(format!(
                     "{} error: {}",
@@ -200,78 +710,136 @@ impl App {
                 )));
             }
             Action::TaskCreated(item) => {
+                spawn_hook(
+                    &self.hooks,
+                    Hook::ItemCreated,
+                    serde_json::json!({ "item": item }),
+                );
                 self.chat_messages
                     .push(ChatMessage::system(format!("Task created: {}", item.title)));
-                self.items.push(item);
+                // The refresh may have brought it in already
+                ItemUpdate::Upsert(item).apply(&mut self.items);
                 // In auto mode, it will be picked up on next tick
                 if !self.auto_mode {
                     self.flash_message = Some(("New task added — press d to dispatch".into(), Instant::now()));
                 }
             }
             Action::TaskCreateError(msg) => {
+                tracing::warn!(error = %msg, "task creation failed");
                 self.chat_messages
                     .push(ChatMessage::system(format!("Failed to create task: {msg}")));
             }
+            Action::TriageReady(suggestions) => {
+                self.waiting_for_response = false;
+                let count = suggestions.len();
+                self.triage = suggestions.into_iter().map(|s| (s.id.clone(), s)).collect();
+                self.chat_messages.push(ChatMessage::system(format!(
+                    "Triage: {count} suggestions — /triage accept [id] to apply, /triage clear to dismiss"
+                )));
+            }
+            Action::SplitReady(parent_id, subtasks) => {
+                self.waiting_for_response = false;
+                let mut lines = vec![format!("Proposed subtasks for {parent_id}:")];
+                lines.extend(
+                    subtasks
+                        .iter()
+                        .enumerate()
+                        .map(|(i, s)| format!("{}. {}", i + 1, s.title)),
+                );
+                lines.push("/split accept to create them, /split clear to dismiss".into());
+                self.chat_messages.push(ChatMessage::system(lines.join("\n")));
+                self.split = Some((parent_id, subtasks));
+            }
+            Action::SplitFailed(error) => {
+                tracing::warn!(%error, "split failed");
+                self.waiting_for_response = false;
+                self.chat_messages
+                    .push(ChatMessage::system(format!("Split failed: {error}")));
+            }
+            Action::TriageFailed(error) => {
+                tracing::warn!(%error, "triage failed");
+                self.waiting_for_response = false;
+                self.chat_messages
+                    .push(ChatMessage::system(format!("Triage failed: {error}")));
+            }
             Action::Quit => {
-                self.should_quit = true;
+                // A second Ctrl+C at the prompt confirms
+                if matches!(self.confirm, Some(Confirm::Quit(_))) {
+                    self.confirm = None;
+                    self.stop_running_agents().await;
+                    self.quit();
+                } else {
+                    self.request_quit().await;
+                }
             }
         }
+
+        if before.is_some_and(|before| before != self.screen_fingerprint()) {
+            self.needs_redraw = true;
+        }
     }
 
     async fn handle_input_key(&mut self, key: KeyAction) {
         match key {
             KeyAction::Escape => {
                 self.input_active = false;
-                self.input_buffer.clear();
-                self.input_cursor = 0;
+                self.input.clear();
+                self.input_history.reset();
             }
             KeyAction::Select => {
                 // Enter submits the input
-                let input = self.input_buffer.clone();
-                self.input_buffer.clear();
-                self.input_cursor = 0;
+                let input = self.input.take();
+                self.input_history.push(&input);
                 self.input_active = false;
                 if !input.trim().is_empty() {
                     self.process_command(input).await;
                 }
             }
-            KeyAction::Backspace => {
-                if self.input_cursor > 0 {
-                    self.input_cursor -= 1;
-                    self.input_buffer.remove(self.input_cursor);
+            KeyAction::Up => {
+                if self.input.up() {
+                    return;
                 }
-            }
-            KeyAction::Left => {
-                if self.input_cursor > 0 {
-                    self.input_cursor -= 1;
+                if let Some(entry) = self.input_history.prev(self.input.text()) {
+                    self.input.set(entry);
                 }
             }
-            KeyAction::Right => {
-                if self.input_cursor < self.input_buffer.len() {
-                    self.input_cursor += 1;
+            KeyAction::Down => {
+                if self.input.down() {
+                    return;
+                }
+                if let Some(entry) = self.input_history.next() {
+                    self.input.set(entry);
                 }
             }
-            KeyAction::Char(c) => {
-                self.input_buffer.insert(self.input_cursor, c);
-                self.input_cursor += 1;
-            }
+            KeyAction::Newline => self.input.insert('\n'),
+            KeyAction::Backspace => self.input.backspace(),
+            KeyAction::Left => self.input.left(),
+            KeyAction::Right => self.input.right(),
+            KeyAction::WordLeft => self.input.word_left(),
+            KeyAction::WordRight => self.input.word_right(),
+            KeyAction::DeleteWord => self.input.delete_word(),
+            KeyAction::DeleteToStart => self.input.delete_to_start(),
+            KeyAction::Home => self.input.home(),
+            KeyAction::End => self.input.end(),
             KeyAction::Tab => {
                 // Auto-complete agent names
                 self.autocomplete_agent();
             }
-            _ => {}
+            other => {
+                if let Some(c) = other.typed_char() {
+                    self.input.insert(c);
+                }
+            }
         }
     }
 
     fn autocomplete_agent(&mut self) {
-        if !self.input_buffer.starts_with('@') {
+        let Some(partial) = self.input.text().strip_prefix('@') else {
             return;
-        }
-        let partial = &self.input_buffer[1..];
-        for name in AgentName::ALL {
+        };
+        for name in self.store.get_all().iter().map(|a| a.name) {
             if name.as_str().starts_with(partial) && partial.len() < name.as_str().len() {
-                self.input_buffer = format!("@{} ", name.as_str());
-                self.input_cursor = self.input_buffer.len();
+                self.input.set(format!("@{} ", name.as_str()));
                 return;
             }
         }
@@ -280,6 +848,8 @@ impl App {
     async fn process_command(&mut self, input: String) {
         if input.starts_with('@') {
             self.process_agent_message(input).await;
+        } else if input.starts_with('/') {
+            self.process_slash_command(input).await;
         } else {
             self.process_task_creation(input).await;
         }
@@ -287,31 +857,16 @@ impl App {
 
     async fn process_agent_message(&mut self, input: String) {
         // Parse @agent_name message
-        let after_at = &input[1..];
-        let mut target_agent = None;
-        let mut agent_message = "";
-
-        for name in AgentName::ALL {
-            let prefix = name.as_str();
-            if after_at.starts_with(prefix) {
-                let rest = &after_at[prefix.len()..];
-                if rest.is_empty() || rest.starts_with(' ') {
-                    target_agent = Some(name);
-                    agent_message = rest.trim();
-                    break;
-                }
-            }
-        }
-
-        let agent_name = match target_agent {
-            Some(n) => n,
-            None => {
-                self.chat_messages.push(ChatMessage::system(
-                    "Unknown agent. Use @ember, @flow, @tempest, or @terra".to_string(),
-                ));
-                return;
-            }
+        let Some((agent_name, agent_message)) = AgentName::parse_mention(&input)
+            .filter(|(name, _)| self.store.get_agent(*name).is_some())
+        else {
+            self.chat_messages.push(ChatMessage::system(
+                "Unknown agent. Use @ember, @flow, @tempest, or @terra (@flow-2 for a second Flow)"
+                    .to_string(),
+            ));
+            return;
         };
+        let agent_message = agent_message.trim_end();
 
         if agent_message.is_empty() {
             self.chat_messages.push(ChatMessage::system(format!(
@@ -324,14 +879,43 @@ impl App {
         // Add user message to chat
         self.chat_messages.push(ChatMessage::user(input.clone()));
 
+        // A pending question takes the message as its answer
+        if let Some(q) = self
+            .pending_questions
+            .get(&agent_name)
+            .and_then(|qs| qs.first())
+            .cloned()
+        {
+            self.answer_question(agent_name, &q, agent_message);
+            return;
+        }
+
+        // Nothing is running yet, so edits to a plan are kept with the plan
+        if self
+            .store
+            .get_agent(agent_name)
+            .is_some_and(|a| a.status == AgentStatus::Plan)
+        {
+            let reply = match plan::add_note(agent_name, agent_message) {
+                Ok(()) => format!("Added to {}'s plan", agent_name.display_name()),
+                Err(e) => format!("Failed to update the plan: {e}"),
+            };
+            self.chat_messages.push(ChatMessage::system(reply));
+            if self.review_diff.as_ref().is_some_and(|(n, _)| *n == agent_name) {
+                self.load_plan(agent_name);
+            }
+            return;
+        }
+
         // Determine work directory and task context
         let agent = self.store.get_agent(agent_name);
         let work_dir;
         let task_context;
         let is_working;
+        let item_id = agent.and_then(|a| a.work_item_id.clone());
 
         if let Some(agent) = agent {
-            is_working = agent.status == AgentStatus::Working;
+            is_working = matches!(agent.status, AgentStatus::Working | AgentStatus::Stalled);
             work_dir = agent
                 .worktree_path
                 .clone()
@@ -344,10 +928,10 @@ impl App {
         }
 
         // Check if the message is feedback for a working/done/error agent
-        let is_feedback = agent.map_or(false, |a| {
+        let is_feedback = agent.is_some_and(|a| {
             matches!(
                 a.status,
-                AgentStatus::Working | AgentStatus::Done | AgentStatus::Error
+                AgentStatus::Working | AgentStatus::Review | AgentStatus::Done | AgentStatus::Error
             )
         });
 
@@ -365,6 +949,13 @@ impl App {
         let msg = agent_message.to_string();
         let ctx = task_context.clone();
 
+        // Earlier turns about the same item give follow-up questions context
+        let history = load_thread(agent_name, item_id.as_deref());
+        let _ = append_turn(
+            agent_name,
+            &Turn::new(Role::User, agent_message, item_id.as_deref()),
+        );
+
         // Log the interaction
         let _ = append_event(&new_event(
             agent_name,
@@ -379,7 +970,7 @@ impl App {
             let wd = work_dir.clone();
             let tc = ctx.unwrap_or_else(|| "No specific task".to_string());
             tokio::spawn(async move {
-                match message::apply_feedback(agent_name, &msg, &wd, &tc).await {
+                match message::apply_feedback(agent_name, &msg, &wd, &tc, &history).await {
                     Ok(response) => {
                         let _ = tx.send(Action::AgentResponse(agent_name, response));
                     }
@@ -395,12 +986,15 @@ impl App {
             // Send message and get response (read-only conversation)
             let wd = work_dir.clone();
             let ctx_str = ctx.as_deref().map(|s| s.to_string());
+            let chat = self.chat_config.clone();
             tokio::spawn(async move {
                 match message::message_agent(
                     agent_name,
                     &msg,
                     &wd,
                     ctx_str.as_deref(),
+                    &history,
+                    chat.as_ref(),
                 )
                 .await
                 {
@@ -418,144 +1012,777 @@ impl App {
         }
     }
 
-    async fn process_task_creation(&mut self, input: String) {
-        let title = input.trim().to_string();
-        if title.is_empty() {
+    fn answer_question(&mut self, name: AgentName, q: &Question, text: &str) {
+        let Some(wt_path) = self
+            .store
+            .get_agent(name)
+            .and_then(|a| a.worktree_path.clone())
+        else {
+            self.chat_messages.push(ChatMessage::system(format!(
+                "{} is no longer running",
+                name.display_name()
+            )));
+            return;
+        };
+        if let Err(e) = questions::answer(Path::new(&wt_path), &q.id, text) {
+            self.chat_messages
+                .push(ChatMessage::system(format!("Failed to send answer: {e}")));
             return;
         }
+        let agent = self.store.get_agent(name);
+        let _ = append_event(&new_event(
+            name,
+            "answered",
+            agent.and_then(|a| a.work_item_id.as_deref()),
+            agent.and_then(|a| a.work_item_title.as_deref()),
+            Some(text),
+        ));
+        if let Some(qs) = self.pending_questions.get_mut(&name) {
+            qs.retain(|p| p.id != q.id);
+            if qs.is_empty() {
+                self.pending_questions.remove(&name);
+            }
+        }
+        self.chat_messages.push(ChatMessage::system(format!(
+            "Answered {}: {}",
+            name.display_name(),
+            q.question
+        )));
+    }
 
-        self.chat_messages.push(ChatMessage::user(format!("New task: {title}")));
-
-        // Create a local work item immediately
-        let local_item = WorkItem {
-            id: format!("LOCAL-{}", self.items.len() + 1),
-            source_id: None,
-            title: title.clone(),
-            description: None,
-            status: Some("Todo".to_string()),
-            priority: None,
-            labels: Vec::new(),
-            source: "Local".to_string(),
-            team: None,
-            url: None,
+    async fn process_slash_command(&mut self, input: String) {
+        self.chat_messages.push(ChatMessage::user(input.clone()));
+        let command = match command::parse_slash(&input) {
+            Ok(command) => command,
+            Err(e) => {
+                self.chat_messages.push(ChatMessage::system(e));
+                return;
+            }
         };
 
-        // Try to create in the active provider
-        let tx = self.action_tx.clone();
-        let mut created_in_provider = false;
-
-        for provider in &self.providers {
-            match provider.create_item(&title, None).await {
-                Ok(Some(item)) => {
-                    let _ = tx.send(Action::TaskCreated(item));
-                    created_in_provider = true;
-                    break;
+        match command {
+            SlashCommand::Dispatch { id, agent } => {
+                let Some(index) = self.find_item(&id) else {
+                    self.chat_messages
+                        .push(ChatMessage::system(format!("No item {id}")));
+                    return;
+                };
+                self.selected_item = index;
+                match agent {
+                    Some(agent_name) => self.assign_selected_to(agent_name).await,
+                    None => self.dispatch_selected().await,
                 }
-                Ok(None) => continue, // Provider doesn't support create
-                Err(e) => {
-                    let _ = tx.send(Action::TaskCreateError(format!(
-                        "{}: {}",
-                        provider.name(),
-                        e
-                    )));
-                    // Fall through to add locally
+            }
+            SlashCommand::Done { id } => {
+                let Some(index) = self.find_item(&id) else {
+                    self.chat_messages
+                        .push(ChatMessage::system(format!("No item {id}")));
+                    return;
+                };
+                let item = self.items[index].clone();
+                // A failed move leaves the item listed; local ones have
+                // nowhere to move and just go
+                if item.source_id.is_some() && !self.move_item_to_done(item.clone()).await {
+                    return;
                 }
+                self.items.retain(|i| i.key() != item.key());
+                self.dispatch_queue.retain(|queued| *queued != item.id);
+                self.snap_selection();
+                self.undo.push(Undoable::Done(item));
+            }
+            SlashCommand::Refresh => self.refresh_items().await,
+            SlashCommand::Filter(filter) => {
+                let msg = if filter.is_empty() {
+                    "Filter cleared".to_string()
+                } else {
+                    format!("Filter: {filter} ({})", self.shown_count(&filter))
+                };
+                self.item_filter = filter;
+                self.active_view = None;
+                self.snap_selection();
+                self.chat_messages.push(ChatMessage::system(msg));
+            }
+            SlashCommand::Events { kind, text } => {
+                let msg = match (kind, &text) {
+                    (EventKind::All, None) => "Showing all events".to_string(),
+                    (kind, None) => format!("Showing {} only", kind.as_str()),
+                    (kind, Some(text)) => format!("Showing {} matching \"{text}\"", kind.as_str()),
+                };
+                self.agent_event_kind = kind;
+                self.agent_event_search = text;
+                self.agent_log_scroll = 0;
+                self.flash_message = Some((msg, Instant::now()));
+            }
+            SlashCommand::Board => {
+                self.view_mode = ViewMode::BoardSelection;
+                self.fetch_boards();
+            }
+            SlashCommand::Calendar => self.open_calendar(),
+            SlashCommand::Failed => self.show_failed(),
+            SlashCommand::Requeue { id } => self.requeue(&id),
+            SlashCommand::Triage(TriageAction::Accept(None)) if self.triage.len() > 1 => {
+                self.confirm_or_run(Confirm::AcceptAllTriage(self.triage.len()))
+                    .await;
+            }
+            SlashCommand::Triage(action) => self.triage(action),
+            SlashCommand::Split(action) => self.split(action).await,
+            SlashCommand::Mirror { id, to } => self.mirror(&id, &to).await,
+            SlashCommand::Edit {
+                id,
+                title,
+                description,
+            } => self.edit_item(&id, &title, description.as_deref()).await,
+            SlashCommand::Help => {
+                let lines: Vec<String> = command::HELP
+                    .iter()
+                    .map(|(usage, what)| format!("{usage} — {what}"))
+                    .collect();
+                self.chat_messages.push(ChatMessage::system(lines.join("\n")));
             }
-        }
-
-        if !created_in_provider {
-            // Add as local item
-            let _ = tx.send(Action::TaskCreated(local_item));
         }
     }
 
-    async fn handle_key(&mut self, key: KeyAction) {
-        match key {
-            KeyAction::ActivateInput => {
-                self.input_active = true;
-                self.input_buffer.clear();
-                self.input_cursor = 0;
-            }
-            // Also allow entering input mode by just typing a character
-            // when not in a view that uses single-char shortcuts
-            KeyAction::Up => match &self.view_mode {
-                ViewMode::BoardSelection => {
-                    if self.selected_board > 0 {
-                        self.selected_board -= 1;
-                    }
-                }
-                ViewMode::Items => {
-                    if self.selected_item > 0 {
-                        self.selected_item -= 1;
-                    }
-                }
-                ViewMode::Agents => {
-                    if self.selected_agent > 0 {
-                        self.selected_agent -= 1;
-                    }
-                }
-                ViewMode::AgentDetail(_) => {
-                    if self.agent_log_scroll > 0 {
-                        self.agent_log_scroll -= 1;
-                    }
-                }
-            },
-            KeyAction::Down => match &self.view_mode {
-                ViewMode::BoardSelection => {
-                    if !self.available_boards.is_empty()
-                        && self.selected_board < self.available_boards.len() - 1
-                    {
-                        self.selected_board += 1;
-                    }
+    fn triage(&mut self, action: TriageAction) {
+        match action {
+            TriageAction::Run => {
+                if self.items.is_empty() {
+                    self.chat_messages
+                        .push(ChatMessage::system("Nothing to triage".to_string()));
+                    return;
                 }
-                ViewMode::Items => {
-                    if !self.items.is_empty() && self.selected_item < self.items.len() - 1 {
-                        self.selected_item += 1;
+                let prompt = triage::build_triage_prompt(&self.items);
+                let work_dir = self.repo_root.clone();
+                let chat = self.chat_config.clone();
+                let tx = self.action_tx.clone();
+                self.waiting_for_response = true;
+                tokio::spawn(async move {
+                    let result = message::complete(&prompt, &work_dir, chat.as_ref())
+                        .await
+                        .and_then(|reply| triage::parse_triage(&reply));
+                    let _ = tx.send(match result {
+                        Ok(suggestions) => Action::TriageReady(suggestions),
+                        Err(e) => Action::TriageFailed(e.to_string()),
+                    });
+                });
+            }
+            TriageAction::Accept(id) => {
+                let ids: Vec<String> = match id {
+                    Some(id) => self
+                        .triage
+                        .keys()
+                        .filter(|k| k.eq_ignore_ascii_case(&id))
+                        .cloned()
+                        .collect(),
+                    None => self.triage.keys().cloned().collect(),
+                };
+                let accepted: Vec<Suggestion> =
+                    ids.iter().filter_map(|id| self.triage.remove(id)).collect();
+                for suggestion in &accepted {
+                    if let Some(priority) = &suggestion.priority {
+                        if let Some(item) = self.items.iter_mut().find(|i| i.id == suggestion.id) {
+                            item.priority = Some(priority.clone());
+                        }
                     }
                 }
-                ViewMode::Agents => {
-                    if self.selected_agent < AgentName::ALL.len() - 1 {
-                        self.selected_agent += 1;
-                    }
+                if let Err(e) = self.triage_overrides.accept(&accepted) {
+                    self.chat_messages.push(ChatMessage::system(format!(
+                        "Failed to save accepted triage: {e}"
+                    )));
                 }
-                ViewMode::AgentDetail(_) => {
-                    self.agent_log_scroll += 1;
+                self.chat_messages.push(ChatMessage::system(format!(
+                    "Accepted {} triage suggestions",
+                    ids.len()
+                )));
+            }
+            TriageAction::Clear => {
+                self.triage.clear();
+                self.chat_messages
+                    .push(ChatMessage::system("Triage suggestions cleared".to_string()));
+            }
+        }
+    }
+
+    async fn split(&mut self, action: SplitAction) {
+        match action {
+            SplitAction::Run(id) => {
+                let index = match id {
+                    Some(id) => self.find_item(&id),
+                    None => self
+                        .visible_item_indices()
+                        .contains(&self.selected_item)
+                        .then_some(self.selected_item),
+                };
+                let Some(item) = index.and_then(|i| self.items.get(i)).cloned() else {
+                    self.chat_messages
+                        .push(ChatMessage::system("No item to split".to_string()));
+                    return;
+                };
+                // Run in the item's repository so the model can look at the code
+                let work_dir = resolve_repo(&self.agents_config, &item, &self.repo_root);
+                let prompt = split::build_split_prompt(&item);
+                let chat = self.chat_config.clone();
+                let tx = self.action_tx.clone();
+                self.waiting_for_response = true;
+                tokio::spawn(async move {
+                    let result = message::complete(&prompt, &work_dir, chat.as_ref())
+                        .await
+                        .and_then(|reply| split::parse_subtasks(&reply));
+                    let _ = tx.send(match result {
+                        Ok(subtasks) => Action::SplitReady(item.id, subtasks),
+                        Err(e) => Action::SplitFailed(e.to_string()),
+                    });
+                });
+            }
+            SplitAction::Accept => {
+                let Some((parent_id, subtasks)) = self.split.take() else {
+                    self.chat_messages
+                        .push(ChatMessage::system("No subtasks to create".to_string()));
+                    return;
+                };
+                let Some(parent) = self.find_item(&parent_id).map(|i| self.items[i].clone())
+                else {
+                    self.chat_messages.push(ChatMessage::system(format!(
+                        "{parent_id} is no longer on the board"
+                    )));
+                    return;
+                };
+                self.create_subtasks(&parent, &subtasks).await;
+            }
+            SplitAction::Clear => {
+                self.split = None;
+                self.chat_messages
+                    .push(ChatMessage::system("Proposed subtasks cleared".to_string()));
+            }
+        }
+    }
+
+    /// Create subtasks as children of the parent in its provider and team or
+    /// project, and list them in a comment on the parent. Providers that
+    /// can't create items get local ones.
+    async fn create_subtasks(&mut self, parent: &WorkItem, subtasks: &[Subtask]) {
+        let provider = self.providers.iter().find(|p| p.name() == parent.source);
+        let mut created = Vec::new();
+        for (i, subtask) in subtasks.iter().enumerate() {
+            let description = split::subtask_description(subtask, parent);
+            let item = match (provider, &parent.source_id) {
+                (Some(provider), Some(parent_id)) => provider
+                    .create_subtask(parent_id, &subtask.title, Some(&description))
+                    .await
+                    .map_err(|e| format!("{}: {e}", provider.name())),
+                (Some(provider), None) => provider
+                    .create_item(&subtask.title, Some(&description))
+                    .await
+                    .map_err(|e| format!("{}: {e}", provider.name())),
+                (None, _) => Ok(None),
+            };
+            let item = match item {
+                Ok(Some(item)) => item,
+                Ok(None) => WorkItem {
+                    id: format!("LOCAL-{}", self.items.len() + i + 1),
+                    source_id: None,
+                    title: subtask.title.clone(),
+                    description: Some(description),
+                    status: Some("Todo".to_string()),
+                    priority: parent.priority.clone(),
+                    labels: parent.labels.clone(),
+                    source: "Local".to_string(),
+                    team: parent.team.clone(),
+                    url: None,
+                    links: Vec::new(),
+                    comments: Vec::new(),
+                    blocked_by: Vec::new(),
+                    due: None,
+                },
+                Err(e) => {
+                    let _ = self.action_tx.send(Action::TaskCreateError(e));
+                    continue;
                 }
-            },
-            KeyAction::Select => {
-                if self.view_mode == ViewMode::BoardSelection && !self.available_boards.is_empty() {
-                    self.select_board().await;
+            };
+            created.push(format!("- {}: {}", item.id, item.title));
+            let _ = self.action_tx.send(Action::TaskCreated(Box::new(item)));
+        }
+
+        if let (Some(provider), Some(source_id)) = (provider, &parent.source_id) {
+            if !created.is_empty() {
+                let body = format!("Split into:\n{}", created.join("\n"));
+                if let Err(e) = provider.add_comment(source_id, &body).await {
+                    self.chat_messages.push(ChatMessage::system(format!(
+                        "Failed to link subtasks on {}: {e}",
+                        parent.id
+                    )));
                 }
             }
-            KeyAction::Right => match &self.view_mode {
-                ViewMode::BoardSelection => {}
-                ViewMode::Items => {
-                    self.view_mode = ViewMode::Agents;
-                    self.selected_agent = 0;
+        }
+        self.chat_messages.push(ChatMessage::system(format!(
+            "Split {} into {} subtasks",
+            parent.id,
+            created.len()
+        )));
+    }
+
+    /// Replace an item's title, and its description when one is given, in its
+    /// provider and in the list.
+    async fn edit_item(&mut self, id: &str, title: &str, description: Option<&str>) {
+        let Some(index) = self.find_item(id) else {
+            self.chat_messages
+                .push(ChatMessage::system(format!("No item {id}")));
+            return;
+        };
+        let item = &self.items[index];
+        let provider = self.providers.iter().find(|p| p.name() == item.source);
+        let result = match (provider, &item.source_id) {
+            (Some(provider), Some(source_id)) => {
+                provider.update_item(source_id, title, description).await
+            }
+            _ => Err(anyhow::anyhow!("{} is not configured", item.source).into()),
+        };
+        let msg = match result {
+            Ok(true) => {
+                let item = &mut self.items[index];
+                item.title = title.to_string();
+                if let Some(description) = description {
+                    item.description = Some(description.to_string());
+                }
+                format!("Updated {}", item.id)
+            }
+            Ok(false) => format!("{} items can't be edited from here", item.source),
+            Err(e) => format!("Failed to update {}: {e}", item.id),
+        };
+        self.chat_messages.push(ChatMessage::system(msg));
+    }
+
+    /// Create a copy of an item in another provider and link the two, so
+    /// finishing either one closes the other.
+    async fn mirror(&mut self, id: &str, to: &str) {
+        let Some(item) = self.find_item(id).map(|i| self.items[i].clone()) else {
+            self.chat_messages
+                .push(ChatMessage::system(format!("No item {id}")));
+            return;
+        };
+        let Some(target) = self
+            .providers
+            .iter()
+            .find(|p| p.name().eq_ignore_ascii_case(to))
+        else {
+            self.chat_messages
+                .push(ChatMessage::system(format!("No provider {to} configured")));
+            return;
+        };
+        if target.name() == item.source {
+            self.chat_messages.push(ChatMessage::system(format!(
+                "{} is already in {}",
+                item.id, item.source
+            )));
+            return;
+        }
+
+        let mut origin = format!("Mirrored from {} {}", item.source, item.id);
+        if let Some(url) = &item.url {
+            origin.push_str(&format!(": {url}"));
+        }
+        let description = match item.description.as_deref().map(str::trim) {
+            Some(desc) if !desc.is_empty() => format!("{desc}\n\n{origin}"),
+            _ => origin,
+        };
+        let mirror = match target.create_item(&item.title, Some(&description)).await {
+            Ok(Some(mirror)) => mirror,
+            Ok(None) => {
+                self.chat_messages.push(ChatMessage::system(format!(
+                    "{} can't create items",
+                    target.name()
+                )));
+                return;
+            }
+            Err(e) => {
+                self.chat_messages.push(ChatMessage::system(format!(
+                    "Failed to mirror {} to {}: {e}",
+                    item.id,
+                    target.name()
+                )));
+                return;
+            }
+        };
+
+        let mut note = format!("Mirrored {} to {} {}", item.id, mirror.source, mirror.id);
+        if let Err(e) = self.item_links.add(ItemRef::of(&item), ItemRef::of(&mirror)) {
+            note.push_str(&format!(" (failed to save the link: {e})"));
+        }
+        let provider = self.providers.iter().find(|p| p.name() == item.source);
+        if let (Some(provider), Some(source_id)) = (provider, &item.source_id) {
+            let mut body = format!("Mirrored to {} {}", mirror.source, mirror.id);
+            if let Some(url) = &mirror.url {
+                body.push_str(&format!(": {url}"));
+            }
+            // Best effort: the link is already recorded locally
+            let _ = provider.add_comment(source_id, &body).await;
+        }
+        let _ = self.action_tx.send(Action::TaskCreated(Box::new(mirror)));
+        self.chat_messages.push(ChatMessage::system(note));
+    }
+
+    /// Close the items mirrored from or to a finished item.
+    async fn close_linked(&mut self, source: &str, id: &str) {
+        let partners = match self.item_links.close(source, id) {
+            Ok(partners) => partners,
+            Err(e) => {
+                self.chat_messages.push(ChatMessage::system(format!(
+                    "Failed to update links for {id}: {e}"
+                )));
+                return;
+            }
+        };
+        // Closed together per provider, so several mirrors take one request
+        // where the provider can batch
+        let mut by_source: BTreeMap<String, Vec<ItemRef>> = BTreeMap::new();
+        for partner in partners {
+            self.items
+                .retain(|i| !(i.source == partner.source && i.id == partner.id));
+            by_source
+                .entry(partner.source.clone())
+                .or_default()
+                .push(partner);
+        }
+        for (source, partners) in by_source {
+            let source_ids: Option<Vec<String>> =
+                partners.iter().map(|p| p.source_id.clone()).collect();
+            let provider = self.providers.iter().find(|p| p.name() == source);
+            let result = match (provider, source_ids) {
+                (Some(provider), Some(source_ids)) => {
+                    provider.move_many_to_done(&source_ids).await
+                }
+                _ => Err(anyhow::anyhow!("{source} is not configured").into()),
+            };
+            let ids: Vec<&str> = partners.iter().map(|p| p.id.as_str()).collect();
+            let ids = ids.join(", ");
+            let msg = match result {
+                Ok(()) => format!("Closed linked {source} {ids} with {id}"),
+                Err(e) => format!("Failed to close linked {source} {ids}: {e}"),
+            };
+            self.chat_messages.push(ChatMessage::system(msg));
+        }
+        self.snap_selection();
+    }
+
+    /// Drop dispatch bookkeeping for an item gone upstream. An agent still
+    /// on it keeps it marked, so it isn't dispatched twice if it comes back.
+    fn forget_item(&mut self, id: &str) {
+        self.dispatch_queue.retain(|queued| queued != id);
+        if self.assigned_agent(id).is_none() {
+            self.dispatched_item_ids.remove(id);
+        }
+    }
+
+    /// Index of the item with `id`, ignoring case.
+    fn find_item(&self, id: &str) -> Option<usize> {
+        self.items.iter().position(|i| i.id.eq_ignore_ascii_case(id))
+    }
+
+    /// Change the item list with `change`, then reselect the item that was
+    /// selected and keep its row where it was on screen, so a background
+    /// refresh doesn't move the highlight. If the item is gone, the
+    /// selection stays at its index.
+    fn keep_selection(&mut self, change: impl FnOnce(&mut Self)) {
+        let anchor = self.items.get(self.selected_item).map(|item| {
+            let (source, id) = item.key();
+            (source.to_string(), id.to_string(), self.visible_row(self.selected_item))
+        });
+        change(self);
+        let found = anchor.and_then(|(source, id, row)| {
+            let index = self
+                .items
+                .iter()
+                .position(|i| i.key() == (source.as_str(), id.as_str()))?;
+            Some((index, row))
+        });
+        match found {
+            Some((index, row)) => {
+                self.selected_item = index;
+                if let (Some(before), Some(after)) = (row, self.visible_row(index)) {
+                    let scroll = self.item_scroll.get();
+                    self.item_scroll.set((scroll + after).saturating_sub(before));
+                }
+            }
+            None => {
+                self.selected_item = self.selected_item.min(self.items.len().saturating_sub(1));
+            }
+        }
+        self.snap_selection();
+    }
+
+    /// Row of `items[index]` in the filtered list.
+    fn visible_row(&self, index: usize) -> Option<usize> {
+        self.visible_item_indices().iter().position(|&i| i == index)
+    }
+
+    /// "3 of 10 items" for the items `filter` shows.
+    fn shown_count(&self, filter: &ItemFilter) -> String {
+        let shown = self.items.iter().filter(|i| filter.matches(i)).count();
+        format!("{shown} of {} items", self.items.len())
+    }
+
+    /// Apply the `index`th configured view's filter.
+    fn switch_view(&mut self, index: usize) {
+        let Some(view) = self.views.get(index).cloned() else {
+            return;
+        };
+        let msg = format!("View: {} ({})", view.name, self.shown_count(&view.filter));
+        self.item_filter = view.filter;
+        self.active_view = Some(view.name);
+        self.snap_selection();
+        self.flash_message = Some((msg, Instant::now()));
+    }
+
+    /// `0`: leave the view or `/filter` and show every item.
+    fn clear_view(&mut self) {
+        if self.item_filter.is_empty() {
+            return;
+        }
+        self.item_filter = ItemFilter::default();
+        self.active_view = None;
+        self.snap_selection();
+        self.flash_message = Some(("All items".to_string(), Instant::now()));
+    }
+
+    /// Keep the selection on an item the filter shows.
+    fn snap_selection(&mut self) {
+        let visible = self.visible_item_indices();
+        if !visible.contains(&self.selected_item) {
+            self.selected_item = visible.first().copied().unwrap_or(0);
+        }
+    }
+
+    /// Indices into `items` that pass the current filter.
+    pub fn visible_item_indices(&self) -> Vec<usize> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| self.item_filter.matches(item))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    async fn process_task_creation(&mut self, input: String) {
+        // The first line is the title; any further lines become the description
+        let input = input.trim();
+        let (title, description) = match input.split_once('\n') {
+            Some((title, rest)) => (title.trim().to_string(), Some(rest.trim().to_string())),
+            None => (input.to_string(), None),
+        };
+        let description = description.filter(|d| !d.is_empty());
+        if title.is_empty() {
+            return;
+        }
+
+        self.chat_messages.push(ChatMessage::user(format!("New task: {title}")));
+
+        // Create a local work item immediately
+        let local_item = WorkItem {
+            id: format!("LOCAL-{}", self.items.len() + 1),
+            source_id: None,
+            title: title.clone(),
+            description: description.clone(),
+            status: Some("Todo".to_string()),
+            priority: None,
+            labels: Vec::new(),
+            source: "Local".to_string(),
+            team: None,
+            url: None,
+            links: Vec::new(),
+            comments: Vec::new(),
+            blocked_by: Vec::new(),
+            due: None,
+        };
+
+        // Try to create in the active provider
+        let tx = self.action_tx.clone();
+        let mut created_in_provider = false;
+
+        for provider in &self.providers {
+            match provider.create_item(&title, description.as_deref()).await {
+                Ok(Some(item)) => {
+                    let _ = tx.send(Action::TaskCreated(Box::new(item)));
+                    created_in_provider = true;
+                    break;
+                }
+                Ok(None) => continue, // Provider doesn't support create
+                Err(e) => {
+                    let _ = tx.send(Action::TaskCreateError(format!(
+                        "{}: {}",
+                        provider.name(),
+                        e
+                    )));
+                    // Fall through to add locally
+                }
+            }
+        }
+
+        if !created_in_provider {
+            // Add as local item
+            let _ = tx.send(Action::TaskCreated(Box::new(local_item)));
+        }
+    }
+
+    async fn handle_key(&mut self, key: KeyAction) {
+        if self.debug_log && self.handle_debug_log_key(&key) {
+            return;
+        }
+        if self.error_center && self.handle_error_center_key(&key) {
+            return;
+        }
+        if self.chat_focus && self.handle_chat_focus_key(&key) {
+            return;
+        }
+        if self.view_mode == ViewMode::Calendar && self.handle_calendar_key(&key) {
+            return;
+        }
+        match key {
+            KeyAction::ActivateInput => {
+                self.input_active = true;
+                self.input.clear();
+            }
+            // Also allow entering input mode by just typing a character
+            // when not in a view that uses single-char shortcuts
+            KeyAction::Up => match &self.view_mode {
+                ViewMode::BoardSelection => {
+                    if self.selected_board > 0 {
+                        self.selected_board -= 1;
+                    }
+                }
+                ViewMode::Items => {
+                    if let Some(&prev) = self
+                        .visible_item_indices()
+                        .iter()
+                        .rev()
+                        .find(|&&i| i < self.selected_item)
+                    {
+                        self.selected_item = prev;
+                    }
+                }
+                ViewMode::Agents => {
+                    if self.selected_agent > 0 {
+                        self.selected_agent -= 1;
+                    }
+                }
+                ViewMode::AgentDetail(_) if self.branch_diff.is_some() => {
+                    self.branch_diff_scroll = self.branch_diff_scroll.saturating_sub(1);
+                }
+                ViewMode::AgentDetail(_) => {
+                    if self.agent_log_scroll > 0 {
+                        self.agent_log_scroll -= 1;
+                    }
+                }
+                ViewMode::Calendar => {}
+            },
+            KeyAction::Down => match &self.view_mode {
+                ViewMode::BoardSelection => {
+                    // Failed providers' retry rows follow the boards
+                    let rows = self.available_boards.len() + self.failed_board_sources().len();
+                    if self.selected_board + 1 < rows {
+                        self.selected_board += 1;
+                    }
+                }
+                ViewMode::Items => {
+                    if let Some(&next) = self
+                        .visible_item_indices()
+                        .iter()
+                        .find(|&&i| i > self.selected_item)
+                    {
+                        self.selected_item = next;
+                    }
+                }
+                ViewMode::Agents => {
+                    if self.selected_agent + 1 < self.store.get_all().len() {
+                        self.selected_agent += 1;
+                    }
+                }
+                ViewMode::AgentDetail(_) if self.branch_diff.is_some() => {
+                    self.scroll_branch_diff_to(self.branch_diff_scroll + 1);
+                }
+                ViewMode::AgentDetail(_) => {
+                    self.agent_log_scroll += 1;
+                }
+                ViewMode::Calendar => {}
+            },
+            KeyAction::Select => {
+                if self.view_mode == ViewMode::BoardSelection {
+                    let boards = self.available_boards.len();
+                    if self.selected_board < boards {
+                        self.select_board().await;
+                    } else if let Some(source) = self
+                        .failed_board_sources()
+                        .get(self.selected_board - boards)
+                        .map(|s| s.to_string())
+                    {
+                        self.retry_boards(&source);
+                    }
+                }
+            }
+            KeyAction::Right => match &self.view_mode {
+                ViewMode::BoardSelection => {}
+                ViewMode::Items => {
+                    self.view_mode = ViewMode::Agents;
+                    self.selected_agent = 0;
                 }
                 ViewMode::Agents => {
-                    let agent_name = AgentName::ALL[self.selected_agent];
+                    let Some(agent_name) = self.selected_agent_name() else {
+                        return;
+                    };
                     self.view_mode = ViewMode::AgentDetail(agent_name);
                     self.agent_log_scroll = 0;
+                    self.agent_event_kind = EventKind::All;
+                    self.agent_event_search = None;
+                    self.agent_output_scroll = 0;
+                    self.review_diff = None;
+                    self.branch_diff = None;
+                    self.measure_disk_usage(agent_name);
+                    self.load_git_summary(agent_name);
+                    match self.store.get_agent(agent_name).map(|a| a.status) {
+                        Some(AgentStatus::Review) => self.load_review_diff(agent_name).await,
+                        Some(AgentStatus::Plan) => self.load_plan(agent_name),
+                        _ => {}
+                    }
                 }
-                ViewMode::AgentDetail(_) => {}
+                ViewMode::AgentDetail(_) | ViewMode::Calendar => {}
             },
             KeyAction::Left | KeyAction::Escape => match &self.view_mode {
                 ViewMode::BoardSelection => {}
-                ViewMode::Items => {}
+                ViewMode::Items => {
+                    if matches!(key, KeyAction::Escape) {
+                        self.incident_alert = None;
+                    }
+                }
                 ViewMode::Agents => {
                     self.view_mode = ViewMode::Items;
                 }
+                ViewMode::AgentDetail(_) if self.branch_diff.is_some() => {
+                    self.branch_diff = None;
+                }
                 ViewMode::AgentDetail(_) => {
                     self.view_mode = ViewMode::Agents;
                 }
+                ViewMode::Calendar => {
+                    self.view_mode = ViewMode::Items;
+                }
             },
-            KeyAction::Dispatch => {
-                if self.view_mode == ViewMode::Items {
-                    self.dispatch_selected().await;
+            KeyAction::Dispatch => match self.view_mode {
+                ViewMode::Items => self.dispatch_selected().await,
+                ViewMode::AgentDetail(name) => self.toggle_branch_diff(name),
+                _ => {}
+            },
+            KeyAction::ToggleAutoMode if self.daemon.is_some() => {
+                if let Some(auto) = self.ask_daemon(Request::ToggleAuto).await {
+                    self.auto_mode = auto.as_bool().unwrap_or(false);
+                    let status = if self.auto_mode { "AUTO" } else { "MANUAL" };
+                    self.flash_message =
+                        Some((format!("Daemon mode: {status}"), Instant::now()));
                 }
             }
             KeyAction::ToggleAutoMode => {
+                if !self.auto_mode && self.over_budget() {
+                    self.flash_message =
+                        Some(("Daily budget reached — auto mode stays off".into(), Instant::now()));
+                    return;
+                }
                 self.auto_mode = !self.auto_mode;
                 let status = if self.auto_mode { "AUTO" } else { "MANUAL" };
                 self.flash_message = Some((format!("Mode: {status}"), Instant::now()));
@@ -575,60 +1802,500 @@ impl App {
                 if matches!(self.view_mode, ViewMode::Agents | ViewMode::AgentDetail(_)) {
                     let agent_name = match &self.view_mode {
                         ViewMode::AgentDetail(name) => *name,
-                        _ => AgentName::ALL[self.selected_agent],
+                        _ => match self.selected_agent_name() {
+                            Some(name) => name,
+                            None => return,
+                        },
                     };
-                    self.clear_agent(agent_name).await;
+                    self.confirm_or_run(Confirm::ClearAgent(agent_name)).await;
                 }
             }
             KeyAction::ClearLogs => {
                 if let ViewMode::AgentDetail(agent_name) = self.view_mode {
-                    let _ = clear_events(agent_name);
-                    self.agent_log_scroll = 0;
-                    self.flash_message = Some((
-                        format!("Cleared logs for {}", agent_name.display_name()),
-                        Instant::now(),
-                    ));
-                    let _ = append_event(&new_event(
-                        agent_name,
-                        "logs-cleared",
-                        None,
-                        None,
-                        Some("Activity log cleared"),
-                    ));
+                    self.confirm_or_run(Confirm::ClearLogs(agent_name)).await;
+                }
+            }
+            KeyAction::PageUp if self.branch_diff.is_some() => {
+                self.branch_diff_scroll = self.branch_diff_scroll.saturating_sub(20);
+            }
+            KeyAction::PageDown if self.branch_diff.is_some() => {
+                self.scroll_branch_diff_to(self.branch_diff_scroll + 20);
+            }
+            KeyAction::Char(c @ ('[' | ']')) if self.branch_diff.is_some() => {
+                self.jump_diff_file(c == ']');
+            }
+            KeyAction::PageUp if self.review_diff.is_some() => {
+                self.review_scroll = self.review_scroll.saturating_sub(10);
+            }
+            KeyAction::PageDown if self.review_diff.is_some() => {
+                let len = self.review_diff.as_ref().map_or(0, |(_, lines)| lines.len());
+                self.review_scroll = (self.review_scroll + 10).min(len);
+            }
+            KeyAction::PageUp => {
+                if let ViewMode::AgentDetail(name) = self.view_mode {
+                    let len = self.agent_output.get(&name).map_or(0, |b| b.len());
+                    self.agent_output_scroll = (self.agent_output_scroll + 10).min(len);
+                }
+            }
+            KeyAction::PageDown => {
+                self.agent_output_scroll = self.agent_output_scroll.saturating_sub(10);
+            }
+            KeyAction::Char(c @ '1'..='4') if self.view_mode == ViewMode::Items => {
+                let index = c as usize - '1' as usize;
+                self.assign_selected_to(AgentName::ALL[index]).await;
+            }
+            KeyAction::Char(c @ '5'..='9') if self.view_mode == ViewMode::Items => {
+                self.switch_view(c as usize - '5' as usize);
+            }
+            KeyAction::Char('0') if self.view_mode == ViewMode::Items => self.clear_view(),
+            KeyAction::Char('y') if self.viewed_agent().is_some() => {
+                if let Some(path) = self.viewed_worktree() {
+                    let msg = match clipboard::copy(&path) {
+                        Ok(()) => format!("Copied {path}"),
+                        Err(e) => format!("{path} ({e})"),
+                    };
+                    self.flash_message = Some((msg, Instant::now()));
+                }
+            }
+            KeyAction::Char('s') if self.viewed_agent().is_some() => {
+                self.shell_request = self.viewed_worktree();
+            }
+            KeyAction::Char('e') if self.view_mode == ViewMode::Items => {
+                self.toggle_queued_selected();
+            }
+            KeyAction::Char('e') if matches!(self.view_mode, ViewMode::AgentDetail(_)) => {
+                self.agent_event_kind = self.agent_event_kind.next();
+                self.agent_log_scroll = 0;
+            }
+            KeyAction::Char('p') if self.view_mode == ViewMode::Items => {
+                self.toggle_focus_selected().await;
+            }
+            KeyAction::Char('o') if self.view_mode == ViewMode::Items => self.open_selected(),
+            KeyAction::Char('t') => self.toggle_pomodoro(),
+            KeyAction::Char('v') if self.view_mode == ViewMode::Items => self.open_calendar(),
+            KeyAction::Char('[') if self.view_mode == ViewMode::Items => {
+                self.move_queued_selected(true);
+            }
+            KeyAction::Char(']') if self.view_mode == ViewMode::Items => {
+                self.move_queued_selected(false);
+            }
+            KeyAction::Char('a') => {
+                if let Some(name) = self.reviewing_agent() {
+                    self.approve_review(name).await;
+                } else if let Some(name) = self.planning_agent() {
+                    self.approve_plan(name).await;
+                }
+            }
+            KeyAction::Char('f') => {
+                if let Some(name) = self.reviewing_agent().or(self.planning_agent()) {
+                    // Feedback goes through chat, which lets the agent edit its
+                    // branch or adds a note to its plan
+                    self.input_active = true;
+                    self.input.set(format!("@{} ", name.as_str()));
                 }
             }
+            KeyAction::Char('u') => self.undo_last().await,
+            KeyAction::Char('z') => {
+                self.chat_focus = true;
+                self.chat_scroll = 0;
+            }
+            KeyAction::Char('L') => {
+                self.debug_log = true;
+                self.debug_log_scroll = 0;
+            }
+            KeyAction::Char('!') => {
+                self.error_center = true;
+                self.selected_error = 0;
+                self.error_expanded = false;
+            }
+            KeyAction::Tab if self.view_mode == ViewMode::Items => {
+                self.show_item_detail = !self.show_item_detail;
+            }
             // Ignore unhandled keys in normal mode
-            KeyAction::Char(_) | KeyAction::Backspace | KeyAction::Tab => {}
+            KeyAction::Quit => self.request_quit().await,
+            KeyAction::Char(_)
+            | KeyAction::Backspace
+            | KeyAction::Tab
+            | KeyAction::WordLeft
+            | KeyAction::WordRight
+            | KeyAction::DeleteWord
+            | KeyAction::DeleteToStart
+            | KeyAction::Home
+            | KeyAction::End
+            | KeyAction::Newline => {}
         }
     }
 
-    async fn handle_tick(&mut self) {
-        let _ = self.store.reload();
+    /// Ask before a destructive action, unless confirmations are turned off.
+    async fn confirm_or_run(&mut self, confirm: Confirm) {
+        if self.confirm_destructive {
+            self.confirm = Some(confirm);
+        } else {
+            self.run_confirmed(confirm).await;
+        }
+    }
 
-        // Auto-release done agents
-        let done_agents: Vec<AgentName> = self
-            .store
+    async fn run_confirmed(&mut self, confirm: Confirm) {
+        match confirm {
+            Confirm::ClearAgent(name) => self.clear_agent(name).await,
+            Confirm::ClearLogs(name) => self.clear_logs(name),
+            Confirm::AcceptAllTriage(_) => self.triage(TriageAction::Accept(None)),
+            Confirm::Quit(_) => {
+                self.stop_running_agents().await;
+                self.quit();
+            }
+        }
+    }
+
+    /// Agents with a claude process or a dispatch in flight. Their output
+    /// pipes close when the app exits, so nothing would see them finish.
+    fn running_agents(&self) -> Vec<AgentName> {
+        self.store
             .get_all()
             .iter()
-            .filter(|a| a.status == AgentStatus::Done)
+            .filter(|a| {
+                matches!(
+                    a.status,
+                    AgentStatus::Provisioning | AgentStatus::Working | AgentStatus::Stalled
+                )
+            })
             .map(|a| a.name)
-            .collect();
-        for name in done_agents {
-            let _ = append_event(&new_event(name, "released", None, None, None));
-            let _ = self.store.release(name);
-        }
+            .collect()
+    }
 
-        // Auto-retry and auto-dispatch only in auto mode
-        if self.auto_mode {
+    /// Answer a request made to `work daemon`. Dispatch and clear reply with
+    /// the message the TUI would have flashed.
+    pub async fn handle_request(&mut self, request: Request) -> Reply {
+        match request {
+            Request::Status => Reply::Ok(serde_json::json!({
+                "auto": self.auto_mode,
+                "agents": self.store.get_all(),
+                "queue": self.dispatch_queue,
+            })),
+            Request::Items { filter } => {
+                let filter = filter.unwrap_or_default();
+                let items: Vec<&WorkItem> =
+                    self.items.iter().filter(|item| filter.matches(item)).collect();
+                Reply::Ok(serde_json::json!(items))
+            }
+            Request::Dispatch { id, agent } => {
+                let Some(index) = self.find_item(&id) else {
+                    return Reply::Error(format!("No item {id}"));
+                };
+                self.selected_item = index;
+                self.flash_message = None;
+                match agent {
+                    Some(agent_name) => self.assign_selected_to(agent_name).await,
+                    None => self.dispatch_selected().await,
+                }
+                Reply::Ok(self.take_flash().into())
+            }
+            Request::Clear { agent } => {
+                if self.store.get_agent(agent).is_none() {
+                    return Reply::Error(format!("No agent {agent}"));
+                }
+                self.flash_message = None;
+                self.clear_agent(agent).await;
+                Reply::Ok(self.take_flash().into())
+            }
+            Request::ToggleAuto => {
+                self.update(Action::Key(KeyAction::ToggleAutoMode)).await;
+                Reply::Ok(self.auto_mode.into())
+            }
+            Request::ApprovePlan { agent } => {
+                if self
+                    .store
+                    .get_agent(agent)
+                    .is_none_or(|a| a.status != AgentStatus::Plan)
+                {
+                    return Reply::Error(format!("{agent} has no plan waiting"));
+                }
+                self.flash_message = None;
+                self.approve_plan(agent).await;
+                match self.take_flash() {
+                    msg if msg.is_empty() => Reply::Ok(format!("Started {agent}'s plan").into()),
+                    msg => Reply::Ok(msg.into()),
+                }
+            }
+        }
+    }
+
+    /// Hand the pool to a running daemon, mirroring its auto mode.
+    pub async fn adopt_daemon(&mut self, client: daemon::Client) {
+        if let Ok(status) = client.call(&Request::Status).await {
+            self.auto_mode = status["auto"].as_bool().unwrap_or(false);
+        }
+        self.daemon = Some(client);
+    }
+
+    /// The daemon went away: run the pool here again.
+    fn lose_daemon(&mut self) {
+        if self.daemon.take().is_some() {
+            tracing::warn!("agent daemon went away");
+            self.flash_message = Some((
+                "Agent daemon stopped — running the pool here".into(),
+                Instant::now(),
+            ));
+        }
+    }
+
+    fn take_flash(&mut self) -> String {
+        self.flash_message.take().map(|(msg, _)| msg).unwrap_or_default()
+    }
+
+    /// Send `request` to the daemon, flashing its answer or why it failed.
+    async fn ask_daemon(&mut self, request: Request) -> Option<serde_json::Value> {
+        let daemon = self.daemon.clone()?;
+        match daemon.call(&request).await {
+            Ok(value) => {
+                if let Some(msg) = value.as_str().filter(|m| !m.is_empty()) {
+                    self.flash_message = Some((msg.to_string(), Instant::now()));
+                }
+                Some(value)
+            }
+            Err(e) => {
+                self.flash_message = Some((format!("Daemon: {e}"), Instant::now()));
+                // A daemon that's gone can't be waited for
+                if !daemon.is_listening().await {
+                    self.lose_daemon();
+                }
+                None
+            }
+        }
+    }
+
+    async fn request_quit(&mut self) {
+        let running = self.running_agents().len();
+        // The daemon's agents keep running without us
+        if running == 0 || self.daemon.is_some() {
+            self.quit();
+        } else if self.confirm_destructive {
+            self.confirm = Some(Confirm::Quit(running));
+        } else {
+            self.stop_running_agents().await;
+            self.quit();
+        }
+    }
+
+    /// Leave the dashboard, logging the time of a running pomodoro so far.
+    fn quit(&mut self) {
+        self.stop_pomodoro(false);
+        self.should_quit = true;
+    }
+
+    /// SIGTERM every running agent, SIGKILL whatever is left after a grace
+    /// period, and mark them failed so the next start offers a clean retry
+    /// instead of reporting an unexpected exit.
+    pub async fn stop_running_agents(&mut self) {
+        let running = self.running_agents();
+        let pids: Vec<u32> = running
+            .iter()
+            .filter_map(|name| self.store.get_agent(*name)?.pid)
+            .collect();
+        for pid in &pids {
+            unsafe {
+                libc::kill(*pid as i32, libc::SIGTERM);
+            }
+        }
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pids.iter().any(|pid| is_process_alive(*pid)) && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        for pid in pids.iter().filter(|pid| is_process_alive(**pid)) {
+            unsafe {
+                libc::kill(*pid as i32, libc::SIGKILL);
+            }
+        }
+
+        for name in running {
+            let agent = self.store.get_agent(name);
+            let work_id = agent.and_then(|a| a.work_item_id.clone());
+            let work_title = agent.and_then(|a| a.work_item_title.clone());
+            let _ = self.store.mark_failed(
+                name,
+                "Stopped when work quit",
+                "The previous run was stopped part-way because the dashboard quit",
+            );
+            let _ = append_event(&new_event(
+                name,
+                "stopped",
+                work_id.as_deref(),
+                work_title.as_deref(),
+                Some("Stopped on quit"),
+            ));
+        }
+    }
+
+    fn clear_logs(&mut self, agent_name: AgentName) {
+        let _ = clear_events(agent_name);
+        let _ = clear_thread(agent_name);
+        self.agent_log_scroll = 0;
+        self.flash_message = Some((
+            format!("Cleared logs for {}", agent_name.display_name()),
+            Instant::now(),
+        ));
+        let _ = append_event(&new_event(
+            agent_name,
+            "logs-cleared",
+            None,
+            None,
+            Some("Activity log cleared"),
+        ));
+    }
+
+    /// Scrolling keys while the chat is expanded. Returns false for keys that
+    /// keep their usual meaning, like `:` and `q`.
+    fn handle_chat_focus_key(&mut self, key: &KeyAction) -> bool {
+        match key {
+            KeyAction::Up => self.chat_scroll = (self.chat_scroll + 1).min(self.chat_line_count()),
+            KeyAction::Down => self.chat_scroll = self.chat_scroll.saturating_sub(1),
+            KeyAction::PageUp => {
+                self.chat_scroll = (self.chat_scroll + 10).min(self.chat_line_count())
+            }
+            KeyAction::PageDown => self.chat_scroll = self.chat_scroll.saturating_sub(10),
+            KeyAction::Escape | KeyAction::Char('z') => {
+                self.chat_focus = false;
+                self.chat_scroll = 0;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Keys for the debug log overlay, which scrolls like the chat. Others
+    /// fall through, so `:` and `q` still work with it open.
+    fn handle_debug_log_key(&mut self, key: &KeyAction) -> bool {
+        let lines = logging::recent().len();
+        match key {
+            KeyAction::Up => self.debug_log_scroll = (self.debug_log_scroll + 1).min(lines),
+            KeyAction::Down => self.debug_log_scroll = self.debug_log_scroll.saturating_sub(1),
+            KeyAction::PageUp => self.debug_log_scroll = (self.debug_log_scroll + 10).min(lines),
+            KeyAction::PageDown => self.debug_log_scroll = self.debug_log_scroll.saturating_sub(10),
+            KeyAction::Escape | KeyAction::Char('L') => self.debug_log = false,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Keys for the error center: pick a failure, expand it to the whole
+    /// message, dismiss it or all of them.
+    fn handle_error_center_key(&mut self, key: &KeyAction) -> bool {
+        let count = self.errors.len();
+        match key {
+            KeyAction::Up => {
+                self.selected_error = self.selected_error.saturating_sub(1);
+                self.error_expanded = false;
+            }
+            KeyAction::Down => {
+                self.selected_error = (self.selected_error + 1).min(count.saturating_sub(1));
+                self.error_expanded = false;
+            }
+            KeyAction::Select => self.error_expanded = !self.error_expanded,
+            KeyAction::Char('x') => {
+                self.errors.dismiss(self.selected_error);
+                self.selected_error = self.selected_error.min(self.errors.len().saturating_sub(1));
+                self.error_expanded = false;
+            }
+            KeyAction::Char('X') => {
+                self.errors.clear();
+                self.selected_error = 0;
+            }
+            KeyAction::Escape | KeyAction::Char('!') => self.error_center = false,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Keep a failed provider call in the error center, pointing at it from
+    /// the footer.
+    fn record_failure(&mut self, source: &str, action: String, error: &ProviderError) {
+        tracing::warn!(
+            source,
+            action = action.as_str(),
+            error = %format!("{error:#}"),
+            "provider call failed"
+        );
+        let kind = Some(error.kind());
+        self.errors.record(source, &action, format!("{error:#}"), kind);
+        self.flash_message = Some((
+            format!("Failed to {action} — press ! for details"),
+            Instant::now(),
+        ));
+    }
+
+    /// Unwrapped chat lines: a header, the body and a blank per message.
+    fn chat_line_count(&self) -> usize {
+        self.chat_messages
+            .iter()
+            .map(|m| m.text.lines().count() + 2)
+            .sum()
+    }
+
+    async fn handle_tick(&mut self) {
+        if self
+            .pomodoro
+            .as_ref()
+            .is_some_and(|p| p.is_over(chrono::Utc::now()))
+        {
+            self.stop_pomodoro(true);
+        }
+
+        // The daemon runs the pool; its changes arrive as PollAgents
+        if self.daemon.is_some() {
+            return;
+        }
+
+        // Changes from other processes arrive as PollAgents; this only
+        // catches agents whose process died without a monitor to report it
+        self.store.check_processes();
+
+        self.follow_schedule();
+
+        if self.last_pr_poll.elapsed() >= PR_POLL_INTERVAL {
+            self.last_pr_poll = Instant::now();
+            self.poll_pending_prs().await;
+            self.poll_ci().await;
+        }
+
+        // Try providers again once their backoff is over
+        let now = Instant::now();
+        let before = self.retry_at.len();
+        self.retry_at.retain(|_, at| *at > now);
+        if self.retry_at.len() < before && self.view_mode != ViewMode::BoardSelection {
+            self.refresh_items().await;
+        }
+
+        // Auto-release done agents
+        let done_agents: Vec<AgentName> = self
+            .store
+            .get_all()
+            .iter()
+            .filter(|a| a.status == AgentStatus::Done)
+            .map(|a| a.name)
+            .collect();
+        for name in done_agents {
+            let _ = append_event(&new_event(name, "released", None, None, None));
+            self.release_agent(name);
+        }
+
+        // Idle agents move on to the next item stacked on them
+        self.advance_backlogs().await;
+
+        self.surface_questions();
+        self.surface_status_reports();
+
+        self.detect_stalled();
+
+        // Urgent incidents jump the queue regardless of mode
+        self.escalate_incidents().await;
+
+        // Manually queued items go out as slots free, in either mode
+        self.drain_dispatch_queue().await;
+
+        // Auto-retry and auto-dispatch only in auto mode
+        if self.auto_mode {
             // Auto-retry errored agents
-            let errored_agents: Vec<AgentName> = self
-                .store
-                .get_all()
-                .iter()
-                .filter(|a| a.status == AgentStatus::Error)
-                .map(|a| a.name)
-                .collect();
-            for name in errored_agents {
+            for name in self.store.retry_candidates() {
                 let retry_count = self.store.increment_retry(name).unwrap_or(0);
                 if retry_count <= MAX_RETRIES {
                     let _ = append_event(&new_event(
@@ -645,17 +2312,10 @@ impl App {
                         {
                             if let Some(item) = self.items.iter().find(|i| i.id == item_id) {
                                 let item = item.clone();
-                                let _ = dispatch::dispatch(
-                                    name,
-                                    &item,
-                                    &self.repo_root,
-                                    &mut self.store,
-                                    self.action_tx.clone(),
-                                )
-                                .await;
+                                let _ = self.dispatch_item(name, &item).await;
                             } else {
                                 // Item not in list anymore, just release
-                                let _ = self.store.release(name);
+                                self.release_agent(name);
                             }
                         }
                     }
@@ -667,202 +2327,1839 @@ impl App {
                         None,
                         Some("Max retries reached"),
                     ));
-                    let _ = self.store.release(name);
+                    self.announce(name, NotifyEvent::MaxRetries, "Max retries reached".into());
+                    self.record_failed_item(name);
+                    self.release_agent(name);
+                }
+            }
+
+            // Auto-dispatch to free agents
+            self.auto_dispatch().await;
+        }
+    }
+
+    /// Today's spend and the configured daily budget, if there is one.
+    pub fn budget_status(&self) -> Option<(f64, f64)> {
+        let budget = self.agents_config.daily_budget_usd?;
+        Some((self.store.costs().spent_on(&today()), budget))
+    }
+
+    fn over_budget(&self) -> bool {
+        self.budget_status()
+            .is_some_and(|(spent, budget)| spent >= budget)
+    }
+
+    /// Switch auto mode on as the configured schedule opens and off as it
+    /// closes, unless the day's budget is already spent.
+    fn follow_schedule(&mut self) {
+        let windows = &self.agents_config.auto_schedule;
+        if windows.is_empty() {
+            return;
+        }
+        let open = schedule::is_open(windows, chrono::Local::now().naive_local());
+        if self.schedule_open.replace(open) == Some(open) || self.auto_mode == open {
+            return;
+        }
+        if open && self.over_budget() {
+            return;
+        }
+        self.auto_mode = open;
+        let status = if open { "AUTO" } else { "MANUAL" };
+        let msg = format!("Schedule: switched to {status} mode");
+        let _ = append_event(&new_event(
+            AgentName::ALL[0],
+            "mode-change",
+            None,
+            None,
+            Some(&msg),
+        ));
+        self.flash_message = Some((msg, Instant::now()));
+    }
+
+    /// Whether the schedule turns auto mode on or off next, and how soon.
+    pub fn schedule_countdown(&self) -> Option<(bool, chrono::Duration)> {
+        let windows = &self.agents_config.auto_schedule;
+        if windows.is_empty() {
+            return None;
+        }
+        let now = chrono::Local::now().naive_local();
+        let next = schedule::next_change(windows, now)?;
+        Some((!schedule::is_open(windows, now), next - now))
+    }
+
+    /// Pause auto mode once today's spend reaches the daily budget. Running
+    /// agents finish; nothing new is dispatched.
+    fn enforce_budget(&mut self) {
+        if !self.auto_mode || !self.over_budget() {
+            return;
+        }
+        self.auto_mode = false;
+        let Some((spent, budget)) = self.budget_status() else {
+            return;
+        };
+        let msg = format!("Spent ${spent:.2} of ${budget:.2} today — auto mode paused");
+        let _ = append_event(&new_event(
+            AgentName::ALL[0],
+            "budget-exceeded",
+            None,
+            None,
+            Some(&msg),
+        ));
+        self.flash_message = Some((msg, Instant::now()));
+    }
+
+    /// Pick up questions running agents asked and post new ones to chat.
+    fn surface_questions(&mut self) {
+        let running: Vec<Agent> = self
+            .store
+            .get_all()
+            .into_iter()
+            .filter(|a| matches!(a.status, AgentStatus::Working | AgentStatus::Stalled))
+            .cloned()
+            .collect();
+
+        let mut pending = HashMap::new();
+        for agent in running {
+            let name = agent.name;
+            let Some(wt_path) = &agent.worktree_path else {
+                continue;
+            };
+            let asked = questions::pending_questions(Path::new(wt_path));
+            if asked.is_empty() {
+                continue;
+            }
+            let known = self.pending_questions.get(&name);
+            for q in &asked {
+                if known.is_some_and(|k| k.contains(q)) {
+                    continue;
+                }
+                self.chat_messages.push(ChatMessage::agent(
+                    name,
+                    format!("\u{2753} {} (reply with @{} <answer>)", q.question, name.as_str()),
+                ));
+                let _ = append_event(&new_event(
+                    name,
+                    "question",
+                    agent.work_item_id.as_deref(),
+                    agent.work_item_title.as_deref(),
+                    Some(&q.question),
+                ));
+                self.flash_message = Some((
+                    format!("{} has a question", name.display_name()),
+                    Instant::now(),
+                ));
+            }
+            pending.insert(name, asked);
+        }
+        self.pending_questions = pending;
+    }
+
+    /// Pick up status lines running agents wrote, logging each new one.
+    fn surface_status_reports(&mut self) {
+        let mut reports = HashMap::new();
+        for agent in self.store.get_all() {
+            if !matches!(agent.status, AgentStatus::Working | AgentStatus::Stalled) {
+                continue;
+            }
+            let Some(status) = agent
+                .worktree_path
+                .as_deref()
+                .and_then(|wt_path| report::read_report(Path::new(wt_path)))
+            else {
+                continue;
+            };
+            let known = self.status_reports.get(&agent.name);
+            if known.is_none_or(|k| k.text != status.text) {
+                let _ = append_event(&new_event(
+                    agent.name,
+                    "status",
+                    agent.work_item_id.as_deref(),
+                    agent.work_item_title.as_deref(),
+                    Some(&status.text),
+                ));
+            }
+            reports.insert(agent.name, status);
+        }
+        self.status_reports = reports;
+    }
+
+    /// Mark agents that have run past `max_runtime_mins` as Stalled, killing
+    /// them when `kill_stalled` is set so the auto-retry path takes over.
+    fn detect_stalled(&mut self) {
+        let Some(max_mins) = self.agents_config.max_runtime_mins else {
+            return;
+        };
+        let max_runtime = chrono::Duration::minutes(max_mins);
+        let now = chrono::Utc::now();
+
+        let overdue: Vec<Agent> = self
+            .store
+            .get_all()
+            .into_iter()
+            .filter(|a| a.status == AgentStatus::Working)
+            .filter(|a| a.runtime(now).is_some_and(|r| r > max_runtime))
+            // Waiting on the user is not stalling
+            .filter(|a| !self.pending_questions.contains_key(&a.name))
+            .cloned()
+            .collect();
+
+        for agent in overdue {
+            let name = agent.name;
+            let msg = format!("No result after {max_mins}m");
+            let _ = append_event(&new_event(
+                name,
+                "stalled",
+                agent.work_item_id.as_deref(),
+                agent.work_item_title.as_deref(),
+                Some(&msg),
+            ));
+
+            if self.agents_config.kill_stalled {
+                if let Some(pid) = agent.pid {
+                    unsafe {
+                        libc::kill(pid as i32, libc::SIGTERM);
+                    }
+                }
+                let reason = format!("Timed out after {max_mins}m");
+                let context = failure_context(
+                    &format!("{reason} without finishing; work in smaller steps"),
+                    &read_log_tail(name),
+                );
+                let _ = self.store.mark_failed(name, &reason, &context);
+            } else {
+                let _ = self.store.mark_stalled(name);
+            }
+
+            self.flash_message = Some((
+                format!("{} stalled: {msg}", name.display_name()),
+                Instant::now(),
+            ));
+        }
+    }
+
+    async fn auto_dispatch(&mut self) {
+        loop {
+            // Next item in queue order: manual queue, then by priority.
+            // Blocked items wait in the queue until their blockers are done,
+            // and items for a repo at capacity until it has room.
+            let next_item = self
+                .queued_item_ids()
+                .iter()
+                .filter_map(|id| self.items.iter().find(|i| i.id == *id))
+                .find(|item| !item.is_blocked() && self.dispatch_blocked(item).is_none())
+                .cloned();
+
+            match next_item {
+                Some(item) => {
+                    let Some(free_agent) = self.choose_agent(&item) else {
+                        break;
+                    };
+                    self.dispatch_queue.retain(|id| *id != item.id);
+                    self.dispatched_item_ids.insert(item.id.clone());
+                    if self.dispatch_item(free_agent, &item).await.is_ok() {
+                        self.move_item_to_in_progress(&item).await;
+                    }
                 }
+                None => break,
+            }
+        }
+    }
+
+    /// Dispatch undispatched Urgent incidents straight to an idle Ember
+    /// worker, bypassing the queue but not the capacity and budget limits.
+    async fn escalate_incidents(&mut self) {
+        if !self.escalate_incidents || self.daemon.is_some() {
+            return;
+        }
+
+        let urgent = queue::priority_rank(Some("Urgent"));
+        let incident = self
+            .items
+            .iter()
+            .find(|item| {
+                providers::is_incident(item)
+                    && queue::priority_rank(item.priority.as_deref()) >= urgent
+                    && !self.dispatched_item_ids.contains(&item.id)
+                    && !self.store.is_failed(&item.id)
+            })
+            .cloned();
+        let Some(item) = incident else {
+            return;
+        };
+
+        let blocked = self.dispatch_blocked(&item).or_else(|| {
+            self.over_budget()
+                .then(|| "today's budget is spent".to_string())
+        });
+        if let Some(reason) = blocked {
+            self.incident_alert = Some(format!(
+                "P1 {}: {} — waiting: {reason}",
+                item.id, item.title
+            ));
+            return;
+        }
+        let ember = self
+            .store
+            .get_all()
+            .iter()
+            .find(|a| {
+                a.name.persona() == Persona::Ember
+                    && a.status == AgentStatus::Idle
+                    && !self.pruning.contains(&a.name)
+                    && self.agents_config.runs(a.name)
+            })
+            .map(|a| a.name);
+        let Some(ember) = ember else {
+            self.incident_alert = Some(format!(
+                "P1 {}: {} — waiting for Ember",
+                item.id, item.title
+            ));
+            return;
+        };
+
+        self.dispatched_item_ids.insert(item.id.clone());
+        match self.dispatch_item(ember, &item).await {
+            Ok(_) => {
+                self.move_item_to_in_progress(&item).await;
+                self.incident_alert = Some(format!(
+                    "P1 {}: {} — escalated to {}",
+                    item.id,
+                    item.title,
+                    ember.display_name()
+                ));
+            }
+            Err(e) => {
+                self.incident_alert =
+                    Some(format!("P1 {}: escalation failed: {e}", item.id));
             }
+        }
+    }
+
+    async fn dispatch_selected(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
+        let item = self.items[self.selected_item].clone();
+        if self.daemon.is_some() {
+            let request = Request::Dispatch {
+                id: item.id,
+                agent: None,
+            };
+            self.ask_daemon(request).await;
+            return;
+        }
+
+        let blocked = self.dispatch_blocked(&item).or_else(|| {
+            self.store
+                .next_free_agent()
+                .is_none()
+                .then(|| "all agents busy".to_string())
+        });
+        if let Some(reason) = blocked {
+            let message = if providers::is_review_request(&item) {
+                format!("Can't review {} now — {reason}", item.id)
+            } else {
+                self.enqueue(&item);
+                format!("Queued {} — {reason}", item.id)
+            };
+            self.flash_message = Some((message, Instant::now()));
+            return;
+        }
+
+        let free_agent = self.choose_agent(&item);
+        match free_agent {
+            Some(agent_name) => {
+                self.dispatched_item_ids.insert(item.id.clone());
+                match self.dispatch_item(agent_name, &item).await {
+                    Ok(_) => {
+                        self.move_item_to_in_progress(&item).await;
+                        self.undo.push(Undoable::Dispatch {
+                            item: item.clone(),
+                            agent: agent_name,
+                        });
+                        self.flash_message = Some((
+                            format!(
+                                "{} dispatched to {}",
+                                item.id,
+                                agent_name.display_name()
+                            ),
+                            Instant::now(),
+                        ));
+                    }
+                    Err(e) => {
+                        self.flash_message =
+                            Some((format!("Dispatch failed: {e}"), Instant::now()));
+                    }
+                }
+            }
+            None => {
+                self.flash_message = Some(("All agents busy".into(), Instant::now()));
+            }
+        }
+    }
+
+    /// Add an item to the dispatch queue behind everything at least as
+    /// important. No-op if it is already queued or dispatched, or is a pull
+    /// request to review, which only starts by hand.
+    fn enqueue(&mut self, item: &WorkItem) {
+        if self.dispatch_queue.contains(&item.id)
+            || self.dispatched_item_ids.contains(&item.id)
+            || providers::is_review_request(item)
+        {
+            return;
+        }
+        let boost = &self.agents_config.priority_labels;
+        let scores: Vec<u8> = self
+            .dispatch_queue
+            .iter()
+            .map(|id| {
+                self.items
+                    .iter()
+                    .find(|i| i.id == *id)
+                    .map_or(0, |i| queue::dispatch_score(i, boost))
+            })
+            .collect();
+        let pos = queue::insert_position(&scores, queue::dispatch_score(item, boost));
+        self.dispatch_queue.insert(pos, item.id.clone());
+    }
+
+    /// `e` on an item: queue it, or take it back out of the queue.
+    fn toggle_queued_selected(&mut self) {
+        let Some(item) = self.items.get(self.selected_item).cloned() else {
+            return;
+        };
+        if let Some(pos) = self.dispatch_queue.iter().position(|id| *id == item.id) {
+            self.dispatch_queue.remove(pos);
+            self.flash_message = Some((format!("Removed {} from queue", item.id), Instant::now()));
+        } else if self.dispatched_item_ids.contains(&item.id) {
+            self.flash_message = Some((format!("{} already dispatched", item.id), Instant::now()));
+        } else if providers::is_review_request(&item) {
+            self.flash_message = Some((
+                format!("{} is a review request — dispatch it with d", item.id),
+                Instant::now(),
+            ));
+        } else {
+            self.enqueue(&item);
+            self.flash_message = Some((format!("Queued {}", item.id), Instant::now()));
+        }
+    }
+
+    /// Show the calendar on the selected item's due date, or today.
+    fn open_calendar(&mut self) {
+        let day = self
+            .items
+            .get(self.selected_item)
+            .and_then(WorkItem::due_date);
+        self.calendar = day.map(Calendar::at).unwrap_or_default();
+        self.view_mode = ViewMode::Calendar;
+    }
+
+    /// Calendar navigation: arrows move by day and week, `[`/`]` pick an item
+    /// due that day, enter opens it and tab switches between month and week.
+    /// Returns false for keys the calendar leaves to the normal handling.
+    fn handle_calendar_key(&mut self, key: &KeyAction) -> bool {
+        let due = self.items_due_on(self.calendar.day).len();
+        match key {
+            KeyAction::Left => self.calendar.move_days(-1),
+            KeyAction::Right => self.calendar.move_days(1),
+            KeyAction::Up => self.calendar.move_days(-7),
+            KeyAction::Down => self.calendar.move_days(7),
+            KeyAction::Tab => self.calendar.month = !self.calendar.month,
+            KeyAction::Char('[') => self.calendar.pick = self.calendar.pick.saturating_sub(1),
+            KeyAction::Char(']') => {
+                self.calendar.pick = (self.calendar.pick + 1).min(due.saturating_sub(1));
+            }
+            KeyAction::Select => {
+                let due = self.items_due_on(self.calendar.day);
+                if let Some(&index) = due.get(self.calendar.pick) {
+                    self.selected_item = index;
+                    self.show_item_detail = true;
+                    self.view_mode = ViewMode::Items;
+                }
+            }
+            KeyAction::Escape | KeyAction::Char('v') => self.view_mode = ViewMode::Items,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Indices of items passing the filter that are due on `day`.
+    pub fn items_due_on(&self, day: chrono::NaiveDate) -> Vec<usize> {
+        self.visible_item_indices()
+            .into_iter()
+            .filter(|&i| self.items[i].due_date() == Some(day))
+            .collect()
+    }
+
+    /// `o`: open the selected item in the browser.
+    fn open_selected(&mut self) {
+        let Some(item) = self.items.get(self.selected_item) else {
+            return;
+        };
+        let msg = match item.url.as_deref().map(browser::open) {
+            Some(Ok(())) => format!("Opened {}", item.id),
+            Some(Err(e)) => format!("Failed to open {}: {e}", item.id),
+            None => format!("{} has no URL", item.id),
+        };
+        self.flash_message = Some((msg, Instant::now()));
+    }
+
+    /// `p`: pin the selected item as the one you're working on, moving it to
+    /// in progress, or unpin it.
+    async fn toggle_focus_selected(&mut self) {
+        let Some(item) = self.items.get(self.selected_item).cloned() else {
+            return;
+        };
+        if self.focus.as_ref().is_some_and(|f| f.is(&item)) {
+            self.unpin();
+            self.flash_message = Some((format!("Unpinned {}", item.id), Instant::now()));
+            return;
+        }
+        self.stop_pomodoro(false);
+        let pinned = Focus::of(&item);
+        if let Err(e) = focus::save_focus(&pinned) {
+            self.flash_message = Some((format!("Failed to pin {}: {e}", item.id), Instant::now()));
+            return;
+        }
+        self.focus = Some(pinned);
+        self.flash_message = Some((format!("Working on {}", item.id), Instant::now()));
+        self.move_item_to_in_progress(&item).await;
+    }
+
+    fn unpin(&mut self) {
+        self.stop_pomodoro(false);
+        self.focus = None;
+        focus::clear_focus();
+    }
+
+    /// `t`: start a pomodoro on the pinned item, or stop the running one.
+    fn toggle_pomodoro(&mut self) {
+        if self.pomodoro.is_some() {
+            self.stop_pomodoro(false);
+            return;
+        }
+        let Some(pinned) = &self.focus else {
+            self.flash_message = Some(("Pin an item with p first".into(), Instant::now()));
+            return;
+        };
+        let session = Pomodoro::start(pinned, self.pomodoro_length, chrono::Utc::now());
+        self.flash_message = Some((
+            format!(
+                "Pomodoro on {} — {}",
+                pinned.id,
+                focus::format_minutes(self.pomodoro_length.num_minutes())
+            ),
+            Instant::now(),
+        ));
+        self.pomodoro = Some(session);
+    }
+
+    /// End the running pomodoro and log its time against the pinned item.
+    /// A session that ran its full length also raises a notification.
+    fn stop_pomodoro(&mut self, finished: bool) {
+        let Some(session) = self.pomodoro.take() else {
+            return;
+        };
+        let entry = session.entry(chrono::Utc::now());
+        let msg = match focus::log_time(&entry) {
+            Ok(()) => format!(
+                "Logged {} on {} ({} total)",
+                focus::format_minutes(entry.minutes),
+                entry.id,
+                focus::format_minutes(focus::tracked_minutes(&entry.source, &entry.id))
+            ),
+            Err(e) => format!("Failed to log time on {}: {e}", entry.id),
+        };
+        if finished {
+            notify(
+                &self.notifications,
+                NotifyEvent::PomodoroDone,
+                format!("Pomodoro done: {}", entry.id),
+                msg.clone(),
+            );
+        }
+        self.flash_message = Some((msg, Instant::now()));
+    }
+
+    /// Move the selected item one place earlier (`up`) or later in the queue.
+    fn move_queued_selected(&mut self, up: bool) {
+        let Some(item) = self.items.get(self.selected_item) else {
+            return;
+        };
+        let Some(pos) = self.dispatch_queue.iter().position(|id| *id == item.id) else {
+            return;
+        };
+        let target = if up {
+            pos.checked_sub(1)
+        } else {
+            Some(pos + 1).filter(|&p| p < self.dispatch_queue.len())
+        };
+        if let Some(target) = target {
+            self.dispatch_queue.swap(pos, target);
+        }
+    }
+
+    /// Dispatch queued items while agents and capacity are available.
+    async fn drain_dispatch_queue(&mut self) {
+        // Drop items that left the list or were picked up elsewhere, and pull
+        // requests, which are only reviewed on request
+        let items = &self.items;
+        let dispatched = &self.dispatched_item_ids;
+        self.dispatch_queue.retain(|id| {
+            !dispatched.contains(id)
+                && items
+                    .iter()
+                    .any(|i| i.id == *id && !providers::is_review_request(i))
+        });
+        while !self.dispatch_queue.is_empty() {
+            if self.store.next_free_agent().is_none() {
+                break;
+            }
+            // The first queued item whose repo has room
+            let Some(item) = self
+                .dispatch_queue
+                .iter()
+                .filter_map(|id| self.items.iter().find(|i| i.id == *id))
+                .find(|item| self.dispatch_blocked(item).is_none())
+                .cloned()
+            else {
+                break;
+            };
+            let Some(agent_name) = self.choose_agent(&item) else {
+                break;
+            };
+            self.dispatch_queue.retain(|id| *id != item.id);
+            self.dispatched_item_ids.insert(item.id.clone());
+            if self.dispatch_item(agent_name, &item).await.is_ok() {
+                self.move_item_to_in_progress(&item).await;
+            }
+        }
+    }
+
+    /// `1`–`4` on an item: give it to that agent now if it's idle, otherwise
+    /// stack it on the agent's backlog.
+    async fn assign_selected_to(&mut self, agent_name: AgentName) {
+        let Some(item) = self.items.get(self.selected_item).cloned() else {
+            return;
+        };
+        if self.daemon.is_some() {
+            let request = Request::Dispatch {
+                id: item.id,
+                agent: Some(agent_name),
+            };
+            self.ask_daemon(request).await;
+            return;
+        }
+        if self.dispatched_item_ids.contains(&item.id) {
+            self.flash_message = Some((format!("{} already assigned", item.id), Instant::now()));
+            return;
+        }
+        self.dispatch_queue.retain(|id| *id != item.id);
+
+        let Some(agent) = self.store.get_agent(agent_name) else {
+            return;
+        };
+        let can_start = agent.status == AgentStatus::Idle
+            && agent.backlog.is_empty()
+            && self.dispatch_blocked(&item).is_none();
+        if can_start {
+            self.dispatched_item_ids.insert(item.id.clone());
+            match self.dispatch_item(agent_name, &item).await {
+                Ok(_) => {
+                    self.move_item_to_in_progress(&item).await;
+                    self.undo.push(Undoable::Dispatch {
+                        item: item.clone(),
+                        agent: agent_name,
+                    });
+                    self.flash_message = Some((
+                        format!("{} dispatched to {}", item.id, agent_name.display_name()),
+                        Instant::now(),
+                    ));
+                }
+                Err(e) => {
+                    self.flash_message = Some((format!("Dispatch failed: {e}"), Instant::now()));
+                }
+            }
+            return;
+        }
+
+        let max = self
+            .agents_config
+            .max_agent_backlog
+            .unwrap_or(DEFAULT_AGENT_BACKLOG);
+        if agent.backlog.len() >= max {
+            self.flash_message = Some((
+                format!("{} already has {max} items stacked", agent_name.display_name()),
+                Instant::now(),
+            ));
+            return;
+        }
+        if let Ok(pos) = self.store.push_backlog(agent_name, &item.id) {
+            self.dispatched_item_ids.insert(item.id.clone());
+            let _ = append_event(&new_event(
+                agent_name,
+                "stacked",
+                Some(&item.id),
+                Some(&item.title),
+                Some(&format!("Backlog position {pos}")),
+            ));
+            self.flash_message = Some((
+                format!("{} stacked on {} (#{pos})", item.id, agent_name.display_name()),
+                Instant::now(),
+            ));
+        }
+    }
+
+    /// Start the next backlog item on each idle agent that has one.
+    async fn advance_backlogs(&mut self) {
+        let ready: Vec<AgentName> = self
+            .store
+            .get_all()
+            .iter()
+            .filter(|a| a.status == AgentStatus::Idle && !a.backlog.is_empty())
+            .map(|a| a.name)
+            .collect();
+        for name in ready {
+            // Items that dropped off the board are skipped
+            while let Some(item_id) = self
+                .store
+                .get_agent(name)
+                .and_then(|a| a.backlog.first().cloned())
+            {
+                let Some(item) = self.items.iter().find(|i| i.id == item_id).cloned() else {
+                    self.store.pop_backlog(name);
+                    continue;
+                };
+                // It waits its turn until its repo has room
+                if self.dispatch_blocked(&item).is_some() {
+                    break;
+                }
+                self.store.pop_backlog(name);
+                if self.dispatch_item(name, &item).await.is_ok() {
+                    self.move_item_to_in_progress(&item).await;
+                }
+                break;
+            }
+        }
+    }
+
+    /// Agent an undispatched item is stacked on, if any.
+    pub fn stacked_agent(&self, item_id: &str) -> Option<AgentName> {
+        self.store
+            .get_all()
+            .iter()
+            .find(|a| a.backlog.iter().any(|id| id == item_id))
+            .map(|a| a.name)
+    }
+
+    /// Put the agent's item on the failed list with the errors of its
+    /// attempts, so auto mode stops picking it up.
+    fn record_failed_item(&mut self, name: AgentName) {
+        let Some(agent) = self.store.get_agent(name) else {
+            return;
+        };
+        let (Some(id), Some(title)) = (agent.work_item_id.clone(), agent.work_item_title.clone())
+        else {
+            return;
+        };
+        let events = query_events(&EventQuery {
+            agent: Some(name),
+            event: Some("error".into()),
+            work_item_id: Some(id.clone()),
+            limit: Some(20),
+            ..Default::default()
+        });
+        let mut errors = error_history(&events);
+        if errors.is_empty() {
+            errors.extend(agent.error.clone());
+        }
+        let _ = self.store.add_failed_item(FailedItem {
+            work_item_id: id.clone(),
+            work_item_title: title,
+            agent: name,
+            failed_at: chrono::Utc::now().to_rfc3339(),
+            errors,
+        });
+        self.chat_messages.push(ChatMessage::system(format!(
+            "{id} failed {MAX_RETRIES} retries and won't be picked again — /requeue {id} to retry it"
+        )));
+    }
+
+    fn requeue(&mut self, id: &str) {
+        let ids: Vec<String> = if id == "all" {
+            self.store
+                .failed_items()
+                .iter()
+                .map(|f| f.work_item_id.clone())
+                .collect()
+        } else {
+            vec![id.to_string()]
+        };
+        let mut requeued = Vec::new();
+        for id in ids {
+            if self.store.requeue_failed(&id).unwrap_or(false) {
+                self.dispatched_item_ids.remove(&id);
+                requeued.push(id);
+            }
+        }
+        let msg = if requeued.is_empty() {
+            format!("{id} is not on the failed list")
+        } else {
+            format!("Requeued {}", requeued.join(", "))
+        };
+        self.flash_message = Some((msg, Instant::now()));
+    }
+
+    /// `/failed`: each failed item with the errors of its attempts.
+    fn show_failed(&mut self) {
+        let failed = self.store.failed_items();
+        let msg = if failed.is_empty() {
+            "No failed items".to_string()
+        } else {
+            failed
+                .iter()
+                .map(|f| {
+                    let mut entry = format!(
+                        "{} {} (last tried by {})",
+                        f.work_item_id,
+                        f.work_item_title,
+                        f.agent.display_name()
+                    );
+                    for error in &f.errors {
+                        entry.push_str(&format!("\n  - {error}"));
+                    }
+                    entry
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        self.chat_messages.push(ChatMessage::system(msg));
+    }
+
+    /// Release an agent and, unless more items are stacked on it, remove its
+    /// worktree in the background so `target/` dirs don't pile up.
+    fn release_agent(&mut self, name: AgentName) {
+        let repo_root = self.agent_repo_root(name);
+        let _ = self.store.release(name);
+        if self
+            .store
+            .get_agent(name)
+            .is_some_and(|a| !a.backlog.is_empty())
+        {
+            return;
+        }
+        self.pruning.insert(name);
+        let tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            let _ = worktree::remove_worktree(&repo_root, name).await;
+            let _ = tx.send(Action::WorktreePruned(name));
+        });
+    }
+
+    fn measure_disk_usage(&self, name: AgentName) {
+        let repo_root = self.agent_repo_root(name);
+        let tx = self.action_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            let size = worktree::worktree_size(&repo_root, name);
+            let _ = tx.send(Action::DiskUsage(name, size));
+        });
+    }
+
+    fn load_git_summary(&self, name: AgentName) {
+        let Some(agent) = self.store.get_agent(name) else {
+            return;
+        };
+        let Some(wt_path) = agent.worktree_path.clone() else {
+            return;
+        };
+        let base = agent.base().to_string();
+        let tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            let summary = git_summary::summarize(&wt_path, &base).await.ok();
+            let _ = tx.send(Action::GitSummary(name, summary));
+        });
+    }
+
+    /// Repository of the agent's current worktree, defaulting to `repo_root`.
+    fn agent_repo_root(&self, name: AgentName) -> String {
+        self.store
+            .get_agent(name)
+            .and_then(|a| a.repo_root.clone())
+            .unwrap_or_else(|| self.repo_root.clone())
+    }
+
+    /// Agent the Agents view is on, or the one whose detail is open.
+    fn viewed_agent(&self) -> Option<AgentName> {
+        match self.view_mode {
+            ViewMode::AgentDetail(name) => Some(name),
+            ViewMode::Agents => self.selected_agent_name(),
+            _ => None,
+        }
+    }
+
+    /// Worktree of the viewed agent, flashing why when there is none.
+    fn viewed_worktree(&mut self) -> Option<String> {
+        let name = self.viewed_agent()?;
+        let path = self
+            .store
+            .get_agent(name)
+            .and_then(|agent| worktree::agent_worktree(agent, &self.repo_root));
+        if path.is_none() {
+            self.flash_message = Some((
+                format!("{} has no worktree", name.display_name()),
+                Instant::now(),
+            ));
+        }
+        path
+    }
+
+    /// Agent under the cursor in the Agents view.
+    fn selected_agent_name(&self) -> Option<AgentName> {
+        self.store.get_all().get(self.selected_agent).map(|a| a.name)
+    }
+
+    /// Idle agent best suited to `item` by configured specialty.
+    fn choose_agent(&self, item: &WorkItem) -> Option<AgentName> {
+        let idle: Vec<AgentName> = self
+            .store
+            .get_all()
+            .iter()
+            .filter(|a| a.status == AgentStatus::Idle && !self.pruning.contains(&a.name))
+            .map(|a| a.name)
+            .filter(|name| {
+                let roster = &self.agents_config.roster;
+                self.agents_config.runs(*name)
+                    && (roster.is_empty()
+                        || roster.iter().any(|r| {
+                            r.eq_ignore_ascii_case(name.as_str())
+                                || r.eq_ignore_ascii_case(name.persona().as_str())
+                        }))
+            })
+            .collect();
+        matching::pick_agent(
+            item,
+            &self.agents_config.specialties,
+            &idle,
+            self.last_assigned,
+        )
+    }
+
+    /// Repo root of each agent provisioning or running.
+    fn active_repo_roots(&self) -> Vec<&str> {
+        self.store
+            .get_all()
+            .into_iter()
+            .filter(|a| {
+                matches!(
+                    a.status,
+                    AgentStatus::Provisioning | AgentStatus::Working | AgentStatus::Stalled
+                )
+            })
+            .map(|a| a.repo_root.as_deref().unwrap_or(&self.repo_root))
+            .collect()
+    }
+
+    /// Why dispatching `item` must wait for capacity in its repo, if it must.
+    fn dispatch_blocked(&self, item: &WorkItem) -> Option<String> {
+        let repo_root = resolve_repo(&self.agents_config, item, &self.repo_root);
+        capacity::dispatch_blocked(&self.agents_config, &repo_root, &self.active_repo_roots())
+    }
+
+    async fn clear_agent(&mut self, agent_name: AgentName) {
+        if self.daemon.is_some() {
+            self.ask_daemon(Request::Clear { agent: agent_name }).await;
+            return;
+        }
+        if let Some(agent) = self.store.get_agent(agent_name) {
+            if agent.status == AgentStatus::Idle {
+                self.flash_message = Some((
+                    format!("{} is already idle", agent_name.display_name()),
+                    Instant::now(),
+                ));
+                return;
+            }
+
+            // Kill process if running
+            if let Some(pid) = agent.pid {
+                unsafe {
+                    libc::kill(pid as i32, libc::SIGTERM);
+                }
+            }
+
+            let work_title = agent.work_item_title.clone();
+            let work_id = agent.work_item_id.clone();
+
+            // Remove item from dispatched set so it can be re-assigned
+            if let Some(item_id) = &work_id {
+                self.dispatched_item_ids.remove(item_id);
+            }
+
+            // Release the agent
+            self.release_agent(agent_name);
+            let _ = append_event(&new_event(
+                agent_name,
+                "cleared",
+                work_id.as_deref(),
+                work_title.as_deref(),
+                Some("Agent cleared by user"),
+            ));
+
+            self.flash_message = Some((
+                format!("{} cleared", agent_name.display_name()),
+                Instant::now(),
+            ));
+        }
+    }
+
+    /// List boards from every provider in the background; each provider's
+    /// boards stream into the picker as its request finishes.
+    pub fn fetch_boards(&mut self) {
+        self.available_boards.clear();
+        self.selected_board = 0;
+        self.board_loads.clear();
+        self.list_boards(None);
+    }
+
+    /// List boards again from a provider whose listing failed.
+    fn retry_boards(&mut self, source: &str) {
+        tracing::info!(source, "retrying board listing");
+        self.list_boards(Some(source));
+        // Its row is a spinner until it answers
+        let rows = self.available_boards.len() + self.failed_board_sources().len();
+        self.selected_board = self.selected_board.min(rows.saturating_sub(1));
+    }
+
+    /// Start listing the boards of every provider, or only `source`.
+    fn list_boards(&mut self, source: Option<&str>) {
+        // Fresh provider instances, since the app's own are borrowed for
+        // item fetches and carry board filters
+        let config = match config::load_config() {
+            Ok(config) => config,
+            Err(e) => {
+                let _ = self.action_tx.send(Action::FetchError {
+                    source: "config".into(),
+                    action: "load config",
+                    error: format!("{e:#}"),
+                    kind: None,
+                });
+                return;
+            }
+        };
+        for provider in providers::create_cached_providers(&config, true) {
+            if source.is_some_and(|s| s != provider.name()) {
+                continue;
+            }
+            let source = provider.name().to_string();
+            match self.board_loads.iter_mut().find(|(s, _)| *s == source) {
+                Some(entry) => entry.1 = BoardLoad::Loading,
+                None => self.board_loads.push((source.clone(), BoardLoad::Loading)),
+            }
+            let tx = self.action_tx.clone();
+            tokio::spawn(async move {
+                let result = provider.list_boards().await.map_err(|e| e.to_string());
+                let _ = tx.send(Action::BoardsLoaded(source, result));
+            });
+        }
+    }
+
+    /// Providers whose board listing failed, in the order the picker shows
+    /// them after the boards.
+    pub fn failed_board_sources(&self) -> Vec<&str> {
+        self.board_loads
+            .iter()
+            .filter(|(_, load)| matches!(load, BoardLoad::Failed(_)))
+            .map(|(source, _)| source.as_str())
+            .collect()
+    }
+
+    pub fn boards_loading(&self) -> bool {
+        self.board_loads
+            .iter()
+            .any(|(_, load)| *load == BoardLoad::Loading)
+    }
+
+    async fn select_board(&mut self) {
+        let board = &self.available_boards[self.selected_board];
+        let mapping = BoardMapping {
+            board_id: board.id.clone(),
+            board_name: board.name.clone(),
+            source: board.source.clone(),
+        };
+
+        // Save mapping
+        if let Err(e) = config::save_board_mapping(&self.project_dir, &mapping) {
+            self.flash_message = Some((format!("Failed to save mapping: {e}"), Instant::now()));
+            return;
+        }
+
+        // Apply board filter to the matching provider
+        for provider in &mut self.providers {
+            if provider.name() == mapping.source {
+                provider.set_board_filter(mapping.board_id.clone());
+            }
+        }
+        // The old board's items are filtered out, not closed, so they mustn't
+        // read as gone upstream and close their mirrors
+        self.keep_selection(|app| {
+            let (gone, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut app.items)
+                .into_iter()
+                .partition(|item| item.source == mapping.source);
+            app.items = kept;
+            for item in &gone {
+                app.forget_item(&item.id);
+            }
+        });
+
+        self.flash_message = Some((format!("Board: {}", mapping.board_name), Instant::now()));
+        self.view_mode = ViewMode::Items;
+        self.refresh_items().await;
+    }
+
+    pub async fn refresh_items(&mut self) {
+        self.loading = true;
+        let tx = self.action_tx.clone();
+
+        let mut all_items = Vec::new();
+        let mut fetched = Vec::new();
+        let mut backoffs = Vec::new();
+        let now = Instant::now();
+        let current = |source: &str| {
+            self.items
+                .iter()
+                .filter(|i| i.source == source)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        // Fetch from all providers (we need to do this on the current task since providers aren't Send-safe with references)
+        for provider in &self.providers {
+            let source = provider.name();
+            // Still backing off: keep what it last returned, so its items
+            // don't read as gone upstream
+            if self.retry_at.get(source).is_some_and(|at| *at > now) {
+                all_items.extend(current(source));
+                continue;
+            }
+            match provider.fetch_items().await {
+                Ok(items) => {
+                    all_items.extend(items);
+                    fetched.push(source.to_string());
+                }
+                Err(e) => {
+                    if let Some(wait) = e.retry_in() {
+                        tracing::info!(source, wait_secs = wait.as_secs(), "backing off");
+                        backoffs.push((source.to_string(), now + wait));
+                    }
+                    all_items.extend(current(source));
+                    let _ = tx.send(Action::FetchError {
+                        source: source.to_string(),
+                        action: "fetch items",
+                        error: format!("{e:#}"),
+                        kind: Some(e.kind()),
+                    });
+                }
+            }
+        }
+
+        for source in fetched {
+            self.errors.resolve(&source, "fetch items");
+        }
+        self.retry_at.extend(backoffs);
+        let _ = tx.send(Action::WorkItemsLoaded(all_items));
+    }
+
+    /// Save a markdown transcript of the agent's run, optionally posting it
+    /// as a comment on the tracker item.
+    async fn export_transcript(&mut self, name: AgentName) {
+        let Some(agent) = self.store.get_agent(name) else {
+            return;
+        };
+        let (Some(item_id), Some(item_title)) =
+            (agent.work_item_id.clone(), agent.work_item_title.clone())
+        else {
+            return;
+        };
+        let started = agent
+            .started_at
+            .as_deref()
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok());
+
+        let events: Vec<AgentEvent> = query_events(&EventQuery {
+            agent: Some(name),
+            work_item_id: Some(item_id.clone()),
+            ..Default::default()
+        });
+        let chat: Vec<&ChatMessage> = self
+            .chat_messages
+            .iter()
+            .filter(|m| m.involves(name))
+            .filter(|m| started.is_none_or(|s| m.sent_at >= s))
+            .collect();
+
+        let transcript = build_transcript(&item_id, &item_title, name, &events, &chat);
+        match save_transcript(&item_id, &transcript) {
+            Ok(path) => {
+                let _ = append_event(&new_event(
+                    name,
+                    "transcript",
+                    Some(&item_id),
+                    Some(&item_title),
+                    Some(&path.display().to_string()),
+                ));
+            }
+            Err(e) => {
+                self.flash_message =
+                    Some((format!("Failed to save transcript: {e}"), Instant::now()));
+            }
+        }
+
+        if !self.agents_config.comment_transcripts {
+            return;
+        }
+        let Some(item) = self.items.iter().find(|i| i.id == item_id) else {
+            return;
+        };
+        let Some(source_id) = &item.source_id else {
+            return;
+        };
+        if let Some(provider) = self.providers.iter().find(|p| p.name() == item.source) {
+            if let Err(e) = provider.add_comment(source_id, &transcript).await {
+                let source = item.source.clone();
+                self.record_failure(&source, format!("comment on {item_id}"), &e);
+            }
+        }
+    }
+
+    /// Run the hygiene check on a finished agent's branch. Returns true (and
+    /// marks the agent for review) when something suspicious was found.
+    async fn flag_unsafe_changes(&mut self, name: AgentName) -> bool {
+        let Some(agent) = self.store.get_agent(name) else {
+            return false;
+        };
+        let Some(wt_path) = agent.worktree_path.clone() else {
+            return false;
+        };
+        let work_id = agent.work_item_id.clone();
+        let work_title = agent.work_item_title.clone();
+        let base = format!("origin/{}", agent.base());
+
+        let findings = match hygiene::check_branch(&wt_path, &base).await {
+            Ok(findings) => findings,
+            Err(e) => {
+                let _ = append_event(&new_event(
+                    name,
+                    "error",
+                    work_id.as_deref(),
+                    work_title.as_deref(),
+                    Some(&format!("Hygiene check failed to run: {e}")),
+                ));
+                return false;
+            }
+        };
+        if findings.is_empty() {
+            return false;
+        }
+
+        let summary: Vec<String> = findings.iter().map(|f| f.to_string()).collect();
+        let msg = format!("Flagged for review: {}", summary.join("; "));
+        let _ = append_event(&new_event(
+            name,
+            "hygiene-flagged",
+            work_id.as_deref(),
+            work_title.as_deref(),
+            Some(&msg),
+        ));
+        let _ = self.store.mark_flagged(name, &msg);
+        self.flash_message = Some((
+            format!("{} flagged for review", name.display_name()),
+            Instant::now(),
+        ));
+        true
+    }
+
+    /// The agent shown in the detail view, if its branch is awaiting review.
+    fn reviewing_agent(&self) -> Option<AgentName> {
+        let ViewMode::AgentDetail(name) = self.view_mode else {
+            return None;
+        };
+        self.store
+            .get_agent(name)
+            .is_some_and(|a| a.status == AgentStatus::Review)
+            .then_some(name)
+    }
+
+    /// The agent shown in the detail view, if its plan is awaiting approval.
+    fn planning_agent(&self) -> Option<AgentName> {
+        let ViewMode::AgentDetail(name) = self.view_mode else {
+            return None;
+        };
+        self.store
+            .get_agent(name)
+            .is_some_and(|a| a.status == AgentStatus::Plan)
+            .then_some(name)
+    }
+
+    fn load_plan(&mut self, name: AgentName) {
+        let plan = plan::load_plan(name).unwrap_or_else(|| "No plan found".into());
+        self.review_diff = Some((name, plan.lines().map(String::from).collect()));
+        self.review_scroll = 0;
+    }
+
+    /// Start implementing an approved plan in the agent's worktree.
+    async fn approve_plan(&mut self, name: AgentName) {
+        // The run must belong to the daemon, or it dies with this terminal
+        if self.daemon.is_some() {
+            self.ask_daemon(Request::ApprovePlan { agent: name }).await;
+            return;
+        }
+        let Some(agent) = self.store.get_agent(name) else {
+            return;
+        };
+        let (Some(wt_path), Some(repo_root), Some(item_id)) = (
+            agent.worktree_path.clone(),
+            agent.repo_root.clone(),
+            agent.work_item_id.clone(),
+        ) else {
+            return;
+        };
+        let base = agent.base().to_string();
+        let Some(item) = self.items.iter().find(|i| i.id == item_id).cloned() else {
+            self.flash_message = Some((
+                format!("{item_id} is no longer on the board"),
+                Instant::now(),
+            ));
+            return;
+        };
+        if providers::is_review_request(&item) {
+            self.post_review(name, &item).await;
+            return;
+        }
+        // Starting the implementation is a dispatch: it waits for room in the
+        // repo and for tomorrow's budget like any other
+        let blocked = self.dispatch_blocked(&item).or_else(|| {
+            self.over_budget()
+                .then(|| "today's budget is spent".to_string())
+        });
+        if let Some(reason) = blocked {
+            self.flash_message = Some((
+                format!("Can't start {item_id} now — {reason}"),
+                Instant::now(),
+            ));
+            return;
+        }
+        let item = self.with_context(&item).await;
+        let mut opts = self.dispatch_options(name, &item, &repo_root).await;
+        opts.base_branch = base;
+
+        self.review_diff = None;
+        if let Err(e) = dispatch::execute_plan(
+            name,
+            &item,
+            &wt_path,
+            &opts,
+            &mut self.store,
+            self.action_tx.clone(),
+        ) {
+            self.flash_message = Some((
+                format!("Failed to start {}: {e}", name.display_name()),
+                Instant::now(),
+            ));
+        }
+    }
+
+    /// Post an approved draft review to its pull request as a comment.
+    async fn post_review(&mut self, name: AgentName, item: &WorkItem) {
+        let Some(draft) = plan::load_plan(name) else {
+            return;
+        };
+        let provider = self.providers.iter().find(|p| p.name() == item.source);
+        let result = match (provider, &item.source_id) {
+            (Some(provider), Some(source_id)) => provider.add_comment(source_id, &draft).await,
+            _ => Err(anyhow::anyhow!("{} is not configured", item.source).into()),
+        };
+        if let Err(e) = result {
+            self.flash_message = Some((
+                format!("Failed to post the review on {}: {e}", item.id),
+                Instant::now(),
+            ));
+            return;
+        }
+
+        plan::clear_plan(name);
+        self.review_diff = None;
+        let _ = self.store.mark_done(name);
+        let _ = append_event(&new_event(
+            name,
+            "review-posted",
+            Some(&item.id),
+            Some(&item.title),
+            item.url.as_deref(),
+        ));
+        self.flash_message = Some((format!("Posted review on {}", item.id), Instant::now()));
+    }
+
+    /// Whether the agent's run is a review of a pull request.
+    pub fn is_reviewing_pr(&self, name: AgentName) -> bool {
+        let item_id = self.store.get_agent(name).and_then(|a| a.work_item_id.as_deref());
+        self.items
+            .iter()
+            .any(|i| Some(i.id.as_str()) == item_id && providers::is_review_request(i))
+    }
+
+    /// Park a finished agent in Review until its branch is approved.
+    async fn request_review(&mut self, name: AgentName) {
+        let _ = self.store.mark_review(name);
+        if let Some(agent) = self.store.get_agent(name) {
+            let _ = append_event(&new_event(
+                name,
+                "review",
+                agent.work_item_id.as_deref(),
+                agent.work_item_title.as_deref(),
+                Some("Waiting for approval"),
+            ));
+        }
+        if self.view_mode == ViewMode::AgentDetail(name) {
+            self.load_review_diff(name).await;
+        }
+        self.flash_message = Some((
+            format!("{} ready for review", name.display_name()),
+            Instant::now(),
+        ));
+    }
+
+    /// `d` in an agent's detail view: show or hide its full diff.
+    fn toggle_branch_diff(&mut self, name: AgentName) {
+        if self.branch_diff.take().is_some() {
+            return;
+        }
+        let Some(agent) = self.store.get_agent(name) else {
+            return;
+        };
+        let Some(wt_path) = agent.worktree_path.clone() else {
+            self.flash_message = Some((
+                format!("{} has no worktree yet", name.display_name()),
+                Instant::now(),
+            ));
+            return;
+        };
+        let base = agent.base().to_string();
+        self.branch_diff = Some(diff::BranchDiff::message(name, "Loading diff…".into()));
+        self.branch_diff_scroll = 0;
+        let tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            let loaded = diff::BranchDiff::load(name, &wt_path, &base).await;
+            let _ = tx.send(Action::BranchDiffLoaded(Box::new(loaded)));
+        });
+    }
 
-            // Auto-dispatch to free agents
-            self.auto_dispatch().await;
+    fn scroll_branch_diff_to(&mut self, offset: usize) {
+        let len = self.branch_diff.as_ref().map_or(0, |d| d.lines.len());
+        self.branch_diff_scroll = offset.min(len.saturating_sub(1));
+    }
+
+    /// `[`/`]`: scroll the open diff to the previous or next file.
+    fn jump_diff_file(&mut self, forward: bool) {
+        let Some(diff) = &self.branch_diff else {
+            return;
+        };
+        let files = &diff.files;
+        let target = if forward {
+            files.iter().find(|f| f.start > self.branch_diff_scroll)
+        } else {
+            files.iter().rev().find(|f| f.start < self.branch_diff_scroll)
+        };
+        if let Some(file) = target {
+            self.branch_diff_scroll = file.start;
         }
     }
 
-    async fn auto_dispatch(&mut self) {
-        loop {
-            let free_agent = self.store.next_free_agent();
-            let free_agent = match free_agent {
-                Some(a) => a,
-                None => break,
-            };
+    async fn load_review_diff(&mut self, name: AgentName) {
+        let Some(agent) = self.store.get_agent(name) else {
+            return;
+        };
+        let Some(wt_path) = agent.worktree_path.clone() else {
+            return;
+        };
+        let base = agent.base().to_string();
+        let lines = match review::branch_diff(&wt_path, &base).await {
+            Ok(diff) if diff.trim().is_empty() => vec![format!("No changes against origin/{base}")],
+            Ok(diff) => diff.lines().map(String::from).collect(),
+            Err(e) => vec![format!("Failed to load diff: {e}")],
+        };
+        self.review_diff = Some((name, lines));
+        self.review_scroll = 0;
+    }
 
-            // Find next unassigned item
-            let next_item = self
-                .items
-                .iter()
-                .find(|item| !self.dispatched_item_ids.contains(&item.id))
-                .cloned();
+    /// Push an approved branch to its base and finish the item.
+    async fn approve_review(&mut self, name: AgentName) {
+        let Some(agent) = self.store.get_agent(name) else {
+            return;
+        };
+        let Some(wt_path) = agent.worktree_path.clone() else {
+            return;
+        };
+        let item_id = agent.work_item_id.clone();
+        let item_title = agent.work_item_title.clone();
+        let base = agent.base().to_string();
 
-            match next_item {
-                Some(item) => {
-                    self.dispatched_item_ids.insert(item.id.clone());
-                    if dispatch::dispatch(
-                        free_agent,
-                        &item,
-                        &self.repo_root,
-                        &mut self.store,
-                        self.action_tx.clone(),
-                    )
-                    .await
-                    .is_ok()
-                    {
-                        self.move_item_to_in_progress(&item).await;
-                    }
-                }
-                None => break,
-            }
+        // Feedback rounds may have changed the branch since it was checked
+        if self.flag_unsafe_changes(name).await {
+            self.review_diff = None;
+            return;
         }
-    }
 
-    async fn dispatch_selected(&mut self) {
-        if self.items.is_empty() {
+        if let Err(e) = review::approve(&wt_path, &base).await {
+            let msg = format!("Approve failed: {e}");
+            let _ = append_event(&new_event(
+                name,
+                "error",
+                item_id.as_deref(),
+                item_title.as_deref(),
+                Some(&msg),
+            ));
+            self.flash_message = Some((msg, Instant::now()));
             return;
         }
-        let item = self.items[self.selected_item].clone();
 
-        let free_agent = self.store.next_free_agent();
-        match free_agent {
-            Some(agent_name) => {
-                self.dispatched_item_ids.insert(item.id.clone());
-                match dispatch::dispatch(
-                    agent_name,
-                    &item,
-                    &self.repo_root,
-                    &mut self.store,
-                    self.action_tx.clone(),
-                )
-                .await
-                {
-                    Ok(_) => {
-                        self.move_item_to_in_progress(&item).await;
-                        self.flash_message = Some((
-                            format!(
-                                "{} dispatched to {}",
-                                item.id,
-                                agent_name.display_name()
-                            ),
-                            Instant::now(),
-                        ));
-                    }
-                    Err(e) => {
-                        self.flash_message =
-                            Some((format!("Dispatch failed: {e}"), Instant::now()));
-                    }
-                }
-            }
-            None => {
-                self.flash_message = Some(("All agents busy".into(), Instant::now()));
-            }
+        let _ = append_event(&new_event(
+            name,
+            "approved",
+            item_id.as_deref(),
+            item_title.as_deref(),
+            Some(&format!("Pushed to {base}")),
+        ));
+        if let Some(item) = item_id.and_then(|id| self.items.iter().find(|i| i.id == id).cloned())
+        {
+            self.move_item_to_done(item).await;
         }
+        self.review_diff = None;
+        self.watch_ci(name).await;
+        let _ = self.store.mark_done(name);
     }
 
-    async fn clear_agent(&mut self, agent_name: AgentName) {
-        if let Some(agent) = self.store.get_agent(agent_name) {
-            if agent.status == AgentStatus::Idle {
-                self.flash_message = Some((
-                    format!("{} is already idle", agent_name.display_name()),
-                    Instant::now(),
+    /// Push a finished agent's branch and open a pull request, recording it
+    /// so the item can be moved to done when the PR merges.
+    async fn open_pr(&mut self, name: AgentName) {
+        let Some(agent) = self.store.get_agent(name) else {
+            return;
+        };
+        let (Some(wt_path), Some(item_id), Some(item_title)) = (
+            agent.worktree_path.clone(),
+            agent.work_item_id.clone(),
+            agent.work_item_title.clone(),
+        ) else {
+            return;
+        };
+        let base = agent.base().to_string();
+        let item = self.items.iter().find(|i| i.id == item_id).cloned();
+
+        let branch = pr::pr_branch_name(name, &item_id);
+        let title = format!("{item_id}: {item_title}");
+        let mut body = format!("Automated change by {} for {item_id}.", name.display_name());
+        if let Some(url) = item.as_ref().and_then(|i| i.url.as_deref()) {
+            body.push_str(&format!("\n\n{url}"));
+        }
+
+        match pr::push_and_open_pr(&wt_path, &branch, &base, &title, &body).await {
+            Ok(url) => {
+                let _ = append_event(&new_event(
+                    name,
+                    "pr-opened",
+                    Some(&item_id),
+                    Some(&item_title),
+                    Some(&url),
                 ));
-                return;
+                let _ = self.store.add_pending_pr(PendingPr {
+                    agent: name,
+                    url: url.clone(),
+                    work_item_id: item_id,
+                    work_item_title: item_title,
+                    source: item.as_ref().map(|i| i.source.clone()).unwrap_or_default(),
+                    source_id: item.and_then(|i| i.source_id),
+                });
+                self.flash_message = Some((format!("PR opened: {url}"), Instant::now()));
             }
-
-            // Kill process if running
-            if let Some(pid) = agent.pid {
-                unsafe {
-                    libc::kill(pid as i32, libc::SIGTERM);
-                }
+            Err(e) => {
+                let msg = format!("Failed to open PR: {e}");
+                let _ = append_event(&new_event(
+                    name,
+                    "error",
+                    Some(&item_id),
+                    Some(&item_title),
+                    Some(&msg),
+                ));
+                self.flash_message = Some((msg, Instant::now()));
             }
+        }
+    }
 
-            let work_title = agent.work_item_title.clone();
-            let work_id = agent.work_item_id.clone();
-
-            // Remove item from dispatched set so it can be re-assigned
-            if let Some(item_id) = &work_id {
-                self.dispatched_item_ids.remove(item_id);
-            }
+    /// Check open agent PRs; merged ones move their item to done, closed
+    /// ones are dropped.
+    async fn poll_pending_prs(&mut self) {
+        let pending: Vec<PendingPr> = self.store.pending_prs().to_vec();
+        let mut merged: BTreeMap<String, Vec<PendingPr>> = BTreeMap::new();
+        for pending_pr in pending {
+            let state = match pr::pr_state(&pending_pr.url).await {
+                Ok(state) => state,
+                Err(_) => continue,
+            };
+            let event_type = match state {
+                PrState::Open => continue,
+                PrState::Merged => "pr-merged",
+                PrState::Closed => "pr-closed",
+            };
 
-            // Release the agent
-            let _ = self.store.release(agent_name);
             let _ = append_event(&new_event(
-                agent_name,
-                "cleared",
-                work_id.as_deref(),
-                work_title.as_deref(),
-                Some("Agent cleared by user"),
+                pending_pr.agent,
+                event_type,
+                Some(&pending_pr.work_item_id),
+                Some(&pending_pr.work_item_title),
+                Some(&pending_pr.url),
             ));
+            if state == PrState::Merged {
+                merged
+                    .entry(pending_pr.source.clone())
+                    .or_default()
+                    .push(pending_pr);
+                continue;
+            }
+            let _ = self.store.remove_pending_pr(&pending_pr.url);
+        }
 
+        // One call per provider, so a batch of merges is a single request
+        // where the provider can make one
+        for (source, prs) in merged {
+            let source_ids: Vec<String> = prs.iter().filter_map(|p| p.source_id.clone()).collect();
+            let item_ids: Vec<&str> = prs.iter().map(|p| p.work_item_id.as_str()).collect();
+            let item_ids = item_ids.join(", ");
+            if let Some(provider) = self.providers.iter().find(|p| p.name() == source) {
+                if !source_ids.is_empty() {
+                    if let Err(e) = provider.move_many_to_done(&source_ids).await {
+                        self.record_failure(&source, format!("move {item_ids} to done"), &e);
+                        continue;
+                    }
+                }
+            }
             self.flash_message = Some((
-                format!("{} cleared", agent_name.display_name()),
+                format!("{item_ids} merged — moved to done"),
                 Instant::now(),
             ));
+            for pending_pr in prs {
+                let _ = self.store.remove_pending_pr(&pending_pr.url);
+            }
         }
     }
 
-    pub async fn fetch_boards(&mut self) {
-        self.loading = true;
-        let mut all_boards = Vec::new();
-        for provider in &self.providers {
-            match provider.list_boards().await {
-                Ok(boards) => all_boards.extend(boards),
-                Err(e) => {
-                    let _ = self
-                        .action_tx
-                        .send(Action::FetchError(format!("{}: {e}", provider.name())));
+    /// Desktop notification and shell hook for an agent lifecycle event.
+    fn announce(&self, name: AgentName, event: NotifyEvent, body: String) {
+        let agent = self.store.get_agent(name);
+        let item = agent.and_then(|a| a.work_item_id.clone()).unwrap_or_default();
+        let hook = match event {
+            NotifyEvent::AgentDone => Hook::Done,
+            _ => Hook::Error,
+        };
+        spawn_hook(
+            &self.hooks,
+            hook,
+            serde_json::json!({
+                "agent": name.as_str(),
+                "item_id": item,
+                "item_title": agent.and_then(|a| a.work_item_title.clone()),
+                "detail": body,
+            }),
+        );
+
+        let summary = match event {
+            NotifyEvent::AgentDone => format!("{} finished {item}", name.display_name()),
+            NotifyEvent::MaxRetries => format!("{} gave up on {item}", name.display_name()),
+            _ => format!("{} failed on {item}", name.display_name()),
+        };
+        notify(&self.notifications, event, summary, body);
+    }
+
+    /// Start watching CI for the commit an agent just pushed.
+    async fn watch_ci(&mut self, name: AgentName) {
+        let Some(agent) = self.store.get_agent(name) else {
+            return;
+        };
+        let Some(wt_path) = agent.worktree_path.clone() else {
+            return;
+        };
+        let Some(item) = agent
+            .work_item_id
+            .as_ref()
+            .and_then(|id| self.items.iter().find(|i| i.id == *id).cloned())
+        else {
+            return;
+        };
+        let Ok(sha) = ci::head_sha(&wt_path).await else {
+            return;
+        };
+        let _ = self.store.watch_ci(CiWatch {
+            agent: name,
+            sha,
+            repo_root: self.agent_repo_root(name),
+            item,
+            status: CiStatus::Pending,
+            failed: Vec::new(),
+            followed_up: false,
+            pushed_at: Some(chrono::Utc::now().to_rfc3339()),
+        });
+    }
+
+    /// Refresh pending CI runs and send red ones back to their agent with
+    /// the failed checks as context.
+    async fn poll_ci(&mut self) {
+        let watches: Vec<CiWatch> = self.store.ci_watches().to_vec();
+        for watch in watches {
+            if watch.status == CiStatus::Pending {
+                let Ok((status, failed)) = ci::ci_status(&watch.repo_root, &watch.sha).await else {
+                    continue;
+                };
+                // No run yet may just mean CI hasn't picked the commit up
+                if status == CiStatus::Pending
+                    || (status == CiStatus::Missing && !watch.ci_overdue(chrono::Utc::now()))
+                {
+                    continue;
+                }
+                let _ = self.store.update_ci(watch.agent, &watch.sha, status, failed);
+                let event_type = match status {
+                    CiStatus::Passed => "ci-passed",
+                    CiStatus::Failed => "ci-failed",
+                    _ => continue,
+                };
+                let _ = append_event(&new_event(
+                    watch.agent,
+                    event_type,
+                    Some(&watch.item.id),
+                    Some(&watch.item.title),
+                    Some(&watch.sha),
+                ));
+                if status == CiStatus::Failed {
+                    self.flash_message = Some((
+                        format!("CI failed for {}", watch.item.id),
+                        Instant::now(),
+                    ));
                 }
             }
+            self.follow_up_ci(watch.agent).await;
         }
-        self.available_boards = all_boards;
-        self.selected_board = 0;
-        self.loading = false;
     }
 
-    async fn select_board(&mut self) {
-        let board = &self.available_boards[self.selected_board];
-        let mapping = BoardMapping {
-            board_id: board.id.clone(),
-            board_name: board.name.clone(),
-            source: board.source.clone(),
+    /// Re-dispatch a red run's item to the agent that pushed it, once it is free.
+    async fn follow_up_ci(&mut self, name: AgentName) {
+        let Some(watch) = self.store.ci_watch(name).cloned() else {
+            return;
         };
-
-        // Save mapping
-        if let Err(e) = config::save_board_mapping(&self.project_dir, &mapping) {
-            self.flash_message = Some((format!("Failed to save mapping: {e}"), Instant::now()));
+        if watch.status != CiStatus::Failed || watch.followed_up || self.pruning.contains(&name) {
             return;
         }
-
-        // Apply board filter to the matching provider
-        for provider in &mut self.providers {
-            if provider.name() == mapping.source {
-                provider.set_board_filter(mapping.board_id.clone());
-            }
+        if !self.store.get_agent(name).is_some_and(|a| a.status == AgentStatus::Idle) {
+            return;
+        }
+        // Waits, like any dispatch, for capacity and budget
+        if self.over_budget() || self.dispatch_blocked(&watch.item).is_some() {
+            return;
         }
 
-        self.flash_message = Some((format!("Board: {}", mapping.board_name), Instant::now()));
-        self.view_mode = ViewMode::Items;
-        self.refresh_items().await;
+        let summary = watch.failure_summary();
+        let _ = self.store.set_last_failure(name, &summary);
+        let _ = self.store.mark_ci_followed_up(name);
+        let _ = append_event(&new_event(
+            name,
+            "ci-followup",
+            Some(&watch.item.id),
+            Some(&watch.item.title),
+            Some(&summary),
+        ));
+        if let Err(e) = self.dispatch_item(name, &watch.item).await {
+            self.flash_message = Some((format!("CI follow-up failed: {e}"), Instant::now()));
+        }
     }
 
-    pub async fn refresh_items(&mut self) {
-        self.loading = true;
-        let tx = self.action_tx.clone();
-
-        let mut all_items = Vec::new();
-        let mut errors = Vec::new();
+    async fn dispatch_item(
+        &mut self,
+        agent_name: AgentName,
+        item: &WorkItem,
+    ) -> anyhow::Result<()> {
+        // The old worktree is still being removed; provisioning a new one at
+        // the same path would race with it
+        if self.pruning.contains(&agent_name) {
+            anyhow::bail!("{agent_name}'s worktree is still being cleaned up");
+        }
+        // Dispatching a failed item by hand counts as requeueing it
+        if self.store.is_failed(&item.id) {
+            let _ = self.store.requeue_failed(&item.id);
+        }
+        let item = &self.with_context(item).await;
+        let repo_root = resolve_repo(&self.agents_config, item, &self.repo_root);
+        let opts = self.dispatch_options(agent_name, item, &repo_root).await;
+        dispatch::dispatch(
+            agent_name,
+            item,
+            &repo_root,
+            &opts,
+            &mut self.store,
+            self.action_tx.clone(),
+        )
+        .await?;
+        self.last_assigned = Some(agent_name);
+        spawn_hook(
+            &self.hooks,
+            Hook::Dispatch,
+            serde_json::json!({ "agent": agent_name.as_str(), "item": item }),
+        );
+        Ok(())
+    }
 
-        // Fetch from all providers (we need to do this on the current task since providers aren't Send-safe with references)
-        for provider in &self.providers {
-            match provider.fetch_items().await {
-                Ok(items) => all_items.extend(items),
-                Err(e) => errors.push(format!("{}: {e}", provider.name())),
+    /// The item with its links and comments fetched from the provider.
+    async fn with_context(&self, item: &WorkItem) -> WorkItem {
+        let mut item = item.clone();
+        if let Some(source_id) = &item.source_id {
+            if let Some(provider) = self.providers.iter().find(|p| p.name() == item.source) {
+                // Links and comments only add context, so failing or stalling
+                // to fetch them must not block the dispatch
+                let (links, comments) = tokio::join!(
+                    tokio::time::timeout(CONTEXT_FETCH_TIMEOUT, provider.fetch_links(source_id)),
+                    tokio::time::timeout(CONTEXT_FETCH_TIMEOUT, provider.fetch_comments(source_id)),
+                );
+                item.links = links.ok().and_then(Result::ok).unwrap_or_default();
+                item.comments = comments.ok().and_then(Result::ok).unwrap_or_default();
             }
         }
+        item
+    }
 
-        if !errors.is_empty() {
-            let _ = tx.send(Action::FetchError(errors.join("; ")));
+    async fn dispatch_options(
+        &self,
+        agent_name: AgentName,
+        item: &WorkItem,
+        repo_root: &str,
+    ) -> DispatchOptions {
+        DispatchOptions {
+            env: resolve_env(&self.agents_config, &self.project_dir, item),
+            workflow: self.agents_config.workflow,
+            verify_command: self.agents_config.verify_command.clone(),
+            previous_failure: self
+                .store
+                .get_agent(agent_name)
+                .and_then(|a| a.last_failure.clone()),
+            base_branch: base_branch(&self.agents_config, repo_root).await,
+            claude_md_template: self.agents_config.claude_md_template.clone(),
+            prompt_template: self.agents_config.prompt_template.clone(),
+            plan_first: self.agents_config.plan_first,
         }
-        let _ = tx.send(Action::WorkItemsLoaded(all_items));
     }
 
+    /// The agent's latest events that pass the activity view's filters.
     pub fn agent_events(&self, name: AgentName) -> Vec<AgentEvent> {
-        read_events(Some(name), Some(200))
+        query_events(&EventQuery {
+            agent: Some(name),
+            kind: self.agent_event_kind,
+            text: self.agent_event_search.clone(),
+            limit: Some(200),
+            ..Default::default()
+        })
     }
 
     async fn move_item_to_in_progress(&mut self, item: &WorkItem) {
@@ -870,10 +4167,58 @@ impl App {
             for provider in &self.providers {
                 if provider.name() == item.source {
                     if let Err(e) = provider.move_to_in_progress(source_id).await {
-                        self.flash_message = Some((
-                            format!("Failed to move {} to in-progress: {e}", item.id),
-                            Instant::now(),
-                        ));
+                        let action = format!("move {} to in-progress", item.id);
+                        self.record_failure(&item.source, action, &e);
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    /// `u`: revert the newest done or dispatch still inside the undo window.
+    async fn undo_last(&mut self) {
+        let Some(action) = self.undo.pop() else {
+            self.flash_message = Some(("Nothing to undo".into(), Instant::now()));
+            return;
+        };
+        match action {
+            // Reopen last so a provider error replaces the success message
+            Undoable::Done(item) => {
+                if !self.items.iter().any(|i| i.id == item.id) {
+                    self.items.push(item.clone());
+                }
+                self.flash_message = Some((format!("Reopened {}", item.id), Instant::now()));
+                self.reopen_item(&item).await;
+            }
+            Undoable::Dispatch { item, agent } => {
+                let holds_item = self
+                    .store
+                    .get_agent(agent)
+                    .is_some_and(|a| a.work_item_id.as_deref() == Some(item.id.as_str()));
+                if !holds_item {
+                    self.flash_message = Some((
+                        format!("{} is no longer on {}", item.id, agent.display_name()),
+                        Instant::now(),
+                    ));
+                    return;
+                }
+                self.clear_agent(agent).await;
+                self.flash_message = Some((
+                    format!("Recalled {} from {}", item.id, agent.display_name()),
+                    Instant::now(),
+                ));
+                self.reopen_item(&item).await;
+            }
+        }
+    }
+
+    async fn reopen_item(&mut self, item: &WorkItem) {
+        if let Some(source_id) = &item.source_id {
+            for provider in &self.providers {
+                if provider.name() == item.source {
+                    if let Err(e) = provider.reopen(source_id).await {
+                        self.record_failure(&item.source, format!("reopen {}", item.id), &e);
                     }
                     break;
                 }
@@ -881,28 +4226,127 @@ impl App {
         }
     }
 
-    async fn move_item_to_done(&mut self, item: WorkItem) {
+    /// Move `item` to done upstream, dropping it from the list once the
+    /// provider accepts. Returns whether it did.
+    async fn move_item_to_done(&mut self, item: WorkItem) -> bool {
+        let mut done = false;
         if let Some(source_id) = &item.source_id {
             for provider in &self.providers {
                 if provider.name() == item.source {
                     match provider.move_to_done(source_id).await {
                         Ok(_) => {
+                            done = true;
                             self.flash_message = Some((
                                 format!("{} moved to done", item.id),
                                 Instant::now(),
                             ));
                         }
                         Err(e) => {
-                            self.flash_message = Some((
-                                format!("Failed to move {} to done: {e}", item.id),
-                                Instant::now(),
-                            ));
+                            let action = format!("move {} to done", item.id);
+                            self.record_failure(&item.source, action, &e);
                         }
                     }
                     break;
                 }
             }
         }
+        if done {
+            if self.focus.as_ref().is_some_and(|f| f.is(&item)) {
+                self.unpin();
+            }
+            // Unblock the items waiting on this one
+            ItemUpdate::Remove {
+                source: item.source.clone(),
+                id: item.id.clone(),
+                source_id: item.source_id.clone(),
+            }
+            .apply(&mut self.items);
+            self.close_linked(&item.source, &item.id).await;
+        }
+        done
+    }
+
+    /// Whether the screen shows anything that changes without new data:
+    /// spinners and elapsed timers. Flash messages expire in `update`.
+    fn is_animating(&self) -> bool {
+        self.loading
+            || self.pomodoro.is_some()
+            || self.boards_loading()
+            || self.waiting_for_response
+            || self.store.get_all().iter().any(|a| {
+                matches!(
+                    a.status,
+                    AgentStatus::Provisioning | AgentStatus::Working | AgentStatus::Stalled
+                )
+            })
+    }
+
+    /// Hash of the state background actions can change on screen: items,
+    /// agents, chat, prompts and the visible output pane. Ticks and output
+    /// lines only trigger a redraw when this moves.
+    fn screen_fingerprint(&self) -> u64 {
+        let mut h = DefaultHasher::new();
+        for item in &self.items {
+            (&item.id, &item.status, &item.priority, &item.title).hash(&mut h);
+        }
+        for agent in self.store.get_all() {
+            format!("{agent:?}").hash(&mut h);
+        }
+        (self.store.pending_prs().len(), format!("{:?}", self.store.ci_watches())).hash(&mut h);
+        (&self.dispatch_queue, self.auto_mode, self.loading).hash(&mut h);
+        for (name, questions) in &self.pending_questions {
+            (name, questions.len()).hash(&mut h);
+        }
+        for (name, status) in &self.status_reports {
+            (name, &status.text).hash(&mut h);
+        }
+        let last_chat = self.chat_messages.last().map(|m| &m.text);
+        (self.chat_messages.len(), last_chat, self.waiting_for_response).hash(&mut h);
+        let flash = self.flash_message.as_ref().map(|(text, _)| text);
+        (flash, &self.incident_alert, &self.confirm).hash(&mut h);
+        let focus = self.focus.as_ref().map(|f| (&f.id, f.elapsed(chrono::Utc::now())));
+        focus.hash(&mut h);
+        (self.debug_log, self.debug_log.then(logging::logged)).hash(&mut h);
+        let newest_error = self.errors.failures().first().map(|f| (&f.message, f.count));
+        (self.errors.len(), newest_error, self.error_center).hash(&mut h);
+        let countdown = self.schedule_countdown();
+        countdown.map(|(on, left)| (on, left.num_minutes())).hash(&mut h);
+        if let ViewMode::AgentDetail(name) = self.view_mode {
+            let output = self.agent_output.get(&name);
+            (output.map(VecDeque::len), output.and_then(VecDeque::back)).hash(&mut h);
+            self.disk_usage.get(&name).hash(&mut h);
+        }
+        h.finish()
+    }
+
+    /// Returns true (and resets the flag) if the UI should be redrawn.
+    pub fn take_redraw(&mut self) -> bool {
+        std::mem::take(&mut self.needs_redraw)
+    }
+
+    /// Items waiting to be dispatched, in pick order: the manual queue first,
+    /// then (in auto mode) everything not yet dispatched, by priority.
+    pub fn queued_item_ids(&self) -> Vec<&str> {
+        let mut queued: Vec<&str> = self.dispatch_queue.iter().map(String::as_str).collect();
+        if self.auto_mode {
+            let mut rest: Vec<&WorkItem> = self
+                .items
+                .iter()
+                .filter(|item| !self.dispatched_item_ids.contains(&item.id))
+                .filter(|item| !self.dispatch_queue.contains(&item.id))
+                .filter(|item| !self.triage_overrides.is_manual_only(&item.id))
+                .filter(|item| !self.store.is_failed(&item.id))
+                .filter(|item| !item.is_blocked())
+                .filter(|item| queue::is_auto_eligible(item, &self.agents_config.auto_rules))
+                .collect();
+            queue::auto_order(&mut rest, &self.agents_config.priority_labels);
+            queued.extend(rest.iter().map(|item| item.id.as_str()));
+        }
+        queued
+    }
+
+    pub fn is_manually_queued(&self, item_id: &str) -> bool {
+        self.dispatch_queue.iter().any(|id| id == item_id)
     }
 
     pub fn assigned_agent(&self, item_id: &str) -> Option<AgentName> {
@@ -910,7 +4354,11 @@ impl App {
             if a.work_item_id.as_deref() == Some(item_id)
                 && matches!(
                     a.status,
-                    AgentStatus::Working | AgentStatus::Provisioning | AgentStatus::Done
+                    AgentStatus::Working
+                        | AgentStatus::Provisioning
+                        | AgentStatus::Stalled
+                        | AgentStatus::Review
+                        | AgentStatus::Done
                 )
             {
                 Some(a.name)
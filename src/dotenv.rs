@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Variables from `<dir>/.env`, or none if the file is missing.
+pub fn load(dir: &Path) -> HashMap<String, String> {
+    std::fs::read_to_string(dir.join(".env"))
+        .map(|contents| parse(&contents))
+        .unwrap_or_default()
+}
+
+/// Parse `KEY=value` lines. Supports `export KEY=...`, `#` comments and
+/// single- or double-quoted values.
+pub fn parse(contents: &str) -> HashMap<String, String> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            if key.is_empty() || key.contains(char::is_whitespace) {
+                return None;
+            }
+            Some((key.to_string(), unquote(value.trim())))
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> String {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner.to_string();
+        }
+    }
+    // Unquoted values may carry a trailing comment
+    match value.find(" #") {
+        Some(i) => value[..i].trim_end().to_string(),
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_common_forms() {
+        let vars = parse(
+            "# credentials\n\
+             WORK_LINEAR_API_KEY=lin_api_1\n\
+             export WORK_JIRA_EMAIL=\"me@example.com\"\n\
+             WORK_TRELLO_TOKEN='a#b' \n\
+             WORK_GITHUB_OWNER=acme # org\n\
+             not a var\n",
+        );
+        assert_eq!(vars["WORK_LINEAR_API_KEY"], "lin_api_1");
+        assert_eq!(vars["WORK_JIRA_EMAIL"], "me@example.com");
+        assert_eq!(vars["WORK_TRELLO_TOKEN"], "a#b");
+        assert_eq!(vars["WORK_GITHUB_OWNER"], "acme");
+        assert_eq!(vars.len(), 4);
+    }
+}
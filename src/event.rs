@@ -3,16 +3,31 @@ use std::time::Duration;
 use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyModifiers};
 use futures::StreamExt;
 use tokio::sync::mpsc;
+use tokio::time::MissedTickBehavior;
 
 use crate::app::Action;
 
-pub async fn run_event_loop(tx: mpsc::UnboundedSender<Action>) {
+/// Read terminal events and emit two ticks: a fast render tick for
+/// animations and a slower data tick for polling agent state.
+pub async fn run_event_loop(
+    tx: mpsc::UnboundedSender<Action>,
+    render_rate: Duration,
+    data_rate: Duration,
+) {
     let mut reader = EventStream::new();
-    let mut tick = tokio::time::interval(Duration::from_secs(2));
+    let mut render_tick = tokio::time::interval(render_rate);
+    let mut data_tick = tokio::time::interval(data_rate);
+    render_tick.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    data_tick.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
     loop {
         tokio::select! {
-            _ = tick.tick() => {
+            _ = render_tick.tick() => {
+                if tx.send(Action::RenderTick).is_err() {
+                    break;
+                }
+            }
+            _ = data_tick.tick() => {
                 if tx.send(Action::Tick).is_err() {
                     break;
                 }
@@ -26,8 +41,12 @@ pub async fn run_event_loop(tx: mpsc::UnboundedSender<Action>) {
                             }
                         }
                     }
+                    Some(Ok(Event::Paste(text))) => {
+                        let _ = tx.send(Action::Paste(text));
+                    }
                     Some(Ok(Event::Resize(_, _))) => {
-                        // Terminal will re-render on next frame
+                        // Force a redraw at the new size
+                        let _ = tx.send(Action::Resize);
                     }
                     Some(Err(_)) | None => break,
                     _ => {}
@@ -38,9 +57,38 @@ pub async fn run_event_loop(tx: mpsc::UnboundedSender<Action>) {
 }
 
 fn key_to_action(key: KeyEvent) -> Option<Action> {
-    // Ctrl+C always quits
-    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
-        return Some(Action::Quit);
+    // Ctrl+C always quits, Ctrl+Z always suspends
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        match key.code {
+            KeyCode::Char('c') => return Some(Action::Quit),
+            KeyCode::Char('z') => return Some(Action::Suspend),
+            _ => {}
+        }
+    }
+
+    // Line editing chords for the command bar
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key.modifiers.contains(KeyModifiers::ALT);
+    let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+    let edit = match key.code {
+        // Ctrl+J is what a bare newline reads as in raw mode
+        KeyCode::Enter if shift || alt => Some(KeyAction::Newline),
+        KeyCode::Char('j') if ctrl => Some(KeyAction::Newline),
+        KeyCode::Left if alt || ctrl => Some(KeyAction::WordLeft),
+        KeyCode::Right if alt || ctrl => Some(KeyAction::WordRight),
+        // macOS terminals send Alt+arrows as Alt+b / Alt+f
+        KeyCode::Char('b') if alt => Some(KeyAction::WordLeft),
+        KeyCode::Char('f') if alt => Some(KeyAction::WordRight),
+        KeyCode::Char('w') if ctrl => Some(KeyAction::DeleteWord),
+        KeyCode::Char('u') if ctrl => Some(KeyAction::DeleteToStart),
+        KeyCode::Char('a') if ctrl => Some(KeyAction::Home),
+        KeyCode::Char('e') if ctrl => Some(KeyAction::End),
+        KeyCode::Home => Some(KeyAction::Home),
+        KeyCode::End => Some(KeyAction::End),
+        _ => None,
+    };
+    if let Some(edit) = edit {
+        return Some(Action::Key(edit));
     }
 
     match key.code {
@@ -48,7 +96,7 @@ fn key_to_action(key: KeyEvent) -> Option<Action> {
         KeyCode::Down => Some(Action::Key(KeyAction::Down)),
         KeyCode::Left => Some(Action::Key(KeyAction::Left)),
         KeyCode::Right => Some(Action::Key(KeyAction::Right)),
-        KeyCode::Char('q') => Some(Action::Quit),
+        KeyCode::Char('q') => Some(Action::Key(KeyAction::Quit)),
         KeyCode::Char('d') => Some(Action::Key(KeyAction::Dispatch)),
         KeyCode::Char('m') => Some(Action::Key(KeyAction::ToggleAutoMode)),
         KeyCode::Char('r') => Some(Action::Key(KeyAction::Refresh)),
@@ -60,6 +108,8 @@ fn key_to_action(key: KeyEvent) -> Option<Action> {
         KeyCode::Char(c) => Some(Action::Key(KeyAction::Char(c))),
         KeyCode::Backspace => Some(Action::Key(KeyAction::Backspace)),
         KeyCode::Tab => Some(Action::Key(KeyAction::Tab)),
+        KeyCode::PageUp => Some(Action::Key(KeyAction::PageUp)),
+        KeyCode::PageDown => Some(Action::Key(KeyAction::PageDown)),
         _ => None,
     }
 }
@@ -81,4 +131,32 @@ pub enum KeyAction {
     Char(char),
     Backspace,
     Tab,
+    PageUp,
+    PageDown,
+    Quit,
+    WordLeft,
+    WordRight,
+    DeleteWord,
+    DeleteToStart,
+    Home,
+    End,
+    Newline,
+}
+
+impl KeyAction {
+    /// The character a key was typed as, so shortcut keys still type text
+    /// while the command bar has focus.
+    pub fn typed_char(&self) -> Option<char> {
+        match self {
+            KeyAction::Char(c) => Some(*c),
+            KeyAction::Dispatch => Some('d'),
+            KeyAction::ToggleAutoMode => Some('m'),
+            KeyAction::Refresh => Some('r'),
+            KeyAction::ClearAgent => Some('c'),
+            KeyAction::ClearLogs => Some('x'),
+            KeyAction::ActivateInput => Some(':'),
+            KeyAction::Quit => Some('q'),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,314 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::agents::log::{query_events, AgentEvent, EventQuery};
+use crate::agents::store::AgentStore;
+use crate::config;
+use crate::model::agent::AgentName;
+use crate::model::work_item::WorkItem;
+use crate::providers;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Table {
+    Items,
+    Events,
+    Dispatches,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExportArgs {
+    pub format: Format,
+    pub since: Option<DateTime<Utc>>,
+    /// Limit CSV output to one table, for piping into other tools
+    pub only: Option<Table>,
+    /// Fetch items live instead of reusing a recent fetch
+    pub fresh: bool,
+}
+
+/// One finished item from an agent's history.
+#[derive(Debug, Clone, Serialize)]
+pub struct DispatchRecord {
+    pub agent: AgentName,
+    pub work_item_id: String,
+    pub work_item_title: String,
+    pub outcome: String,
+    pub finished_at: String,
+}
+
+/// `work export [--format csv|json] [--since DATE] [--only TABLE]`: dump
+/// items, agent activity and dispatch history to stdout.
+pub async fn handle_export(args: &[String]) -> Result<()> {
+    let args = parse_export_args(args)?;
+
+    let config = config::load_config()?;
+    let mut providers = providers::create_cached_providers(&config, args.fresh);
+    let project_dir = std::env::current_dir()
+        .ok()
+        .and_then(|p| p.canonicalize().ok())
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    if let Some(mapping) = config::board_mapping(&project_dir) {
+        for provider in &mut providers {
+            if provider.name() == mapping.source {
+                provider.set_board_filter(mapping.board_id.clone());
+            }
+        }
+    }
+
+    let mut items = Vec::new();
+    for provider in &providers {
+        match provider.fetch_items().await {
+            Ok(fetched) => items.extend(fetched),
+            Err(e) => eprintln!("{}: {e}", provider.name()),
+        }
+    }
+
+    let events: Vec<AgentEvent> = query_events(&EventQuery {
+        since: args.since,
+        ..Default::default()
+    });
+
+    let store = AgentStore::new()?;
+    let dispatches: Vec<DispatchRecord> = store
+        .get_all()
+        .into_iter()
+        .flat_map(|agent| {
+            agent.history.iter().map(|r| DispatchRecord {
+                agent: agent.name,
+                work_item_id: r.work_item_id.clone(),
+                work_item_title: r.work_item_title.clone(),
+                outcome: r.outcome.clone(),
+                finished_at: r.finished_at.clone(),
+            })
+        })
+        .filter(|r| is_since(&r.finished_at, args.since))
+        .collect();
+
+    match args.format {
+        Format::Json => {
+            let json = serde_json::json!({
+                "exported_at": Utc::now().to_rfc3339(),
+                "items": items,
+                "events": events,
+                "dispatches": dispatches,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        Format::Csv => {
+            let tables = [
+                (Table::Items, "items", items_csv(&items)),
+                (Table::Events, "events", events_csv(&events)),
+                (Table::Dispatches, "dispatches", dispatches_csv(&dispatches)),
+            ];
+            match args.only {
+                Some(only) => {
+                    if let Some((_, _, csv)) = tables.iter().find(|(t, _, _)| *t == only) {
+                        print!("{csv}");
+                    }
+                }
+                None => {
+                    let sections: Vec<String> = tables
+                        .iter()
+                        .map(|(_, name, csv)| format!("# {name}\n{csv}"))
+                        .collect();
+                    print!("{}", sections.join("\n"));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn parse_export_args(args: &[String]) -> Result<ExportArgs> {
+    let mut parsed = ExportArgs {
+        format: Format::Json,
+        since: None,
+        only: None,
+        fresh: false,
+    };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--format" | "-f" => {
+                i += 1;
+                parsed.format = match args.get(i).map(String::as_str) {
+                    Some("json") => Format::Json,
+                    Some("csv") => Format::Csv,
+                    Some(other) => bail!("Unknown export format: {other}"),
+                    None => bail!("Missing value for --format flag"),
+                };
+            }
+            "--since" | "-s" => {
+                i += 1;
+                let value = args.get(i).context("Missing value for --since flag")?;
+                parsed.since = Some(parse_since(value)?);
+            }
+            "--only" => {
+                i += 1;
+                parsed.only = Some(match args.get(i).map(String::as_str) {
+                    Some("items") => Table::Items,
+                    Some("events") => Table::Events,
+                    Some("dispatches") => Table::Dispatches,
+                    Some(other) => bail!("Unknown table: {other}"),
+                    None => bail!("Missing value for --only flag"),
+                });
+            }
+            "--fresh" => parsed.fresh = true,
+            other => bail!("Unknown export option: {other}"),
+        }
+        i += 1;
+    }
+    Ok(parsed)
+}
+
+/// Accepts a date (`2026-01-31`, midnight UTC) or an RFC 3339 timestamp.
+fn parse_since(value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .with_context(|| format!("Invalid --since date: {value} (use YYYY-MM-DD)"))?;
+    Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+}
+
+fn is_since(timestamp: &str, since: Option<DateTime<Utc>>) -> bool {
+    let Some(since) = since else {
+        return true;
+    };
+    DateTime::parse_from_rfc3339(timestamp).is_ok_and(|ts| ts >= since)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_row(fields: &[&str]) -> String {
+    let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+    row.join(",") + "\n"
+}
+
+fn items_csv(items: &[WorkItem]) -> String {
+    let mut out = csv_row(&[
+        "id", "source", "title", "status", "priority", "labels", "team", "url",
+    ]);
+    for item in items {
+        out.push_str(&csv_row(&[
+            &item.id,
+            &item.source,
+            &item.title,
+            item.status.as_deref().unwrap_or(""),
+            item.priority.as_deref().unwrap_or(""),
+            &item.labels.join(";"),
+            item.team.as_deref().unwrap_or(""),
+            item.url.as_deref().unwrap_or(""),
+        ]));
+    }
+    out
+}
+
+fn events_csv(events: &[AgentEvent]) -> String {
+    let mut out = csv_row(&[
+        "timestamp",
+        "agent",
+        "event",
+        "work_item_id",
+        "work_item_title",
+        "message",
+    ]);
+    for e in events {
+        out.push_str(&csv_row(&[
+            &e.timestamp,
+            e.agent.as_str(),
+            &e.event,
+            e.work_item_id.as_deref().unwrap_or(""),
+            e.work_item_title.as_deref().unwrap_or(""),
+            e.message.as_deref().unwrap_or(""),
+        ]));
+    }
+    out
+}
+
+fn dispatches_csv(records: &[DispatchRecord]) -> String {
+    let mut out = csv_row(&[
+        "finished_at",
+        "agent",
+        "work_item_id",
+        "work_item_title",
+        "outcome",
+    ]);
+    for r in records {
+        out.push_str(&csv_row(&[
+            &r.finished_at,
+            r.agent.as_str(),
+            &r.work_item_id,
+            &r.work_item_title,
+            &r.outcome,
+        ]));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(strs: &[&str]) -> Vec<String> {
+        strs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_export_args() {
+        let parsed = parse_export_args(&args(&[])).unwrap();
+        assert_eq!(parsed.format, Format::Json);
+        assert_eq!(parsed.since, None);
+
+        let parsed = parse_export_args(&args(&[
+            "--format",
+            "csv",
+            "--since",
+            "2026-03-01",
+            "--only",
+            "events",
+        ]))
+        .unwrap();
+        assert_eq!(parsed.format, Format::Csv);
+        assert_eq!(parsed.only, Some(Table::Events));
+        assert_eq!(
+            parsed.since.unwrap().to_rfc3339(),
+            "2026-03-01T00:00:00+00:00"
+        );
+
+        assert!(parse_export_args(&args(&["--format", "xml"])).is_err());
+        assert!(parse_export_args(&args(&["--since", "last week"])).is_err());
+        assert!(parse_export_args(&args(&["--verbose"])).is_err());
+    }
+
+    #[test]
+    fn since_filters_by_timestamp() {
+        let since = parse_since("2026-03-01").ok();
+        assert!(is_since("2026-03-02T10:00:00+00:00", since));
+        assert!(!is_since("2026-02-28T23:59:59+00:00", since));
+        assert!(!is_since("garbage", since));
+        assert!(is_since("garbage", None));
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(
+            csv_row(&["ENG-1", "Fix \"login\", again", "a\nb"]),
+            "ENG-1,\"Fix \"\"login\"\", again\",\"a\nb\"\n"
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::data_dir;
+use crate::model::work_item::WorkItem;
+
+/// The item the user is working on themselves, pinned with `p`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Focus {
+    pub id: String,
+    pub source: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub started_at: String,
+}
+
+impl Focus {
+    pub fn of(item: &WorkItem) -> Self {
+        Self {
+            id: item.id.clone(),
+            source: item.source.clone(),
+            title: item.title.clone(),
+            url: item.url.clone(),
+            started_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    pub fn is(&self, item: &WorkItem) -> bool {
+        self.source == item.source && self.id == item.id
+    }
+
+    /// Time on the item, e.g. `42m` or `1h05m`.
+    pub fn elapsed(&self, now: DateTime<Utc>) -> String {
+        let minutes = DateTime::parse_from_rfc3339(&self.started_at)
+            .map(|started| now.signed_duration_since(started).num_minutes())
+            .unwrap_or(0);
+        format_minutes(minutes)
+    }
+}
+
+pub fn format_minutes(minutes: i64) -> String {
+    let minutes = minutes.max(0);
+    if minutes < 60 {
+        format!("{minutes}m")
+    } else {
+        format!("{}h{:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+/// A timed session on the pinned item, started and stopped with `t`.
+#[derive(Debug, Clone)]
+pub struct Pomodoro {
+    pub focus: Focus,
+    pub started_at: DateTime<Utc>,
+    pub length: Duration,
+}
+
+impl Pomodoro {
+    pub fn start(focus: &Focus, length: Duration, now: DateTime<Utc>) -> Self {
+        Self {
+            focus: focus.clone(),
+            started_at: now,
+            length,
+        }
+    }
+
+    pub fn is_over(&self, now: DateTime<Utc>) -> bool {
+        now >= self.started_at + self.length
+    }
+
+    /// Time left as `mm:ss`.
+    pub fn countdown(&self, now: DateTime<Utc>) -> String {
+        let left = (self.started_at + self.length - now).num_seconds().max(0);
+        format!("{:02}:{:02}", left / 60, left % 60)
+    }
+
+    /// The session so far as a time entry, capped at its length.
+    pub fn entry(&self, now: DateTime<Utc>) -> TimeEntry {
+        let spent = (now - self.started_at).min(self.length);
+        TimeEntry {
+            id: self.focus.id.clone(),
+            source: self.focus.source.clone(),
+            title: self.focus.title.clone(),
+            started_at: self.started_at.to_rfc3339(),
+            minutes: spent.num_minutes().max(0),
+        }
+    }
+}
+
+/// Time spent on an item in one session, one line of the time log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub id: String,
+    pub source: String,
+    pub title: String,
+    pub started_at: String,
+    pub minutes: i64,
+}
+
+fn time_log_path() -> PathBuf {
+    data_dir().join("time-log.jsonl")
+}
+
+pub fn log_time(entry: &TimeEntry) -> Result<()> {
+    log_time_in(&time_log_path(), entry)
+}
+
+fn log_time_in(path: &Path, entry: &TimeEntry) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// Total minutes logged against an item.
+pub fn tracked_minutes(source: &str, id: &str) -> i64 {
+    tracked_minutes_in(&time_log_path(), source, id)
+}
+
+fn tracked_minutes_in(path: &Path, source: &str, id: &str) -> i64 {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str::<TimeEntry>(line).ok())
+        .filter(|e| e.source == source && e.id == id)
+        .map(|e| e.minutes)
+        .sum()
+}
+
+/// Kept in a file rather than app state so `work status` can print it for a
+/// shell prompt while the dashboard runs.
+fn focus_path() -> PathBuf {
+    data_dir().join("focus.json")
+}
+
+pub fn load_focus() -> Option<Focus> {
+    let contents = std::fs::read_to_string(focus_path()).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+pub fn save_focus(focus: &Focus) -> Result<()> {
+    let path = focus_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(focus)?)?;
+    Ok(())
+}
+
+pub fn clear_focus() {
+    let _ = std::fs::remove_file(focus_path());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elapsed_shows_hours_past_sixty_minutes() {
+        let now = Utc::now();
+        let mut focus = Focus {
+            id: "ENG-7".into(),
+            source: "Linear".into(),
+            title: "Rework billing".into(),
+            url: None,
+            started_at: (now - chrono::Duration::minutes(42)).to_rfc3339(),
+        };
+        assert_eq!(focus.elapsed(now), "42m");
+        focus.started_at = (now - chrono::Duration::minutes(65)).to_rfc3339();
+        assert_eq!(focus.elapsed(now), "1h05m");
+    }
+
+    #[test]
+    fn sessions_log_time_up_to_their_length() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("time-log.jsonl");
+        let now = Utc::now();
+        let focus = Focus {
+            id: "ENG-7".into(),
+            source: "Linear".into(),
+            title: "Rework billing".into(),
+            url: None,
+            started_at: now.to_rfc3339(),
+        };
+        let session = Pomodoro::start(&focus, Duration::minutes(25), now);
+        assert_eq!(session.countdown(now + Duration::seconds(90)), "23:30");
+        assert!(!session.is_over(now + Duration::minutes(24)));
+
+        let stopped = session.entry(now + Duration::minutes(10));
+        let finished = session.entry(now + Duration::minutes(40));
+        assert_eq!((stopped.minutes, finished.minutes), (10, 25));
+        log_time_in(&log, &stopped).unwrap();
+        log_time_in(&log, &finished).unwrap();
+        assert_eq!(tracked_minutes_in(&log, "Linear", "ENG-7"), 35);
+        assert_eq!(tracked_minutes_in(&log, "Jira", "ENG-7"), 0);
+    }
+}
@@ -0,0 +1,121 @@
+use std::process::Stdio;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+
+use crate::config::HooksConfig;
+
+/// Lifecycle events that can run a user shell hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    Dispatch,
+    Done,
+    Error,
+    ItemCreated,
+}
+
+impl Hook {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Hook::Dispatch => "dispatch",
+            Hook::Done => "done",
+            Hook::Error => "error",
+            Hook::ItemCreated => "item_created",
+        }
+    }
+}
+
+impl HooksConfig {
+    pub fn command(&self, hook: Hook) -> Option<&str> {
+        match hook {
+            Hook::Dispatch => self.on_dispatch.as_deref(),
+            Hook::Done => self.on_done.as_deref(),
+            Hook::Error => self.on_error.as_deref(),
+            Hook::ItemCreated => self.on_item_created.as_deref(),
+        }
+    }
+}
+
+/// Event JSON written to a hook's stdin: `{"event", "timestamp", ...fields}`.
+pub fn payload(hook: Hook, fields: Value) -> Value {
+    let mut payload = serde_json::json!({
+        "event": hook.as_str(),
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    });
+    if let (Some(obj), Value::Object(fields)) = (payload.as_object_mut(), fields) {
+        obj.extend(fields);
+    }
+    payload
+}
+
+/// Run `command` through `sh -c` with the payload on stdin and wait for it.
+pub async fn run_hook(command: &str, payload: &Value) -> Result<()> {
+    let mut child = tokio::process::Command::new("sh")
+        .args(["-c", command])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run hook: {command}"))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores stdin may exit before reading it
+        let _ = stdin.write_all(payload.to_string().as_bytes()).await;
+    }
+
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Hook `{command}` failed: {}", stderr.trim());
+    }
+    Ok(())
+}
+
+/// Run the configured hook for `hook` in the background, if any.
+pub fn spawn_hook(cfg: &HooksConfig, hook: Hook, fields: Value) {
+    let Some(command) = cfg.command(hook).map(String::from) else {
+        return;
+    };
+    let payload = payload(hook, fields);
+    tokio::spawn(async move {
+        let _ = run_hook(&command, &payload).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_merges_fields() {
+        let p = payload(Hook::Done, serde_json::json!({ "agent": "ember" }));
+        assert_eq!(p["event"], "done");
+        assert_eq!(p["agent"], "ember");
+        assert!(p["timestamp"].is_string());
+    }
+
+    #[tokio::test]
+    async fn hook_receives_json_on_stdin() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("event.json");
+        let command = format!("cat > {}", out.display());
+
+        let p = payload(
+            Hook::ItemCreated,
+            serde_json::json!({ "item": { "id": "ENG-1" } }),
+        );
+        run_hook(&command, &p).await.unwrap();
+
+        let written: Value = serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(written["event"], "item_created");
+        assert_eq!(written["item"]["id"], "ENG-1");
+    }
+
+    #[tokio::test]
+    async fn failing_hook_reports_stderr() {
+        let p = payload(Hook::Error, Value::Null);
+        let err = run_hook("echo boom >&2; exit 1", &p).await.unwrap_err();
+        assert!(err.to_string().contains("boom"));
+    }
+}
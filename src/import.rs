@@ -0,0 +1,276 @@
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use crate::cli;
+use crate::config;
+
+/// A task read from an import file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Task {
+    pub title: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ImportArgs {
+    pub path: String,
+    /// Create without asking, e.g. from a script
+    pub yes: bool,
+    /// Only print the preview
+    pub dry_run: bool,
+}
+
+/// `work import <file> [--yes] [--dry-run]`: create a task per row of a CSV
+/// file or per open item of a Markdown checklist, in the mapped board,
+/// after showing what will be created.
+pub async fn handle_import(args: &[String]) -> Result<()> {
+    let args = parse_import_args(args)?;
+    let text = std::fs::read_to_string(&args.path)
+        .with_context(|| format!("Failed to read {}", args.path))?;
+    let is_csv = Path::new(&args.path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    let tasks = if is_csv {
+        parse_csv_tasks(&text)?
+    } else {
+        parse_checklist(&text)
+    };
+    if tasks.is_empty() {
+        bail!("No tasks found in {}", args.path);
+    }
+
+    let project_dir = cli::current_project_dir();
+    let board = config::board_mapping(&project_dir)
+        .map_or_else(|| "the first provider that can create".to_string(), |m| m.board_name);
+    for (i, task) in tasks.iter().enumerate() {
+        let notes = if task.description.is_some() { "  (+ description)" } else { "" };
+        println!("{:>3}. {}{notes}", i + 1, task.title);
+    }
+    if args.dry_run {
+        return Ok(());
+    }
+    if !args.yes {
+        let stdin = std::io::stdin();
+        if !stdin.is_terminal() {
+            bail!("Pass --yes to import without a terminal to confirm on");
+        }
+        let prompt = format!("Create {} tasks in {board}? [y/N]", tasks.len());
+        if !confirm(&mut stdin.lock(), &mut std::io::stdout(), &prompt)? {
+            println!("Nothing imported");
+            return Ok(());
+        }
+    }
+
+    let config = config::load_config()?;
+    let providers = cli::task_providers(&config, &project_dir)?;
+    for (i, task) in tasks.iter().enumerate() {
+        let item = cli::create_in(&providers, &config, &task.title, task.description.as_deref())
+            .await
+            // Say how far it got, so a rerun can skip what's already in
+            .with_context(|| format!("Imported {i} of {} tasks", tasks.len()))?;
+        println!("Created in {}: {} — {}", item.source, item.id, item.title);
+    }
+    Ok(())
+}
+
+pub fn parse_import_args(args: &[String]) -> Result<ImportArgs> {
+    let mut path = None;
+    let mut yes = false;
+    let mut dry_run = false;
+    for arg in args {
+        match arg.as_str() {
+            "-y" | "--yes" => yes = true,
+            "-n" | "--dry-run" => dry_run = true,
+            other if other.starts_with('-') => bail!("Unknown import option: {other}"),
+            other if path.is_none() => path = Some(other.to_string()),
+            _ => bail!("Import one file at a time"),
+        }
+    }
+    let path = path.context(
+        "Usage: work import <file.csv|file.md> [--yes] [--dry-run]\n\n\
+         CSV needs a title column; description is optional.\n\
+         Markdown imports each unchecked `- [ ]` item, with indented lines\n\
+         under it as its description.",
+    )?;
+    Ok(ImportArgs {
+        path,
+        yes,
+        dry_run,
+    })
+}
+
+fn confirm(input: &mut impl BufRead, out: &mut impl Write, prompt: &str) -> Result<bool> {
+    write!(out, "{prompt} ")?;
+    out.flush()?;
+    let mut line = String::new();
+    input.read_line(&mut line)?;
+    Ok(matches!(line.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Unchecked `- [ ]` / `* [ ]` items of a Markdown checklist; checked ones
+/// are done already. Lines indented under an item, other than nested items,
+/// are its description.
+pub fn parse_checklist(text: &str) -> Vec<Task> {
+    let mut tasks = Vec::new();
+    // Indent and description lines of the open item being read
+    let mut current: Option<(usize, Vec<&str>)> = None;
+    let finish = |current: &mut Option<(usize, Vec<&str>)>, tasks: &mut Vec<Task>| {
+        if let Some((_, lines)) = current.take() {
+            if let Some(task) = tasks.last_mut() {
+                let description = lines.join("\n").trim().to_string();
+                task.description = Some(description).filter(|d| !d.is_empty());
+            }
+        }
+    };
+
+    for line in text.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim_start();
+        let item = ["- [", "* [", "+ ["]
+            .iter()
+            .find_map(|prefix| trimmed.strip_prefix(prefix))
+            .and_then(|rest| Some((rest.chars().next()?, rest.get(1..)?.strip_prefix(']')?)));
+        if let Some((mark, title)) = item {
+            finish(&mut current, &mut tasks);
+            let title = title.trim();
+            if mark == ' ' && !title.is_empty() {
+                tasks.push(Task {
+                    title: title.to_string(),
+                    description: None,
+                });
+                current = Some((indent, Vec::new()));
+            }
+            continue;
+        }
+        match &mut current {
+            Some((item_indent, lines)) if indent > *item_indent || trimmed.is_empty() => {
+                lines.push(trimmed);
+            }
+            _ => finish(&mut current, &mut tasks),
+        }
+    }
+    finish(&mut current, &mut tasks);
+    tasks
+}
+
+/// Rows of a CSV file with a header naming a `title` column and optionally
+/// a `description` one; other columns are ignored.
+pub fn parse_csv_tasks(text: &str) -> Result<Vec<Task>> {
+    let mut rows = parse_csv(text).into_iter();
+    let header = rows.next().unwrap_or_default();
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|h| names.iter().any(|n| h.trim().eq_ignore_ascii_case(n)))
+    };
+    let title = column(&["title", "summary", "name"]).context("CSV needs a title column")?;
+    let description = column(&["description", "desc", "body"]);
+    Ok(rows
+        .filter_map(|row| {
+            let title = row.get(title)?.trim();
+            (!title.is_empty()).then(|| Task {
+                title: title.to_string(),
+                description: description
+                    .and_then(|d| row.get(d))
+                    .map(|d| d.trim().to_string())
+                    .filter(|d| !d.is_empty()),
+            })
+        })
+        .collect())
+}
+
+/// Split CSV into rows of fields: quoted fields may hold commas, newlines
+/// and doubled quotes. Blank lines are skipped.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                if row.iter().any(|f| !f.is_empty()) {
+                    rows.push(std::mem::take(&mut row));
+                }
+                row.clear();
+            }
+            c => field.push(c),
+        }
+    }
+    row.push(field);
+    if row.iter().any(|f| !f.is_empty()) {
+        rows.push(row);
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(title: &str, description: Option<&str>) -> Task {
+        Task {
+            title: title.to_string(),
+            description: description.map(String::from),
+        }
+    }
+
+    #[test]
+    fn imports_open_checklist_items_with_their_notes() {
+        let doc = "# Q3 plan\n\n\
+                   - [ ] Migrate billing to Stripe\n  Keep the old webhooks until August.\n\n  Ask finance first.\n\
+                   - [x] Pick a vendor\n\
+                   * [ ] Remove legacy export\n    - [ ] Nested follow-up\n\
+                   Closing remarks\n";
+        assert_eq!(
+            parse_checklist(doc),
+            [
+                task(
+                    "Migrate billing to Stripe",
+                    Some("Keep the old webhooks until August.\n\nAsk finance first.")
+                ),
+                task("Remove legacy export", None),
+                task("Nested follow-up", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn imports_csv_rows_by_header() {
+        let csv = "Priority,Title,Description\n\
+                   high,Fix login,\"Users see \"\"403\"\", then\na blank page\"\r\n\
+                   low,Tidy docs,\n\
+                   \n\
+                   low,,skipped without a title\n";
+        assert_eq!(
+            parse_csv_tasks(csv).unwrap(),
+            [
+                task("Fix login", Some("Users see \"403\", then\na blank page")),
+                task("Tidy docs", None),
+            ]
+        );
+        assert!(parse_csv_tasks("priority,owner\nhigh,ana\n").is_err());
+    }
+
+    #[test]
+    fn parses_import_args() {
+        let args = |a: &[&str]| parse_import_args(&a.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+        let parsed = args(&["plan.md", "--yes"]).unwrap();
+        assert_eq!(parsed.path, "plan.md");
+        assert!(parsed.yes && !parsed.dry_run);
+        assert!(args(&[]).is_err());
+        assert!(args(&["a.md", "b.md"]).is_err());
+        assert!(args(&["a.md", "--force"]).is_err());
+    }
+}
@@ -0,0 +1,396 @@
+use anyhow::Result;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::data_dir;
+
+/// Submitted lines kept for Up/Down recall across sessions.
+const HISTORY_LIMIT: usize = 500;
+
+/// Text being edited in the command bar, usually one line but multi-line
+/// after a paste or Shift+Enter. The cursor is a byte offset that always
+/// sits on a grapheme boundary, so emoji and combining characters move and
+/// delete as one unit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputLine {
+    text: String,
+    cursor: usize,
+}
+
+impl InputLine {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replace the text and put the cursor at the end.
+    pub fn set(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.len();
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    /// Take the text out, leaving the line empty.
+    pub fn take(&mut self) -> String {
+        self.cursor = 0;
+        std::mem::take(&mut self.text)
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.text.split('\n').count()
+    }
+
+    /// Line index and terminal column of the cursor.
+    pub fn cursor_position(&self) -> (usize, usize) {
+        let before = &self.text[..self.cursor];
+        let row = before.matches('\n').count();
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (row, before[line_start..].width())
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// Insert pasted text, normalising line endings.
+    pub fn insert_str(&mut self, s: &str) {
+        let s = s.replace("\r\n", "\n").replace('\r', "\n");
+        self.text.insert_str(self.cursor, &s);
+        self.cursor += s.len();
+    }
+
+    pub fn backspace(&mut self) {
+        let start = self.prev_boundary();
+        self.text.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    pub fn left(&mut self) {
+        self.cursor = self.prev_boundary();
+    }
+
+    pub fn right(&mut self) {
+        self.cursor = self.next_boundary();
+    }
+
+    pub fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.cursor = self.text.len();
+    }
+
+    /// Move to the same column on the previous line. Returns false on the
+    /// first line so Up can fall through to history.
+    pub fn up(&mut self) -> bool {
+        let line_start = self.line_start(self.cursor);
+        if line_start == 0 {
+            return false;
+        }
+        let column = self.text[line_start..self.cursor].width();
+        let prev_start = self.line_start(line_start - 1);
+        self.cursor = self.column_offset(prev_start, column);
+        true
+    }
+
+    /// Move to the same column on the next line. Returns false on the last.
+    pub fn down(&mut self) -> bool {
+        let Some(newline) = self.text[self.cursor..].find('\n') else {
+            return false;
+        };
+        let column = self.text[self.line_start(self.cursor)..self.cursor].width();
+        self.cursor = self.column_offset(self.cursor + newline + 1, column);
+        true
+    }
+
+    /// Move to the start of the current or previous word.
+    pub fn word_left(&mut self) {
+        self.cursor = self.prev_word_start();
+    }
+
+    /// Move past the end of the current or next word.
+    pub fn word_right(&mut self) {
+        let rest = &self.text[self.cursor..];
+        let skipped = rest.len() - rest.trim_start().len();
+        let word = rest[skipped..]
+            .find(char::is_whitespace)
+            .unwrap_or(rest.len() - skipped);
+        self.cursor += skipped + word;
+    }
+
+    /// Ctrl+W: delete the word before the cursor.
+    pub fn delete_word(&mut self) {
+        let start = self.prev_word_start();
+        self.text.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    /// Ctrl+U: delete everything before the cursor.
+    pub fn delete_to_start(&mut self) {
+        self.text.drain(..self.cursor);
+        self.cursor = 0;
+    }
+
+    fn line_start(&self, offset: usize) -> usize {
+        self.text[..offset].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    /// Byte offset of the grapheme at `column` on the line starting at
+    /// `start`, or the end of that line if it is shorter.
+    fn column_offset(&self, start: usize, column: usize) -> usize {
+        let line = self.text[start..].split('\n').next().unwrap_or("");
+        let mut width = 0;
+        for (i, g) in line.grapheme_indices(true) {
+            if width >= column {
+                return start + i;
+            }
+            width += g.width();
+        }
+        start + line.len()
+    }
+
+    fn prev_boundary(&self) -> usize {
+        self.text[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.text[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map_or(self.cursor, |g| self.cursor + g.len())
+    }
+
+    fn prev_word_start(&self) -> usize {
+        let before = self.text[..self.cursor].trim_end();
+        before
+            .rfind(char::is_whitespace)
+            .map_or(0, |i| i + before[i..].chars().next().map_or(1, char::len_utf8))
+    }
+}
+
+/// Previously submitted commands, messages and task titles, oldest first.
+#[derive(Debug, Default)]
+pub struct InputHistory {
+    entries: Vec<String>,
+    /// Entry being shown while browsing; None when editing a fresh line
+    position: Option<usize>,
+    /// The unsent line to restore after browsing past the newest entry
+    draft: String,
+    path: Option<PathBuf>,
+}
+
+fn history_path() -> PathBuf {
+    data_dir().join("input_history.jsonl")
+}
+
+impl InputHistory {
+    pub fn load() -> Self {
+        Self::load_at(history_path())
+    }
+
+    fn load_at(path: PathBuf) -> Self {
+        let entries: Vec<String> = std::fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        let mut history = InputHistory {
+            path: Some(path),
+            ..Default::default()
+        };
+        if entries.len() > HISTORY_LIMIT * 2 {
+            // Compact so the file doesn't grow without bound
+            history.entries = entries[entries.len() - HISTORY_LIMIT..].to_vec();
+            if let Some(path) = &history.path {
+                let _ = write_entries(path, &history.entries);
+            }
+        } else {
+            let skip = entries.len().saturating_sub(HISTORY_LIMIT);
+            history.entries = entries.into_iter().skip(skip).collect();
+        }
+        history
+    }
+
+    /// Record a submitted line; repeats of the newest entry are skipped.
+    pub fn push(&mut self, entry: &str) {
+        self.reset();
+        if entry.trim().is_empty() || self.entries.last().is_some_and(|e| e == entry) {
+            return;
+        }
+        self.entries.push(entry.to_string());
+        if self.entries.len() > HISTORY_LIMIT {
+            self.entries.remove(0);
+        }
+        if let Some(path) = &self.path {
+            let _ = append_entry(path, entry);
+        }
+    }
+
+    /// Step back to an older entry, saving `current` as the draft first.
+    pub fn prev(&mut self, current: &str) -> Option<&str> {
+        let index = match self.position {
+            None if self.entries.is_empty() => return None,
+            None => {
+                self.draft = current.to_string();
+                self.entries.len() - 1
+            }
+            Some(0) => 0,
+            Some(i) => i - 1,
+        };
+        self.position = Some(index);
+        Some(&self.entries[index])
+    }
+
+    /// Step forward to a newer entry, ending on the saved draft.
+    pub fn next(&mut self) -> Option<String> {
+        let index = self.position?;
+        if index + 1 < self.entries.len() {
+            self.position = Some(index + 1);
+            Some(self.entries[index + 1].clone())
+        } else {
+            self.position = None;
+            Some(std::mem::take(&mut self.draft))
+        }
+    }
+
+    /// Stop browsing, e.g. when the command bar closes.
+    pub fn reset(&mut self) {
+        self.position = None;
+        self.draft.clear();
+    }
+}
+
+fn append_entry(path: &Path, entry: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+fn write_entries(path: &Path, entries: &[String]) -> Result<()> {
+    let mut contents = String::new();
+    for entry in entries {
+        contents.push_str(&serde_json::to_string(entry)?);
+        contents.push('\n');
+    }
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str) -> InputLine {
+        let mut input = InputLine::default();
+        input.set(text);
+        input
+    }
+
+    #[test]
+    fn edits_whole_graphemes() {
+        let mut input = line("héllo 👍🏽");
+        input.backspace();
+        assert_eq!(input.text(), "héllo ");
+
+        input.set("日本");
+        input.left();
+        input.insert('x');
+        assert_eq!(input.text(), "日x本");
+        assert_eq!(input.cursor_position(), (0, 3));
+        input.right();
+        input.right();
+        assert_eq!(input.cursor_position(), (0, 5));
+    }
+
+    #[test]
+    fn moves_by_word() {
+        let mut input = line("@flow fix  the build");
+        input.word_left();
+        input.word_left();
+        assert_eq!(&input.text()[input.cursor..], "the build");
+        input.word_right();
+        assert_eq!(&input.text()[input.cursor..], " build");
+        input.home();
+        input.word_right();
+        assert_eq!(&input.text()[input.cursor..], " fix  the build");
+    }
+
+    #[test]
+    fn deletes_words_and_lines() {
+        let mut input = line("@flow fix the 漢字  ");
+        input.delete_word();
+        assert_eq!(input.text(), "@flow fix the ");
+        input.delete_word();
+        assert_eq!(input.text(), "@flow fix ");
+
+        input.left();
+        input.delete_to_start();
+        assert_eq!(input.text(), " ");
+        assert_eq!(input.cursor_position(), (0, 0));
+    }
+
+    #[test]
+    fn pastes_and_moves_between_lines() {
+        let mut input = InputLine::default();
+        input.insert_str("Crash on save\r\nStack: 日本語\r\nSteps");
+        assert_eq!(input.text(), "Crash on save\nStack: 日本語\nSteps");
+        assert_eq!(input.line_count(), 3);
+        assert_eq!(input.cursor_position(), (2, 5));
+
+        assert!(input.up());
+        assert_eq!(input.cursor_position(), (1, 5));
+        assert!(input.up());
+        assert_eq!(input.cursor_position(), (0, 5));
+        assert!(!input.up());
+
+        // Columns clamp to shorter lines and never split a wide character
+        input.set("ab\nCrash on save");
+        assert!(input.up());
+        assert_eq!(input.cursor_position(), (0, 2));
+        assert!(input.down());
+        assert_eq!(input.cursor_position(), (1, 2));
+        input.set("日本語\nabc");
+        assert!(input.up());
+        assert_eq!(input.cursor_position(), (0, 4));
+        assert!(input.down());
+        assert!(!input.down());
+    }
+
+    #[test]
+    fn history_browses_and_restores_draft() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let mut history = InputHistory::load_at(path.clone());
+        history.push("@flow run the tests");
+        history.push("/refresh");
+        history.push("/refresh");
+
+        assert_eq!(history.prev("@fl"), Some("/refresh"));
+        assert_eq!(history.prev(""), Some("@flow run the tests"));
+        assert_eq!(history.prev(""), Some("@flow run the tests"));
+        assert_eq!(history.next().as_deref(), Some("/refresh"));
+        assert_eq!(history.next().as_deref(), Some("@fl"));
+        assert_eq!(history.next(), None);
+
+        // Persisted across sessions
+        let mut reloaded = InputHistory::load_at(path);
+        assert_eq!(reloaded.prev(""), Some("/refresh"));
+        assert_eq!(reloaded.prev(""), Some("@flow run the tests"));
+    }
+}
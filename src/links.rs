@@ -0,0 +1,152 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::config::data_dir;
+use crate::model::work_item::WorkItem;
+
+/// An item in one provider, enough to find it again and close it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemRef {
+    pub source: String,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<String>,
+}
+
+impl ItemRef {
+    pub fn of(item: &WorkItem) -> Self {
+        Self {
+            source: item.source.clone(),
+            id: item.id.clone(),
+            source_id: item.source_id.clone(),
+        }
+    }
+
+    fn is(&self, source: &str, id: &str) -> bool {
+        self.source == source && self.id == id
+    }
+}
+
+/// An item mirrored into another provider; finishing either closes both.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemLink {
+    pub from: ItemRef,
+    pub to: ItemRef,
+    pub created_at: String,
+}
+
+/// Mirrored items, persisted so the sync survives restarts.
+#[derive(Debug, Default)]
+pub struct ItemLinks {
+    links: Vec<ItemLink>,
+    path: Option<PathBuf>,
+}
+
+fn links_path() -> PathBuf {
+    data_dir().join("item-links.json")
+}
+
+impl ItemLinks {
+    pub fn load() -> Self {
+        Self::load_at(links_path())
+    }
+
+    fn load_at(path: PathBuf) -> Self {
+        let links = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            links,
+            path: Some(path),
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, serde_json::to_string_pretty(&self.links)?)?;
+        }
+        Ok(())
+    }
+
+    pub fn add(&mut self, from: ItemRef, to: ItemRef) -> Result<()> {
+        self.links.push(ItemLink {
+            from,
+            to,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        });
+        self.save()
+    }
+
+    /// Items mirrored from or to this one.
+    pub fn partners(&self, source: &str, id: &str) -> Vec<&ItemRef> {
+        self.links
+            .iter()
+            .filter_map(|link| {
+                if link.from.is(source, id) {
+                    Some(&link.to)
+                } else if link.to.is(source, id) {
+                    Some(&link.from)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Drop the links of a finished item and return the items to close with
+    /// it. Removing the links first keeps closing a partner from coming back.
+    pub fn close(&mut self, source: &str, id: &str) -> Result<Vec<ItemRef>> {
+        let partners: Vec<ItemRef> = self.partners(source, id).into_iter().cloned().collect();
+        if partners.is_empty() {
+            return Ok(partners);
+        }
+        self.links
+            .retain(|link| !link.from.is(source, id) && !link.to.is(source, id));
+        self.save()?;
+        Ok(partners)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item_ref(source: &str, id: &str) -> ItemRef {
+        ItemRef {
+            source: source.into(),
+            id: id.into(),
+            source_id: Some(format!("{id}-full")),
+        }
+    }
+
+    #[test]
+    fn closing_either_side_returns_the_other_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("item-links.json");
+        let mut links = ItemLinks::load_at(path.clone());
+        links
+            .add(item_ref("Jira", "ENG-1"), item_ref("GitHub", "#4"))
+            .unwrap();
+
+        let reloaded = ItemLinks::load_at(path.clone());
+        assert_eq!(
+            reloaded.partners("Jira", "ENG-1"),
+            [&item_ref("GitHub", "#4")]
+        );
+
+        let mut links = reloaded;
+        assert_eq!(
+            links.close("GitHub", "#4").unwrap(),
+            [item_ref("Jira", "ENG-1")]
+        );
+        assert!(links.close("Jira", "ENG-1").unwrap().is_empty());
+        assert!(ItemLinks::load_at(path)
+            .partners("Jira", "ENG-1")
+            .is_empty());
+    }
+}
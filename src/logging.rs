@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use tracing::Level;
+
+use crate::config::data_dir;
+
+/// Lines kept in memory for the debug log overlay.
+const RECENT_LINES: usize = 500;
+
+/// A log past this size is moved to `work.log.1` at startup.
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+static RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Lines logged since startup, so the TUI can tell when the overlay is stale.
+static LOGGED: AtomicUsize = AtomicUsize::new(0);
+
+/// Remove `-v`/`--verbose` given before the subcommand from `args`, and
+/// whether it was there. Later ones belong to the subcommand, e.g. a task
+/// titled "-v" in `work add -v`.
+pub fn take_verbose_flag(args: &mut Vec<String>) -> bool {
+    let flags = args
+        .iter()
+        .skip(1)
+        .take_while(|a| *a == "-v" || *a == "--verbose")
+        .count();
+    args.drain(1..1 + flags);
+    flags > 0
+}
+
+pub fn log_path() -> PathBuf {
+    data_dir().join("work.log")
+}
+
+/// Send `tracing` events to `work.log` and the overlay: info and up, or
+/// debug and up with `--verbose`. Nothing goes to the terminal, which the
+/// TUI owns.
+pub fn init(verbose: bool) -> Result<()> {
+    let path = log_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() > MAX_LOG_BYTES) {
+        std::fs::rename(&path, path.with_extension("log.1"))?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+    let writer = LogWriter(Arc::new(Mutex::new(file)));
+    tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .with_max_level(if verbose { Level::DEBUG } else { Level::INFO })
+        .try_init()
+        .map_err(|e| anyhow!(e))
+}
+
+/// The latest log lines, oldest first.
+pub fn recent() -> Vec<String> {
+    RECENT
+        .lock()
+        .map(|r| r.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+pub fn logged() -> usize {
+    LOGGED.load(Ordering::Relaxed)
+}
+
+/// Appends each formatted event to the file and keeps its lines for the
+/// overlay.
+#[derive(Clone)]
+struct LogWriter(Arc<Mutex<File>>);
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Ok(mut file) = self.0.lock() {
+            file.write_all(buf)?;
+        }
+        if let Ok(mut recent) = RECENT.lock() {
+            for line in String::from_utf8_lossy(buf).lines() {
+                if recent.len() == RECENT_LINES {
+                    recent.pop_front();
+                }
+                recent.push_back(line.to_string());
+                LOGGED.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.0.lock() {
+            Ok(mut file) => file.flush(),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verbose_is_only_a_flag_before_the_subcommand() {
+        let args = |line: &str| -> Vec<String> { line.split(' ').map(String::from).collect() };
+
+        let mut before = args("work -v --verbose list");
+        assert!(take_verbose_flag(&mut before));
+        assert_eq!(before, ["work", "list"]);
+
+        let mut after = args("work add -v");
+        assert!(!take_verbose_flag(&mut after));
+        assert_eq!(after, ["work", "add", "-v"]);
+    }
+
+    #[test]
+    fn keeps_the_latest_lines_for_the_overlay() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = File::create(dir.path().join("work.log")).unwrap();
+        let mut writer = LogWriter(Arc::new(Mutex::new(file)));
+        let before = logged();
+
+        writer
+            .write_all(b"first event\nwith a second line\n")
+            .unwrap();
+        // The subscriber hands over each event in one write
+        for n in 0..RECENT_LINES {
+            writer.write_all(format!("event {n}\n").as_bytes()).unwrap();
+        }
+
+        assert_eq!(logged() - before, RECENT_LINES + 2);
+        let recent = recent();
+        assert_eq!(recent.len(), RECENT_LINES);
+        assert_eq!(
+            recent.last().unwrap(),
+            &format!("event {}", RECENT_LINES - 1)
+        );
+        let written = std::fs::read_to_string(dir.path().join("work.log")).unwrap();
+        assert!(written.starts_with("first event\nwith a second line\nevent 0\n"));
+    }
+}
@@ -1,20 +1,45 @@
 mod agents;
 mod app;
 mod cli;
+mod command;
 mod config;
+mod daemon;
+mod dotenv;
 mod event;
+mod export;
+mod focus;
+mod hooks;
+mod import;
+mod input;
+mod links;
+mod logging;
 mod model;
+mod notify;
 mod providers;
+mod secrets;
+mod serve;
+mod setup;
+mod status;
 mod ui;
+mod undo;
 mod util;
+mod webhook;
 
-use std::io;
+use std::io::{self, IsTerminal};
 use std::panic;
 
 use anyhow::Result;
 use crossterm::{
+    cursor::Show,
+    event::{
+        DisableBracketedPaste, EnableBracketedPaste, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{
+        disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use tokio::sync::mpsc;
@@ -24,10 +49,30 @@ use app::{Action, App};
 #[tokio::main]
 async fn main() -> Result<()> {
     // Check for CLI subcommands before launching TUI
-    let args: Vec<String> = std::env::args().collect();
+    let mut args: Vec<String> = std::env::args().collect();
+    // Debug logging, for the TUI or any subcommand
+    let verbose = logging::take_verbose_flag(&mut args);
+    // Running without a log file beats not running
+    let _ = logging::init(verbose);
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), args = ?&args[1..], "starting");
+
     if args.len() > 1 {
         match args[1].as_str() {
             "add" => return cli::handle_add(&args[2..]).await,
+            "quick" => return cli::handle_quick(&args[2..]).await,
+            "serve" => return serve::handle_serve(&args[2..]).await,
+            "daemon" => return daemon::handle_daemon(&args[2..]).await,
+            "clean" => return cli::handle_clean(&args[2..]).await,
+            "list" => return cli::handle_list(&args[2..]).await,
+            "agents" => return cli::handle_agents(&args[2..]),
+            "cd" => return cli::handle_cd(&args[2..]),
+            "export" => return export::handle_export(&args[2..]).await,
+            "import" => return import::handle_import(&args[2..]).await,
+            "init" => return setup::handle_init(&args[2..]).await,
+            "config" => return cli::handle_config(&args[2..]).await,
+            "status" => return cli::handle_status(&args[2..]),
+            "hygiene-check" => return cli::handle_hygiene_check().await,
+            "ask" => return cli::handle_ask(&args[2..]).await,
             "help" | "--help" | "-h" => {
                 cli::print_help();
                 return Ok(());
@@ -36,6 +81,19 @@ async fn main() -> Result<()> {
         }
     }
 
+    // First run: offer setup instead of starting with no providers
+    if !config::config_path().exists()
+        && io::stdin().is_terminal()
+        && providers::create_providers(&config::load_config()?).is_empty()
+    {
+        setup::run_wizard().await?;
+    }
+
+    // A checked-in .work.toml only runs commands once this user trusts it
+    if io::stdin().is_terminal() {
+        setup::confirm_project_trust()?;
+    }
+
     // Load config
     let config = config::load_config()?;
 
@@ -48,46 +106,71 @@ async fn main() -> Result<()> {
     // Create app
     let mut app = App::new(&config, store, action_tx.clone());
 
+    // Hand the pool to a running `work daemon`, mirroring its auto mode
+    if let Some(daemon) = daemon::Client::connect().await {
+        app.adopt_daemon(daemon).await;
+    }
+
     // Set up terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
+    // Lets terminals that support it report Shift+Enter for multi-line input
+    let enhanced_keys = supports_keyboard_enhancement().unwrap_or(false);
+    enter_terminal(enhanced_keys)?;
+    let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
     terminal.hide_cursor()?;
 
     // Set up panic hook to restore terminal
     let original_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
-        let _ = disable_raw_mode();
-        let _ = execute!(io::stdout(), LeaveAlternateScreen);
+        let _ = leave_terminal(enhanced_keys);
         original_hook(panic_info);
     }));
 
     // Spawn event reader
-    let event_tx = action_tx.clone();
-    tokio::spawn(async move {
-        event::run_event_loop(event_tx).await;
-    });
+    let ui_config = config.ui.clone().unwrap_or_default();
+    let mut events = spawn_event_reader(action_tx.clone(), &ui_config);
+
+    // Agent state written by background tasks or other processes
+    tokio::spawn(agents::store::watch(action_tx.clone()));
+    // A daemon started or stopped after us takes over or returns the pool
+    tokio::spawn(daemon::watch(action_tx.clone()));
+
+    // Provider webhooks push item changes straight into the app
+    if let Some(webhooks) = config.webhooks.as_ref().filter(|w| w.port != 0) {
+        let addr = format!("127.0.0.1:{}", webhooks.port);
+        let guard = serve::WebhookGuard::new(Some(webhooks), &app.project_dir);
+        let (update_tx, mut update_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let _ = serve::listen_webhooks(&addr, guard, update_tx).await;
+        });
+        let webhook_tx = action_tx.clone();
+        tokio::spawn(async move {
+            while let Some(update) = update_rx.recv().await {
+                let _ = webhook_tx.send(Action::ItemUpdated(update));
+            }
+        });
+    }
 
     // Initial fetch: if no board mapping, show picker; otherwise load items
     if app.view_mode == app::ViewMode::BoardSelection {
-        app.fetch_boards().await;
+        app.fetch_boards();
     } else {
         app.refresh_items().await;
     }
 
     // Main loop
     loop {
-        // Show/hide cursor based on input mode
-        if app.input_active {
-            terminal.show_cursor()?;
-        } else {
-            terminal.hide_cursor()?;
-        }
+        // Render only when something visible changed
+        if app.take_redraw() {
+            // Show/hide cursor based on input mode
+            if app.input_active {
+                terminal.show_cursor()?;
+            } else {
+                terminal.hide_cursor()?;
+            }
 
-        // Render
-        terminal.draw(|f| ui::render(f, &app))?;
+            terminal.draw(|f| ui::render(f, &app))?;
+        }
 
         // Wait for action
         if let Some(action) = action_rx.recv().await {
@@ -98,12 +181,72 @@ async fn main() -> Result<()> {
         } else {
             break;
         }
+
+        if let Some(dir) = app.shell_request.take() {
+            // The reader would otherwise take the shell's keystrokes
+            events.abort();
+            let _ = (&mut events).await;
+            leave_terminal(enhanced_keys)?;
+            run_shell(&dir).await;
+            enter_terminal(enhanced_keys)?;
+            terminal.clear()?;
+            events = spawn_event_reader(action_tx.clone(), &ui_config);
+            let _ = action_tx.send(Action::Resize);
+        }
     }
 
     // Restore terminal
-    terminal.show_cursor()?;
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    leave_terminal(enhanced_keys)?;
 
     Ok(())
 }
+
+fn spawn_event_reader(
+    tx: mpsc::UnboundedSender<Action>,
+    ui_config: &config::UiConfig,
+) -> tokio::task::JoinHandle<()> {
+    let (render_tick, data_tick) = (ui_config.render_tick(), ui_config.data_tick());
+    tokio::spawn(event::run_event_loop(tx, render_tick, data_tick))
+}
+
+/// Raw mode on the alternate screen, with bracketed paste and, where
+/// supported, enhanced key reporting.
+fn enter_terminal(enhanced_keys: bool) -> io::Result<()> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
+    if enhanced_keys {
+        execute!(
+            stdout,
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )?;
+    }
+    Ok(())
+}
+
+/// Undo `enter_terminal`, leaving the shell as it was.
+fn leave_terminal(enhanced_keys: bool) -> io::Result<()> {
+    disable_raw_mode()?;
+    let mut stdout = io::stdout();
+    if enhanced_keys {
+        execute!(stdout, PopKeyboardEnhancementFlags)?;
+    }
+    execute!(stdout, DisableBracketedPaste, LeaveAlternateScreen, Show)
+}
+
+/// Run `$SHELL` in `dir` until it exits. Agents and their monitors keep
+/// running meanwhile; what they report is applied on return.
+async fn run_shell(dir: &str) {
+    // Ctrl+C in the shell must not reach past it to us
+    let _sigint = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::interrupt());
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "sh".into());
+    println!("Shell in {dir}; agents keep running. Exit to return to work");
+    if let Err(e) = tokio::process::Command::new(&shell)
+        .current_dir(dir)
+        .status()
+        .await
+    {
+        eprintln!("Failed to run {shell}: {e}");
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    }
+}
@@ -1,16 +1,46 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum AgentName {
+/// The four agent personalities; each can run several workers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Persona {
     Ember,
     Flow,
     Tempest,
     Terra,
 }
 
+/// One worker: a persona plus its instance number. The first instance keeps
+/// the bare persona name (`flow`), later ones are numbered (`flow-2`), and
+/// branches, worktrees and logs are all keyed by that name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AgentName {
+    persona: Persona,
+    instance: u8,
+}
+
+const NAMES: [[&str; AgentName::MAX_INSTANCES as usize]; 4] = [
+    ["ember", "ember-2", "ember-3", "ember-4"],
+    ["flow", "flow-2", "flow-3", "flow-4"],
+    ["tempest", "tempest-2", "tempest-3", "tempest-4"],
+    ["terra", "terra-2", "terra-3", "terra-4"],
+];
+
+const DISPLAY_NAMES: [[&str; AgentName::MAX_INSTANCES as usize]; 4] = [
+    ["Ember", "Ember 2", "Ember 3", "Ember 4"],
+    ["Flow", "Flow 2", "Flow 3", "Flow 4"],
+    ["Tempest", "Tempest 2", "Tempest 3", "Tempest 4"],
+    ["Terra", "Terra 2", "Terra 3", "Terra 4"],
+];
+
+#[allow(non_upper_case_globals)]
 impl AgentName {
+    pub const Ember: AgentName = AgentName::first(Persona::Ember);
+    pub const Flow: AgentName = AgentName::first(Persona::Flow);
+    pub const Tempest: AgentName = AgentName::first(Persona::Tempest);
+    pub const Terra: AgentName = AgentName::first(Persona::Terra);
+
+    /// The first worker of each persona
     pub const ALL: [AgentName; 4] = [
         AgentName::Ember,
         AgentName::Flow,
@@ -18,34 +48,97 @@ impl AgentName {
         AgentName::Terra,
     ];
 
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            AgentName::Ember => "ember",
-            AgentName::Flow => "flow",
-            AgentName::Tempest => "tempest",
-            AgentName::Terra => "terra",
+    /// Most workers one persona can scale to
+    pub const MAX_INSTANCES: u8 = 4;
+
+    const fn first(persona: Persona) -> AgentName {
+        AgentName {
+            persona,
+            instance: 1,
         }
     }
 
+    /// Worker `instance` (1-based) of `persona`, if within `MAX_INSTANCES`.
+    pub fn new(persona: Persona, instance: u8) -> Option<AgentName> {
+        (1..=Self::MAX_INSTANCES)
+            .contains(&instance)
+            .then_some(AgentName { persona, instance })
+    }
+
+    pub fn persona(&self) -> Persona {
+        self.persona
+    }
+
+    pub fn instance(&self) -> u8 {
+        self.instance
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        NAMES[self.persona as usize][self.instance as usize - 1]
+    }
+
     pub fn display_name(&self) -> &'static str {
-        match self {
-            AgentName::Ember => "Ember",
-            AgentName::Flow => "Flow",
-            AgentName::Tempest => "Tempest",
-            AgentName::Terra => "Terra",
-        }
+        DISPLAY_NAMES[self.persona as usize][self.instance as usize - 1]
     }
 
     pub fn emoji(&self) -> &'static str {
-        match self {
-            AgentName::Ember => "\u{1F468}\u{200D}\u{1F692}",
-            AgentName::Flow => "\u{1F3C4}\u{200D}\u{2640}\u{FE0F}",
-            AgentName::Tempest => "\u{1F9DD}\u{200D}\u{2640}\u{FE0F}",
-            AgentName::Terra => "\u{1F469}\u{200D}\u{1F33E}",
+        match self.persona {
+            Persona::Ember => "\u{1F468}\u{200D}\u{1F692}",
+            Persona::Flow => "\u{1F3C4}\u{200D}\u{2640}\u{FE0F}",
+            Persona::Tempest => "\u{1F9DD}\u{200D}\u{2640}\u{FE0F}",
+            Persona::Terra => "\u{1F469}\u{200D}\u{1F33E}",
         }
     }
 }
 
+impl AgentName {
+    /// Split `@flow-2 message` into the agent and the message after it.
+    pub fn parse_mention(text: &str) -> Option<(AgentName, &str)> {
+        let after_at = text.strip_prefix('@')?;
+        let end = after_at.find(char::is_whitespace).unwrap_or(after_at.len());
+        let name = after_at[..end].parse().ok()?;
+        Some((name, after_at[end..].trim_start()))
+    }
+}
+
+impl Persona {
+    pub fn as_str(&self) -> &'static str {
+        AgentName::first(*self).as_str()
+    }
+}
+
+impl std::str::FromStr for AgentName {
+    type Err = String;
+
+    /// `flow`, `flow-1` and `flow-2` style names, case-insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        let (base, instance) = match lower.rsplit_once('-') {
+            Some((base, n)) => (base, n.parse().map_err(|_| format!("Unknown agent: {s}"))?),
+            None => (lower.as_str(), 1),
+        };
+        AgentName::ALL
+            .into_iter()
+            .find(|a| a.as_str() == base)
+            .and_then(|a| AgentName::new(a.persona, instance))
+            .ok_or_else(|| format!("Unknown agent: {s}"))
+    }
+}
+
+impl Serialize for AgentName {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for AgentName {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        String::deserialize(d)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 impl fmt::Display for AgentName {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(self.as_str())
@@ -58,6 +151,13 @@ pub enum AgentStatus {
     Idle,
     Provisioning,
     Working,
+    /// Still running, but past the configured max runtime
+    Stalled,
+    /// A plan-only run finished; the plan waits for approval before the
+    /// implementation run starts
+    Plan,
+    /// Finished; the branch waits for approval before landing on main
+    Review,
     Done,
     Error,
 }
@@ -68,6 +168,9 @@ impl fmt::Display for AgentStatus {
             AgentStatus::Idle => f.write_str("idle"),
             AgentStatus::Provisioning => f.write_str("provisioning"),
             AgentStatus::Working => f.write_str("working"),
+            AgentStatus::Stalled => f.write_str("stalled"),
+            AgentStatus::Plan => f.write_str("plan"),
+            AgentStatus::Review => f.write_str("review"),
             AgentStatus::Done => f.write_str("done"),
             AgentStatus::Error => f.write_str("error"),
         }
@@ -86,6 +189,12 @@ pub struct Agent {
     pub branch: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub worktree_path: Option<String>,
+    /// Repository the worktree belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo_root: Option<String>,
+    /// Branch the work started from and lands on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_branch: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pid: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -94,6 +203,108 @@ pub struct Agent {
     pub error: Option<String>,
     #[serde(default)]
     pub retry_count: u32,
+    /// Why the previous attempt failed, fed into the retry prompt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_failure: Option<String>,
+    /// The hygiene check flagged the branch: a person decides what happens
+    /// next, so auto-retry leaves the agent alone
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub flagged: bool,
+    /// Item IDs assigned to this agent, worked through after the current one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub backlog: Vec<String>,
+    /// Items this agent has finished with, most recent last
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<ItemRecord>,
+    /// Totals over every item finished with, kept past the history's limit
+    #[serde(default)]
+    pub stats: AgentStats,
+}
+
+/// Outcome of one item an agent worked on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemRecord {
+    pub work_item_id: String,
+    pub work_item_title: String,
+    /// `done`, `failed` or `released`
+    pub outcome: String,
+    pub finished_at: String,
+    /// Time on the item's last attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<i64>,
+    #[serde(default)]
+    pub retries: u32,
+}
+
+/// An agent's lifetime record, for comparing agents with each other.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentStats {
+    pub done: u32,
+    pub failed: u32,
+    /// Unassigned or cleared before finishing
+    pub released: u32,
+    /// Retries across all items
+    pub retries: u32,
+    /// Time spent on items that ended done
+    pub done_secs: i64,
+}
+
+impl AgentStats {
+    pub fn record(&mut self, record: &ItemRecord) {
+        match record.outcome.as_str() {
+            "done" => {
+                self.done += 1;
+                self.done_secs += record.duration_secs.unwrap_or(0);
+            }
+            "failed" => self.failed += 1,
+            _ => self.released += 1,
+        }
+        self.retries += record.retries;
+    }
+
+    pub fn finished(&self) -> u32 {
+        self.done + self.failed + self.released
+    }
+
+    /// Share of items that ended done rather than failed; released ones
+    /// didn't get the chance either way.
+    pub fn success_rate(&self) -> Option<f64> {
+        let decided = self.done + self.failed;
+        (decided > 0).then(|| f64::from(self.done) / f64::from(decided))
+    }
+
+    pub fn average_duration(&self) -> Option<chrono::Duration> {
+        (self.done > 0).then(|| chrono::Duration::seconds(self.done_secs / i64::from(self.done)))
+    }
+
+    pub fn retries_per_item(&self) -> f64 {
+        match self.finished() {
+            0 => 0.0,
+            n => f64::from(self.retries) / f64::from(n),
+        }
+    }
+
+    /// e.g. `12 done · 86% ok · avg 14m · 0.4 retries`
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!("{} done", self.done)];
+        if let Some(rate) = self.success_rate() {
+            parts.push(format!("{:.0}% ok", rate * 100.0));
+        }
+        if let Some(avg) = self.average_duration() {
+            parts.push(format!("avg {}", short_duration(avg)));
+        }
+        parts.push(format!("{:.1} retries", self.retries_per_item()));
+        parts.join(" · ")
+    }
+}
+
+/// Compact duration, e.g. `45s`, `14m`, `2h05m`.
+pub fn short_duration(d: chrono::Duration) -> String {
+    match d.num_seconds() {
+        s if s < 60 => format!("{s}s"),
+        s if s < 3600 => format!("{}m", s / 60),
+        s => format!("{}h{:02}m", s / 3600, s % 3600 / 60),
+    }
 }
 
 impl Agent {
@@ -105,10 +316,102 @@ impl Agent {
             work_item_title: None,
             branch: None,
             worktree_path: None,
+            repo_root: None,
+            base_branch: None,
             pid: None,
             started_at: None,
             error: None,
             retry_count: 0,
+            last_failure: None,
+            flagged: false,
+            backlog: Vec::new(),
+            history: Vec::new(),
+            stats: AgentStats::default(),
         }
     }
+
+    /// Branch the current work lands on.
+    pub fn base(&self) -> &str {
+        self.base_branch.as_deref().unwrap_or("main")
+    }
+
+    /// Time since the current dispatch started, if there is one.
+    pub fn runtime(&self, now: chrono::DateTime<chrono::Utc>) -> Option<chrono::Duration> {
+        let started = self.started_at.as_deref()?;
+        let started = chrono::DateTime::parse_from_rfc3339(started).ok()?;
+        Some(now.signed_duration_since(started))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runtime_is_none_without_start() {
+        let agent = Agent::new(AgentName::Ember);
+        assert!(agent.runtime(chrono::Utc::now()).is_none());
+    }
+
+    #[test]
+    fn runtime_measures_from_start() {
+        let now = chrono::Utc::now();
+        let mut agent = Agent::new(AgentName::Flow);
+        agent.started_at = Some((now - chrono::Duration::minutes(42)).to_rfc3339());
+        assert_eq!(agent.runtime(now).unwrap().num_minutes(), 42);
+    }
+
+    #[test]
+    fn stats_rate_only_decided_items() {
+        let record = |outcome: &str, mins: i64, retries: u32| ItemRecord {
+            work_item_id: "ENG-1".into(),
+            work_item_title: "Fix login".into(),
+            outcome: outcome.into(),
+            finished_at: String::new(),
+            duration_secs: Some(mins * 60),
+            retries,
+        };
+        let mut stats = AgentStats::default();
+        assert_eq!(stats.success_rate(), None);
+        for r in [
+            record("done", 10, 0),
+            record("done", 20, 2),
+            record("failed", 5, 2),
+            record("released", 1, 0),
+        ] {
+            stats.record(&r);
+        }
+        assert_eq!(stats.success_rate(), Some(2.0 / 3.0));
+        assert_eq!(stats.average_duration().unwrap().num_minutes(), 15);
+        assert_eq!(stats.retries_per_item(), 1.0);
+        assert_eq!(stats.summary(), "2 done · 67% ok · avg 15m · 1.0 retries");
+        assert_eq!(short_duration(chrono::Duration::minutes(125)), "2h05m");
+    }
+
+    #[test]
+    fn stalled_status_round_trips() {
+        let json = serde_json::to_string(&AgentStatus::Stalled).unwrap();
+        assert_eq!(json, "\"stalled\"");
+        assert_eq!(AgentStatus::Stalled.to_string(), "stalled");
+    }
+
+    #[test]
+    fn review_status_round_trips() {
+        let json = serde_json::to_string(&AgentStatus::Review).unwrap();
+        assert_eq!(json, "\"review\"");
+        assert_eq!(AgentStatus::Review.to_string(), "review");
+    }
+
+    #[test]
+    fn scaled_agent_names_round_trip() {
+        let flow2 = AgentName::new(Persona::Flow, 2).unwrap();
+        assert_eq!(flow2.as_str(), "flow-2");
+        assert_eq!(flow2.display_name(), "Flow 2");
+        assert_eq!(serde_json::to_string(&flow2).unwrap(), "\"flow-2\"");
+        assert_eq!(serde_json::from_str::<AgentName>("\"flow-2\"").unwrap(), flow2);
+        assert_eq!("Flow-1".parse::<AgentName>(), Ok(AgentName::Flow));
+        assert_eq!("ember".parse::<AgentName>(), Ok(AgentName::Ember));
+        assert!("flow-5".parse::<AgentName>().is_err());
+        assert!("wind".parse::<AgentName>().is_err());
+    }
 }
@@ -0,0 +1,99 @@
+use chrono::{Datelike, Days, Local, NaiveDate};
+
+/// Cursor and span of the due-date calendar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calendar {
+    /// The highlighted day
+    pub day: NaiveDate,
+    /// A month grid instead of a single week
+    pub month: bool,
+    /// Highlighted item among those due on `day`
+    pub pick: usize,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self::at(Local::now().date_naive())
+    }
+}
+
+impl Calendar {
+    pub fn at(day: NaiveDate) -> Self {
+        Self {
+            day,
+            month: true,
+            pick: 0,
+        }
+    }
+
+    pub fn move_days(&mut self, days: i64) {
+        let moved = if days < 0 {
+            self.day.checked_sub_days(Days::new(days.unsigned_abs()))
+        } else {
+            self.day.checked_add_days(Days::new(days as u64))
+        };
+        if let Some(day) = moved {
+            self.day = day;
+            self.pick = 0;
+        }
+    }
+
+    /// Days shown, in rows of seven starting on Monday: the cursor's week,
+    /// or every week touching its month.
+    pub fn grid(&self) -> Vec<Vec<NaiveDate>> {
+        let (first, last) = if self.month {
+            let first = self.day.with_day(1).unwrap_or(self.day);
+            let next = first.checked_add_months(chrono::Months::new(1));
+            let last = next.and_then(|n| n.pred_opt()).unwrap_or(first);
+            (first, last)
+        } else {
+            (self.day, self.day)
+        };
+        let mut start = monday_of(first);
+        let mut rows = Vec::new();
+        while start <= last {
+            rows.push(start.iter_days().take(7).collect());
+            start = start + Days::new(7);
+        }
+        rows
+    }
+
+    pub fn title(&self) -> String {
+        if self.month {
+            self.day.format("%B %Y").to_string()
+        } else {
+            format!("week of {}", monday_of(self.day).format("%b %-d, %Y"))
+        }
+    }
+}
+
+fn monday_of(day: NaiveDate) -> NaiveDate {
+    day - Days::new(day.weekday().num_days_from_monday() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn month_grid_covers_whole_weeks() {
+        let mut cal = Calendar::at(date("2026-10-16"));
+        let grid = cal.grid();
+        assert_eq!(grid.len(), 5);
+        assert_eq!(grid[0][0], date("2026-09-28"));
+        assert_eq!(grid[4][6], date("2026-11-01"));
+        assert_eq!(cal.title(), "October 2026");
+
+        cal.month = false;
+        cal.move_days(-7);
+        assert_eq!(
+            cal.grid(),
+            vec![date("2026-10-05").iter_days().take(7).collect::<Vec<_>>()]
+        );
+        assert_eq!(cal.title(), "week of Oct 5, 2026");
+    }
+}
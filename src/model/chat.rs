@@ -1,3 +1,5 @@
+use chrono::{DateTime, Utc};
+
 use crate::model::agent::AgentName;
 
 #[derive(Debug, Clone)]
@@ -12,30 +14,57 @@ pub struct ChatMessage {
     pub sender: ChatSender,
     pub text: String,
     pub timestamp: String,
+    pub sent_at: DateTime<Utc>,
 }
 
 impl ChatMessage {
-    pub fn user(text: impl Into<String>) -> Self {
+    fn new(sender: ChatSender, text: String) -> Self {
+        let sent_at = Utc::now();
         Self {
-            sender: ChatSender::User,
-            text: text.into(),
-            timestamp: chrono::Utc::now().format("%H:%M:%S").to_string(),
+            sender,
+            text,
+            timestamp: sent_at.format("%H:%M:%S").to_string(),
+            sent_at,
         }
     }
 
+    pub fn user(text: impl Into<String>) -> Self {
+        Self::new(ChatSender::User, text.into())
+    }
+
     pub fn agent(name: AgentName, text: impl Into<String>) -> Self {
-        Self {
-            sender: ChatSender::Agent(name),
-            text: text.into(),
-            timestamp: chrono::Utc::now().format("%H:%M:%S").to_string(),
-        }
+        Self::new(ChatSender::Agent(name), text.into())
     }
 
     pub fn system(text: impl Into<String>) -> Self {
-        Self {
-            sender: ChatSender::System,
-            text: text.into(),
-            timestamp: chrono::Utc::now().format("%H:%M:%S").to_string(),
+        Self::new(ChatSender::System, text.into())
+    }
+
+    /// Whether this message was sent to or by the given agent.
+    pub fn involves(&self, name: AgentName) -> bool {
+        match &self.sender {
+            ChatSender::Agent(sender) => *sender == name,
+            ChatSender::User => self
+                .text
+                .strip_prefix('@')
+                .and_then(|rest| rest.strip_prefix(name.as_str()))
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(' ')),
+            ChatSender::System => false,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn involves_matches_sender_and_mentions() {
+        assert!(ChatMessage::agent(AgentName::Ember, "hi").involves(AgentName::Ember));
+        assert!(!ChatMessage::agent(AgentName::Ember, "hi").involves(AgentName::Flow));
+        assert!(ChatMessage::user("@flow status?").involves(AgentName::Flow));
+        assert!(!ChatMessage::user("@flowers status?").involves(AgentName::Flow));
+        assert!(!ChatMessage::user("New task: flow").involves(AgentName::Flow));
+        assert!(!ChatMessage::system("Ember error").involves(AgentName::Ember));
+    }
+}
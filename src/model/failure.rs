@@ -0,0 +1,273 @@
+use chrono::{DateTime, Utc};
+
+use crate::providers::ErrorKind;
+
+/// Failures kept before the oldest are dropped.
+const MAX_FAILURES: usize = 50;
+
+/// Characters of the error shown before the entry is expanded.
+const EXCERPT_CHARS: usize = 160;
+
+/// A provider call that failed, kept until dismissed or until the same call
+/// succeeds, so auth and rate-limit errors outlive the flash line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub provider: String,
+    /// What was attempted, e.g. "fetch items" or "move ENG-1 to done"
+    pub action: String,
+    /// The full error chain
+    pub message: String,
+    /// From the provider's typed error, when it raised one
+    pub kind: Option<ErrorKind>,
+    pub at: DateTime<Utc>,
+    /// How many times in a row it failed the same way
+    pub count: usize,
+}
+
+impl Failure {
+    /// The HTTP status in the error, from reqwest (`HTTP status client error
+    /// (401 Unauthorized)`), `gh` (`HTTP 401: Bad credentials`) or our own
+    /// `returned 401` messages.
+    pub fn status(&self) -> Option<u16> {
+        ["error (", "HTTP ", "returned ", "status "]
+            .iter()
+            .flat_map(|marker| self.message.match_indices(marker))
+            .filter_map(|(at, marker)| {
+                let rest = &self.message[at + marker.len()..];
+                let digits = rest.get(..3)?;
+                let ends = !rest[3..].starts_with(|c: char| c.is_ascii_digit());
+                digits
+                    .parse()
+                    .ok()
+                    .filter(|s| (400..600).contains(s) && ends)
+            })
+            .next()
+    }
+
+    /// The error on one line, cut short for the collapsed view.
+    pub fn excerpt(&self) -> String {
+        let line = self
+            .message
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        match line.char_indices().nth(EXCERPT_CHARS) {
+            Some((cut, _)) => format!("{}…", &line[..cut]),
+            None => line,
+        }
+    }
+
+    /// What the user can do about it, when the error says enough to tell.
+    /// Messages without a typed error, like `gh` output, are matched on
+    /// their wording.
+    pub fn remedy(&self) -> Option<&'static str> {
+        match self
+            .kind
+            .or_else(|| self.status().map(ErrorKind::from_status))
+        {
+            Some(ErrorKind::Auth) => return Some(AUTH),
+            Some(ErrorKind::Permission) => return Some(PERMISSION),
+            Some(ErrorKind::NotFound) => return Some(NOT_FOUND),
+            Some(ErrorKind::RateLimited) => return Some(RATE_LIMITED),
+            Some(ErrorKind::Server) => return Some(SERVER),
+            Some(ErrorKind::Network) => return Some(NETWORK),
+            Some(ErrorKind::Other) | None => {}
+        }
+        let lower = self.message.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|w| lower.contains(w));
+        if has(&[
+            "unauthorized",
+            "authentication",
+            "bad credentials",
+            "api key",
+        ]) {
+            Some(AUTH)
+        } else if has(&["rate limit", "ratelimit", "too many requests"]) {
+            Some(RATE_LIMITED)
+        } else if has(&["forbidden", "permission"]) {
+            Some(PERMISSION)
+        } else if has(&[
+            "error sending request",
+            "dns error",
+            "connection",
+            "timed out",
+        ]) {
+            Some(NETWORK)
+        } else if has(&["error decoding", "expected value", "missing field"]) {
+            Some(UNEXPECTED)
+        } else {
+            None
+        }
+    }
+}
+
+const AUTH: &str =
+    "Token expired or invalid — update it with `work config set-secret`, then run `work config validate`";
+const PERMISSION: &str =
+    "The token lacks access — check its scopes and that the account can see this board or project";
+const NOT_FOUND: &str =
+    "Not found — check the configured domain, org or team, or pick the board again with /board";
+const RATE_LIMITED: &str =
+    "Rate limited — wait a minute before refreshing, or raise [cache] ttl_secs to call less often";
+const SERVER: &str = "The tracker is having trouble — try again shortly";
+const NETWORK: &str = "Could not reach the tracker — check your connection, VPN or proxy";
+const UNEXPECTED: &str =
+    "Unexpected response — run `work -v` and check work.log (L) for the full exchange";
+
+/// The provider failures shown in the error center, newest first.
+#[derive(Debug, Default)]
+pub struct ErrorCenter {
+    failures: Vec<Failure>,
+}
+
+impl ErrorCenter {
+    /// Record a failure, newest first; another failure of the same call
+    /// replaces its row, counting the repeats of an identical error.
+    pub fn record(
+        &mut self,
+        provider: &str,
+        action: &str,
+        message: String,
+        kind: Option<ErrorKind>,
+    ) {
+        let now = Utc::now();
+        if let Some(pos) = self
+            .failures
+            .iter()
+            .position(|f| f.provider == provider && f.action == action)
+        {
+            let mut failure = self.failures.remove(pos);
+            if failure.message == message {
+                failure.count += 1;
+            } else {
+                failure.message = message;
+                failure.count = 1;
+            }
+            failure.kind = kind;
+            failure.at = now;
+            self.failures.insert(0, failure);
+            return;
+        }
+        self.failures.insert(
+            0,
+            Failure {
+                provider: provider.to_string(),
+                action: action.to_string(),
+                message,
+                kind,
+                at: now,
+                count: 1,
+            },
+        );
+        self.failures.truncate(MAX_FAILURES);
+    }
+
+    /// Drop the failures of a call that has since succeeded.
+    pub fn resolve(&mut self, provider: &str, action: &str) {
+        self.failures
+            .retain(|f| !(f.provider == provider && f.action == action));
+    }
+
+    pub fn dismiss(&mut self, index: usize) {
+        if index < self.failures.len() {
+            self.failures.remove(index);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.failures.clear();
+    }
+
+    pub fn failures(&self) -> &[Failure] {
+        &self.failures
+    }
+
+    pub fn len(&self) -> usize {
+        self.failures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(message: &str) -> Failure {
+        Failure {
+            provider: "Jira".into(),
+            action: "fetch items".into(),
+            message: message.into(),
+            kind: None,
+            at: Utc::now(),
+            count: 1,
+        }
+    }
+
+    #[test]
+    fn reads_the_status_and_suggests_a_fix() {
+        let reqwest = failure(
+            "HTTP status client error (401 Unauthorized) for url (https://x.atlassian.net/rest)",
+        );
+        assert_eq!(reqwest.status(), Some(401));
+        assert_eq!(reqwest.remedy(), Some(AUTH));
+
+        let gh = failure("gh issue list failed: HTTP 403: Resource not accessible");
+        assert_eq!(gh.status(), Some(403));
+        assert_eq!(gh.remedy(), Some(PERMISSION));
+
+        let sentry = failure("Sentry returned 429 Too Many Requests: slow down");
+        assert_eq!(sentry.status(), Some(429));
+        assert_eq!(sentry.remedy(), Some(RATE_LIMITED));
+
+        let linear = failure("Linear API error: Rate limit exceeded");
+        assert_eq!(linear.status(), None);
+        assert_eq!(linear.remedy(), Some(RATE_LIMITED));
+
+        let offline =
+            failure("error sending request for url (https://api.trello.com/1): dns error");
+        assert_eq!(offline.remedy(), Some(NETWORK));
+
+        // A typed error wins over the wording
+        let linear_auth = Failure {
+            kind: Some(ErrorKind::Auth),
+            ..failure("HTTP 400: Authentication required, not authenticated")
+        };
+        assert_eq!(linear_auth.remedy(), Some(AUTH));
+
+        // Numbers that are not statuses
+        assert_eq!(failure("returned 4040 rows").status(), None);
+        assert_eq!(failure("No todo list found on board").remedy(), None);
+    }
+
+    #[test]
+    fn repeats_collapse_and_successes_resolve() {
+        let mut center = ErrorCenter::default();
+        let timeout = || "timed out".to_string();
+        center.record("Jira", "fetch items", timeout(), None);
+        center.record("Linear", "fetch items", timeout(), None);
+        center.record("Jira", "fetch items", timeout(), None);
+
+        assert_eq!(center.len(), 2);
+        assert_eq!(center.failures()[0].provider, "Jira");
+        assert_eq!(center.failures()[0].count, 2);
+
+        // A different error on the same call starts the count over
+        center.record("Jira", "fetch items", "401".into(), None);
+        assert_eq!(center.failures()[0].count, 1);
+
+        center.resolve("Jira", "fetch items");
+        assert_eq!(center.failures().len(), 1);
+        assert_eq!(center.failures()[0].provider, "Linear");
+    }
+
+    #[test]
+    fn excerpt_is_one_short_line() {
+        let long = failure(&format!("Sentry returned 500:\n{}", "x".repeat(400)));
+        let excerpt = long.excerpt();
+        assert!(!excerpt.contains('\n'));
+        assert_eq!(excerpt.chars().count(), EXCERPT_CHARS + 1);
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::work_item::WorkItem;
+
+/// Item list filter from `/filter`, e.g. `label:bug source:linear login`.
+/// Every term must match; bare words match the ID or title.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ItemFilter {
+    terms: Vec<FilterTerm>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FilterTerm {
+    Label(String),
+    Source(String),
+    Priority(String),
+    Status(String),
+    Text(String),
+}
+
+impl ItemFilter {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let terms = input
+            .split_whitespace()
+            .map(|word| {
+                let Some((key, value)) = word.split_once(':') else {
+                    return Ok(FilterTerm::Text(word.to_lowercase()));
+                };
+                let value = value.to_lowercase();
+                match key {
+                    "label" => Ok(FilterTerm::Label(value)),
+                    "source" => Ok(FilterTerm::Source(value)),
+                    "priority" => Ok(FilterTerm::Priority(value)),
+                    "status" => Ok(FilterTerm::Status(value)),
+                    _ => Err(format!("Unknown filter key: {key}")),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(ItemFilter { terms })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, item: &WorkItem) -> bool {
+        let eq =
+            |field: Option<&str>, value: &str| field.is_some_and(|f| f.eq_ignore_ascii_case(value));
+        self.terms.iter().all(|term| match term {
+            FilterTerm::Label(l) => item.labels.iter().any(|il| il.eq_ignore_ascii_case(l)),
+            FilterTerm::Source(s) => item.source.eq_ignore_ascii_case(s),
+            FilterTerm::Priority(p) => eq(item.priority.as_deref(), p),
+            FilterTerm::Status(s) => eq(item.status.as_deref(), s),
+            FilterTerm::Text(t) => {
+                item.id.to_lowercase().contains(t) || item.title.to_lowercase().contains(t)
+            }
+        })
+    }
+}
+
+/// Filters in config, such as a view's, use the `/filter` syntax.
+impl TryFrom<String> for ItemFilter {
+    type Error = String;
+
+    fn try_from(input: String) -> Result<Self, String> {
+        ItemFilter::parse(&input)
+    }
+}
+
+impl From<ItemFilter> for String {
+    fn from(filter: ItemFilter) -> String {
+        filter.to_string()
+    }
+}
+
+impl fmt::Display for ItemFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms: Vec<String> = self
+            .terms
+            .iter()
+            .map(|term| match term {
+                FilterTerm::Label(v) => format!("label:{v}"),
+                FilterTerm::Source(v) => format!("source:{v}"),
+                FilterTerm::Priority(v) => format!("priority:{v}"),
+                FilterTerm::Status(v) => format!("status:{v}"),
+                FilterTerm::Text(v) => v.clone(),
+            })
+            .collect();
+        f.write_str(&terms.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item() -> WorkItem {
+        WorkItem::test("ENG-7")
+            .with_title("Fix login redirect")
+            .with_status("Todo")
+            .with_priority("High")
+            .with_labels(&["Bug"])
+    }
+
+    #[test]
+    fn all_terms_must_match() {
+        let f = |s: &str| ItemFilter::parse(s).unwrap().matches(&item());
+        assert!(f("label:bug"));
+        assert!(f("label:bug source:linear priority:high"));
+        assert!(f("login"));
+        assert!(f("eng-7"));
+        assert!(!f("label:bug source:jira"));
+        assert!(!f("signup"));
+        assert!(f(""));
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(ItemFilter::parse("owner:me").is_err());
+        assert_eq!(
+            ItemFilter::parse("label:Bug login").unwrap().to_string(),
+            "label:bug login"
+        );
+    }
+}
@@ -1,4 +1,7 @@
 pub mod agent;
+pub mod calendar;
 pub mod chat;
+pub mod failure;
+pub mod filter;
 pub mod personality;
 pub mod work_item;
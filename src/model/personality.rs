@@ -1,4 +1,4 @@
-use super::agent::AgentName;
+use super::agent::{AgentName, Persona};
 
 pub struct AgentPersonality {
     pub tagline: &'static str,
@@ -8,11 +8,11 @@ pub struct AgentPersonality {
 }
 
 pub fn personality(name: AgentName) -> &'static AgentPersonality {
-    match name {
-        AgentName::Ember => &EMBER,
-        AgentName::Flow => &FLOW,
-        AgentName::Tempest => &TEMPEST,
-        AgentName::Terra => &TERRA,
+    match name.persona() {
+        Persona::Ember => &EMBER,
+        Persona::Flow => &FLOW,
+        Persona::Tempest => &TEMPEST,
+        Persona::Terra => &TERRA,
     }
 }
 
@@ -20,4 +20,194 @@ pub struct WorkItem {
     pub team: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
+    /// Attachments and linked resources, fetched when the item is dispatched
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<Link>,
+    /// Recent discussion on the item, oldest first; fetched at dispatch
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<Comment>,
+    /// IDs of unfinished items, in the same provider, that block this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_by: Vec<String>,
+    /// Due date as `YYYY-MM-DD`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+}
+
+impl WorkItem {
+    /// Identity across fetches: the tracker's own ID where there is one,
+    /// within its source.
+    pub fn key(&self) -> (&str, &str) {
+        (&self.source, self.source_id.as_deref().unwrap_or(&self.id))
+    }
+
+    pub fn is_blocked(&self) -> bool {
+        !self.blocked_by.is_empty()
+    }
+
+    pub fn due_date(&self) -> Option<chrono::NaiveDate> {
+        let due = self.due.as_deref()?;
+        chrono::NaiveDate::parse_from_str(due.get(..10)?, "%Y-%m-%d").ok()
+    }
+}
+
+/// Test fixtures: a bare Linear item, with the fields a test cares about set
+/// through the `with_*` methods.
+#[cfg(test)]
+impl WorkItem {
+    pub fn test(id: &str) -> Self {
+        Self {
+            id: id.into(),
+            source_id: None,
+            title: "Fix login".into(),
+            description: None,
+            status: None,
+            priority: None,
+            labels: Vec::new(),
+            source: "Linear".into(),
+            team: None,
+            url: None,
+            links: Vec::new(),
+            comments: Vec::new(),
+            blocked_by: Vec::new(),
+            due: None,
+        }
+    }
+
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.source = source.into();
+        self
+    }
+
+    pub fn with_source_id(mut self, source_id: &str) -> Self {
+        self.source_id = Some(source_id.into());
+        self
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn with_status(mut self, status: &str) -> Self {
+        self.status = Some(status.into());
+        self
+    }
+
+    pub fn with_priority(mut self, priority: &str) -> Self {
+        self.priority = Some(priority.into());
+        self
+    }
+
+    pub fn with_labels(mut self, labels: &[&str]) -> Self {
+        self.labels = labels.iter().map(|l| l.to_string()).collect();
+        self
+    }
+
+    pub fn with_team(mut self, team: &str) -> Self {
+        self.team = Some(team.into());
+        self
+    }
+
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+/// A resource referenced by a work item, e.g. a design doc, an attached log
+/// or a pull request linked to the issue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Link {
+    pub title: String,
+    pub url: String,
+    /// Text of the resource when it could be downloaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+/// A comment from the item's discussion thread.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    pub author: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    pub body: String,
+}
+
+/// Bring `items` in line with a fresh fetch, in fetch order. Items are
+/// matched on [`WorkItem::key`], so one fetched twice or also added locally
+/// (a task just created) appears once, and keeps the links and comments
+/// loaded at dispatch. Returns the items no longer upstream.
+pub fn reconcile(items: &mut Vec<WorkItem>, fetched: Vec<WorkItem>) -> Vec<WorkItem> {
+    let mut previous: Vec<Option<WorkItem>> = std::mem::take(items).into_iter().map(Some).collect();
+    for mut item in fetched {
+        if items.iter().any(|i| i.key() == item.key()) {
+            continue;
+        }
+        let earlier = previous
+            .iter_mut()
+            .find(|p| p.as_ref().is_some_and(|p| p.key() == item.key()))
+            .and_then(Option::take);
+        if let Some(earlier) = earlier {
+            if item.links.is_empty() {
+                item.links = earlier.links;
+            }
+            if item.comments.is_empty() {
+                item.comments = earlier.comments;
+            }
+        }
+        items.push(item);
+    }
+    previous.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(source: &str, source_id: &str, title: &str) -> WorkItem {
+        WorkItem::test(&source_id.to_uppercase())
+            .with_source(source)
+            .with_source_id(source_id)
+            .with_title(title)
+    }
+
+    #[test]
+    fn reconcile_merges_dedups_and_reports_vanished_items() {
+        let mut dispatched = item("Linear", "eng-1", "Fix login");
+        dispatched.comments.push(Comment {
+            author: "ana".into(),
+            created_at: None,
+            body: "SSO only".into(),
+        });
+        let mut items = vec![
+            dispatched,
+            item("Linear", "eng-2", "Closed upstream"),
+            // Created locally, then fetched as well
+            item("Jira", "ops-3", "New task"),
+        ];
+
+        let removed = reconcile(
+            &mut items,
+            vec![
+                item("Linear", "eng-1", "Fix login redirect"),
+                item("Jira", "ops-3", "New task"),
+                item("Jira", "ops-3", "New task"),
+                // Same ID in another source is another item
+                item("Jira", "eng-1", "Unrelated"),
+            ],
+        );
+
+        let titles: Vec<&str> = items.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(titles, ["Fix login redirect", "New task", "Unrelated"]);
+        assert_eq!(items[0].comments.len(), 1);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].id, "ENG-2");
+    }
 }
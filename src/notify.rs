@@ -0,0 +1,65 @@
+use crate::config::NotificationsConfig;
+
+/// Lifecycle events that can raise a desktop notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyEvent {
+    AgentDone,
+    AgentError,
+    MaxRetries,
+    FetchError,
+    PomodoroDone,
+}
+
+impl NotificationsConfig {
+    pub fn wants(&self, event: NotifyEvent) -> bool {
+        let per_event = match event {
+            NotifyEvent::AgentDone => self.agent_done,
+            NotifyEvent::AgentError => self.agent_error,
+            NotifyEvent::MaxRetries => self.max_retries,
+            NotifyEvent::FetchError => self.fetch_error,
+            NotifyEvent::PomodoroDone => self.pomodoro,
+        };
+        self.enabled.unwrap_or(true) && per_event.unwrap_or(true)
+    }
+}
+
+/// Show a desktop notification if the event type is enabled. Delivery runs
+/// off the async runtime and failures (no notification daemon) are ignored.
+pub fn notify(cfg: &NotificationsConfig, event: NotifyEvent, summary: String, body: String) {
+    if !cfg.wants(event) {
+        return;
+    }
+    tokio::task::spawn_blocking(move || {
+        let _ = notify_rust::Notification::new()
+            .appname("work")
+            .summary(&summary)
+            .body(&body)
+            .show();
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_default_on_and_toggle_individually() {
+        let cfg = NotificationsConfig::default();
+        assert!(cfg.wants(NotifyEvent::AgentDone));
+        assert!(cfg.wants(NotifyEvent::FetchError));
+
+        let cfg = NotificationsConfig {
+            fetch_error: Some(false),
+            ..Default::default()
+        };
+        assert!(cfg.wants(NotifyEvent::AgentError));
+        assert!(!cfg.wants(NotifyEvent::FetchError));
+
+        let cfg = NotificationsConfig {
+            enabled: Some(false),
+            agent_done: Some(true),
+            ..Default::default()
+        };
+        assert!(!cfg.wants(NotifyEvent::AgentDone));
+    }
+}
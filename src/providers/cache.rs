@@ -0,0 +1,280 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{BoardInfo, Provider, ProviderError};
+use crate::config::{data_dir, CacheConfig};
+use crate::model::work_item::{Comment, Link, WorkItem};
+
+/// One provider's last fetch.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    fetched_at: DateTime<Utc>,
+    #[serde(default)]
+    board: Option<String>,
+    items: Vec<WorkItem>,
+}
+
+/// Serves `fetch_items` from the provider's last fetch while it is younger
+/// than the TTL, and writes every live fetch back, so the TUI keeps the
+/// cache warm for CLI commands. Changes through the provider drop its entry.
+pub struct CachedProvider {
+    inner: Box<dyn Provider>,
+    ttl: Duration,
+    /// Skip reading the cache; live results are still written
+    fresh: bool,
+    board: Option<String>,
+    dir: PathBuf,
+}
+
+fn cache_dir() -> PathBuf {
+    data_dir().join("cache")
+}
+
+/// Wrap providers in the shared cache. `fresh` always fetches live.
+pub fn cached(
+    providers: Vec<Box<dyn Provider>>,
+    config: &CacheConfig,
+    fresh: bool,
+) -> Vec<Box<dyn Provider>> {
+    providers
+        .into_iter()
+        .map(|inner| {
+            let ttl = config.ttl(inner.name());
+            Box::new(CachedProvider::new(inner, ttl, fresh, cache_dir())) as Box<dyn Provider>
+        })
+        .collect()
+}
+
+impl CachedProvider {
+    fn new(inner: Box<dyn Provider>, ttl: Duration, fresh: bool, dir: PathBuf) -> Self {
+        Self {
+            inner,
+            ttl,
+            fresh,
+            board: None,
+            dir,
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        self.dir
+            .join(format!("{}.json", self.inner.name().to_lowercase()))
+    }
+
+    fn read(&self, path: &Path) -> Option<Vec<WorkItem>> {
+        let entry: Entry = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+        let age = (Utc::now() - entry.fetched_at).to_std().ok()?;
+        (entry.board == self.board && age < self.ttl).then_some(entry.items)
+    }
+
+    fn write(&self, path: &Path, items: &[WorkItem]) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let entry = Entry {
+            fetched_at: Utc::now(),
+            board: self.board.clone(),
+            items: items.to_vec(),
+        };
+        // Rename into place so a concurrent reader never sees half a file
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string(&entry)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Log a call through to the provider; every provider call passes here.
+    fn traced<T>(
+        &self,
+        op: &str,
+        started: Instant,
+        result: Result<T, ProviderError>,
+    ) -> Result<T, ProviderError> {
+        let provider = self.inner.name();
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        match &result {
+            Ok(_) => tracing::debug!(provider, op, elapsed_ms, "provider call"),
+            Err(e) => {
+                let error = format!("{e:#}");
+                tracing::warn!(provider, op, elapsed_ms, %error, "provider call failed")
+            }
+        }
+        result
+    }
+
+    fn invalidate<T>(&self, result: Result<T, ProviderError>) -> Result<T, ProviderError> {
+        if result.is_ok() {
+            let _ = std::fs::remove_file(self.path());
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl Provider for CachedProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn fetch_items(&self) -> Result<Vec<WorkItem>, ProviderError> {
+        let path = self.path();
+        if !self.fresh && !self.ttl.is_zero() {
+            if let Some(items) = self.read(&path) {
+                tracing::debug!(
+                    provider = self.inner.name(),
+                    items = items.len(),
+                    "served from cache"
+                );
+                return Ok(items);
+            }
+        }
+        let started = Instant::now();
+        let items = self.traced("fetch_items", started, self.inner.fetch_items().await)?;
+        tracing::info!(
+            provider = self.inner.name(),
+            items = items.len(),
+            "fetched items"
+        );
+        let _ = self.write(&path, &items);
+        Ok(items)
+    }
+
+    async fn list_boards(&self) -> Result<Vec<BoardInfo>, ProviderError> {
+        let started = Instant::now();
+        self.traced("list_boards", started, self.inner.list_boards().await)
+    }
+
+    fn set_board_filter(&mut self, board_id: String) {
+        self.board = Some(board_id.clone());
+        self.inner.set_board_filter(board_id);
+    }
+
+    async fn move_to_done(&self, source_id: &str) -> Result<(), ProviderError> {
+        let started = Instant::now();
+        let result = self.inner.move_to_done(source_id).await;
+        self.invalidate(self.traced("move_to_done", started, result))
+    }
+
+    async fn move_many_to_done(&self, source_ids: &[String]) -> Result<(), ProviderError> {
+        let started = Instant::now();
+        let result = self.inner.move_many_to_done(source_ids).await;
+        self.invalidate(self.traced("move_many_to_done", started, result))
+    }
+
+    async fn move_to_in_progress(&self, source_id: &str) -> Result<(), ProviderError> {
+        let started = Instant::now();
+        let result = self.inner.move_to_in_progress(source_id).await;
+        self.invalidate(self.traced("move_to_in_progress", started, result))
+    }
+
+    async fn reopen(&self, source_id: &str) -> Result<(), ProviderError> {
+        let started = Instant::now();
+        let result = self.inner.reopen(source_id).await;
+        self.invalidate(self.traced("reopen", started, result))
+    }
+
+    async fn add_comment(&self, source_id: &str, body: &str) -> Result<(), ProviderError> {
+        let started = Instant::now();
+        let result = self.inner.add_comment(source_id, body).await;
+        self.traced("add_comment", started, result)
+    }
+
+    async fn create_item(
+        &self,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<Option<WorkItem>, ProviderError> {
+        let started = Instant::now();
+        let result = self.inner.create_item(title, description).await;
+        self.invalidate(self.traced("create_item", started, result))
+    }
+
+    async fn create_subtask(
+        &self,
+        parent_source_id: &str,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<Option<WorkItem>, ProviderError> {
+        let started = Instant::now();
+        let result = self
+            .inner
+            .create_subtask(parent_source_id, title, description)
+            .await;
+        self.invalidate(self.traced("create_subtask", started, result))
+    }
+
+    async fn update_item(
+        &self,
+        source_id: &str,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<bool, ProviderError> {
+        let started = Instant::now();
+        let result = self.inner.update_item(source_id, title, description).await;
+        self.invalidate(self.traced("update_item", started, result))
+    }
+
+    async fn fetch_links(&self, source_id: &str) -> Result<Vec<Link>, ProviderError> {
+        let started = Instant::now();
+        let result = self.inner.fetch_links(source_id).await;
+        self.traced("fetch_links", started, result)
+    }
+
+    async fn fetch_comments(&self, source_id: &str) -> Result<Vec<Comment>, ProviderError> {
+        let started = Instant::now();
+        let result = self.inner.fetch_comments(source_id).await;
+        self.traced("fetch_comments", started, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct Counting(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl Provider for Counting {
+        fn name(&self) -> &str {
+            "Linear"
+        }
+        async fn fetch_items(&self) -> Result<Vec<WorkItem>, ProviderError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(Vec::new())
+        }
+        async fn list_boards(&self) -> Result<Vec<BoardInfo>, ProviderError> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn serves_recent_fetches_until_fresh_or_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = |fresh| {
+            let inner = Box::new(Counting(calls.clone()));
+            CachedProvider::new(inner, Duration::from_secs(60), fresh, dir.path().into())
+        };
+
+        provider(false).fetch_items().await.unwrap();
+        provider(false).fetch_items().await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        provider(true).fetch_items().await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let mut boarded = provider(false);
+        boarded.set_board_filter("team-2".into());
+        boarded.fetch_items().await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        boarded.move_to_done("uuid").await.unwrap();
+        boarded.fetch_items().await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+}
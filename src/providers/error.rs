@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+/// Why a provider call failed, so the app can back off, point at the
+/// credentials or just report it.
+#[derive(Debug, thiserror::Error)]
+pub enum ProviderError {
+    /// The token is missing, expired or revoked
+    #[error("authentication failed: {message}")]
+    Auth { message: String },
+    /// The token works but may not see or change this
+    #[error("permission denied: {message}")]
+    Forbidden { message: String },
+    #[error("not found: {message}")]
+    NotFound { message: String },
+    /// Wait `retry_after` when the tracker said how long
+    #[error("rate limited: {message}")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        message: String,
+    },
+    /// The request got no answer: offline, DNS, TLS or a timeout
+    #[error("network error: {0}")]
+    Network(String),
+    /// Any other error status
+    #[error("HTTP {status}: {message}")]
+    Api { status: u16, message: String },
+    /// Failures with no HTTP meaning, like a board without a Done list
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+/// What a failure means for the user, without the details; cheap to copy
+/// into actions and the error center.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    Auth,
+    Permission,
+    NotFound,
+    RateLimited,
+    Network,
+    /// The tracker failed on its side
+    Server,
+    Other,
+}
+
+impl ErrorKind {
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            401 => ErrorKind::Auth,
+            403 => ErrorKind::Permission,
+            404 | 410 => ErrorKind::NotFound,
+            429 => ErrorKind::RateLimited,
+            500..=599 => ErrorKind::Server,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+impl ProviderError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            ProviderError::Auth { .. } => ErrorKind::Auth,
+            ProviderError::Forbidden { .. } => ErrorKind::Permission,
+            ProviderError::NotFound { .. } => ErrorKind::NotFound,
+            ProviderError::RateLimited { .. } => ErrorKind::RateLimited,
+            ProviderError::Network(_) => ErrorKind::Network,
+            ProviderError::Api { status, .. } => ErrorKind::from_status(*status),
+            ProviderError::Other(_) => ErrorKind::Other,
+        }
+    }
+
+    /// How long to leave the provider alone before trying again, for
+    /// failures that pass by themselves.
+    pub fn retry_in(&self) -> Option<Duration> {
+        match self {
+            ProviderError::RateLimited { retry_after, .. } => {
+                Some(retry_after.unwrap_or(Duration::from_secs(60)))
+            }
+            ProviderError::Network(_) => Some(Duration::from_secs(30)),
+            _ => None,
+        }
+    }
+}
+
+/// Keeps the typed error when one comes back through `anyhow`, e.g. from a
+/// helper with `.context()`, and recognises requests that never got an
+/// answer.
+impl From<anyhow::Error> for ProviderError {
+    fn from(error: anyhow::Error) -> Self {
+        let error = match error.downcast::<ProviderError>() {
+            Ok(typed) => return typed,
+            Err(error) => error,
+        };
+        match error.downcast_ref::<reqwest::Error>() {
+            Some(e) if !e.is_decode() => ProviderError::Network(format!("{error:#}")),
+            _ => ProviderError::Other(error),
+        }
+    }
+}
+
+impl From<reqwest::Error> for ProviderError {
+    fn from(error: reqwest::Error) -> Self {
+        anyhow::Error::from(error).into()
+    }
+}
@@ -2,16 +2,52 @@ use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde::Deserialize;
 
-use super::{BoardInfo, Provider};
-use crate::model::work_item::WorkItem;
+use super::{blockers_in_text, BoardInfo, Provider, ProviderError, MAX_COMMENTS};
+use crate::model::work_item::{Comment, Link, WorkItem};
 
+mod api;
+
+use api::{graphql_data, issue_states, issue_states_query, repo_from_remote, GitHubApi};
+
+/// Issues assigned to `owner`, through the API when a token is configured
+/// and the gh CLI otherwise.
 pub struct GitHubProvider {
     owner: String,
+    api: Option<GitHubApi>,
+    /// `owner/repo` picked as the board; items are fetched from it and new
+    /// issues created in it
+    repo: Option<String>,
 }
 
 impl GitHubProvider {
-    pub fn new(owner: String) -> Self {
-        Self { owner }
+    pub fn new(owner: String, token: Option<String>) -> Self {
+        Self {
+            owner,
+            api: token.filter(|t| !t.is_empty()).map(GitHubApi::new),
+            repo: None,
+        }
+    }
+
+    /// Open issues or pull requests (`kind`) matching a gh search `filter`
+    /// such as `--assignee`, within the picked repository if there is one.
+    async fn gh_search(&self, kind: &str, filter: &str) -> Result<Vec<GhIssue>> {
+        let mut args = vec![
+            "search",
+            kind,
+            filter,
+            &self.owner,
+            "--state",
+            "open",
+            "--json",
+            "number,title,body,state,url,labels,repository",
+            "--limit",
+            "50",
+        ];
+        if let Some(repo) = &self.repo {
+            args.extend(["--repo", repo]);
+        }
+        let stdout = gh(&args).await?;
+        serde_json::from_slice(&stdout).context("Failed to parse gh output")
     }
 }
 
@@ -38,100 +74,258 @@ struct GhRepo {
     name_with_owner: String,
 }
 
+#[derive(Deserialize)]
+struct GhLinkedPrs {
+    #[serde(rename = "closedByPullRequestsReferences", default)]
+    prs: Vec<GhPrRef>,
+}
+
+#[derive(Deserialize)]
+struct GhPrRef {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct GhPr {
+    number: u64,
+    title: String,
+    body: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GhComments {
+    comments: Vec<GhComment>,
+}
+
+#[derive(Deserialize)]
+struct GhComment {
+    author: Option<GhAuthor>,
+    body: String,
+    #[serde(rename = "createdAt")]
+    created_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GhAuthor {
+    login: String,
+}
+
+/// Run `gh` and return its stdout, failing with its stderr.
+async fn gh(args: &[&str]) -> Result<Vec<u8>> {
+    let output = tokio::process::Command::new("gh")
+        .args(args)
+        .output()
+        .await
+        .context("Failed to run gh CLI")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("gh {} failed: {stderr}", args[..2].join(" "));
+    }
+    Ok(output.stdout)
+}
+
+/// The state of each issue in `refs` (`owner/repo`, number), read in one
+/// GraphQL query; `None` where it couldn't be read.
+async fn issue_states_of(api: Option<&GitHubApi>, refs: &[(String, u64)]) -> Vec<Option<String>> {
+    if refs.is_empty() {
+        return Vec::new();
+    }
+    let query = issue_states_query(refs);
+    let data = match api {
+        Some(api) => api.graphql_data(&query).await,
+        None => gh_graphql(&query).await,
+    };
+    match data {
+        Ok(data) => issue_states(&data, refs.len()),
+        Err(e) => {
+            tracing::warn!(error = %e, "looking up blockers failed");
+            vec![None; refs.len()]
+        }
+    }
+}
+
+/// Run a GraphQL query through `gh api`. gh exits non-zero when any field
+/// failed, so its output is read either way.
+async fn gh_graphql(query: &str) -> Result<serde_json::Value> {
+    let output = tokio::process::Command::new("gh")
+        .args(["api", "graphql", "-f", &format!("query={query}")])
+        .output()
+        .await
+        .context("Failed to run gh CLI")?;
+    let resp = serde_json::from_slice(&output.stdout).with_context(|| {
+        format!(
+            "gh api graphql failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )
+    })?;
+    graphql_data(resp)
+}
+
+/// Pull requests are other people's work: listed for review, never moved.
+fn is_pull(source_id: &str) -> bool {
+    source_id.contains("/pull/")
+}
+
+/// The current directory's GitHub repository, from gh or the origin remote.
+async fn current_repo(api: Option<&GitHubApi>) -> Option<String> {
+    if api.is_none() {
+        let out = gh(&["repo", "view", "--json", "nameWithOwner"]).await.ok()?;
+        let info: GhRepo = serde_json::from_slice(&out).ok()?;
+        return Some(info.name_with_owner);
+    }
+    let output = tokio::process::Command::new("git")
+        .args(["remote", "get-url", "origin"])
+        .output()
+        .await
+        .ok()?;
+    repo_from_remote(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn work_item(issue: GhIssue, blocked_by: Vec<String>) -> WorkItem {
+    WorkItem {
+        id: format!("#{}", issue.number),
+        source_id: issue.url.clone(),
+        title: issue.title,
+        description: issue
+            .body
+            .filter(|b| !b.trim().is_empty())
+            .map(|b| b.chars().take(500).collect::<String>()),
+        status: issue.state,
+        priority: None,
+        labels: issue.labels.into_iter().map(|l| l.name).collect(),
+        source: "GitHub".into(),
+        team: issue.repository.map(|r| r.name_with_owner),
+        url: issue.url,
+        links: Vec::new(),
+        comments: Vec::new(),
+        blocked_by,
+        due: None,
+    }
+}
+
+fn pr_link(url: String, pr: GhPr) -> Link {
+    Link {
+        title: format!("PR #{}: {}", pr.number, pr.title),
+        url,
+        content: pr.body.filter(|b| !b.trim().is_empty()),
+    }
+}
+
 #[async_trait]
 impl Provider for GitHubProvider {
     fn name(&self) -> &str {
         "GitHub"
     }
 
-    async fn fetch_items(&self) -> Result<Vec<WorkItem>> {
-        let output = tokio::process::Command::new("gh")
-            .args([
-                "search",
-                "issues",
-                "--assignee",
-                &self.owner,
-                "--state",
-                "open",
-                "--json",
-                "number,title,body,state,url,labels,repository",
-                "--limit",
-                "50",
-            ])
-            .output()
-            .await
-            .context("Failed to run gh CLI")?;
+    async fn fetch_items(&self) -> Result<Vec<WorkItem>, ProviderError> {
+        let issues = match &self.api {
+            Some(api) => api.search_assigned(&self.owner, self.repo.as_deref()).await?,
+            None => self.gh_search("issues", "--assignee").await?,
+        };
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("gh search issues failed: {stderr}");
+        // Every issue's blockers are looked up together. One whose state
+        // can't be read is kept, so an item is never dispatched too early.
+        let blockers: Vec<Vec<(String, u64)>> = issues
+            .iter()
+            .map(|issue| match (&issue.repository, &issue.body) {
+                (Some(repo), Some(body)) => blockers_in_text(body)
+                    .iter()
+                    .filter_map(|id| id.trim_start_matches('#').parse().ok())
+                    .map(|number| (repo.name_with_owner.clone(), number))
+                    .collect(),
+                _ => Vec::new(),
+            })
+            .collect();
+        let refs: Vec<(String, u64)> = blockers.iter().flatten().cloned().collect();
+        let states = issue_states_of(self.api.as_ref(), &refs).await;
+        let mut states = states.into_iter();
+
+        let mut items = Vec::new();
+        for (issue, blockers) in issues.into_iter().zip(blockers) {
+            let blocked_by = blockers
+                .into_iter()
+                .zip(states.by_ref())
+                .filter(|(_, state)| state.as_ref().is_none_or(|s| s.eq_ignore_ascii_case("open")))
+                .map(|((_, number), _)| format!("#{number}"))
+                .collect();
+            items.push(work_item(issue, blocked_by));
         }
 
-        let issues: Vec<GhIssue> =
-            serde_json::from_slice(&output.stdout).context("Failed to parse gh output")?;
+        // Best-effort: a failed review search shouldn't hide the issues
+        let review_requests = match &self.api {
+            Some(api) => {
+                api.search_review_requested(&self.owner, self.repo.as_deref())
+                    .await
+            }
+            None => self.gh_search("prs", "--review-requested").await,
+        };
+        match review_requests {
+            Ok(prs) => items.extend(prs.into_iter().map(|pr| work_item(pr, Vec::new()))),
+            Err(e) => tracing::warn!(error = %e, "listing review requests failed"),
+        }
 
-        let items = issues
+        Ok(items)
+    }
+
+    /// Repositories stand in for boards: picking one limits the items to it
+    /// and is where new issues are created.
+    async fn list_boards(&self) -> Result<Vec<BoardInfo>, ProviderError> {
+        let repos = match &self.api {
+            Some(api) => api.repos(&self.owner).await?,
+            None => {
+                let stdout = gh(&[
+                    "repo",
+                    "list",
+                    &self.owner,
+                    "--no-archived",
+                    "--json",
+                    "nameWithOwner",
+                    "--limit",
+                    "100",
+                ])
+                .await?;
+                serde_json::from_slice::<Vec<GhRepo>>(&stdout)
+                    .context("Failed to parse gh output")?
+            }
+        };
+        Ok(repos
             .into_iter()
-            .map(|issue| {
-                let description = issue
-                    .body
-                    .filter(|b| !b.trim().is_empty())
-                    .map(|b| b.chars().take(500).collect::<String>());
-                let labels = issue.labels.into_iter().map(|l| l.name).collect();
-                let team = issue.repository.map(|r| r.name_with_owner);
-
-                WorkItem {
-                    id: format!("#{}", issue.number),
-                    source_id: issue.url.clone(),
-                    title: issue.title,
-                    description,
-                    status: issue.state,
-                    priority: None,
-                    labels,
-                    source: "GitHub".into(),
-                    team,
-                    url: issue.url,
-                }
+            .map(|r| BoardInfo {
+                id: r.name_with_owner.clone(),
+                name: r.name_with_owner,
+                source: "GitHub".into(),
             })
-            .collect();
-
-        Ok(items)
+            .collect())
     }
 
-    async fn list_boards(&self) -> Result<Vec<BoardInfo>> {
-        Ok(vec![])
+    fn set_board_filter(&mut self, board_id: String) {
+        self.repo = Some(board_id);
     }
 
     async fn create_item(
         &self,
         title: &str,
         description: Option<&str>,
-    ) -> Result<Option<WorkItem>> {
-        // Detect the current repo using gh
-        let repo_output = tokio::process::Command::new("gh")
-            .args(["repo", "view", "--json", "nameWithOwner"])
-            .output()
-            .await
-            .context("Failed to run gh CLI to detect repo")?;
-
-        if !repo_output.status.success() {
-            // Not in a git repo or gh not configured — skip
+    ) -> Result<Option<WorkItem>, ProviderError> {
+        let repo = match &self.repo {
+            Some(repo) => Some(repo.clone()),
+            None => current_repo(self.api.as_ref()).await,
+        };
+        let Some(repo) = repo else {
+            // Not in a GitHub repo or gh not configured — skip
             return Ok(None);
+        };
+        if let Some(api) = &self.api {
+            let issue = api.create_issue(&repo, title, description).await?;
+            return Ok(Some(work_item(issue, Vec::new())));
         }
 
-        let repo_info: serde_json::Value =
-            serde_json::from_slice(&repo_output.stdout).context("Failed to parse gh repo view")?;
-        let repo = repo_info
-            .get("nameWithOwner")
-            .and_then(|v| v.as_str())
-            .context("No nameWithOwner in gh repo view output")?;
-
         // Build the gh issue create command
         let mut cmd_args = vec![
             "issue".to_string(),
             "create".to_string(),
             "--repo".to_string(),
-            repo.to_string(),
+            repo.clone(),
             "--title".to_string(),
             title.to_string(),
         ];
@@ -149,7 +343,7 @@ impl Provider for GitHubProvider {
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("gh issue create failed: {stderr}");
+            return Err(anyhow::anyhow!("gh issue create failed: {stderr}").into());
         }
 
         // gh issue create outputs the URL of the new issue
@@ -171,14 +365,24 @@ impl Provider for GitHubProvider {
             priority: None,
             labels: Vec::new(),
             source: "GitHub".into(),
-            team: Some(repo.to_string()),
+            team: Some(repo),
             url: Some(url),
+            links: Vec::new(),
+            comments: Vec::new(),
+            blocked_by: Vec::new(),
+            due: None,
         };
 
         Ok(Some(item))
     }
 
-    async fn move_to_done(&self, source_id: &str) -> Result<()> {
+    async fn move_to_done(&self, source_id: &str) -> Result<(), ProviderError> {
+        if is_pull(source_id) {
+            return Ok(());
+        }
+        if let Some(api) = &self.api {
+            return Ok(api.set_state(source_id, "closed").await?);
+        }
         // source_id is the issue URL, close it via gh CLI
         let output = tokio::process::Command::new("gh")
             .args(["issue", "close", source_id])
@@ -188,13 +392,103 @@ impl Provider for GitHubProvider {
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("gh issue close failed: {stderr}");
+            return Err(anyhow::anyhow!("gh issue close failed: {stderr}").into());
+        }
+
+        Ok(())
+    }
+
+    /// Pull requests linked to the issue, with their descriptions, or a
+    /// pull request's diff.
+    async fn fetch_links(&self, source_id: &str) -> Result<Vec<Link>, ProviderError> {
+        if is_pull(source_id) {
+            let diff = match &self.api {
+                Some(api) => api.pr_diff(source_id).await?,
+                None => String::from_utf8_lossy(&gh(&["pr", "diff", source_id]).await?).into(),
+            };
+            return Ok(vec![Link {
+                title: "Diff".into(),
+                url: format!("{source_id}/files"),
+                content: Some(diff),
+            }]);
+        }
+        if let Some(api) = &self.api {
+            return Ok(api
+                .linked_prs(source_id)
+                .await?
+                .into_iter()
+                .map(|(url, pr)| pr_link(url, pr))
+                .collect());
+        }
+        let stdout = gh(&[
+            "issue",
+            "view",
+            source_id,
+            "--json",
+            "closedByPullRequestsReferences",
+        ])
+        .await?;
+        let linked: GhLinkedPrs =
+            serde_json::from_slice(&stdout).context("Failed to parse gh output")?;
+
+        let mut links = Vec::new();
+        for pr_ref in linked.prs {
+            let stdout = gh(&["pr", "view", &pr_ref.url, "--json", "number,title,body"]).await?;
+            let pr: GhPr = serde_json::from_slice(&stdout).context("Failed to parse gh output")?;
+            links.push(pr_link(pr_ref.url, pr));
+        }
+        Ok(links)
+    }
+
+    async fn fetch_comments(&self, source_id: &str) -> Result<Vec<Comment>, ProviderError> {
+        let comments = match &self.api {
+            Some(api) => api.comments(source_id).await?,
+            None => {
+                let kind = if is_pull(source_id) { "pr" } else { "issue" };
+                let stdout = gh(&[kind, "view", source_id, "--json", "comments"]).await?;
+                let thread: GhComments =
+                    serde_json::from_slice(&stdout).context("Failed to parse gh output")?;
+                thread.comments
+            }
+        };
+        let skip = comments.len().saturating_sub(MAX_COMMENTS);
+        Ok(comments
+            .into_iter()
+            .skip(skip)
+            .map(|c| Comment {
+                author: c.author.map_or_else(|| "unknown".into(), |a| a.login),
+                created_at: c.created_at,
+                body: c.body,
+            })
+            .collect())
+    }
+
+    async fn add_comment(&self, source_id: &str, body: &str) -> Result<(), ProviderError> {
+        if let Some(api) = &self.api {
+            return Ok(api.add_comment(source_id, body).await?);
+        }
+        let kind = if is_pull(source_id) { "pr" } else { "issue" };
+        let output = tokio::process::Command::new("gh")
+            .args([kind, "comment", source_id, "--body", body])
+            .output()
+            .await
+            .context("Failed to run gh CLI")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("gh issue comment failed: {stderr}").into());
         }
 
         Ok(())
     }
 
-    async fn move_to_in_progress(&self, source_id: &str) -> Result<()> {
+    async fn move_to_in_progress(&self, source_id: &str) -> Result<(), ProviderError> {
+        if is_pull(source_id) {
+            return Ok(());
+        }
+        if let Some(api) = &self.api {
+            return Ok(api.add_label(source_id, "in-progress").await?);
+        }
         let output = tokio::process::Command::new("gh")
             .args(["issue", "edit", source_id, "--add-label", "in-progress"])
             .output()
@@ -203,7 +497,39 @@ impl Provider for GitHubProvider {
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("gh issue edit failed: {stderr}");
+            return Err(anyhow::anyhow!("gh issue edit failed: {stderr}").into());
+        }
+
+        Ok(())
+    }
+
+    async fn reopen(&self, source_id: &str) -> Result<(), ProviderError> {
+        // Undo both close and the in-progress label
+        if is_pull(source_id) {
+            return Ok(());
+        }
+        if let Some(api) = &self.api {
+            api.set_state(source_id, "open").await?;
+            return Ok(api.remove_label(source_id, "in-progress").await?);
+        }
+        let output = tokio::process::Command::new("gh")
+            .args(["issue", "reopen", source_id])
+            .output()
+            .await
+            .context("Failed to run gh CLI")?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() && !stderr.contains("already open") {
+            return Err(anyhow::anyhow!("gh issue reopen failed: {stderr}").into());
+        }
+
+        let output = tokio::process::Command::new("gh")
+            .args(["issue", "edit", source_id, "--remove-label", "in-progress"])
+            .output()
+            .await
+            .context("Failed to run gh CLI")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("gh issue edit failed: {stderr}").into());
         }
 
         Ok(())
@@ -0,0 +1,467 @@
+use anyhow::{Context, Result};
+use reqwest::{Method, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use super::{GhComment, GhIssue, GhLabel, GhPr, GhRepo};
+use crate::providers::http::CheckStatus;
+use crate::providers::MAX_COMMENTS;
+
+const API: &str = "https://api.github.com";
+
+/// GitHub's REST and GraphQL APIs with a personal access token, used instead
+/// of the gh CLI when a token is configured.
+pub(super) struct GitHubApi {
+    token: String,
+    client: reqwest::Client,
+}
+
+/// An issue as the REST API returns it.
+#[derive(Deserialize)]
+struct RestIssue {
+    number: u64,
+    title: String,
+    body: Option<String>,
+    state: String,
+    html_url: String,
+    #[serde(default)]
+    labels: Vec<GhLabel>,
+    /// Only set in search results, e.g. `https://api.github.com/repos/acme/app`
+    repository_url: Option<String>,
+}
+
+impl From<RestIssue> for GhIssue {
+    fn from(issue: RestIssue) -> Self {
+        let repository = issue
+            .repository_url
+            .as_deref()
+            .and_then(|url| url.strip_prefix(&format!("{API}/repos/")))
+            .map(|repo| GhRepo {
+                name_with_owner: repo.to_string(),
+            });
+        GhIssue {
+            number: issue.number,
+            title: issue.title,
+            body: issue.body,
+            state: Some(issue.state),
+            url: Some(issue.html_url),
+            labels: issue.labels,
+            repository,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SearchResults {
+    items: Vec<RestIssue>,
+}
+
+#[derive(Deserialize)]
+struct Nodes<T> {
+    nodes: Vec<T>,
+}
+
+/// `owner/repo` and number of an issue URL, e.g.
+/// `https://github.com/acme/app/issues/42`.
+fn issue_ref(url: &str) -> Result<(String, u64)> {
+    let path = url
+        .trim_end_matches('/')
+        .strip_prefix("https://github.com/")
+        .with_context(|| format!("Not a GitHub issue URL: {url}"))?;
+    match path.split('/').collect::<Vec<_>>()[..] {
+        [owner, repo, "issues" | "pull", number] => Ok((
+            format!("{owner}/{repo}"),
+            number
+                .parse()
+                .with_context(|| format!("Not a GitHub issue URL: {url}"))?,
+        )),
+        _ => anyhow::bail!("Not a GitHub issue URL: {url}"),
+    }
+}
+
+/// `owner/repo` of a GitHub remote, over SSH or HTTPS.
+pub(super) fn repo_from_remote(remote: &str) -> Option<String> {
+    let path = [
+        "git@github.com:",
+        "ssh://git@github.com/",
+        "https://github.com/",
+    ]
+    .iter()
+    .find_map(|prefix| remote.trim().strip_prefix(prefix))?;
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    let (owner, repo) = path.split_once('/')?;
+    (!owner.is_empty() && !repo.is_empty() && !repo.contains('/')).then(|| path.to_string())
+}
+
+impl GitHubApi {
+    pub(super) fn new(token: String) -> Self {
+        Self {
+            token,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.client
+            .request(method, format!("{API}{path}"))
+            .bearer_auth(&self.token)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28")
+            // GitHub rejects requests without one
+            .header("User-Agent", "work-cli")
+    }
+
+    async fn send<T: DeserializeOwned>(&self, req: RequestBuilder) -> Result<T> {
+        let resp = req.send().await.context("GitHub API request failed")?;
+        let resp = resp.check_status().await?;
+        resp.json().await.context("Failed to parse GitHub response")
+    }
+
+    async fn graphql<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+        pointer: &str,
+    ) -> Result<T> {
+        let body = serde_json::json!({ "query": query, "variables": variables });
+        let resp: serde_json::Value = self
+            .send(self.request(Method::POST, "/graphql").json(&body))
+            .await?;
+        if let Some(message) = resp.pointer("/errors/0/message").and_then(|v| v.as_str()) {
+            anyhow::bail!("GitHub API error: {message}");
+        }
+        let value = resp
+            .pointer(pointer)
+            .cloned()
+            .context("Issue not found on GitHub")?;
+        serde_json::from_value(value).context("Failed to parse GitHub response")
+    }
+
+    /// Open issues assigned to `owner`, in `repo` or across every repository.
+    pub(super) async fn search_assigned(
+        &self,
+        owner: &str,
+        repo: Option<&str>,
+    ) -> Result<Vec<GhIssue>> {
+        let query = search_query(format!("assignee:{owner} is:issue is:open"), repo);
+        let results: SearchResults = self
+            .send(
+                self.request(Method::GET, "/search/issues")
+                    .query(&[("q", query.as_str()), ("per_page", "50")]),
+            )
+            .await?;
+        Ok(results.items.into_iter().map(GhIssue::from).collect())
+    }
+
+    /// `owner`'s unarchived repositories, most recently pushed first: the
+    /// listing `gh repo list <owner>` makes, so the board picker is the same
+    /// with or without a token.
+    pub(super) async fn repos(&self, owner: &str) -> Result<Vec<GhRepo>> {
+        let query = r#"query($owner: String!) {
+          repositoryOwner(login: $owner) {
+            repositories(
+              first: 100, isArchived: false, orderBy: { field: PUSHED_AT, direction: DESC }
+            ) {
+              nodes { nameWithOwner }
+            }
+          }
+        }"#;
+        self.graphql(
+            query,
+            serde_json::json!({ "owner": owner }),
+            "/data/repositoryOwner/repositories/nodes",
+        )
+        .await
+        .with_context(|| format!("Failed to list {owner}'s repositories"))
+    }
+
+    /// The `data` of a GraphQL `query`, keeping what resolved when some
+    /// fields failed, e.g. a blocker that was deleted.
+    pub(super) async fn graphql_data(&self, query: &str) -> Result<serde_json::Value> {
+        let body = serde_json::json!({ "query": query });
+        let resp: serde_json::Value = self
+            .send(self.request(Method::POST, "/graphql").json(&body))
+            .await?;
+        graphql_data(resp)
+    }
+
+    pub(super) async fn create_issue(
+        &self,
+        repo: &str,
+        title: &str,
+        body: Option<&str>,
+    ) -> Result<GhIssue> {
+        let issue: RestIssue = self
+            .send(
+                self.request(Method::POST, &format!("/repos/{repo}/issues"))
+                    .json(&serde_json::json!({ "title": title, "body": body })),
+            )
+            .await
+            .context("Failed to create GitHub issue")?;
+        let mut issue = GhIssue::from(issue);
+        issue.repository = Some(GhRepo {
+            name_with_owner: repo.to_string(),
+        });
+        Ok(issue)
+    }
+
+    /// Set an issue `open` or `closed`.
+    pub(super) async fn set_state(&self, url: &str, state: &str) -> Result<()> {
+        let (repo, number) = issue_ref(url)?;
+        let _: serde_json::Value = self
+            .send(
+                self.request(Method::PATCH, &format!("/repos/{repo}/issues/{number}"))
+                    .json(&serde_json::json!({ "state": state })),
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub(super) async fn add_label(&self, url: &str, label: &str) -> Result<()> {
+        let (repo, number) = issue_ref(url)?;
+        let _: serde_json::Value = self
+            .send(
+                self.request(
+                    Method::POST,
+                    &format!("/repos/{repo}/issues/{number}/labels"),
+                )
+                .json(&serde_json::json!({ "labels": [label] })),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Remove a label; one the issue doesn't have is not an error.
+    pub(super) async fn remove_label(&self, url: &str, label: &str) -> Result<()> {
+        let (repo, number) = issue_ref(url)?;
+        let resp = self
+            .request(
+                Method::DELETE,
+                &format!("/repos/{repo}/issues/{number}/labels/{label}"),
+            )
+            .send()
+            .await
+            .context("GitHub API request failed")?;
+        if resp.status() != StatusCode::NOT_FOUND {
+            resp.check_status().await?;
+        }
+        Ok(())
+    }
+
+    pub(super) async fn add_comment(&self, url: &str, body: &str) -> Result<()> {
+        let (repo, number) = issue_ref(url)?;
+        let _: serde_json::Value = self
+            .send(
+                self.request(
+                    Method::POST,
+                    &format!("/repos/{repo}/issues/{number}/comments"),
+                )
+                .json(&serde_json::json!({ "body": body })),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// The latest comments on an issue or pull request, oldest first.
+    /// GraphQL can page from the end, which the REST endpoint can't.
+    pub(super) async fn comments(&self, url: &str) -> Result<Vec<GhComment>> {
+        let query = r#"query($owner: String!, $name: String!, $number: Int!, $last: Int!) {
+          repository(owner: $owner, name: $name) {
+            issueOrPullRequest(number: $number) {
+              ... on Issue { comments(last: $last) { nodes { author { login } body createdAt } } }
+              ... on PullRequest {
+                comments(last: $last) { nodes { author { login } body createdAt } }
+              }
+            }
+          }
+        }"#;
+        let comments: Nodes<GhComment> = self
+            .graphql(
+                query,
+                issue_variables(url, MAX_COMMENTS)?,
+                "/data/repository/issueOrPullRequest/comments",
+            )
+            .await?;
+        Ok(comments.nodes)
+    }
+
+    /// Open pull requests waiting on `owner`'s review, in `repo` or across
+    /// every repository.
+    pub(super) async fn search_review_requested(
+        &self,
+        owner: &str,
+        repo: Option<&str>,
+    ) -> Result<Vec<GhIssue>> {
+        let query = search_query(format!("review-requested:{owner} is:pr is:open"), repo);
+        let results: SearchResults = self
+            .send(
+                self.request(Method::GET, "/search/issues")
+                    .query(&[("q", query.as_str()), ("per_page", "50")]),
+            )
+            .await?;
+        Ok(results.items.into_iter().map(GhIssue::from).collect())
+    }
+
+    /// A pull request's changes as a unified diff.
+    pub(super) async fn pr_diff(&self, url: &str) -> Result<String> {
+        let (repo, number) = issue_ref(url)?;
+        let diff = self
+            .request(Method::GET, &format!("/repos/{repo}/pulls/{number}"))
+            .header("Accept", "application/vnd.github.diff")
+            .send()
+            .await
+            .context("GitHub API request failed")?
+            .check_status()
+            .await?
+            .text()
+            .await?;
+        Ok(diff)
+    }
+
+    /// Pull requests that will close the issue when merged.
+    pub(super) async fn linked_prs(&self, url: &str) -> Result<Vec<(String, GhPr)>> {
+        let query = r#"query($owner: String!, $name: String!, $number: Int!, $last: Int!) {
+          repository(owner: $owner, name: $name) {
+            issue(number: $number) {
+              closedByPullRequestsReferences(last: $last) { nodes { number title body url } }
+            }
+          }
+        }"#;
+
+        #[derive(Deserialize)]
+        struct LinkedPr {
+            url: String,
+            #[serde(flatten)]
+            pr: GhPr,
+        }
+
+        let prs: Nodes<LinkedPr> = self
+            .graphql(
+                query,
+                issue_variables(url, 10)?,
+                "/data/repository/issue/closedByPullRequestsReferences",
+            )
+            .await?;
+        Ok(prs.nodes.into_iter().map(|p| (p.url, p.pr)).collect())
+    }
+}
+
+/// A search `query` narrowed to `repo` when there is one.
+fn search_query(query: String, repo: Option<&str>) -> String {
+    match repo {
+        Some(repo) => format!("{query} repo:{repo}"),
+        None => query,
+    }
+}
+
+fn issue_variables(url: &str, last: usize) -> Result<serde_json::Value> {
+    let (repo, number) = issue_ref(url)?;
+    let (owner, name) = repo.split_once('/').unwrap_or_default();
+    Ok(serde_json::json!({
+        "owner": owner,
+        "name": name,
+        "number": number,
+        "last": last,
+    }))
+}
+
+/// One GraphQL query for the states of the issues `refs` (`owner/repo`,
+/// number), each aliased by its position.
+pub(super) fn issue_states_query(refs: &[(String, u64)]) -> String {
+    let fields: String = refs
+        .iter()
+        .enumerate()
+        .map(|(i, (repo, number))| {
+            let (owner, name) = repo.split_once('/').unwrap_or_default();
+            format!(
+                " i{i}: repository(owner: {owner:?}, name: {name:?}) {{ \
+                 issueOrPullRequest(number: {number}) {{ \
+                 ... on Issue {{ state }} ... on PullRequest {{ state }} }} }}"
+            )
+        })
+        .collect();
+    format!("query {{{fields} }}")
+}
+
+/// The states `data` answered to [`issue_states_query`], in the order of its
+/// refs; `None` where the issue couldn't be read.
+pub(super) fn issue_states(data: &serde_json::Value, count: usize) -> Vec<Option<String>> {
+    (0..count)
+        .map(|i| {
+            data.pointer(&format!("/i{i}/issueOrPullRequest/state"))
+                .and_then(|s| s.as_str())
+                .map(String::from)
+        })
+        .collect()
+}
+
+/// The `data` of a GraphQL response, failing only when nothing resolved.
+pub(super) fn graphql_data(resp: serde_json::Value) -> Result<serde_json::Value> {
+    match resp.get("data") {
+        Some(data) if !data.is_null() => Ok(data.clone()),
+        _ => {
+            let message = resp
+                .pointer("/errors/0/message")
+                .and_then(|v| v.as_str())
+                .unwrap_or("no data");
+            anyhow::bail!("GitHub API error: {message}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_issue_urls_and_remotes() {
+        assert_eq!(
+            issue_ref("https://github.com/acme/app/issues/42").unwrap(),
+            ("acme/app".to_string(), 42)
+        );
+        assert!(issue_ref("https://github.com/acme/app").is_err());
+        assert!(issue_ref("https://gitlab.com/acme/app/issues/42").is_err());
+
+        for remote in [
+            "git@github.com:acme/app.git",
+            "https://github.com/acme/app.git\n",
+            "ssh://git@github.com/acme/app",
+        ] {
+            assert_eq!(repo_from_remote(remote).as_deref(), Some("acme/app"));
+        }
+        assert_eq!(repo_from_remote("git@gitlab.com:acme/app.git"), None);
+    }
+
+    #[test]
+    fn looks_up_issue_states_in_one_query() {
+        let refs = vec![("acme/app".to_string(), 12), ("acme/api".to_string(), 3)];
+        let query = issue_states_query(&refs);
+        assert!(query.contains(r#"i0: repository(owner: "acme", name: "app")"#));
+        assert!(query.contains("issueOrPullRequest(number: 3)"));
+
+        // A deleted issue fails its own field only
+        let resp = serde_json::json!({
+            "data": {
+                "i0": { "issueOrPullRequest": { "state": "CLOSED" } },
+                "i1": { "issueOrPullRequest": null },
+            },
+            "errors": [{ "message": "Could not resolve to an issue or pull request" }],
+        });
+        let data = graphql_data(resp).unwrap();
+        assert_eq!(issue_states(&data, 2), [Some("CLOSED".to_string()), None]);
+
+        let resp =
+            serde_json::json!({ "data": null, "errors": [{ "message": "Bad credentials" }] });
+        assert!(graphql_data(resp).is_err());
+    }
+
+    #[test]
+    fn scopes_searches_to_the_picked_repo() {
+        let query = "assignee:ana is:issue is:open".to_string();
+        assert_eq!(search_query(query.clone(), None), query);
+        assert_eq!(
+            search_query(query, Some("acme/app")),
+            "assignee:ana is:issue is:open repo:acme/app"
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Response, StatusCode};
+
+use super::error::{ErrorKind, ProviderError};
+
+/// Characters of an unrecognised error body kept in the message.
+const MAX_BODY_CHARS: usize = 300;
+
+/// The typed error for a response with an error status, with the message
+/// the tracker gave in its body when it gave one.
+fn error_for(status: StatusCode, headers: &HeaderMap, body: &str) -> ProviderError {
+    let header = |name| headers.get(name).and_then(|v| v.to_str().ok());
+    let json: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+    let message = body_message(&json, body).unwrap_or_else(|| {
+        status
+            .canonical_reason()
+            .unwrap_or("no details")
+            .to_string()
+    });
+    let code = json
+        .pointer("/errors/0/extensions/code")
+        .and_then(|c| c.as_str());
+    let kind = match code {
+        // GraphQL APIs report auth and rate limits as 400s with a code
+        Some("AUTHENTICATION_ERROR" | "UNAUTHENTICATED") => ErrorKind::Auth,
+        Some("FORBIDDEN") => ErrorKind::Permission,
+        Some("RATELIMITED") => ErrorKind::RateLimited,
+        // GitHub answers an exhausted quota with a 403
+        _ if header("x-ratelimit-remaining") == Some("0") => ErrorKind::RateLimited,
+        _ => ErrorKind::from_status(status.as_u16()),
+    };
+    match kind {
+        ErrorKind::Auth => ProviderError::Auth { message },
+        ErrorKind::Permission => ProviderError::Forbidden { message },
+        ErrorKind::NotFound => ProviderError::NotFound { message },
+        ErrorKind::RateLimited => ProviderError::RateLimited {
+            retry_after: header(RETRY_AFTER.as_str())
+                .and_then(|v| v.trim().parse().ok())
+                .map(Duration::from_secs),
+            message,
+        },
+        _ => ProviderError::Api {
+            status: status.as_u16(),
+            message,
+        },
+    }
+}
+
+/// The error message in a response body, as Jira (`errorMessages`),
+/// GraphQL (`errors`), GitHub and Sentry (`message`, `detail`) or Trello
+/// (`error`, or plain text) put it.
+fn body_message(json: &serde_json::Value, body: &str) -> Option<String> {
+    let found = [
+        "/errorMessages/0",
+        "/errors/0/message",
+        "/message",
+        "/detail",
+        "/error",
+    ]
+    .iter()
+    .find_map(|pointer| json.pointer(pointer)?.as_str());
+    if let Some(message) = found {
+        return Some(message.to_string());
+    }
+    let text = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then(|| text.chars().take(MAX_BODY_CHARS).collect())
+}
+
+/// Turn error statuses into a [`ProviderError`] before the body is parsed, so
+/// a 401 reads as one rather than as a malformed response.
+#[async_trait]
+pub trait CheckStatus: Sized {
+    async fn check_status(self) -> Result<Self, ProviderError>;
+}
+
+#[async_trait]
+impl CheckStatus for Response {
+    async fn check_status(self) -> Result<Self, ProviderError> {
+        let status = self.status();
+        if status.is_success() {
+            return Ok(self);
+        }
+        let headers = self.headers().clone();
+        let body = self.text().await.unwrap_or_default();
+        Err(error_for(status, &headers, &body))
+    }
+}
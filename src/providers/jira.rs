@@ -1,16 +1,28 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use base64::Engine;
 use serde::Deserialize;
 
-use super::{BoardInfo, Provider};
-use crate::model::work_item::WorkItem;
-use crate::util::adf::extract_text_from_adf;
+use super::http::CheckStatus;
+use super::{is_text_attachment, BoardInfo, Provider, ProviderError, MAX_COMMENTS, MAX_PAGES};
+use crate::model::work_item::{Comment, Link, WorkItem};
+use crate::util::adf::{adf_to_markdown_with, markdown_to_adf, unnamed_mentions};
+
+/// Accounts `/user/bulk` returns per request
+const USERS_PER_PAGE: usize = 10;
 
 pub struct JiraProvider {
     base_url: String,
     auth_header: String,
     client: reqwest::Client,
+    /// Project key picked as the board; new issues are created in it
+    project: Option<String>,
+    /// Display names by account id, for mentions that carry only the id;
+    /// `None` for accounts Jira didn't return, so they aren't asked for again
+    user_names: Mutex<HashMap<String, Option<String>>>,
 }
 
 impl JiraProvider {
@@ -21,13 +33,169 @@ impl JiraProvider {
             base_url: format!("https://{domain}.atlassian.net"),
             auth_header: format!("Basic {encoded}"),
             client: reqwest::Client::new(),
+            project: None,
+            user_names: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Point at a stand-in server, for tests.
+    #[cfg(test)]
+    pub(crate) fn with_base_url(mut self, base_url: String) -> Self {
+        self.base_url = base_url;
+        self
+    }
+
+    /// Create an issue from `fields`, adding the description as ADF.
+    async fn create_issue(
+        &self,
+        mut fields: serde_json::Value,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<WorkItem> {
+        if let Some(description) = description {
+            fields["description"] = markdown_to_adf(description);
         }
+        let created: CreatedIssue = self
+            .client
+            .post(format!("{}/rest/api/3/issue", self.base_url))
+            .header("Authorization", &self.auth_header)
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({ "fields": fields }))
+            .send()
+            .await
+            .context("Failed to create Jira issue")?
+            .check_status()
+            .await?
+            .json()
+            .await
+            .context("Failed to parse Jira create issue response")?;
+
+        Ok(WorkItem {
+            id: created.key.clone(),
+            source_id: Some(created.key.clone()),
+            title: title.to_string(),
+            description: description.map(String::from),
+            status: None,
+            priority: None,
+            labels: Vec::new(),
+            source: "Jira".into(),
+            team: None,
+            url: Some(format!("{}/browse/{}", self.base_url, created.key)),
+            links: Vec::new(),
+            comments: Vec::new(),
+            blocked_by: Vec::new(),
+            due: None,
+        })
     }
+
+    /// Display names for the unnamed mentions in `docs`, looking up the
+    /// accounts not seen before a page of `USERS_PER_PAGE` at a time. Lookup
+    /// failures leave the mentions unnamed.
+    async fn mention_names<'a>(
+        &self,
+        docs: impl Iterator<Item = &'a serde_json::Value>,
+    ) -> HashMap<String, String> {
+        let missing: Vec<String> = {
+            let known = self.user_names.lock().unwrap();
+            let mut missing: Vec<String> = docs
+                .flat_map(unnamed_mentions)
+                .filter(|id| !known.contains_key(id))
+                .collect();
+            missing.sort();
+            missing.dedup();
+            missing
+        };
+        for chunk in missing.chunks(USERS_PER_PAGE) {
+            let mut query: Vec<(&str, &str)> =
+                chunk.iter().map(|id| ("accountId", id.as_str())).collect();
+            let page_size = USERS_PER_PAGE.to_string();
+            query.push(("maxResults", &page_size));
+            let resp = self
+                .client
+                .get(format!("{}/rest/api/3/user/bulk", self.base_url))
+                .header("Authorization", &self.auth_header)
+                .header("Accept", "application/json")
+                .query(&query)
+                .send()
+                .await;
+            let Ok(resp) = resp.and_then(|r| r.error_for_status()) else {
+                continue;
+            };
+            let Ok(users) = resp.json::<serde_json::Value>().await else {
+                continue;
+            };
+            let mut known = self.user_names.lock().unwrap();
+            for id in chunk {
+                known.insert(id.clone(), None);
+            }
+            for user in users["values"].as_array().into_iter().flatten() {
+                if let (Some(id), Some(name)) =
+                    (user["accountId"].as_str(), user["displayName"].as_str())
+                {
+                    known.insert(id.to_string(), Some(name.to_string()));
+                }
+            }
+        }
+        self.user_names
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(id, name)| Some((id.clone(), name.clone()?)))
+            .collect()
+    }
+
+    async fn download(&self, url: &str) -> Result<String> {
+        let text = self
+            .client
+            .get(url)
+            .header("Authorization", &self.auth_header)
+            .send()
+            .await?
+            .check_status()
+            .await?
+            .text()
+            .await?;
+        Ok(text)
+    }
+}
+
+#[derive(Deserialize)]
+struct RemoteLink {
+    object: RemoteObject,
+}
+
+#[derive(Deserialize)]
+struct RemoteObject {
+    url: String,
+    title: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AttachmentIssue {
+    fields: AttachmentFields,
+}
+
+#[derive(Deserialize)]
+struct AttachmentFields {
+    #[serde(default)]
+    attachment: Vec<JiraAttachment>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JiraAttachment {
+    filename: String,
+    content: String,
+    mime_type: Option<String>,
+    size: Option<u64>,
 }
 
 #[derive(Deserialize)]
 struct SearchResponse {
     issues: Vec<JiraIssue>,
+    /// Matching issues across all pages
+    #[serde(default)]
+    total: usize,
 }
 
 #[derive(Deserialize)]
@@ -45,11 +213,63 @@ struct IssueFields {
     #[serde(default)]
     labels: Vec<String>,
     project: Option<ProjectField>,
+    #[serde(default)]
+    issuelinks: Vec<IssueLink>,
+    duedate: Option<String>,
 }
 
 #[derive(Deserialize)]
 struct StatusField {
     name: String,
+    #[serde(rename = "statusCategory")]
+    category: Option<StatusCategory>,
+}
+
+#[derive(Deserialize)]
+struct StatusCategory {
+    key: String,
+}
+
+#[derive(Deserialize)]
+struct IssueLink {
+    #[serde(rename = "type")]
+    link_type: IssueLinkType,
+    #[serde(rename = "inwardIssue")]
+    inward_issue: Option<LinkedIssue>,
+}
+
+#[derive(Deserialize)]
+struct IssueLinkType {
+    inward: String,
+}
+
+#[derive(Deserialize)]
+struct LinkedIssue {
+    key: String,
+    fields: LinkedIssueFields,
+}
+
+#[derive(Deserialize)]
+struct LinkedIssueFields {
+    status: Option<StatusField>,
+}
+
+/// Keys of the unfinished issues linked as blocking this one.
+fn open_blockers(links: &[IssueLink]) -> Vec<String> {
+    links
+        .iter()
+        .filter(|link| link.link_type.inward.eq_ignore_ascii_case("is blocked by"))
+        .filter_map(|link| link.inward_issue.as_ref())
+        .filter(|issue| {
+            let category = issue
+                .fields
+                .status
+                .as_ref()
+                .and_then(|s| s.category.as_ref());
+            category.is_none_or(|c| c.key != "done")
+        })
+        .map(|issue| issue.key.clone())
+        .collect()
 }
 
 #[derive(Deserialize)]
@@ -62,43 +282,103 @@ struct ProjectField {
     name: String,
 }
 
+#[derive(Deserialize)]
+struct Project {
+    key: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct ParentIssue {
+    fields: ParentFields,
+}
+
+#[derive(Deserialize)]
+struct ParentFields {
+    project: Project,
+}
+
+#[derive(Deserialize)]
+struct ProjectIssueTypes {
+    #[serde(rename = "issueTypes", default)]
+    issue_types: Vec<IssueType>,
+}
+
+#[derive(Deserialize)]
+struct IssueType {
+    name: String,
+    #[serde(default)]
+    subtask: bool,
+}
+
+#[derive(Deserialize)]
+struct CreatedIssue {
+    key: String,
+}
+
 #[async_trait]
 impl Provider for JiraProvider {
     fn name(&self) -> &str {
         "Jira"
     }
 
-    async fn fetch_items(&self) -> Result<Vec<WorkItem>> {
-        let jql = "assignee=currentUser() AND statusCategory!=Done ORDER BY priority ASC";
-        let url = format!(
-            "{}/rest/api/3/search?jql={}&maxResults=50&fields=summary,description,status,priority,labels,project",
-            self.base_url,
-            urlencoding::encode(jql)
+    async fn fetch_items(&self) -> Result<Vec<WorkItem>, ProviderError> {
+        let project = match &self.project {
+            Some(key) => format!("project=\"{key}\" AND "),
+            None => String::new(),
+        };
+        let jql = format!(
+            "{project}assignee=currentUser() AND statusCategory!=Done ORDER BY priority ASC"
         );
+        // Page through the results until `total` is reached
+        let mut issues = Vec::new();
+        for _ in 0..MAX_PAGES {
+            let url = format!(
+                "{}/rest/api/3/search?jql={}&startAt={}&maxResults=50&fields=summary,description,status,priority,labels,project,issuelinks,duedate",
+                self.base_url,
+                urlencoding::encode(&jql),
+                issues.len()
+            );
 
-        let resp = self
-            .client
-            .get(&url)
-            .header("Authorization", &self.auth_header)
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .context("Jira API request failed")?;
+            let resp = self
+                .client
+                .get(&url)
+                .header("Authorization", &self.auth_header)
+                .header("Accept", "application/json")
+                .send()
+                .await
+                .context("Jira API request failed")?
+                .check_status()
+                .await?;
 
-        let search: SearchResponse = resp.json().await.context("Failed to parse Jira response")?;
+            let search: SearchResponse =
+                resp.json().await.context("Failed to parse Jira response")?;
+            let last_page = search.issues.is_empty();
+            issues.extend(search.issues);
+            if last_page || issues.len() >= search.total {
+                break;
+            }
+        }
 
-        let items = search
-            .issues
+        let names = self
+            .mention_names(
+                issues
+                    .iter()
+                    .filter_map(|issue| issue.fields.description.as_ref()),
+            )
+            .await;
+        let items = issues
             .into_iter()
             .map(|issue| {
                 let description = issue
                     .fields
                     .description
                     .as_ref()
-                    .and_then(|d| extract_text_from_adf(d))
-                    .map(|d| d.chars().take(500).collect::<String>());
+                    // Kept whole: `/edit` writes it back as the new description
+                    .and_then(|d| adf_to_markdown_with(d, &names));
 
                 let url = format!("{}/browse/{}", self.base_url, issue.key);
+                let blocked_by = open_blockers(&issue.fields.issuelinks);
 
                 WorkItem {
                     id: issue.key.clone(),
@@ -111,6 +391,10 @@ impl Provider for JiraProvider {
                     source: "Jira".into(),
                     team: issue.fields.project.map(|p| p.name),
                     url: Some(url),
+                    links: Vec::new(),
+                    comments: Vec::new(),
+                    blocked_by,
+                    due: issue.fields.duedate,
                 }
             })
             .collect();
@@ -118,11 +402,127 @@ impl Provider for JiraProvider {
         Ok(items)
     }
 
-    async fn list_boards(&self) -> Result<Vec<BoardInfo>> {
-        Ok(vec![])
+    /// Projects stand in for boards: picking one filters the items and is
+    /// where new issues are created.
+    async fn list_boards(&self) -> Result<Vec<BoardInfo>, ProviderError> {
+        let projects: Vec<Project> = self
+            .client
+            .get(format!("{}/rest/api/3/project", self.base_url))
+            .header("Authorization", &self.auth_header)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .context("Failed to fetch Jira projects")?
+            .check_status()
+            .await?
+            .json()
+            .await?;
+        Ok(projects
+            .into_iter()
+            .map(|p| BoardInfo {
+                id: p.key,
+                name: p.name,
+                source: "Jira".into(),
+            })
+            .collect())
+    }
+
+    fn set_board_filter(&mut self, board_id: String) {
+        self.project = Some(board_id);
+    }
+
+    async fn create_item(
+        &self,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<Option<WorkItem>, ProviderError> {
+        let Some(project) = &self.project else {
+            // No project selected — can't create
+            return Ok(None);
+        };
+
+        let fields = serde_json::json!({
+            "project": { "key": project },
+            "summary": title,
+            "issuetype": { "name": "Task" },
+        });
+        Ok(Some(self.create_issue(fields, title, description).await?))
     }
 
-    async fn move_to_done(&self, source_id: &str) -> Result<()> {
+    async fn create_subtask(
+        &self,
+        parent_source_id: &str,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<Option<WorkItem>, ProviderError> {
+        // The parent's project, and the sub-task type it's set up with
+        let parent: ParentIssue = self
+            .client
+            .get(format!(
+                "{}/rest/api/3/issue/{parent_source_id}?fields=project",
+                self.base_url
+            ))
+            .header("Authorization", &self.auth_header)
+            .send()
+            .await
+            .context("Failed to fetch Jira parent issue")?
+            .check_status()
+            .await?
+            .json()
+            .await
+            .context("Failed to parse Jira parent issue")?;
+        let project = parent.fields.project.key;
+        let types: ProjectIssueTypes = self
+            .client
+            .get(format!("{}/rest/api/3/project/{project}", self.base_url))
+            .header("Authorization", &self.auth_header)
+            .send()
+            .await
+            .context("Failed to fetch Jira project")?
+            .check_status()
+            .await?
+            .json()
+            .await
+            .context("Failed to parse Jira project")?;
+        let issue_type = types
+            .issue_types
+            .into_iter()
+            .find(|t| t.subtask)
+            .map_or_else(|| "Subtask".to_string(), |t| t.name);
+
+        let fields = serde_json::json!({
+            "project": { "key": project },
+            "parent": { "key": parent_source_id },
+            "summary": title,
+            "issuetype": { "name": issue_type },
+        });
+        Ok(Some(self.create_issue(fields, title, description).await?))
+    }
+
+    async fn update_item(
+        &self,
+        source_id: &str,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<bool, ProviderError> {
+        let mut fields = serde_json::json!({ "summary": title });
+        if let Some(description) = description {
+            fields["description"] = markdown_to_adf(description);
+        }
+        self.client
+            .put(format!("{}/rest/api/3/issue/{source_id}", self.base_url))
+            .header("Authorization", &self.auth_header)
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({ "fields": fields }))
+            .send()
+            .await
+            .context("Failed to update Jira issue")?
+            .check_status()
+            .await?;
+        Ok(true)
+    }
+
+    async fn move_to_done(&self, source_id: &str) -> Result<(), ProviderError> {
         // Get available transitions for this issue
         let url = format!(
             "{}/rest/api/3/issue/{}/transitions",
@@ -137,6 +537,8 @@ impl Provider for JiraProvider {
             .send()
             .await
             .context("Failed to fetch Jira transitions")?
+            .check_status()
+            .await?
             .json()
             .await?;
 
@@ -168,12 +570,122 @@ impl Provider for JiraProvider {
             .json(&body)
             .send()
             .await
-            .context("Failed to transition Jira issue to Done")?;
+            .context("Failed to transition Jira issue to Done")?
+            .check_status()
+            .await?;
+
+        Ok(())
+    }
+
+    async fn fetch_links(&self, source_id: &str) -> Result<Vec<Link>, ProviderError> {
+        let remote: Vec<RemoteLink> = self
+            .client
+            .get(format!(
+                "{}/rest/api/3/issue/{}/remotelink",
+                self.base_url, source_id
+            ))
+            .header("Authorization", &self.auth_header)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .context("Failed to fetch Jira remote links")?
+            .check_status()
+            .await?
+            .json()
+            .await?;
+        let issue: AttachmentIssue = self
+            .client
+            .get(format!("{}/rest/api/3/issue/{}", self.base_url, source_id))
+            .header("Authorization", &self.auth_header)
+            .header("Accept", "application/json")
+            .query(&[("fields", "attachment")])
+            .send()
+            .await
+            .context("Failed to fetch Jira attachments")?
+            .check_status()
+            .await?
+            .json()
+            .await?;
+
+        let mut links: Vec<Link> = remote
+            .into_iter()
+            .map(|link| Link {
+                title: link.object.title.unwrap_or_else(|| link.object.url.clone()),
+                url: link.object.url,
+                content: None,
+            })
+            .collect();
+        for attachment in issue.fields.attachment {
+            let content = if is_text_attachment(attachment.mime_type.as_deref(), attachment.size) {
+                self.download(&attachment.content).await.ok()
+            } else {
+                None
+            };
+            links.push(Link {
+                title: attachment.filename,
+                url: attachment.content,
+                content,
+            });
+        }
+        Ok(links)
+    }
+
+    async fn fetch_comments(&self, source_id: &str) -> Result<Vec<Comment>, ProviderError> {
+        let url = format!("{}/rest/api/3/issue/{}/comment", self.base_url, source_id);
+
+        let resp: serde_json::Value = self
+            .client
+            .get(&url)
+            .header("Authorization", &self.auth_header)
+            .header("Accept", "application/json")
+            .query(&[
+                ("orderBy", "-created"),
+                ("maxResults", &MAX_COMMENTS.to_string()),
+            ])
+            .send()
+            .await
+            .context("Failed to fetch Jira comments")?
+            .check_status()
+            .await?
+            .json()
+            .await?;
+
+        let comments = resp["comments"].as_array().cloned().unwrap_or_default();
+        let names = self
+            .mention_names(comments.iter().map(|c| &c["body"]))
+            .await;
+        Ok(comments
+            .iter()
+            .rev()
+            .map(|c| Comment {
+                author: c["author"]["displayName"]
+                    .as_str()
+                    .unwrap_or("unknown")
+                    .to_string(),
+                created_at: c["created"].as_str().map(String::from),
+                body: adf_to_markdown_with(&c["body"], &names).unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    async fn add_comment(&self, source_id: &str, body: &str) -> Result<(), ProviderError> {
+        let url = format!("{}/rest/api/3/issue/{}/comment", self.base_url, source_id);
+
+        self.client
+            .post(&url)
+            .header("Authorization", &self.auth_header)
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({ "body": markdown_to_adf(body) }))
+            .send()
+            .await
+            .context("Failed to add Jira comment")?
+            .check_status()
+            .await?;
 
         Ok(())
     }
 
-    async fn move_to_in_progress(&self, source_id: &str) -> Result<()> {
+    async fn move_to_in_progress(&self, source_id: &str) -> Result<(), ProviderError> {
         let url = format!(
             "{}/rest/api/3/issue/{}/transitions",
             self.base_url, source_id
@@ -187,6 +699,8 @@ impl Provider for JiraProvider {
             .send()
             .await
             .context("Failed to fetch Jira transitions")?
+            .check_status()
+            .await?
             .json()
             .await?;
 
@@ -216,7 +730,62 @@ impl Provider for JiraProvider {
             .json(&body)
             .send()
             .await
-            .context("Failed to transition Jira issue to In Progress")?;
+            .context("Failed to transition Jira issue to In Progress")?
+            .check_status()
+            .await?;
+
+        Ok(())
+    }
+
+    async fn reopen(&self, source_id: &str) -> Result<(), ProviderError> {
+        let url = format!(
+            "{}/rest/api/3/issue/{}/transitions",
+            self.base_url, source_id
+        );
+
+        let resp: serde_json::Value = self
+            .client
+            .get(&url)
+            .header("Authorization", &self.auth_header)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .context("Failed to fetch Jira transitions")?
+            .check_status()
+            .await?
+            .json()
+            .await?;
+
+        // "new" is the To Do status category
+        let transition_id = resp
+            .get("transitions")
+            .and_then(|t| t.as_array())
+            .and_then(|transitions| {
+                transitions.iter().find_map(|t| {
+                    let category = t.pointer("/to/statusCategory/key")?.as_str()?;
+                    if category == "new" {
+                        t.get("id")?.as_str().map(|s| s.to_string())
+                    } else {
+                        None
+                    }
+                })
+            })
+            .context("No transition to To Do status found")?;
+
+        let body = serde_json::json!({
+            "transition": { "id": transition_id }
+        });
+
+        self.client
+            .post(&url)
+            .header("Authorization", &self.auth_header)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to transition Jira issue to To Do")?
+            .check_status()
+            .await?;
 
         Ok(())
     }
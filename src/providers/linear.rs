@@ -1,36 +1,314 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde::Deserialize;
 
-use super::{BoardInfo, Provider};
-use crate::model::work_item::WorkItem;
+use super::http::CheckStatus;
+use super::{BoardInfo, Provider, ProviderError, MAX_COMMENTS, MAX_PAGES};
+use crate::model::work_item::{Comment, WorkItem};
+
+const API_URL: &str = "https://api.linear.app/graphql";
+/// Most issues Linear returns for one query
+const ISSUES_PER_PAGE: usize = 250;
 
 pub struct LinearProvider {
+    api_url: String,
     api_key: String,
     client: reqwest::Client,
+    /// Team ID of each issue seen, by issue UUID
+    issue_teams: Mutex<HashMap<String, String>>,
+    /// Workflow state IDs per team, by state type
+    team_states: Mutex<HashMap<String, HashMap<String, String>>>,
 }
 
 impl LinearProvider {
     pub fn new(api_key: String) -> Self {
         Self {
+            api_url: API_URL.to_string(),
             api_key,
             client: reqwest::Client::new(),
+            issue_teams: Mutex::new(HashMap::new()),
+            team_states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Point at a stand-in server, for tests.
+    #[cfg(test)]
+    pub(crate) fn with_api_url(mut self, api_url: String) -> Self {
+        self.api_url = api_url;
+        self
+    }
+
+    async fn graphql(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let body = serde_json::json!({ "query": query, "variables": variables });
+        let resp: serde_json::Value = self
+            .client
+            .post(&self.api_url)
+            .header("Authorization", &self.api_key)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send()
+            .await
+            .context("Linear API request failed")?
+            .check_status()
+            .await?
+            .json()
+            .await
+            .context("Failed to parse Linear response")?;
+        if let Some(message) = resp.pointer("/errors/0/message").and_then(|v| v.as_str()) {
+            anyhow::bail!("Linear API error: {message}");
+        }
+        Ok(resp)
+    }
+
+    /// The cached state of the given type for the issue's team, if the team
+    /// is known from an earlier fetch or transition.
+    fn cached_state(&self, source_id: &str, kind: &str) -> Option<String> {
+        let team = self.issue_teams.lock().ok()?.get(source_id)?.clone();
+        self.team_states.lock().ok()?.get(&team)?.get(kind).cloned()
+    }
+
+    /// Look up the issue's team and all of its workflow states in one query,
+    /// so later transitions for any issue on the team skip the lookup.
+    async fn load_states(&self, source_id: &str, kind: &str) -> Result<String> {
+        let query = r#"query($id: String!) {
+          issue(id: $id) {
+            team {
+              id
+              states { nodes { id type position } }
+            }
+          }
+        }"#;
+        let resp = self
+            .graphql(query, serde_json::json!({ "id": source_id }))
+            .await?;
+        let team: TeamStates = serde_json::from_value(
+            resp.pointer("/data/issue/team")
+                .cloned()
+                .context("Linear issue not found")?,
+        )?;
+        let states = first_state_of_each_type(team.states.nodes);
+        let state_id = states
+            .get(kind)
+            .cloned()
+            .with_context(|| format!("No '{kind}' state found for issue's team"))?;
+
+        if let Ok(mut issue_teams) = self.issue_teams.lock() {
+            issue_teams.insert(source_id.to_string(), team.id.clone());
+        }
+        if let Ok(mut team_states) = self.team_states.lock() {
+            team_states.insert(team.id, states);
+        }
+        Ok(state_id)
+    }
+
+    /// Look up the teams and workflow states of several issues in one query.
+    async fn load_states_of(&self, source_ids: &[&str]) -> Result<()> {
+        let query = r#"query($ids: [ID!], $first: Int) {
+          issues(filter: { id: { in: $ids } }, first: $first) {
+            nodes {
+              id
+              team {
+                id
+                states { nodes { id type position } }
+              }
+            }
+          }
+        }"#;
+        let resp = self
+            .graphql(
+                query,
+                serde_json::json!({ "ids": source_ids, "first": ISSUES_PER_PAGE }),
+            )
+            .await?;
+        let issues: Vec<IssueTeam> = serde_json::from_value(
+            resp.pointer("/data/issues/nodes")
+                .cloned()
+                .context("Unexpected Linear response")?,
+        )?;
+        for issue in issues {
+            if let Ok(mut issue_teams) = self.issue_teams.lock() {
+                issue_teams.insert(issue.id, issue.team.id.clone());
+            }
+            if let Ok(mut team_states) = self.team_states.lock() {
+                team_states.insert(
+                    issue.team.id,
+                    first_state_of_each_type(issue.team.states.nodes),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Move an issue to the first workflow state of `kind`. Once the team's
+    /// states are cached this is a single mutation.
+    async fn transition(&self, source_id: &str, kind: &str) -> Result<()> {
+        let state_id = match self.cached_state(source_id, kind) {
+            Some(state_id) => state_id,
+            None => self.load_states(source_id, kind).await?,
+        };
+
+        let mutation = r#"mutation($id: String!, $stateId: String!) {
+          issueUpdate(id: $id, input: { stateId: $stateId }) {
+            success
+          }
+        }"#;
+        let resp = self
+            .graphql(mutation, serde_json::json!({ "id": source_id, "stateId": state_id }))
+            .await;
+        let ok = resp.as_ref().is_ok_and(|r| {
+            r.pointer("/data/issueUpdate/success").and_then(|v| v.as_bool()) == Some(true)
+        });
+        if !ok {
+            self.forget_states(source_id);
+            resp?;
+            anyhow::bail!("Linear did not update the issue");
         }
+        Ok(())
     }
+
+    /// Move several issues to the first workflow state of `kind`, with one
+    /// batch mutation per target state rather than one per issue.
+    async fn transition_many(&self, source_ids: &[String], kind: &str) -> Result<()> {
+        let unknown: Vec<&str> = source_ids
+            .iter()
+            .filter(|id| self.cached_state(id, kind).is_none())
+            .map(String::as_str)
+            .collect();
+        for chunk in unknown.chunks(ISSUES_PER_PAGE) {
+            self.load_states_of(chunk).await?;
+        }
+        let mut by_state: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+        for source_id in source_ids {
+            let state_id = self
+                .cached_state(source_id, kind)
+                .with_context(|| format!("No '{kind}' state found for {source_id}'s team"))?;
+            by_state.entry(state_id).or_default().push(source_id);
+        }
+
+        let mutation = r#"mutation($ids: [UUID!]!, $stateId: String!) {
+          issueBatchUpdate(ids: $ids, input: { stateId: $stateId }) {
+            success
+          }
+        }"#;
+        for (state_id, ids) in by_state {
+            let resp = self
+                .graphql(mutation, serde_json::json!({ "ids": ids, "stateId": state_id }))
+                .await;
+            let ok = resp.as_ref().is_ok_and(|r| {
+                r.pointer("/data/issueBatchUpdate/success").and_then(|v| v.as_bool())
+                    == Some(true)
+            });
+            if !ok {
+                for source_id in ids {
+                    self.forget_states(source_id);
+                }
+                resp?;
+                anyhow::bail!("Linear did not update the issues");
+            }
+        }
+        Ok(())
+    }
+
+    /// Create an issue in `team`, under `parent_id` when it's a sub-issue.
+    async fn create_issue(
+        &self,
+        title: &str,
+        description: Option<&str>,
+        team: Team,
+        parent_id: Option<&str>,
+    ) -> Result<WorkItem> {
+        let mutation = r#"mutation($title: String!, $teamId: String!, $description: String, $parentId: String) {
+          issueCreate(input: { title: $title, teamId: $teamId, description: $description, parentId: $parentId }) {
+            success
+            issue { id identifier title description url state { name } }
+          }
+        }"#;
+
+        let mut variables = serde_json::json!({
+            "title": title,
+            "teamId": team.id,
+        });
+        if let Some(desc) = description {
+            variables["description"] = serde_json::Value::String(desc.to_string());
+        }
+        if let Some(parent_id) = parent_id {
+            variables["parentId"] = serde_json::Value::String(parent_id.to_string());
+        }
+
+        let resp = self
+            .graphql(mutation, variables)
+            .await
+            .context("Failed to create Linear issue")?;
+
+        let issue = resp.pointer("/data/issueCreate/issue")
+            .context("No issue in create response")?;
+
+        Ok(WorkItem {
+            id: issue.get("identifier").and_then(|v| v.as_str()).unwrap_or("?").to_string(),
+            source_id: issue.get("id").and_then(|v| v.as_str()).map(String::from),
+            title: title.to_string(),
+            description: description.map(String::from),
+            status: issue.pointer("/state/name").and_then(|v| v.as_str()).map(String::from),
+            priority: None,
+            labels: Vec::new(),
+            source: "Linear".into(),
+            team: Some(team.name),
+            url: issue.get("url").and_then(|v| v.as_str()).map(String::from),
+            links: Vec::new(),
+            comments: Vec::new(),
+            blocked_by: Vec::new(),
+            due: None,
+        })
+    }
+
+    /// The team's workflow may have changed; look it up again next time.
+    fn forget_states(&self, source_id: &str) {
+        let team = self.issue_teams.lock().ok().and_then(|t| t.get(source_id).cloned());
+        if let (Some(team), Ok(mut team_states)) = (team, self.team_states.lock()) {
+            team_states.remove(&team);
+        }
+    }
+}
+
+/// A team's workflow states, keyed by type (`unstarted`, `started`,
+/// `completed`, ...) and keeping the leftmost state of each type.
+pub(crate) fn first_state_of_each_type(nodes: Vec<WorkflowState>) -> HashMap<String, String> {
+    let mut first: HashMap<String, WorkflowState> = HashMap::new();
+    for node in nodes {
+        match first.get(&node.kind) {
+            Some(seen) if seen.position <= node.position => {}
+            _ => {
+                first.insert(node.kind.clone(), node);
+            }
+        }
+    }
+    first.into_iter().map(|(kind, s)| (kind, s.id)).collect()
 }
 
-const QUERY: &str = r#"{
+const QUERY: &str = r#"query($after: String) {
   viewer {
     assignedIssues(
       filter: { state: { type: { nin: ["completed", "canceled"] } } }
       first: 50
+      after: $after
     ) {
       nodes {
-        id identifier title description priority url
+        id identifier title description priority url dueDate
         state { name }
-        team { name }
+        team { id name }
         labels { nodes { name } }
+        inverseRelations(first: 20) {
+          nodes { type issue { identifier state { type } } }
+        }
       }
+      pageInfo { hasNextPage endCursor }
     }
   }
 }"#;
@@ -54,6 +332,16 @@ struct Viewer {
 #[derive(Deserialize)]
 struct IssueConnection {
     nodes: Vec<Issue>,
+    #[serde(rename = "pageInfo")]
+    page_info: Option<PageInfo>,
+}
+
+#[derive(Deserialize)]
+struct PageInfo {
+    #[serde(rename = "hasNextPage")]
+    has_next_page: bool,
+    #[serde(rename = "endCursor")]
+    end_cursor: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -64,9 +352,38 @@ struct Issue {
     description: Option<String>,
     priority: Option<u8>,
     url: Option<String>,
+    #[serde(rename = "dueDate")]
+    due_date: Option<String>,
     state: Option<State>,
     team: Option<Team>,
     labels: Option<LabelConnection>,
+    #[serde(rename = "inverseRelations")]
+    inverse_relations: Option<RelationConnection>,
+}
+
+#[derive(Deserialize)]
+struct RelationConnection {
+    nodes: Vec<Relation>,
+}
+
+/// A relation pointing at the fetched issue; `blocks` means `issue` blocks it.
+#[derive(Deserialize)]
+struct Relation {
+    #[serde(rename = "type")]
+    kind: String,
+    issue: RelatedIssue,
+}
+
+#[derive(Deserialize)]
+struct RelatedIssue {
+    identifier: String,
+    state: Option<RelatedState>,
+}
+
+#[derive(Deserialize)]
+struct RelatedState {
+    #[serde(rename = "type")]
+    kind: String,
 }
 
 #[derive(Deserialize)]
@@ -76,9 +393,35 @@ struct State {
 
 #[derive(Deserialize)]
 struct Team {
+    id: String,
     name: String,
 }
 
+#[derive(Deserialize)]
+struct IssueTeam {
+    id: String,
+    team: TeamStates,
+}
+
+#[derive(Deserialize)]
+struct TeamStates {
+    id: String,
+    states: WorkflowStateConnection,
+}
+
+#[derive(Deserialize)]
+struct WorkflowStateConnection {
+    nodes: Vec<WorkflowState>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct WorkflowState {
+    pub(crate) id: String,
+    #[serde(rename = "type")]
+    pub(crate) kind: String,
+    pub(crate) position: f64,
+}
+
 #[derive(Deserialize)]
 struct LabelConnection {
     nodes: Vec<Label>,
@@ -89,7 +432,7 @@ struct Label {
     name: String,
 }
 
-fn map_priority(p: Option<u8>) -> Option<String> {
+pub(crate) fn map_priority(p: Option<u8>) -> Option<String> {
     match p {
         Some(1) => Some("Urgent".into()),
         Some(2) => Some("High".into()),
@@ -105,25 +448,52 @@ impl Provider for LinearProvider {
         "Linear"
     }
 
-    async fn fetch_items(&self) -> Result<Vec<WorkItem>> {
-        let body = serde_json::json!({ "query": QUERY });
-        let resp = self
-            .client
-            .post("https://api.linear.app/graphql")
-            .header("Authorization", &self.api_key)
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .context("Linear API request failed")?;
+    async fn fetch_items(&self) -> Result<Vec<WorkItem>, ProviderError> {
+        // Follow the cursor until the last page
+        let mut issues = Vec::new();
+        let mut after: Option<String> = None;
+        for _ in 0..MAX_PAGES {
+            let body = serde_json::json!({ "query": QUERY, "variables": { "after": after } });
+            let resp = self
+                .client
+                .post(&self.api_url)
+                .header("Authorization", &self.api_key)
+                .header("Content-Type", "application/json")
+                .json(&body)
+                .send()
+                .await
+                .context("Linear API request failed")?
+                .check_status()
+                .await?;
+
+            let gql: GqlResponse = resp
+                .json()
+                .await
+                .context("Failed to parse Linear response")?;
+            let page = gql
+                .data
+                .context("No data in Linear response")?
+                .viewer
+                .assigned_issues;
+            issues.extend(page.nodes);
+            match page.page_info {
+                Some(PageInfo {
+                    has_next_page: true,
+                    end_cursor: Some(cursor),
+                }) => after = Some(cursor),
+                _ => break,
+            }
+        }
 
-        let gql: GqlResponse = resp.json().await.context("Failed to parse Linear response")?;
-        let data = gql.data.context("No data in Linear response")?;
+        if let Ok(mut issue_teams) = self.issue_teams.lock() {
+            for issue in &issues {
+                if let Some(team) = &issue.team {
+                    issue_teams.insert(issue.id.clone(), team.id.clone());
+                }
+            }
+        }
 
-        let items = data
-            .viewer
-            .assigned_issues
-            .nodes
+        let items = issues
             .into_iter()
             .map(|issue| {
                 let description = issue
@@ -133,6 +503,20 @@ impl Provider for LinearProvider {
                     .labels
                     .map(|lc| lc.nodes.into_iter().map(|l| l.name).collect())
                     .unwrap_or_default();
+                let blocked_by = issue
+                    .inverse_relations
+                    .map(|rc| rc.nodes)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|r| r.kind == "blocks")
+                    .filter(|r| {
+                        r.issue
+                            .state
+                            .as_ref()
+                            .is_none_or(|s| s.kind != "completed" && s.kind != "canceled")
+                    })
+                    .map(|r| r.issue.identifier)
+                    .collect();
 
                 WorkItem {
                     id: issue.identifier,
@@ -145,6 +529,10 @@ impl Provider for LinearProvider {
                     source: "Linear".into(),
                     team: issue.team.map(|t| t.name),
                     url: issue.url,
+                    links: Vec::new(),
+                    comments: Vec::new(),
+                    blocked_by,
+                    due: issue.due_date,
                 }
             })
             .collect();
@@ -152,83 +540,42 @@ impl Provider for LinearProvider {
         Ok(items)
     }
 
-    async fn list_boards(&self) -> Result<Vec<BoardInfo>> {
+    async fn list_boards(&self) -> Result<Vec<BoardInfo>, ProviderError> {
         Ok(vec![])
     }
 
-    async fn move_to_done(&self, source_id: &str) -> Result<()> {
-        // Find the issue's team and its completed workflow state
-        let query = r#"query($id: String!) {
-          issue(id: $id) {
-            team {
-              states(filter: { type: { eq: "completed" } }) {
-                nodes { id name }
-              }
-            }
-          }
-        }"#;
-
-        let body = serde_json::json!({
-            "query": query,
-            "variables": { "id": source_id }
-        });
-
-        let resp: serde_json::Value = self
-            .client
-            .post("https://api.linear.app/graphql")
-            .header("Authorization", &self.api_key)
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
+    async fn move_to_done(&self, source_id: &str) -> Result<(), ProviderError> {
+        Ok(self.transition(source_id, "completed")
             .await
-            .context("Linear API request failed")?
-            .json()
-            .await?;
-
-        let state_id = resp
-            .pointer("/data/issue/team/states/nodes/0/id")
-            .and_then(|v| v.as_str())
-            .context("No completed state found for issue's team")?
-            .to_string();
-
-        // Update the issue state
-        let mutation = r#"mutation($id: String!, $stateId: String!) {
-          issueUpdate(id: $id, input: { stateId: $stateId }) {
-            success
-          }
-        }"#;
-
-        let body = serde_json::json!({
-            "query": mutation,
-            "variables": { "id": source_id, "stateId": state_id }
-        });
+            .context("Failed to update Linear issue state")?)
+    }
 
-        self.client
-            .post("https://api.linear.app/graphql")
-            .header("Authorization", &self.api_key)
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
+    async fn move_many_to_done(&self, source_ids: &[String]) -> Result<(), ProviderError> {
+        Ok(self.transition_many(source_ids, "completed")
             .await
-            .context("Failed to update Linear issue state")?;
-
-        Ok(())
+            .context("Failed to update Linear issue states")?)
     }
 
-    async fn create_item(&self, title: &str, description: Option<&str>) -> Result<Option<WorkItem>> {
+    async fn create_item(
+        &self,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<Option<WorkItem>, ProviderError> {
         // First get the viewer's first team
         let team_query = r#"{ viewer { teams(first: 1) { nodes { id name } } } }"#;
         let body = serde_json::json!({ "query": team_query });
 
         let resp: serde_json::Value = self
             .client
-            .post("https://api.linear.app/graphql")
+            .post(&self.api_url)
             .header("Authorization", &self.api_key)
             .header("Content-Type", "application/json")
             .json(&body)
             .send()
             .await
             .context("Linear API request failed")?
+            .check_status()
+            .await?
             .json()
             .await?;
 
@@ -244,112 +591,114 @@ impl Provider for LinearProvider {
             .unwrap_or("Unknown")
             .to_string();
 
-        // Create the issue
-        let mutation = r#"mutation($title: String!, $teamId: String!, $description: String) {
-          issueCreate(input: { title: $title, teamId: $teamId, description: $description }) {
-            success
-            issue { id identifier title description url state { name } }
-          }
-        }"#;
-
-        let mut variables = serde_json::json!({
-            "title": title,
-            "teamId": team_id,
-        });
-        if let Some(desc) = description {
-            variables["description"] = serde_json::Value::String(desc.to_string());
-        }
-
-        let body = serde_json::json!({
-            "query": mutation,
-            "variables": variables,
-        });
-
-        let resp: serde_json::Value = self
-            .client
-            .post("https://api.linear.app/graphql")
-            .header("Authorization", &self.api_key)
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .context("Failed to create Linear issue")?
-            .json()
-            .await?;
-
-        let issue = resp.pointer("/data/issueCreate/issue")
-            .context("No issue in create response")?;
-
-        let item = WorkItem {
-            id: issue.get("identifier").and_then(|v| v.as_str()).unwrap_or("?").to_string(),
-            source_id: issue.get("id").and_then(|v| v.as_str()).map(String::from),
-            title: title.to_string(),
-            description: description.map(String::from),
-            status: issue.pointer("/state/name").and_then(|v| v.as_str()).map(String::from),
-            priority: None,
-            labels: Vec::new(),
-            source: "Linear".into(),
-            team: Some(team_name),
-            url: issue.get("url").and_then(|v| v.as_str()).map(String::from),
+        let team = Team {
+            id: team_id,
+            name: team_name,
         };
+        Ok(Some(self.create_issue(title, description, team, None).await?))
+    }
 
+    async fn create_subtask(
+        &self,
+        parent_source_id: &str,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<Option<WorkItem>, ProviderError> {
+        let query = r#"query($id: String!) { issue(id: $id) { team { id name } } }"#;
+        let resp = self
+            .graphql(query, serde_json::json!({ "id": parent_source_id }))
+            .await?;
+        let team: Team = serde_json::from_value(
+            resp.pointer("/data/issue/team")
+                .cloned()
+                .context("Linear parent issue not found")?,
+        )
+        .context("Failed to parse Linear team")?;
+        let item = self
+            .create_issue(title, description, team, Some(parent_source_id))
+            .await?;
         Ok(Some(item))
     }
 
-    async fn move_to_in_progress(&self, source_id: &str) -> Result<()> {
-        let query = r#"query($id: String!) {
+    async fn fetch_comments(&self, source_id: &str) -> Result<Vec<Comment>, ProviderError> {
+        let query = r#"query($id: String!, $last: Int!) {
           issue(id: $id) {
-            team {
-              states(filter: { type: { eq: "started" } }) {
-                nodes { id name }
-              }
+            comments(last: $last) {
+              nodes { body createdAt user { name } }
             }
           }
         }"#;
 
         let body = serde_json::json!({
             "query": query,
-            "variables": { "id": source_id }
+            "variables": { "id": source_id, "last": MAX_COMMENTS }
         });
 
         let resp: serde_json::Value = self
             .client
-            .post("https://api.linear.app/graphql")
+            .post(&self.api_url)
             .header("Authorization", &self.api_key)
             .header("Content-Type", "application/json")
             .json(&body)
             .send()
             .await
-            .context("Linear API request failed")?
+            .context("Failed to fetch Linear comments")?
+            .check_status()
+            .await?
             .json()
             .await?;
 
-        let state_id = resp
-            .pointer("/data/issue/team/states/nodes/0/id")
-            .and_then(|v| v.as_str())
-            .context("No 'started' state found for issue's team")?
-            .to_string();
+        let nodes = resp["data"]["issue"]["comments"]["nodes"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        let mut comments: Vec<Comment> = nodes
+            .iter()
+            .map(|c| Comment {
+                author: c["user"]["name"].as_str().unwrap_or("unknown").to_string(),
+                created_at: c["createdAt"].as_str().map(String::from),
+                body: c["body"].as_str().unwrap_or_default().to_string(),
+            })
+            .collect();
+        comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(comments)
+    }
 
-        let mutation = r#"mutation($id: String!, $stateId: String!) {
-          issueUpdate(id: $id, input: { stateId: $stateId }) {
+    async fn add_comment(&self, source_id: &str, body: &str) -> Result<(), ProviderError> {
+        let mutation = r#"mutation($issueId: String!, $body: String!) {
+          commentCreate(input: { issueId: $issueId, body: $body }) {
             success
           }
         }"#;
 
         let body = serde_json::json!({
             "query": mutation,
-            "variables": { "id": source_id, "stateId": state_id }
+            "variables": { "issueId": source_id, "body": body }
         });
 
         self.client
-            .post("https://api.linear.app/graphql")
+            .post(&self.api_url)
             .header("Authorization", &self.api_key)
             .header("Content-Type", "application/json")
             .json(&body)
             .send()
             .await
-            .context("Failed to update Linear issue to In Progress")?;
+            .context("Failed to create Linear comment")?
+            .check_status()
+            .await?;
 
         Ok(())
     }
+
+    async fn move_to_in_progress(&self, source_id: &str) -> Result<(), ProviderError> {
+        Ok(self.transition(source_id, "started")
+            .await
+            .context("Failed to update Linear issue to In Progress")?)
+    }
+
+    async fn reopen(&self, source_id: &str) -> Result<(), ProviderError> {
+        Ok(self.transition(source_id, "unstarted")
+            .await
+            .context("Failed to move Linear issue back to Todo")?)
+    }
 }
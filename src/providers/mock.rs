@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::{BoardInfo, Provider, ProviderError, MAX_COMMENTS};
+use crate::model::work_item::{Comment, WorkItem};
+
+/// Boards the fake items are spread over, as `(id, name)`.
+const BOARDS: &[(&str, &str)] = &[
+    ("mock-web", "Demo Web"),
+    ("mock-api", "Demo API"),
+    ("mock-mobile", "Demo Mobile"),
+];
+
+const TASKS: &[&str] = &[
+    "Fix login redirect loop",
+    "Add dark mode toggle",
+    "Paginate the search results",
+    "Cache avatar thumbnails",
+    "Retry failed webhook deliveries",
+    "Show unsaved changes warning",
+    "Rate-limit password resets",
+    "Migrate settings to the new form",
+    "Export reports as CSV",
+    "Speed up the dashboard query",
+    "Handle expired sessions gracefully",
+    "Add keyboard shortcuts help",
+];
+
+const STATUSES: &[&str] = &["Backlog", "Todo", "Todo", "In Progress"];
+
+const PRIORITIES: &[Option<&str>] = &[
+    Some("Urgent"),
+    Some("High"),
+    Some("Medium"),
+    Some("Low"),
+    None,
+];
+
+/// An in-memory tracker with generated items, enabled by `[mock]`, so the
+/// TUI and the agent flow can be tried without credentials and tests run
+/// without a network. Items are the same on every start; moves, comments and
+/// new items last until the process exits.
+pub struct MockProvider {
+    items: Mutex<Vec<WorkItem>>,
+    comments: Mutex<HashMap<String, Vec<Comment>>>,
+    board_id: Option<String>,
+}
+
+impl MockProvider {
+    pub fn new(count: usize) -> Self {
+        let today = chrono::Local::now().date_naive();
+        Self {
+            items: Mutex::new((1..=count).map(|n| fake_item(n, today)).collect()),
+            comments: Mutex::new(HashMap::new()),
+            board_id: None,
+        }
+    }
+
+    fn set_status(&self, source_id: &str, status: &str) -> Result<(), ProviderError> {
+        let mut items = self.items.lock().unwrap();
+        let Some(item) = items
+            .iter_mut()
+            .find(|i| i.source_id.as_deref() == Some(source_id))
+        else {
+            return Err(ProviderError::NotFound {
+                message: format!("No mock item {source_id}"),
+            });
+        };
+        item.status = Some(status.to_string());
+        Ok(())
+    }
+}
+
+fn fake_item(n: usize, today: chrono::NaiveDate) -> WorkItem {
+    let (_, board) = BOARDS[(n - 1) % BOARDS.len()];
+    let task = TASKS[(n - 1) % TASKS.len()];
+    // Past the end of the list, number the repeats so titles stay distinct
+    let title = match (n - 1) / TASKS.len() {
+        0 => task.to_string(),
+        round => format!("{task} ({})", round + 1),
+    };
+    WorkItem {
+        id: format!("MOCK-{n}"),
+        source_id: Some(format!("mock-{n}")),
+        description: Some(format!(
+            "{title} on {board}.\n\n- Reproduce it locally\n- Add a test\n- Keep the change small"
+        )),
+        title,
+        status: Some(STATUSES[n % STATUSES.len()].to_string()),
+        priority: PRIORITIES[n % PRIORITIES.len()].map(String::from),
+        labels: vec![board.rsplit(' ').next().unwrap_or(board).to_lowercase()],
+        source: "Mock".to_string(),
+        team: Some(board.to_string()),
+        url: None,
+        links: Vec::new(),
+        comments: Vec::new(),
+        blocked_by: if n.is_multiple_of(7) {
+            vec![format!("MOCK-{}", n - 1)]
+        } else {
+            Vec::new()
+        },
+        due: n
+            .is_multiple_of(4)
+            .then(|| (today + chrono::Duration::days(n as i64 / 2)).to_string()),
+    }
+}
+
+#[async_trait]
+impl Provider for MockProvider {
+    fn name(&self) -> &str {
+        "Mock"
+    }
+
+    async fn fetch_items(&self) -> Result<Vec<WorkItem>, ProviderError> {
+        let board = self
+            .board_id
+            .as_deref()
+            .and_then(|id| BOARDS.iter().find(|(b, _)| *b == id))
+            .map(|(_, name)| *name);
+        let items = self.items.lock().unwrap();
+        Ok(items
+            .iter()
+            .filter(|i| i.status.as_deref() != Some("Done"))
+            .filter(|i| board.is_none() || i.team.as_deref() == board)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_boards(&self) -> Result<Vec<BoardInfo>, ProviderError> {
+        Ok(BOARDS
+            .iter()
+            .map(|(id, name)| BoardInfo {
+                id: id.to_string(),
+                name: name.to_string(),
+                source: "Mock".to_string(),
+            })
+            .collect())
+    }
+
+    fn set_board_filter(&mut self, board_id: String) {
+        self.board_id = Some(board_id);
+    }
+
+    async fn move_to_done(&self, source_id: &str) -> Result<(), ProviderError> {
+        self.set_status(source_id, "Done")
+    }
+
+    async fn move_to_in_progress(&self, source_id: &str) -> Result<(), ProviderError> {
+        self.set_status(source_id, "In Progress")
+    }
+
+    async fn reopen(&self, source_id: &str) -> Result<(), ProviderError> {
+        self.set_status(source_id, "Backlog")
+    }
+
+    async fn add_comment(&self, source_id: &str, body: &str) -> Result<(), ProviderError> {
+        self.comments
+            .lock()
+            .unwrap()
+            .entry(source_id.to_string())
+            .or_default()
+            .push(Comment {
+                author: "you".to_string(),
+                created_at: Some(chrono::Utc::now().to_rfc3339()),
+                body: body.to_string(),
+            });
+        Ok(())
+    }
+
+    async fn create_item(
+        &self,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<Option<WorkItem>, ProviderError> {
+        let mut items = self.items.lock().unwrap();
+        let n = items.len() + 1;
+        let board = self
+            .board_id
+            .as_deref()
+            .and_then(|id| BOARDS.iter().find(|(b, _)| *b == id))
+            .map_or(BOARDS[0].1, |(_, name)| *name);
+        let item = WorkItem {
+            id: format!("MOCK-{n}"),
+            source_id: Some(format!("mock-{n}")),
+            title: title.to_string(),
+            description: description.map(String::from),
+            status: Some("Todo".to_string()),
+            priority: None,
+            labels: Vec::new(),
+            source: "Mock".to_string(),
+            team: Some(board.to_string()),
+            url: None,
+            links: Vec::new(),
+            comments: Vec::new(),
+            blocked_by: Vec::new(),
+            due: None,
+        };
+        items.push(item.clone());
+        Ok(Some(item))
+    }
+
+    async fn update_item(
+        &self,
+        source_id: &str,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<bool, ProviderError> {
+        let mut items = self.items.lock().unwrap();
+        let Some(item) = items
+            .iter_mut()
+            .find(|i| i.source_id.as_deref() == Some(source_id))
+        else {
+            return Ok(false);
+        };
+        item.title = title.to_string();
+        item.description = description.map(String::from);
+        Ok(true)
+    }
+
+    async fn fetch_comments(&self, source_id: &str) -> Result<Vec<Comment>, ProviderError> {
+        let comments = self.comments.lock().unwrap();
+        let all = comments.get(source_id).map_or(&[][..], Vec::as_slice);
+        Ok(all[all.len().saturating_sub(MAX_COMMENTS)..].to_vec())
+    }
+}
@@ -1,14 +1,22 @@
+pub mod cache;
+pub mod error;
 pub mod github;
+pub mod http;
 pub mod jira;
 pub mod linear;
+pub mod mock;
+pub mod sentry;
 pub mod trello;
 
 use anyhow::Result;
 use async_trait::async_trait;
 
+pub use error::{ErrorKind, ProviderError};
+
 use crate::config::AppConfig;
-use crate::model::work_item::WorkItem;
+use crate::model::work_item::{Comment, Link, WorkItem};
 
+#[derive(Debug, Clone)]
 pub struct BoardInfo {
     pub id: String,
     pub name: String,
@@ -18,24 +26,155 @@ pub struct BoardInfo {
 #[async_trait]
 pub trait Provider: Send + Sync {
     fn name(&self) -> &str;
-    async fn fetch_items(&self) -> Result<Vec<WorkItem>>;
-    async fn list_boards(&self) -> Result<Vec<BoardInfo>>;
+    async fn fetch_items(&self) -> Result<Vec<WorkItem>, ProviderError>;
+    async fn list_boards(&self) -> Result<Vec<BoardInfo>, ProviderError>;
     fn set_board_filter(&mut self, _board_id: String) {}
-    async fn move_to_done(&self, _source_id: &str) -> Result<()> {
+    async fn move_to_done(&self, _source_id: &str) -> Result<(), ProviderError> {
+        Ok(())
+    }
+    /// Move several items to done. Providers that can change many items in
+    /// one request override this; the rest move them one at a time.
+    async fn move_many_to_done(&self, source_ids: &[String]) -> Result<(), ProviderError> {
+        for source_id in source_ids {
+            self.move_to_done(source_id).await?;
+        }
+        Ok(())
+    }
+    async fn move_to_in_progress(&self, _source_id: &str) -> Result<(), ProviderError> {
         Ok(())
     }
-    async fn move_to_in_progress(&self, _source_id: &str) -> Result<()> {
+    /// Move an item back to the backlog, undoing done or in-progress.
+    async fn reopen(&self, _source_id: &str) -> Result<(), ProviderError> {
+        Ok(())
+    }
+    async fn add_comment(&self, _source_id: &str, _body: &str) -> Result<(), ProviderError> {
         Ok(())
     }
     /// Create a new work item in the provider. Returns None if provider doesn't support creation.
-    async fn create_item(&self, _title: &str, _description: Option<&str>) -> Result<Option<WorkItem>> {
+    async fn create_item(
+        &self,
+        _title: &str,
+        _description: Option<&str>,
+    ) -> Result<Option<WorkItem>, ProviderError> {
         Ok(None)
     }
+    /// Create an item as a child of `parent_source_id`, in the parent's team
+    /// or project. Providers without native sub-items create a plain item.
+    async fn create_subtask(
+        &self,
+        _parent_source_id: &str,
+        title: &str,
+        description: Option<&str>,
+    ) -> Result<Option<WorkItem>, ProviderError> {
+        self.create_item(title, description).await
+    }
+    /// Replace an item's title and description, the description written as
+    /// Markdown-style text. Returns false if the provider can't edit items.
+    async fn update_item(
+        &self,
+        _source_id: &str,
+        _title: &str,
+        _description: Option<&str>,
+    ) -> Result<bool, ProviderError> {
+        Ok(false)
+    }
+    /// Attachments and linked resources of an item, with the text of those
+    /// small enough to put in a dispatch prompt.
+    async fn fetch_links(&self, _source_id: &str) -> Result<Vec<Link>, ProviderError> {
+        Ok(Vec::new())
+    }
+    /// The item's most recent comments, at most `MAX_COMMENTS`, oldest first.
+    async fn fetch_comments(&self, _source_id: &str) -> Result<Vec<Comment>, ProviderError> {
+        Ok(Vec::new())
+    }
+}
+
+/// Comments fetched per item for a dispatch prompt.
+pub const MAX_COMMENTS: usize = 20;
+
+/// Most pages of assigned items a fetch follows, so a runaway backlog can't
+/// stall a refresh.
+pub const MAX_PAGES: usize = 10;
+
+/// Largest attachment whose text is downloaded for the agent.
+pub const MAX_LINK_BYTES: u64 = 64 * 1024;
+
+/// Whether an attachment is text small enough to download.
+pub fn is_text_attachment(mime_type: Option<&str>, bytes: Option<u64>) -> bool {
+    let text = mime_type.is_some_and(|m| {
+        m.starts_with("text/") || matches!(m, "application/json" | "application/xml")
+    });
+    text && bytes.is_some_and(|b| b <= MAX_LINK_BYTES)
+}
+
+/// Phrases that mark the issues after them as blockers, e.g.
+/// "Blocked by #12, #14" or "depends on #3".
+const BLOCKER_KEYWORDS: &[&str] = &["blocked by", "depends on"];
+
+/// Issue numbers (as `#N`) an issue body declares as its blockers.
+pub fn blockers_in_text(text: &str) -> Vec<String> {
+    // ASCII lowercasing keeps byte offsets valid in the original text
+    let lower = text.to_ascii_lowercase();
+    let mut ids = Vec::new();
+    for keyword in BLOCKER_KEYWORDS {
+        for (start, _) in lower.match_indices(keyword) {
+            let mut rest = lower[start + keyword.len()..].trim_start_matches(':');
+            loop {
+                rest = rest.trim_start_matches([' ', ',']);
+                rest = rest.strip_prefix("and ").unwrap_or(rest);
+                let Some(after) = rest.strip_prefix('#') else {
+                    break;
+                };
+                let digits = after
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(after.len());
+                if digits == 0 {
+                    break;
+                }
+                let id = format!("#{}", &after[..digits]);
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+                rest = &after[digits..];
+            }
+        }
+    }
+    ids
+}
+
+/// Sources whose items are production incidents rather than planned work.
+pub const INCIDENT_SOURCES: &[&str] = &["Sentry"];
+
+pub fn is_incident(item: &WorkItem) -> bool {
+    INCIDENT_SOURCES.contains(&item.source.as_str())
+}
+
+/// Pull requests waiting on the user's review, listed alongside GitHub issues.
+pub fn is_review_request(item: &WorkItem) -> bool {
+    item.source == "GitHub" && item.url.as_deref().is_some_and(|u| u.contains("/pull/"))
+}
+
+/// Map an incident severity (Sentry level, PagerDuty urgency, P-number) to a WorkItem priority.
+pub fn severity_priority(severity: &str) -> Option<String> {
+    match severity.to_lowercase().as_str() {
+        "fatal" | "critical" | "p1" => Some("Urgent".into()),
+        "error" | "high" | "p2" => Some("High".into()),
+        "warning" | "p3" => Some("Medium".into()),
+        "info" | "debug" | "low" | "p4" | "p5" => Some("Low".into()),
+        _ => None,
+    }
 }
 
 #[cfg(test)]
 pub mod tests;
 
+/// Providers behind the shared item cache. The TUI and `serve` pass `fresh`
+/// so their fetches are always live but still warm the cache.
+pub fn create_cached_providers(config: &AppConfig, fresh: bool) -> Vec<Box<dyn Provider>> {
+    let cache = config.cache.clone().unwrap_or_default();
+    cache::cached(create_providers(config), &cache, fresh)
+}
+
 pub fn create_providers(config: &AppConfig) -> Vec<Box<dyn Provider>> {
     let mut providers: Vec<Box<dyn Provider>> = Vec::new();
 
@@ -56,7 +195,19 @@ pub fn create_providers(config: &AppConfig) -> Vec<Box<dyn Provider>> {
         )));
     }
     if let Some(cfg) = &config.github {
-        providers.push(Box::new(github::GitHubProvider::new(cfg.owner.clone())));
+        providers.push(Box::new(github::GitHubProvider::new(
+            cfg.owner.clone(),
+            cfg.token.clone(),
+        )));
+    }
+    if let Some(cfg) = &config.sentry {
+        providers.push(Box::new(sentry::SentryProvider::new(
+            cfg.org.clone(),
+            cfg.auth_token.clone(),
+        )));
+    }
+    if let Some(cfg) = &config.mock {
+        providers.push(Box::new(mock::MockProvider::new(cfg.items)));
     }
 
     providers
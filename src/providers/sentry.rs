@@ -0,0 +1,141 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use super::http::CheckStatus;
+use super::{severity_priority, BoardInfo, Provider, ProviderError};
+use crate::model::work_item::WorkItem;
+
+pub struct SentryProvider {
+    org: String,
+    auth_token: String,
+    client: reqwest::Client,
+}
+
+impl SentryProvider {
+    pub fn new(org: String, auth_token: String) -> Self {
+        Self {
+            org,
+            auth_token,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+const BASE_URL: &str = "https://sentry.io/api/0";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SentryIssue {
+    id: String,
+    short_id: String,
+    title: String,
+    culprit: Option<String>,
+    level: Option<String>,
+    status: Option<String>,
+    permalink: Option<String>,
+    project: Option<SentryProject>,
+}
+
+#[derive(Deserialize)]
+struct SentryProject {
+    name: String,
+}
+
+#[async_trait]
+impl Provider for SentryProvider {
+    fn name(&self) -> &str {
+        "Sentry"
+    }
+
+    async fn fetch_items(&self) -> Result<Vec<WorkItem>, ProviderError> {
+        let resp = self
+            .client
+            .get(format!("{BASE_URL}/organizations/{}/issues/", self.org))
+            .bearer_auth(&self.auth_token)
+            .query(&[("query", "is:unresolved assigned:me"), ("limit", "50")])
+            .send()
+            .await
+            .context("Sentry API request failed")?
+            .check_status()
+            .await?;
+
+        let issues: Vec<SentryIssue> = resp
+            .json()
+            .await
+            .context("Failed to parse Sentry response")?;
+
+        let items = issues
+            .into_iter()
+            .map(|issue| {
+                let priority = issue.level.as_deref().and_then(severity_priority);
+                let labels = issue.level.into_iter().collect();
+
+                WorkItem {
+                    id: issue.short_id,
+                    source_id: Some(issue.id),
+                    title: issue.title,
+                    description: issue.culprit.filter(|c| !c.trim().is_empty()),
+                    status: issue.status,
+                    priority,
+                    labels,
+                    source: "Sentry".into(),
+                    team: issue.project.map(|p| p.name),
+                    url: issue.permalink,
+                    links: Vec::new(),
+                    comments: Vec::new(),
+                    blocked_by: Vec::new(),
+                    due: None,
+                }
+            })
+            .collect();
+
+        Ok(items)
+    }
+
+    async fn list_boards(&self) -> Result<Vec<BoardInfo>, ProviderError> {
+        Ok(vec![])
+    }
+
+    async fn add_comment(&self, source_id: &str, body: &str) -> Result<(), ProviderError> {
+        self.client
+            .post(format!("{BASE_URL}/issues/{source_id}/comments/"))
+            .bearer_auth(&self.auth_token)
+            .json(&serde_json::json!({ "text": body }))
+            .send()
+            .await
+            .context("Failed to add Sentry comment")?
+            .check_status()
+            .await?;
+
+        Ok(())
+    }
+
+    async fn move_to_done(&self, source_id: &str) -> Result<(), ProviderError> {
+        self.client
+            .put(format!("{BASE_URL}/issues/{source_id}/"))
+            .bearer_auth(&self.auth_token)
+            .json(&serde_json::json!({ "status": "resolved" }))
+            .send()
+            .await
+            .context("Failed to resolve Sentry issue")?
+            .check_status()
+            .await?;
+
+        Ok(())
+    }
+
+    async fn reopen(&self, source_id: &str) -> Result<(), ProviderError> {
+        self.client
+            .put(format!("{BASE_URL}/issues/{source_id}/"))
+            .bearer_auth(&self.auth_token)
+            .json(&serde_json::json!({ "status": "unresolved" }))
+            .send()
+            .await
+            .context("Failed to unresolve Sentry issue")?
+            .check_status()
+            .await?;
+
+        Ok(())
+    }
+}
@@ -3,15 +3,18 @@ use std::sync::{Arc, Mutex};
 use anyhow::Result;
 use async_trait::async_trait;
 
-use super::{BoardInfo, Provider};
+use super::{BoardInfo, Provider, ProviderError};
 use crate::model::work_item::WorkItem;
 
-/// A mock provider that tracks move_to_done and move_to_in_progress calls for testing.
+type CreatedItems = Arc<Mutex<Vec<(String, Option<String>)>>>;
+
+/// A mock provider that tracks move_to_done, move_to_in_progress and reopen calls for testing.
 struct MockProvider {
     provider_name: String,
     done_ids: Arc<Mutex<Vec<String>>>,
     in_progress_ids: Arc<Mutex<Vec<String>>>,
-    created_items: Arc<Mutex<Vec<(String, Option<String>)>>>,
+    reopened_ids: Arc<Mutex<Vec<String>>>,
+    created_items: CreatedItems,
     should_fail: bool,
     supports_create: bool,
 }
@@ -22,6 +25,7 @@ impl MockProvider {
             provider_name: name.to_string(),
             done_ids: Arc::new(Mutex::new(Vec::new())),
             in_progress_ids: Arc::new(Mutex::new(Vec::new())),
+            reopened_ids: Arc::new(Mutex::new(Vec::new())),
             created_items: Arc::new(Mutex::new(Vec::new())),
             should_fail: false,
             supports_create: false,
@@ -45,25 +49,25 @@ impl Provider for MockProvider {
         &self.provider_name
     }
 
-    async fn fetch_items(&self) -> Result<Vec<WorkItem>> {
+    async fn fetch_items(&self) -> Result<Vec<WorkItem>, ProviderError> {
         Ok(vec![])
     }
 
-    async fn list_boards(&self) -> Result<Vec<BoardInfo>> {
+    async fn list_boards(&self) -> Result<Vec<BoardInfo>, ProviderError> {
         Ok(vec![])
     }
 
-    async fn move_to_done(&self, source_id: &str) -> Result<()> {
+    async fn move_to_done(&self, source_id: &str) -> Result<(), ProviderError> {
         if self.should_fail {
-            anyhow::bail!("Mock failure");
+            return Err(anyhow::anyhow!("Mock failure").into());
         }
         self.done_ids.lock().unwrap().push(source_id.to_string());
         Ok(())
     }
 
-    async fn move_to_in_progress(&self, source_id: &str) -> Result<()> {
+    async fn move_to_in_progress(&self, source_id: &str) -> Result<(), ProviderError> {
         if self.should_fail {
-            anyhow::bail!("Mock failure");
+            return Err(anyhow::anyhow!("Mock failure").into());
         }
         self.in_progress_ids
             .lock()
@@ -72,49 +76,51 @@ impl Provider for MockProvider {
         Ok(())
     }
 
+    async fn reopen(&self, source_id: &str) -> Result<(), ProviderError> {
+        if self.should_fail {
+            return Err(anyhow::anyhow!("Mock failure").into());
+        }
+        self.reopened_ids.lock().unwrap().push(source_id.to_string());
+        Ok(())
+    }
+
     async fn create_item(
         &self,
         title: &str,
         description: Option<&str>,
-    ) -> Result<Option<WorkItem>> {
+    ) -> Result<Option<WorkItem>, ProviderError> {
         if !self.supports_create {
             return Ok(None);
         }
         if self.should_fail {
-            anyhow::bail!("Mock create failure");
+            return Err(anyhow::anyhow!("Mock create failure").into());
         }
         self.created_items
             .lock()
             .unwrap()
             .push((title.to_string(), description.map(String::from)));
 
+        let item = WorkItem::test("MOCK-1")
+            .with_source_id("mock-source-id")
+            .with_title(title)
+            .with_status("Todo")
+            .with_source(&self.provider_name)
+            .with_url("https://mock.test/item/1");
         Ok(Some(WorkItem {
-            id: format!("MOCK-1"),
-            source_id: Some("mock-source-id".to_string()),
-            title: title.to_string(),
             description: description.map(String::from),
-            status: Some("Todo".to_string()),
-            priority: None,
-            labels: Vec::new(),
-            source: self.provider_name.clone(),
-            team: None,
-            url: Some("https://mock.test/item/1".to_string()),
+            ..item
         }))
     }
 }
 
 fn make_work_item(id: &str, source: &str, source_id: Option<&str>) -> WorkItem {
+    let item = WorkItem::test(id)
+        .with_title(&format!("Test item {id}"))
+        .with_status("Todo")
+        .with_source(source);
     WorkItem {
-        id: id.to_string(),
-        source_id: source_id.map(|s| s.to_string()),
-        title: format!("Test item {id}"),
-        description: None,
-        status: Some("Todo".into()),
-        priority: None,
-        labels: vec![],
-        source: source.to_string(),
-        team: None,
-        url: None,
+        source_id: source_id.map(String::from),
+        ..item
     }
 }
 
@@ -151,10 +157,10 @@ async fn move_to_done_default_is_noop() {
         fn name(&self) -> &str {
             "Noop"
         }
-        async fn fetch_items(&self) -> Result<Vec<WorkItem>> {
+        async fn fetch_items(&self) -> Result<Vec<WorkItem>, ProviderError> {
             Ok(vec![])
         }
-        async fn list_boards(&self) -> Result<Vec<BoardInfo>> {
+        async fn list_boards(&self) -> Result<Vec<BoardInfo>, ProviderError> {
             Ok(vec![])
         }
         // move_to_done intentionally not implemented — uses default
@@ -223,10 +229,10 @@ async fn move_to_in_progress_default_is_noop() {
         fn name(&self) -> &str {
             "Noop"
         }
-        async fn fetch_items(&self) -> Result<Vec<WorkItem>> {
+        async fn fetch_items(&self) -> Result<Vec<WorkItem>, ProviderError> {
             Ok(vec![])
         }
-        async fn list_boards(&self) -> Result<Vec<BoardInfo>> {
+        async fn list_boards(&self) -> Result<Vec<BoardInfo>, ProviderError> {
             Ok(vec![])
         }
     }
@@ -243,6 +249,37 @@ async fn move_to_in_progress_propagates_errors() {
     assert!(result.unwrap_err().to_string().contains("Mock failure"));
 }
 
+#[tokio::test]
+async fn reopen_calls_correct_provider() {
+    let provider = MockProvider::new("Linear");
+    let reopened_ids = provider.reopened_ids.clone();
+
+    provider.move_to_done("issue-1").await.unwrap();
+    provider.reopen("issue-1").await.unwrap();
+
+    assert_eq!(reopened_ids.lock().unwrap().as_slice(), &["issue-1"]);
+}
+
+#[tokio::test]
+async fn reopen_default_is_noop() {
+    struct NoopProvider;
+
+    #[async_trait]
+    impl Provider for NoopProvider {
+        fn name(&self) -> &str {
+            "Noop"
+        }
+        async fn fetch_items(&self) -> Result<Vec<WorkItem>, ProviderError> {
+            Ok(vec![])
+        }
+        async fn list_boards(&self) -> Result<Vec<BoardInfo>, ProviderError> {
+            Ok(vec![])
+        }
+    }
+
+    assert!(NoopProvider.reopen("anything").await.is_ok());
+}
+
 #[test]
 fn work_item_serialization_without_source_id() {
     let item = make_work_item("abc", "Trello", None);
@@ -267,10 +304,10 @@ async fn create_item_default_returns_none() {
         fn name(&self) -> &str {
             "Noop"
         }
-        async fn fetch_items(&self) -> Result<Vec<WorkItem>> {
+        async fn fetch_items(&self) -> Result<Vec<WorkItem>, ProviderError> {
             Ok(vec![])
         }
-        async fn list_boards(&self) -> Result<Vec<BoardInfo>> {
+        async fn list_boards(&self) -> Result<Vec<BoardInfo>, ProviderError> {
             Ok(vec![])
         }
     }
@@ -385,18 +422,15 @@ async fn create_item_skips_failing_provider() {
 #[test]
 fn create_item_result_has_correct_fields() {
     // Verify WorkItem structure for a created item
-    let item = WorkItem {
-        id: "TRE-123".to_string(),
-        source_id: Some("full-trello-id".to_string()),
-        title: "My new task".to_string(),
-        description: Some("Detailed description".to_string()),
-        status: Some("Todo".to_string()),
-        priority: None,
-        labels: vec!["feature".to_string()],
-        source: "Trello".to_string(),
-        team: Some("My Board".to_string()),
-        url: Some("https://trello.com/c/abc123".to_string()),
-    };
+    let item = WorkItem::test("TRE-123")
+        .with_source_id("full-trello-id")
+        .with_title("My new task")
+        .with_description("Detailed description")
+        .with_status("Todo")
+        .with_labels(&["feature"])
+        .with_source("Trello")
+        .with_team("My Board")
+        .with_url("https://trello.com/c/abc123");
 
     let json = serde_json::to_string(&item).unwrap();
     let deserialized: WorkItem = serde_json::from_str(&json).unwrap();
@@ -413,3 +447,865 @@ fn create_item_result_has_correct_fields() {
     assert_eq!(deserialized.source, "Trello");
     assert_eq!(deserialized.url, Some("https://trello.com/c/abc123".to_string()));
 }
+
+#[test]
+fn blockers_are_read_from_issue_text() {
+    let body = "Needs the new API.\n\nBlocked by: #12, #14 and #15\nDepends on #3. See #99.";
+    assert_eq!(
+        super::blockers_in_text(body),
+        ["#12", "#14", "#15", "#3"]
+    );
+    assert!(super::blockers_in_text("Blocked by the design review").is_empty());
+}
+
+// --- incident severity tests ---
+
+#[test]
+fn severity_maps_to_priority() {
+    assert_eq!(super::severity_priority("fatal"), Some("Urgent".to_string()));
+    assert_eq!(super::severity_priority("P1"), Some("Urgent".to_string()));
+    assert_eq!(super::severity_priority("error"), Some("High".to_string()));
+    assert_eq!(super::severity_priority("warning"), Some("Medium".to_string()));
+    assert_eq!(super::severity_priority("debug"), Some("Low".to_string()));
+    assert_eq!(super::severity_priority("sample"), None);
+}
+
+#[test]
+fn only_incident_sources_are_incidents() {
+    assert!(super::is_incident(&make_work_item("PROJ-1A", "Sentry", Some("123"))));
+    assert!(!super::is_incident(&make_work_item("ENG-1", "Linear", Some("uuid"))));
+}
+
+#[test]
+fn only_github_pull_requests_are_review_requests() {
+    let mut pr = make_work_item("#7", "GitHub", Some("https://github.com/acme/app/pull/7"));
+    pr.url = pr.source_id.clone();
+    assert!(super::is_review_request(&pr));
+
+    let mut issue = make_work_item("#8", "GitHub", Some("https://github.com/acme/app/issues/8"));
+    issue.url = issue.source_id.clone();
+    assert!(!super::is_review_request(&issue));
+}
+
+#[tokio::test]
+async fn add_comment_default_is_noop() {
+    let provider = MockProvider::new("Trello");
+    assert!(provider.add_comment("card-123", "transcript").await.is_ok());
+}
+
+// --- Linear workflow state tests ---
+
+#[test]
+fn linear_transitions_use_the_leftmost_state_of_each_type() {
+    use super::linear::{first_state_of_each_type, WorkflowState};
+    let state = |id: &str, kind: &str, position| WorkflowState {
+        id: id.into(),
+        kind: kind.into(),
+        position,
+    };
+    let states = first_state_of_each_type(vec![
+        state("review", "started", 2.0),
+        state("doing", "started", 1.0),
+        state("todo", "unstarted", 0.0),
+        state("done", "completed", 3.0),
+    ]);
+    assert_eq!(states["started"], "doing");
+    assert_eq!(states["unstarted"], "todo");
+    assert_eq!(states["completed"], "done");
+    assert!(!states.contains_key("canceled"));
+}
+
+// --- Trello list cache tests ---
+
+#[test]
+fn trello_list_cache_expires_and_invalidates() {
+    use super::trello::ListCache;
+    use std::time::{Duration, Instant};
+
+    let now = Instant::now();
+    let lists = vec![("l1".to_string(), "Doing".to_string())];
+    let mut cache = ListCache::default();
+    cache.insert("board", lists.clone(), now);
+
+    assert_eq!(cache.get("board", now + Duration::from_secs(60)), Some(lists));
+    assert_eq!(cache.get("board", now + Duration::from_secs(3600)), None);
+    assert_eq!(cache.get("other", now), None);
+
+    cache.invalidate("board");
+    assert_eq!(cache.get("board", now), None);
+}
+
+// --- Built-in mock provider tests ---
+
+#[tokio::test]
+async fn mock_config_serves_fake_items_and_boards() {
+    let config: crate::config::AppConfig = toml::from_str("[mock]\nitems = 8").unwrap();
+    let mut providers = super::create_providers(&config);
+    assert_eq!(providers.len(), 1);
+    let provider = &mut providers[0];
+
+    let items = provider.fetch_items().await.unwrap();
+    assert_eq!(items.len(), 8);
+    assert_eq!(items[0].id, "MOCK-1");
+    assert!(items.iter().all(|i| i.source == "Mock"));
+    assert_eq!(items[6].blocked_by, ["MOCK-6"]);
+
+    provider.move_to_done("mock-1").await.unwrap();
+    assert_eq!(provider.fetch_items().await.unwrap().len(), 7);
+    assert!(provider.move_to_done("mock-99").await.is_err());
+
+    let boards = provider.list_boards().await.unwrap();
+    assert_eq!(boards.len(), 3);
+    provider.set_board_filter(boards[1].id.clone());
+    let board_items = provider.fetch_items().await.unwrap();
+    assert!(board_items
+        .iter()
+        .all(|i| i.team.as_deref() == Some(boards[1].name.as_str())));
+    assert_eq!(board_items.len(), 3);
+
+    provider.add_comment("mock-2", "Looks good").await.unwrap();
+    assert_eq!(provider.fetch_comments("mock-2").await.unwrap()[0].body, "Looks good");
+}
+
+// --- HTTP contract tests, against a local stand-in for each API ---
+
+mod http {
+    use serde_json::json;
+    use wiremock::matchers::{body_string_contains, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use std::time::Duration;
+
+    use crate::providers::{ErrorKind, ProviderError};
+    use crate::providers::jira::JiraProvider;
+    use crate::providers::linear::LinearProvider;
+    use crate::providers::trello::TrelloProvider;
+    use crate::providers::Provider;
+
+    fn ok(body: serde_json::Value) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(body)
+    }
+
+    fn trello(server: &MockServer) -> TrelloProvider {
+        TrelloProvider::new("key".into(), "token".into()).with_base_url(server.uri())
+    }
+
+    fn linear(server: &MockServer) -> LinearProvider {
+        LinearProvider::new("lin_api".into()).with_api_url(format!("{}/graphql", server.uri()))
+    }
+
+    fn jira(server: &MockServer) -> JiraProvider {
+        JiraProvider::new("acme".into(), "me@acme.test".into(), "secret".into())
+            .with_base_url(server.uri())
+    }
+
+    async fn mount_trello_lists(server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/boards/b1/lists"))
+            .respond_with(ok(json!([
+                { "id": "l-todo", "name": "To Do" },
+                { "id": "l-doing", "name": "Doing" },
+                { "id": "l-done", "name": "Done" },
+            ])))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn trello_fetch_maps_cards_and_skips_done_lists() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/members/me"))
+            .and(query_param("key", "key"))
+            .and(query_param("token", "token"))
+            .respond_with(ok(json!({ "id": "m1" })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/members/m1/boards"))
+            .and(query_param("filter", "open"))
+            .respond_with(ok(json!([{ "id": "b1", "name": "Roadmap" }])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/members/m1/cards"))
+            .respond_with(ok(json!([
+                {
+                    "id": "c0ffee1234", "name": "Fix login", "desc": "SSO breaks",
+                    "shortUrl": "https://trello.test/c/1", "idList": "l-doing",
+                    "idBoard": "b1", "labels": [{ "name": "bug" }, { "name": "" }],
+                    "due": "2026-11-02T12:00:00.000Z"
+                },
+                { "id": "deadbeef99", "name": "Shipped", "idList": "l-done", "idBoard": "b1" },
+            ])))
+            .mount(&server)
+            .await;
+        mount_trello_lists(&server).await;
+
+        let items = trello(&server).fetch_items().await.unwrap();
+        assert_eq!(items.len(), 1);
+        let item = &items[0];
+        assert_eq!(item.id, "c0ffee12");
+        assert_eq!(item.source_id.as_deref(), Some("c0ffee1234"));
+        assert_eq!(item.status.as_deref(), Some("Doing"));
+        assert_eq!(item.team.as_deref(), Some("Roadmap"));
+        assert_eq!(item.labels, ["bug"]);
+        assert_eq!(item.due.as_deref(), Some("2026-11-02"));
+    }
+
+    #[tokio::test]
+    async fn trello_board_filter_fetches_only_that_board() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/members/me"))
+            .respond_with(ok(json!({ "id": "m1" })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/boards/b1"))
+            .respond_with(ok(json!({ "id": "b1", "name": "Roadmap" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/boards/b1/cards"))
+            .respond_with(ok(json!([
+                { "id": "c1", "name": "Fix login", "idList": "l-todo", "idBoard": "b1" },
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/members/m1/cards"))
+            .respond_with(ok(json!([])))
+            .expect(0)
+            .mount(&server)
+            .await;
+        mount_trello_lists(&server).await;
+
+        let mut provider = trello(&server);
+        provider.set_board_filter("b1".into());
+        let items = provider.fetch_items().await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].status.as_deref(), Some("To Do"));
+    }
+
+    #[tokio::test]
+    async fn unreachable_trackers_fail_as_network_errors() {
+        // A port nothing listens on any more
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let provider = TrelloProvider::new("key".into(), "token".into())
+            .with_base_url(format!("http://127.0.0.1:{port}"));
+
+        let err = provider.fetch_items().await.unwrap_err();
+        assert!(matches!(err, ProviderError::Network(_)), "{err:?}");
+        assert_eq!(err.retry_in(), Some(Duration::from_secs(30)));
+    }
+
+    #[tokio::test]
+    async fn trello_moves_cards_between_lists() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards/c1"))
+            .and(query_param("fields", "idBoard"))
+            .respond_with(ok(json!({ "id": "c1", "name": "Fix login", "idBoard": "b1" })))
+            // The board is remembered after the first lookup
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/boards/b1/lists"))
+            .respond_with(ok(json!([
+                { "id": "l-todo", "name": "To Do" },
+                { "id": "l-doing", "name": "Doing" },
+                { "id": "l-done", "name": "Done" },
+            ])))
+            // And so are its lists
+            .expect(1)
+            .mount(&server)
+            .await;
+        for list in ["l-done", "l-doing"] {
+            Mock::given(method("PUT"))
+                .and(path("/cards/c1"))
+                .and(query_param("idList", list))
+                .respond_with(ok(json!({})))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let provider = trello(&server);
+        provider.move_to_in_progress("c1").await.unwrap();
+        provider.move_to_done("c1").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn trello_move_without_a_matching_list_refetches_once_then_fails() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/cards/c1"))
+            .respond_with(ok(json!({ "id": "c1", "name": "Fix login", "idBoard": "b1" })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/boards/b1/lists"))
+            .respond_with(ok(json!([{ "id": "l-todo", "name": "To Do" }])))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let err = trello(&server).move_to_done("c1").await.unwrap_err();
+        assert!(format!("{err:#}").contains("No 'Done' list"), "{err:#}");
+    }
+
+    #[tokio::test]
+    async fn trello_creates_cards_on_the_todo_list() {
+        let server = MockServer::start().await;
+        mount_trello_lists(&server).await;
+        Mock::given(method("POST"))
+            .and(path("/cards"))
+            .and(query_param("idList", "l-todo"))
+            .and(query_param("name", "Write docs"))
+            .and(query_param("desc", "For the API"))
+            .respond_with(ok(json!({
+                "id": "abc12345678", "name": "Write docs", "desc": "For the API",
+                "shortUrl": "https://trello.test/c/2"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut provider = trello(&server);
+        // Without a board there is nowhere to put the card
+        assert!(provider.create_item("Write docs", None).await.unwrap().is_none());
+
+        provider.set_board_filter("b1".into());
+        let item = provider
+            .create_item("Write docs", Some("For the API"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(item.id, "abc12345");
+        assert_eq!(item.status.as_deref(), Some("To Do"));
+        assert_eq!(item.url.as_deref(), Some("https://trello.test/c/2"));
+    }
+
+    #[tokio::test]
+    async fn trello_error_and_rate_limit_responses_fail_the_fetch() {
+        for (response, kind, message) in [
+            (
+                ResponseTemplate::new(401).set_body_string("invalid token"),
+                ErrorKind::Auth,
+                "invalid token",
+            ),
+            (
+                ResponseTemplate::new(429)
+                    .insert_header("Retry-After", "10")
+                    .set_body_json(json!({ "error": "API_TOKEN_LIMIT_EXCEEDED" })),
+                ErrorKind::RateLimited,
+                "API_TOKEN_LIMIT_EXCEEDED",
+            ),
+        ] {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/members/me"))
+                .respond_with(response)
+                .mount(&server)
+                .await;
+            let err = trello(&server).fetch_items().await.unwrap_err();
+            assert_eq!(err.kind(), kind);
+            assert!(err.to_string().ends_with(message), "{err}");
+            assert!(trello(&server).list_boards().await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn linear_fetch_follows_the_cursor_and_keeps_open_blockers() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_string_contains("assignedIssues"))
+            .and(body_string_contains("first: 50"))
+            .and(body_string_contains(r#""after":null"#))
+            .respond_with(ok(json!({ "data": { "viewer": { "assignedIssues": {
+                "nodes": [{
+                    "id": "uuid-0", "identifier": "ENG-0", "title": "Triage inbox",
+                    "state": { "name": "Todo" },
+                    "team": { "id": "t1", "name": "Engineering" },
+                }],
+                "pageInfo": { "hasNextPage": true, "endCursor": "cursor-1" },
+            } } } })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_string_contains(r#""after":"cursor-1""#))
+            .respond_with(ok(json!({ "data": { "viewer": { "assignedIssues": { "nodes": [{
+                "id": "uuid-1", "identifier": "ENG-1", "title": "Fix login",
+                "description": "SSO breaks", "priority": 2,
+                "url": "https://linear.test/ENG-1", "dueDate": "2026-11-02",
+                "state": { "name": "Todo" },
+                "team": { "id": "t1", "name": "Engineering" },
+                "labels": { "nodes": [{ "name": "bug" }] },
+                "inverseRelations": { "nodes": [
+                    { "type": "blocks",
+                      "issue": { "identifier": "ENG-2", "state": { "type": "started" } } },
+                    { "type": "blocks",
+                      "issue": { "identifier": "ENG-3", "state": { "type": "completed" } } },
+                    { "type": "related",
+                      "issue": { "identifier": "ENG-4", "state": { "type": "started" } } },
+                ] },
+            }], "pageInfo": { "hasNextPage": false, "endCursor": "cursor-2" } } } } })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let items = linear(&server).fetch_items().await.unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].id, "ENG-0");
+        let item = &items[1];
+        assert_eq!(item.id, "ENG-1");
+        assert_eq!(item.source_id.as_deref(), Some("uuid-1"));
+        assert_eq!(item.priority.as_deref(), Some("High"));
+        assert_eq!(item.team.as_deref(), Some("Engineering"));
+        assert_eq!(item.blocked_by, ["ENG-2"]);
+        assert_eq!(item.due.as_deref(), Some("2026-11-02"));
+    }
+
+    #[tokio::test]
+    async fn linear_transitions_look_up_team_states_once() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_string_contains("states"))
+            .respond_with(ok(json!({ "data": { "issue": { "team": {
+                "id": "t1",
+                "states": { "nodes": [
+                    { "id": "s-todo", "type": "unstarted", "position": 0.0 },
+                    { "id": "s-doing", "type": "started", "position": 1.0 },
+                    { "id": "s-done", "type": "completed", "position": 2.0 },
+                ] },
+            } } } })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        for state in ["s-doing", "s-done"] {
+            Mock::given(method("POST"))
+                .and(path("/graphql"))
+                .and(body_string_contains("issueUpdate"))
+                .and(body_string_contains(state))
+                .respond_with(ok(json!({ "data": { "issueUpdate": { "success": true } } })))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let provider = linear(&server);
+        provider.move_to_in_progress("uuid-1").await.unwrap();
+        provider.move_to_done("uuid-1").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn linear_moves_many_issues_in_one_batch_mutation() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_string_contains("states"))
+            .respond_with(ok(json!({ "data": { "issues": { "nodes": [
+                { "id": "uuid-1", "team": { "id": "t1", "states": { "nodes": [
+                    { "id": "s-done", "type": "completed", "position": 2.0 },
+                ] } } },
+                { "id": "uuid-2", "team": { "id": "t1", "states": { "nodes": [
+                    { "id": "s-done", "type": "completed", "position": 2.0 },
+                ] } } },
+            ] } } })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_string_contains("issueBatchUpdate"))
+            .and(body_string_contains(r#""ids":["uuid-1","uuid-2"]"#))
+            .and(body_string_contains("s-done"))
+            .respond_with(ok(json!({ "data": { "issueBatchUpdate": { "success": true } } })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        linear(&server)
+            .move_many_to_done(&["uuid-1".into(), "uuid-2".into()])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn linear_graphql_errors_carry_their_message() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "errors": [{
+                    "message": "Rate limit exceeded",
+                    "extensions": { "code": "RATELIMITED" },
+                }],
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = linear(&server);
+        let err = provider.move_to_done("uuid-1").await.unwrap_err();
+        assert!(format!("{err:#}").contains("Rate limit exceeded"), "{err:#}");
+        // The item fetch has no data to fall back on
+        assert!(provider.fetch_items().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn linear_auth_errors_are_typed_by_their_code() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "errors": [{
+                    "message": "Authentication required, not authenticated",
+                    "extensions": { "code": "AUTHENTICATION_ERROR" },
+                }],
+            })))
+            .mount(&server)
+            .await;
+
+        let err = linear(&server).fetch_items().await.unwrap_err();
+        assert!(
+            matches!(&err, ProviderError::Auth { message }
+                if message == "Authentication required, not authenticated"),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn linear_creates_issues_in_the_first_team() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_string_contains("teams(first: 1)"))
+            .respond_with(ok(json!({ "data": { "viewer": { "teams": { "nodes": [
+                { "id": "t1", "name": "Engineering" },
+            ] } } } })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_string_contains("issueCreate"))
+            .and(body_string_contains(r#""teamId":"t1""#))
+            .respond_with(ok(json!({ "data": { "issueCreate": {
+                "success": true,
+                "issue": {
+                    "id": "uuid-9", "identifier": "ENG-9", "title": "Write docs",
+                    "url": "https://linear.test/ENG-9", "state": { "name": "Backlog" },
+                },
+            } } })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let item = linear(&server)
+            .create_item("Write docs", Some("For the API"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(item.id, "ENG-9");
+        assert_eq!(item.source_id.as_deref(), Some("uuid-9"));
+        assert_eq!(item.status.as_deref(), Some("Backlog"));
+        assert_eq!(item.team.as_deref(), Some("Engineering"));
+    }
+
+    #[tokio::test]
+    async fn linear_creates_sub_issues_in_the_parent_team() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_string_contains("team { id name }"))
+            .and(body_string_contains(r#""id":"uuid-7""#))
+            .respond_with(ok(json!({ "data": { "issue": {
+                "team": { "id": "t2", "name": "Billing" },
+            } } })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_string_contains("issueCreate"))
+            .and(body_string_contains(r#""teamId":"t2""#))
+            .and(body_string_contains(r#""parentId":"uuid-7""#))
+            .respond_with(ok(json!({ "data": { "issueCreate": {
+                "success": true,
+                "issue": { "id": "uuid-10", "identifier": "BIL-10", "title": "Add client" },
+            } } })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let item = linear(&server)
+            .create_subtask("uuid-7", "Add client", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(item.id, "BIL-10");
+        assert_eq!(item.team.as_deref(), Some("Billing"));
+    }
+
+    #[tokio::test]
+    async fn jira_fetch_pages_through_open_assigned_issues() {
+        let server = MockServer::start().await;
+        // Long descriptions come through whole, so `/edit` can't cut them
+        let description = format!("SSO breaks{}", "!".repeat(600));
+        Mock::given(method("GET"))
+            .and(path("/rest/api/3/search"))
+            .and(query_param("startAt", "0"))
+            .and(query_param("maxResults", "50"))
+            .and(query_param(
+                "jql",
+                "project=\"ENG\" AND assignee=currentUser() AND statusCategory!=Done ORDER BY priority ASC",
+            ))
+            .respond_with(ok(json!({ "total": 2, "issues": [{
+                "key": "ENG-6",
+                "fields": { "summary": "Triage inbox", "issuelinks": [] },
+            }] })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/rest/api/3/search"))
+            .and(query_param("startAt", "1"))
+            .respond_with(ok(json!({ "total": 2, "issues": [{
+                "key": "ENG-7",
+                "fields": {
+                    "summary": "Fix login",
+                    "description": {
+                        "type": "doc", "version": 1,
+                        "content": [{ "type": "paragraph", "content": [
+                            { "type": "text", "text": description },
+                        ] }],
+                    },
+                    "status": { "name": "To Do" },
+                    "priority": { "name": "High" },
+                    "labels": ["bug"],
+                    "project": { "name": "Engineering" },
+                    "duedate": "2026-11-02",
+                    "issuelinks": [],
+                },
+            }] })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut provider = jira(&server);
+        provider.set_board_filter("ENG".into());
+        let items = provider.fetch_items().await.unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].id, "ENG-6");
+        let item = &items[1];
+        assert_eq!(item.id, "ENG-7");
+        assert_eq!(item.description, Some(description));
+        assert_eq!(item.priority.as_deref(), Some("High"));
+        assert_eq!(item.team.as_deref(), Some("Engineering"));
+        assert_eq!(item.url, Some(format!("{}/browse/ENG-7", server.uri())));
+    }
+
+    #[tokio::test]
+    async fn jira_error_and_rate_limit_responses_fail_the_fetch() {
+        for (response, kind, retry_after) in [
+            (
+                ResponseTemplate::new(401).set_body_string("Unauthorized"),
+                ErrorKind::Auth,
+                None,
+            ),
+            (
+                ResponseTemplate::new(429)
+                    .insert_header("Retry-After", "30")
+                    .set_body_json(json!({ "errorMessages": ["Rate limit exceeded."] })),
+                ErrorKind::RateLimited,
+                Some(30),
+            ),
+        ] {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/rest/api/3/search"))
+                .respond_with(response.clone())
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/rest/api/3/project"))
+                .respond_with(response)
+                .mount(&server)
+                .await;
+            // Checked before the body is parsed as search results
+            let err = jira(&server).fetch_items().await.unwrap_err();
+            assert!(!format!("{err:#}").contains("parse"), "{err:#}");
+            assert_eq!(err.kind(), kind);
+            let waited = match err {
+                ProviderError::RateLimited { retry_after, .. } => retry_after,
+                _ => None,
+            };
+            assert_eq!(waited, retry_after.map(Duration::from_secs));
+            assert!(jira(&server).list_boards().await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn jira_moves_issues_through_their_done_transition() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rest/api/3/issue/ENG-7/transitions"))
+            .respond_with(ok(json!({ "transitions": [
+                { "id": "11", "to": { "statusCategory": { "key": "indeterminate" } } },
+                { "id": "31", "to": { "statusCategory": { "key": "done" } } },
+            ] })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/rest/api/3/issue/ENG-7/transitions"))
+            .and(body_string_contains(r#""id":"31""#))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        jira(&server).move_to_done("ENG-7").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn jira_refused_transitions_fail_the_move() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rest/api/3/issue/ENG-7/transitions"))
+            .respond_with(ok(json!({ "transitions": [
+                { "id": "31", "to": { "statusCategory": { "key": "done" } } },
+            ] })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/rest/api/3/issue/ENG-7/transitions"))
+            .respond_with(ResponseTemplate::new(403).set_body_json(json!({
+                "errorMessages": ["You do not have permission to transition this issue."],
+            })))
+            .mount(&server)
+            .await;
+
+        let err = jira(&server).move_to_done("ENG-7").await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Permission);
+        assert!(format!("{err:#}").contains("do not have permission"), "{err:#}");
+    }
+
+    #[tokio::test]
+    async fn jira_names_mentions_a_page_at_a_time_and_remembers_misses() {
+        let server = MockServer::start().await;
+        let ids: Vec<String> = (1..=11).map(|n| format!("acc-{n:02}")).collect();
+        let mentions: Vec<serde_json::Value> = ids
+            .iter()
+            .map(|id| json!({ "type": "mention", "attrs": { "id": id } }))
+            .collect();
+        Mock::given(method("GET"))
+            .and(path("/rest/api/3/issue/ENG-7/comment"))
+            .respond_with(ok(json!({ "comments": [{
+                "author": { "displayName": "Ana" },
+                "body": { "type": "doc", "content": [
+                    { "type": "paragraph", "content": mentions },
+                ] },
+            }] })))
+            .mount(&server)
+            .await;
+        let user = |id: &String| json!({ "accountId": id, "displayName": format!("User {id}") });
+        // acc-02 is gone from the site, so the first page comes back short
+        let first: Vec<serde_json::Value> =
+            ids[..10].iter().filter(|id| *id != "acc-02").map(user).collect();
+        for (id, users) in [("acc-01", first), ("acc-11", vec![user(&ids[10])])] {
+            Mock::given(method("GET"))
+                .and(path("/rest/api/3/user/bulk"))
+                .and(query_param("accountId", id))
+                .and(query_param("maxResults", "10"))
+                .respond_with(ok(json!({ "values": users })))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let provider = jira(&server);
+        for _ in 0..2 {
+            let comments = provider.fetch_comments("ENG-7").await.unwrap();
+            let body = &comments[0].body;
+            assert!(body.starts_with("@User acc-01@someone@User acc-03"), "{body}");
+            assert!(body.ends_with("@User acc-11"), "{body}");
+        }
+    }
+
+    #[tokio::test]
+    async fn jira_creates_tasks_in_the_picked_project() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/rest/api/3/issue"))
+            .and(body_string_contains(r#""project":{"key":"ENG"}"#))
+            .and(body_string_contains(r#""summary":"Write docs""#))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({ "key": "ENG-8" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut provider = jira(&server);
+        assert!(provider.create_item("Write docs", None).await.unwrap().is_none());
+        provider.set_board_filter("ENG".into());
+        let item = provider
+            .create_item("Write docs", Some("For the API"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(item.id, "ENG-8");
+        assert_eq!(item.url, Some(format!("{}/browse/ENG-8", server.uri())));
+    }
+
+    #[tokio::test]
+    async fn jira_creates_sub_tasks_in_the_parent_project() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rest/api/3/issue/OPS-7"))
+            .respond_with(ok(json!({ "key": "OPS-7", "fields": {
+                "project": { "key": "OPS", "name": "Operations" },
+            } })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/rest/api/3/project/OPS"))
+            .respond_with(ok(json!({ "key": "OPS", "issueTypes": [
+                { "name": "Task", "subtask": false },
+                { "name": "Sub-task", "subtask": true },
+            ] })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/rest/api/3/issue"))
+            .and(body_string_contains(r#""project":{"key":"OPS"}"#))
+            .and(body_string_contains(r#""parent":{"key":"OPS-7"}"#))
+            .and(body_string_contains(r#""issuetype":{"name":"Sub-task"}"#))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({ "key": "OPS-8" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // The mapped board doesn't matter; the parent's project does
+        let mut provider = jira(&server);
+        provider.set_board_filter("ENG".into());
+        let item = provider
+            .create_subtask("OPS-7", "Write docs", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(item.id, "OPS-8");
+    }
+}
@@ -2,30 +2,203 @@ use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-use super::{BoardInfo, Provider};
-use crate::model::work_item::WorkItem;
+use super::http::CheckStatus;
+use super::{is_text_attachment, BoardInfo, Provider, ProviderError, MAX_COMMENTS};
+use crate::model::work_item::{Comment, Link, WorkItem};
 
 pub struct TrelloProvider {
+    base_url: String,
     api_key: String,
     token: String,
     client: reqwest::Client,
     board_id: Option<String>,
+    lists: Mutex<ListCache>,
+    /// Board ID of each card seen, by card ID
+    card_boards: Mutex<HashMap<String, String>>,
+}
+
+const BASE_URL: &str = "https://api.trello.com/1";
+
+/// How long a board's lists are reused before being fetched again.
+const LIST_TTL: Duration = Duration::from_secs(600);
+
+/// Each board's lists, kept across refreshes and card moves. Lists are
+/// rarely renamed, so entries live for `LIST_TTL` or until a lookup on them
+/// misses.
+#[derive(Default)]
+pub(crate) struct ListCache {
+    boards: HashMap<String, (Instant, Vec<(String, String)>)>,
+}
+
+impl ListCache {
+    /// `(list ID, name)` pairs for a board, unless stale.
+    pub(crate) fn get(&self, board_id: &str, now: Instant) -> Option<Vec<(String, String)>> {
+        let (fetched, lists) = self.boards.get(board_id)?;
+        (now.duration_since(*fetched) < LIST_TTL).then(|| lists.clone())
+    }
+
+    pub(crate) fn insert(&mut self, board_id: &str, lists: Vec<(String, String)>, now: Instant) {
+        self.boards.insert(board_id.to_string(), (now, lists));
+    }
+
+    pub(crate) fn invalidate(&mut self, board_id: &str) {
+        self.boards.remove(board_id);
+    }
 }
 
 impl TrelloProvider {
     pub fn new(api_key: String, token: String) -> Self {
         Self {
+            base_url: BASE_URL.to_string(),
             api_key,
             token,
             client: reqwest::Client::new(),
             board_id: None,
+            lists: Mutex::new(ListCache::default()),
+            card_boards: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Point at a stand-in server, for tests.
+    #[cfg(test)]
+    pub(crate) fn with_base_url(mut self, base_url: String) -> Self {
+        self.base_url = base_url;
+        self
+    }
+
+    /// A board's `(list ID, name)` pairs, from the cache when fresh.
+    async fn board_lists(&self, board_id: &str) -> Result<Vec<(String, String)>> {
+        let cached = self.lists.lock().ok().and_then(|c| c.get(board_id, Instant::now()));
+        if let Some(lists) = cached {
+            return Ok(lists);
+        }
+        let lists: Vec<TrelloList> = self
+            .client
+            .get(format!("{}/boards/{board_id}/lists", self.base_url))
+            .query(&self.auth_params())
+            .query(&[("fields", "id,name")])
+            .send()
+            .await
+            .context("Failed to fetch Trello board lists")?
+            .check_status()
+            .await?
+            .json()
+            .await?;
+        let lists: Vec<(String, String)> = lists.into_iter().map(|l| (l.id, l.name)).collect();
+        if let Ok(mut cache) = self.lists.lock() {
+            cache.insert(board_id, lists.clone(), Instant::now());
+        }
+        Ok(lists)
+    }
+
+    fn forget_lists(&self, board_id: &str) {
+        if let Ok(mut cache) = self.lists.lock() {
+            cache.invalidate(board_id);
+        }
+    }
+
+    /// The card's board, from the last fetch when it was in it.
+    async fn card_board(&self, source_id: &str) -> Result<String> {
+        let known = self.card_boards.lock().ok().and_then(|c| c.get(source_id).cloned());
+        if let Some(board_id) = known {
+            return Ok(board_id);
+        }
+        let card: Card = self
+            .client
+            .get(format!("{}/cards/{source_id}", self.base_url))
+            .query(&self.auth_params())
+            .query(&[("fields", "idBoard")])
+            .send()
+            .await
+            .context("Failed to fetch Trello card")?
+            .check_status()
+            .await?
+            .json()
+            .await?;
+        let board_id = card.id_board.context("Card has no board ID")?;
+        if let Ok(mut boards) = self.card_boards.lock() {
+            boards.insert(source_id.to_string(), board_id.clone());
+        }
+        Ok(board_id)
+    }
+
+    /// Move a card to the first list on its board whose lowercased name
+    /// matches. A miss or a failed move refetches the lists once, in case
+    /// they changed since they were cached.
+    async fn move_card(
+        &self,
+        source_id: &str,
+        wanted: &str,
+        matches: impl Fn(&str) -> bool,
+    ) -> Result<()> {
+        let board_id = self.card_board(source_id).await?;
+        let find = |lists: &[(String, String)]| {
+            lists
+                .iter()
+                .find(|(_, name)| matches(&name.to_lowercase()))
+                .map(|(id, _)| id.clone())
+        };
+
+        let mut retried = false;
+        loop {
+            let list_id = match find(&self.board_lists(&board_id).await?) {
+                Some(id) => id,
+                None if !retried => {
+                    self.forget_lists(&board_id);
+                    retried = true;
+                    continue;
+                }
+                None => anyhow::bail!("No {wanted} list found on board"),
+            };
+
+            let moved: Result<_> = async {
+                Ok(self
+                    .client
+                    .put(format!("{}/cards/{source_id}", self.base_url))
+                    .query(&self.auth_params())
+                    .query(&[("idList", &list_id)])
+                    .send()
+                    .await?
+                    .check_status()
+                    .await?)
+            }
+            .await;
+            match moved {
+                Ok(_) => return Ok(()),
+                Err(_) if !retried => {
+                    self.forget_lists(&board_id);
+                    retried = true;
+                }
+                Err(e) => return Err(e),
+            }
         }
     }
 
     fn auth_params(&self) -> [(&str, &str); 2] {
         [("key", &self.api_key), ("token", &self.token)]
     }
+
+    /// Uploaded attachments reject the query-string credentials the API takes.
+    async fn download(&self, url: &str) -> Result<String> {
+        let auth = format!(
+            "OAuth oauth_consumer_key=\"{}\", oauth_token=\"{}\"",
+            self.api_key, self.token
+        );
+        let text = self
+            .client
+            .get(url)
+            .header("Authorization", auth)
+            .send()
+            .await?
+            .check_status()
+            .await?
+            .text()
+            .await?;
+        Ok(text)
+    }
 }
 
 #[derive(Deserialize)]
@@ -60,6 +233,37 @@ struct Card {
     id_list: Option<String>,
     id_board: Option<String>,
     labels: Option<Vec<TrelloLabel>>,
+    due: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Attachment {
+    name: String,
+    url: String,
+    mime_type: Option<String>,
+    bytes: Option<u64>,
+    #[serde(default)]
+    is_upload: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CommentAction {
+    date: Option<String>,
+    data: CommentData,
+    member_creator: Option<CommentAuthor>,
+}
+
+#[derive(Deserialize)]
+struct CommentData {
+    text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CommentAuthor {
+    full_name: String,
 }
 
 const EXCLUDED_LISTS: &[&str] = &["done", "in review"];
@@ -70,8 +274,8 @@ impl Provider for TrelloProvider {
         "Trello"
     }
 
-    async fn fetch_items(&self) -> Result<Vec<WorkItem>> {
-        let base = "https://api.trello.com/1";
+    async fn fetch_items(&self) -> Result<Vec<WorkItem>, ProviderError> {
+        let base = &self.base_url;
 
         // Get member ID
         let member: Member = self
@@ -81,6 +285,8 @@ impl Provider for TrelloProvider {
             .send()
             .await
             .context("Trello members/me failed")?
+            .check_status()
+            .await?
             .json()
             .await?;
 
@@ -99,13 +305,13 @@ impl Provider for TrelloProvider {
                 .query(&self.auth_params())
                 .query(&[(
                     "fields",
-                    "id,name,desc,shortUrl,idList,labels,idBoard",
+                    "id,name,desc,shortUrl,idList,labels,idBoard,due",
                 )])
                 .send();
 
             let (board_resp, cards_resp) = tokio::try_join!(board_fut, cards_fut)?;
-            let board: Board = board_resp.json().await?;
-            let cards: Vec<Card> = cards_resp.json().await?;
+            let board: Board = board_resp.check_status().await?.json().await?;
+            let cards: Vec<Card> = cards_resp.check_status().await?.json().await?;
             (vec![board], cards)
         } else {
             // Unfiltered: fetch all boards and cards
@@ -122,13 +328,13 @@ impl Provider for TrelloProvider {
                 .query(&self.auth_params())
                 .query(&[(
                     "fields",
-                    "id,name,desc,shortUrl,idList,labels,idBoard",
+                    "id,name,desc,shortUrl,idList,labels,idBoard,due",
                 )])
                 .send();
 
             let (boards_resp, cards_resp) = tokio::try_join!(boards_fut, cards_fut)?;
-            let boards: Vec<Board> = boards_resp.json().await?;
-            let cards: Vec<Card> = cards_resp.json().await?;
+            let boards: Vec<Board> = boards_resp.check_status().await?.json().await?;
+            let cards: Vec<Card> = cards_resp.check_status().await?.json().await?;
             (boards, cards)
         };
 
@@ -145,17 +351,14 @@ impl Provider for TrelloProvider {
 
         let mut list_map: HashMap<String, String> = HashMap::new();
         for board_id in &board_ids {
-            let lists: Vec<TrelloList> = self
-                .client
-                .get(format!("{base}/boards/{board_id}/lists"))
-                .query(&self.auth_params())
-                .query(&[("fields", "id,name")])
-                .send()
-                .await?
-                .json()
-                .await?;
-            for list in lists {
-                list_map.insert(list.id, list.name);
+            list_map.extend(self.board_lists(board_id).await?);
+        }
+
+        if let Ok(mut card_boards) = self.card_boards.lock() {
+            for card in &cards {
+                if let Some(board_id) = &card.id_board {
+                    card_boards.insert(card.id.clone(), board_id.clone());
+                }
             }
         }
 
@@ -204,6 +407,11 @@ impl Provider for TrelloProvider {
                     source: "Trello".into(),
                     team,
                     url: card.short_url,
+                    links: Vec::new(),
+                    comments: Vec::new(),
+                    blocked_by: Vec::new(),
+                    // A timestamp; the date is all the calendar needs
+                    due: card.due.and_then(|d| d.get(..10).map(String::from)),
                 }
             })
             .collect();
@@ -211,8 +419,8 @@ impl Provider for TrelloProvider {
         Ok(items)
     }
 
-    async fn list_boards(&self) -> Result<Vec<BoardInfo>> {
-        let base = "https://api.trello.com/1";
+    async fn list_boards(&self) -> Result<Vec<BoardInfo>, ProviderError> {
+        let base = &self.base_url;
 
         let member: Member = self
             .client
@@ -221,6 +429,8 @@ impl Provider for TrelloProvider {
             .send()
             .await
             .context("Trello members/me failed")?
+            .check_status()
+            .await?
             .json()
             .await?;
 
@@ -231,6 +441,8 @@ impl Provider for TrelloProvider {
             .query(&[("fields", "id,name"), ("filter", "open")])
             .send()
             .await?
+            .check_status()
+            .await?
             .json()
             .await?;
 
@@ -248,90 +460,37 @@ impl Provider for TrelloProvider {
         self.board_id = Some(board_id);
     }
 
-    async fn move_to_done(&self, source_id: &str) -> Result<()> {
-        let base = "https://api.trello.com/1";
-
-        // Get the card's board ID
-        let card: Card = self
-            .client
-            .get(format!("{base}/cards/{source_id}"))
-            .query(&self.auth_params())
-            .query(&[("fields", "idBoard")])
-            .send()
+    async fn move_to_done(&self, source_id: &str) -> Result<(), ProviderError> {
+        Ok(self.move_card(source_id, "'Done'", |name| name == "done")
             .await
-            .context("Failed to fetch Trello card")?
-            .json()
-            .await?;
-
-        let board_id = card
-            .id_board
-            .context("Card has no board ID")?;
-
-        // Get the board's lists and find one named "Done"
-        let lists: Vec<TrelloList> = self
-            .client
-            .get(format!("{base}/boards/{board_id}/lists"))
-            .query(&self.auth_params())
-            .query(&[("fields", "id,name")])
-            .send()
-            .await?
-            .json()
-            .await?;
-
-        let done_list = lists
-            .iter()
-            .find(|l| l.name.eq_ignore_ascii_case("done"))
-            .context("No 'Done' list found on board")?;
-
-        // Move card to Done list
-        self.client
-            .put(format!("{base}/cards/{source_id}"))
-            .query(&self.auth_params())
-            .query(&[("idList", &done_list.id)])
-            .send()
-            .await
-            .context("Failed to move Trello card to Done")?;
-
-        Ok(())
+            .context("Failed to move Trello card to Done")?)
     }
 
     async fn create_item(
         &self,
         title: &str,
         description: Option<&str>,
-    ) -> Result<Option<WorkItem>> {
+    ) -> Result<Option<WorkItem>, ProviderError> {
         let board_id = match &self.board_id {
             Some(id) => id.clone(),
             None => return Ok(None), // No board selected — can't create
         };
 
-        let base = "https://api.trello.com/1";
+        let base = &self.base_url;
 
         // Get the board's lists and find a suitable one for new cards
-        let lists: Vec<TrelloList> = self
-            .client
-            .get(format!("{base}/boards/{board_id}/lists"))
-            .query(&self.auth_params())
-            .query(&[("fields", "id,name")])
-            .send()
-            .await
-            .context("Failed to fetch Trello board lists")?
-            .json()
-            .await?;
+        let lists = self.board_lists(&board_id).await?;
 
         // Prefer "Todo"/"To Do"/"Backlog", fall back to the first list
-        let target_list = lists
+        let (list_id, list_name) = lists
             .iter()
-            .find(|l| {
-                let lower = l.name.to_lowercase();
+            .find(|(_, name)| {
+                let lower = name.to_lowercase();
                 lower == "todo" || lower == "to do" || lower == "backlog"
             })
             .or_else(|| lists.first())
             .context("Board has no lists — cannot create card")?;
 
-        let list_id = &target_list.id;
-        let list_name = &target_list.name;
-
         // Create the card
         let mut params: Vec<(&str, &str)> = vec![
             ("key", &self.api_key),
@@ -352,10 +511,16 @@ impl Provider for TrelloProvider {
             .send()
             .await
             .context("Failed to create Trello card")?
+            .check_status()
+            .await?
             .json()
             .await
             .context("Failed to parse Trello create card response")?;
 
+        if let Ok(mut card_boards) = self.card_boards.lock() {
+            card_boards.insert(card.id.clone(), board_id.clone());
+        }
+
         let item = WorkItem {
             id: card.id[..8.min(card.id.len())].to_string(),
             source_id: Some(card.id),
@@ -376,55 +541,111 @@ impl Provider for TrelloProvider {
             source: "Trello".into(),
             team: None,
             url: card.short_url,
+            links: Vec::new(),
+            comments: Vec::new(),
+            blocked_by: Vec::new(),
+            due: None,
         };
 
         Ok(Some(item))
     }
 
-    async fn move_to_in_progress(&self, source_id: &str) -> Result<()> {
-        let base = "https://api.trello.com/1";
+    async fn fetch_links(&self, source_id: &str) -> Result<Vec<Link>, ProviderError> {
+        let base = &self.base_url;
 
-        let card: Card = self
+        let attachments: Vec<Attachment> = self
             .client
-            .get(format!("{base}/cards/{source_id}"))
+            .get(format!("{base}/cards/{source_id}/attachments"))
             .query(&self.auth_params())
-            .query(&[("fields", "idBoard")])
+            .query(&[("fields", "name,url,mimeType,bytes,isUpload")])
             .send()
             .await
-            .context("Failed to fetch Trello card")?
+            .context("Failed to fetch Trello attachments")?
+            .check_status()
+            .await?
             .json()
             .await?;
 
-        let board_id = card
-            .id_board
-            .context("Card has no board ID")?;
+        let mut links = Vec::new();
+        for attachment in attachments {
+            let content = if attachment.is_upload
+                && is_text_attachment(attachment.mime_type.as_deref(), attachment.bytes)
+            {
+                self.download(&attachment.url).await.ok()
+            } else {
+                None
+            };
+            links.push(Link {
+                title: attachment.name,
+                url: attachment.url,
+                content,
+            });
+        }
+        Ok(links)
+    }
 
-        let lists: Vec<TrelloList> = self
+    async fn fetch_comments(&self, source_id: &str) -> Result<Vec<Comment>, ProviderError> {
+        let base = &self.base_url;
+
+        let actions: Vec<CommentAction> = self
             .client
-            .get(format!("{base}/boards/{board_id}/lists"))
+            .get(format!("{base}/cards/{source_id}/actions"))
             .query(&self.auth_params())
-            .query(&[("fields", "id,name")])
+            .query(&[
+                ("filter", "commentCard"),
+                ("limit", &MAX_COMMENTS.to_string()),
+            ])
             .send()
+            .await
+            .context("Failed to fetch Trello comments")?
+            .check_status()
             .await?
             .json()
             .await?;
 
-        let in_progress_list = lists
-            .iter()
-            .find(|l| {
-                let lower = l.name.to_lowercase();
-                lower == "in progress" || lower == "doing" || lower == "in-progress"
+        // Trello lists newest first
+        Ok(actions
+            .into_iter()
+            .rev()
+            .map(|action| Comment {
+                author: action
+                    .member_creator
+                    .map_or_else(|| "unknown".into(), |m| m.full_name),
+                created_at: action.date,
+                body: action.data.text,
             })
-            .context("No 'In Progress' or 'Doing' list found on board")?;
+            .collect())
+    }
+
+    async fn add_comment(&self, source_id: &str, body: &str) -> Result<(), ProviderError> {
+        let base = &self.base_url;
 
         self.client
-            .put(format!("{base}/cards/{source_id}"))
+            .post(format!("{base}/cards/{source_id}/actions/comments"))
             .query(&self.auth_params())
-            .query(&[("idList", &in_progress_list.id)])
+            .query(&[("text", body)])
             .send()
             .await
-            .context("Failed to move Trello card to In Progress")?;
+            .context("Failed to add Trello comment")?
+            .check_status()
+            .await?;
 
         Ok(())
     }
+
+    async fn move_to_in_progress(&self, source_id: &str) -> Result<(), ProviderError> {
+        Ok(self.move_card(source_id, "'In Progress' or 'Doing'", |name| {
+            name == "in progress" || name == "doing" || name == "in-progress"
+        })
+        .await
+        .context("Failed to move Trello card to In Progress")?)
+    }
+
+    async fn reopen(&self, source_id: &str) -> Result<(), ProviderError> {
+        Ok(self.move_card(source_id, "'To Do' or 'Backlog'", |name| {
+            name == "to do" || name == "todo" || name == "backlog"
+        })
+        .await
+        .context("Failed to move Trello card to To Do")?)
+    }
 }
@@ -0,0 +1,53 @@
+use anyhow::{Context, Result};
+
+/// Config values of the form `keyring:<name>` are looked up in the OS
+/// keyring instead of being read literally.
+const KEYRING_PREFIX: &str = "keyring:";
+
+/// Service name secrets are stored under in the keyring.
+const SERVICE: &str = "work-cli";
+
+fn keyring_name(value: &str) -> Option<&str> {
+    value
+        .strip_prefix(KEYRING_PREFIX)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+}
+
+/// Resolve a config value, fetching it from the keyring when it names one.
+pub fn resolve(value: &str) -> Result<String> {
+    let Some(name) = keyring_name(value) else {
+        return Ok(value.to_string());
+    };
+    keyring::Entry::new(SERVICE, name)
+        .and_then(|entry| entry.get_password())
+        .with_context(|| {
+            format!("No secret \"{name}\" in the OS keyring; run `work config set-secret {name}`")
+        })
+}
+
+/// Resolve a config field in place, naming the field on error.
+pub fn resolve_field(field: &str, value: &mut String) -> Result<()> {
+    *value = resolve(value).with_context(|| format!("Failed to resolve {field}"))?;
+    Ok(())
+}
+
+pub fn store(name: &str, secret: &str) -> Result<()> {
+    keyring::Entry::new(SERVICE, name)
+        .and_then(|entry| entry.set_password(secret))
+        .with_context(|| format!("Failed to store \"{name}\" in the OS keyring"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_prefixed_values_use_the_keyring() {
+        assert_eq!(keyring_name("keyring:linear"), Some("linear"));
+        assert_eq!(keyring_name("keyring: jira "), Some("jira"));
+        assert_eq!(keyring_name("keyring:"), None);
+        assert_eq!(keyring_name("lin_api_abc"), None);
+        assert_eq!(resolve("lin_api_abc").unwrap(), "lin_api_abc");
+    }
+}
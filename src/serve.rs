@@ -0,0 +1,680 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, Mutex, RwLock};
+
+use crate::agents::log::read_events;
+use crate::agents::store::AgentStore;
+use crate::config::{self, WebhookConfig};
+use crate::model::work_item::WorkItem;
+use crate::providers;
+use crate::webhook::{parse_webhook, verify_signature, webhook_board, ItemUpdate};
+
+const DEFAULT_ADDR: &str = "127.0.0.1:7420";
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+/// Webhook payloads (issue bodies, label lists) can be large
+const MAX_REQUEST_BYTES: usize = 1024 * 1024;
+/// How long a client gets to send its whole request before it's dropped
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct ServeState {
+    items: RwLock<Vec<WorkItem>>,
+    store: Mutex<AgentStore>,
+    updates: mpsc::UnboundedSender<ItemUpdate>,
+    /// Off with `--web`, so a dashboard exposed to other machines can't be
+    /// used to push items
+    webhooks: bool,
+    guard: WebhookGuard,
+    /// Required on dashboard requests when set
+    token: Option<String>,
+}
+
+/// What webhook deliveries are checked against before they touch items.
+#[derive(Debug, Clone, Default)]
+pub struct WebhookGuard {
+    /// Signing secrets by lowercase provider name
+    pub secrets: HashMap<String, String>,
+    /// Public base URL, for the callback URL Trello signs
+    pub public_url: Option<String>,
+    /// Project whose board mapping deliveries must match
+    pub project_dir: String,
+}
+
+impl WebhookGuard {
+    pub fn new(config: Option<&WebhookConfig>, project_dir: &str) -> Self {
+        let config = config.cloned().unwrap_or_default();
+        Self {
+            secrets: config
+                .secrets
+                .into_iter()
+                .map(|(provider, secret)| (provider.to_lowercase(), secret))
+                .collect(),
+            public_url: config.public_url,
+            project_dir: project_dir.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ServeArgs {
+    pub addr: String,
+    /// Serve only the dashboard, without webhook endpoints
+    pub web_only: bool,
+    /// Secret the dashboard asks for, from `--token`
+    pub token: Option<String>,
+}
+
+/// `work serve [--web] [--addr host:port] [--token secret]`: read-only
+/// dashboard of items, agents and activity, plus webhook endpoints that
+/// apply provider pushes between refreshes.
+pub async fn handle_serve(args: &[String]) -> Result<()> {
+    let ServeArgs {
+        addr,
+        web_only,
+        token,
+    } = parse_serve_args(args)?;
+    let token = token
+        .or_else(|| std::env::var("WORK_SERVE_TOKEN").ok())
+        .filter(|t| !t.is_empty());
+    // Items and agent activity shouldn't be open to the whole network
+    if token.is_none() && !is_loopback(&addr) {
+        bail!("{addr} is reachable from other machines; pass --token <secret> or set WORK_SERVE_TOKEN");
+    }
+
+    let config = config::load_config()?;
+    let mut providers = providers::create_cached_providers(&config, true);
+
+    let project_dir = std::env::current_dir()
+        .ok()
+        .and_then(|p| p.canonicalize().ok())
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    if let Some(mapping) = config::board_mapping(&project_dir) {
+        for provider in &mut providers {
+            if provider.name() == mapping.source {
+                provider.set_board_filter(mapping.board_id.clone());
+            }
+        }
+    }
+
+    let (updates, mut update_rx) = mpsc::unbounded_channel();
+    let state = Arc::new(ServeState {
+        items: RwLock::new(Vec::new()),
+        store: Mutex::new(AgentStore::new()?),
+        updates,
+        webhooks: !web_only,
+        guard: WebhookGuard::new(config.webhooks.as_ref(), &project_dir),
+        token,
+    });
+
+    let update_state = state.clone();
+    tokio::spawn(async move {
+        while let Some(update) = update_rx.recv().await {
+            update.apply(&mut *update_state.items.write().await);
+        }
+    });
+
+    // Refresh items in the background so requests never wait on providers.
+    // A provider whose fetch fails keeps showing its last good items.
+    let refresh_state = state.clone();
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(REFRESH_INTERVAL);
+        let mut last_good: HashMap<String, Vec<WorkItem>> = HashMap::new();
+        loop {
+            tick.tick().await;
+            let mut all_items = Vec::new();
+            for provider in &providers {
+                match provider.fetch_items().await {
+                    Ok(items) => {
+                        last_good.insert(provider.name().to_string(), items);
+                    }
+                    Err(e) => eprintln!("{}: {e}", provider.name()),
+                }
+                if let Some(items) = last_good.get(provider.name()) {
+                    all_items.extend(items.iter().cloned());
+                }
+            }
+            *refresh_state.items.write().await = all_items;
+        }
+    });
+
+    let listener = TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("Failed to bind {addr}"))?;
+    println!("Serving dashboard at http://{addr}");
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let state = state.clone();
+        tokio::spawn(async move {
+            let _ = handle_connection(stream, state).await;
+        });
+    }
+}
+
+/// Parse `work serve` arguments.
+pub fn parse_serve_args(args: &[String]) -> Result<ServeArgs> {
+    let mut addr = DEFAULT_ADDR.to_string();
+    let mut web_only = false;
+    let mut token = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--web" | "-w" => web_only = true,
+            "--addr" | "-a" => {
+                i += 1;
+                match args.get(i) {
+                    Some(value) => addr = value.clone(),
+                    None => bail!("Missing value for --addr flag"),
+                }
+            }
+            "--token" | "-t" => {
+                i += 1;
+                match args.get(i) {
+                    Some(value) => token = Some(value.clone()),
+                    None => bail!("Missing value for --token flag"),
+                }
+            }
+            "--port" | "-p" => {
+                i += 1;
+                let port: u16 = args
+                    .get(i)
+                    .context("Missing value for --port flag")?
+                    .parse()
+                    .context("Port must be a number")?;
+                addr = format!("127.0.0.1:{port}");
+            }
+            other => bail!("Unknown serve option: {other}"),
+        }
+        i += 1;
+    }
+    Ok(ServeArgs {
+        addr,
+        web_only,
+        token,
+    })
+}
+
+/// Whether `addr` only takes connections from this machine.
+fn is_loopback(addr: &str) -> bool {
+    match addr.parse::<std::net::SocketAddr>() {
+        Ok(addr) => addr.ip().is_loopback(),
+        Err(_) => addr
+            .rsplit_once(':')
+            .is_some_and(|(host, _)| host == "localhost"),
+    }
+}
+
+/// Whether a dashboard request carries `token`, as `?token=` (so the link
+/// opens on a phone) or an `Authorization: Bearer` header.
+fn authorized(request: &str, token: Option<&str>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    let bearer = header(request, "authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(String::from);
+    bearer
+        .or_else(|| query_param(request, "token"))
+        .is_some_and(|given| same_secret(&given, token))
+}
+
+/// A decoded query parameter of the request line.
+fn query_param(request: &str, name: &str) -> Option<String> {
+    let target = request.lines().next()?.split_whitespace().nth(1)?;
+    let (_, query) = target.split_once('?')?;
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == name).then(|| urlencoding::decode(value).ok().map(|v| v.into_owned()))?
+    })
+}
+
+/// Compare without stopping at the first difference, so response times
+/// don't leak how much of a guess was right.
+fn same_secret(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Accept provider webhooks on `addr` and forward item updates to `tx`.
+/// Used by the TUI, which has no dashboard to serve.
+pub async fn listen_webhooks(
+    addr: &str,
+    guard: WebhookGuard,
+    tx: mpsc::UnboundedSender<ItemUpdate>,
+) -> Result<()> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind {addr}"))?;
+    let guard = Arc::new(guard);
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let tx = tx.clone();
+        let guard = guard.clone();
+        tokio::spawn(async move {
+            let raw = read_request(&mut stream).await?;
+            let (request, body) = split_request(&raw);
+            let (status, body) = if is_webhook_check(&request) {
+                ("200 OK", "")
+            } else {
+                match webhook_provider(&request) {
+                    Some(provider) => handle_webhook(provider, &request, body, &guard, &tx),
+                    None => ("404 Not Found", "Not found"),
+                }
+            };
+            respond(&mut stream, status, "text/plain", body).await
+        });
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, state: Arc<ServeState>) -> Result<()> {
+    let raw = read_request(&mut stream).await?;
+    let (request, body) = split_request(&raw);
+
+    if state.webhooks && is_webhook_check(&request) {
+        return respond(&mut stream, "200 OK", "text/plain", "").await;
+    }
+    if let Some(provider) = webhook_provider(&request) {
+        let (status, body) = if state.webhooks {
+            handle_webhook(provider, &request, body, &state.guard, &state.updates)
+        } else {
+            ("403 Forbidden", "Webhooks are off in --web mode")
+        };
+        return respond(&mut stream, status, "text/plain", body).await;
+    }
+    if !authorized(&request, state.token.as_deref()) {
+        return respond(
+            &mut stream,
+            "401 Unauthorized",
+            "text/plain",
+            "Open the dashboard with ?token=<token>",
+        )
+        .await;
+    }
+
+    let (status, content_type, body) = match request_path(&request) {
+        Some("/") => (
+            "200 OK",
+            "text/html; charset=utf-8",
+            DASHBOARD_HTML.to_string(),
+        ),
+        Some("/api/state") => (
+            "200 OK",
+            "application/json",
+            state_json(&state).await.to_string(),
+        ),
+        Some(_) => ("404 Not Found", "text/plain", "Not found".to_string()),
+        None => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "GET only".to_string(),
+        ),
+    };
+
+    respond(&mut stream, status, content_type, &body).await
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    Ok(())
+}
+
+/// Read headers and, if there is a Content-Length, the full body.
+/// Read one request, giving up after `READ_TIMEOUT` so an idle client
+/// can't hold the connection open.
+async fn read_request(stream: &mut TcpStream) -> Result<Vec<u8>> {
+    tokio::time::timeout(READ_TIMEOUT, read_until_complete(stream))
+        .await
+        .map_err(|_| anyhow!("Request timed out"))?
+}
+
+async fn read_until_complete(stream: &mut TcpStream) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+    loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if buf.len() >= MAX_REQUEST_BYTES {
+            bail!("Request too large");
+        }
+        let text = String::from_utf8_lossy(&buf);
+        if let Some(header_end) = text.find("\r\n\r\n") {
+            let body_len = content_length(&text[..header_end]).unwrap_or(0);
+            if buf.len() >= header_end + 4 + body_len {
+                break;
+            }
+        }
+    }
+    Ok(buf)
+}
+
+/// Request line and headers as text, and the raw body signatures are
+/// computed over.
+fn split_request(raw: &[u8]) -> (String, &[u8]) {
+    match raw.windows(4).position(|w| w == b"\r\n\r\n") {
+        Some(end) => (String::from_utf8_lossy(&raw[..end]).to_string(), &raw[end + 4..]),
+        None => (String::from_utf8_lossy(raw).to_string(), &[]),
+    }
+}
+
+fn content_length(headers: &str) -> Option<usize> {
+    header(headers, "content-length")?.parse().ok()
+}
+
+fn header<'a>(headers: &'a str, name: &str) -> Option<&'a str> {
+    headers.lines().skip(1).find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+}
+
+/// Provider of a `POST /webhooks/<provider>` request.
+pub fn webhook_provider(request: &str) -> Option<&str> {
+    let mut parts = request.lines().next()?.split_whitespace();
+    if parts.next()? != "POST" {
+        return None;
+    }
+    parts.next()?.split('?').next()?.strip_prefix("/webhooks/")
+}
+
+/// `HEAD /webhooks/<provider>`: Trello checks the callback URL answers
+/// before it creates a webhook.
+fn is_webhook_check(request: &str) -> bool {
+    let mut parts = request.lines().next().unwrap_or("").split_whitespace();
+    parts.next() == Some("HEAD") && parts.next().is_some_and(|t| t.starts_with("/webhooks/"))
+}
+
+fn handle_webhook(
+    provider: &str,
+    request: &str,
+    body: &[u8],
+    guard: &WebhookGuard,
+    tx: &mpsc::UnboundedSender<ItemUpdate>,
+) -> (&'static str, &'static str) {
+    let Some(secret) = guard.secrets.get(provider) else {
+        return ("401 Unauthorized", "No webhook secret configured");
+    };
+    let target = request.split_whitespace().nth(1).unwrap_or("");
+    let callback_url = match &guard.public_url {
+        Some(base) => format!("{}{target}", base.trim_end_matches('/')),
+        None => format!("http://{}{target}", header(request, "host").unwrap_or("")),
+    };
+    let signed = verify_signature(
+        provider,
+        secret,
+        |name| header(request, name).map(String::from),
+        body,
+        &callback_url,
+    );
+    if !signed {
+        return ("401 Unauthorized", "Bad signature");
+    }
+    let Ok(json) = serde_json::from_slice::<serde_json::Value>(body) else {
+        return ("400 Bad Request", "Invalid JSON");
+    };
+    // Acknowledge events we don't track so providers don't retry them. Item
+    // IDs repeat across boards, so other boards' events are dropped too.
+    let mapping = config::board_mapping(&guard.project_dir);
+    let other_board = mapping.is_some_and(|m| {
+        m.source.eq_ignore_ascii_case(provider)
+            && webhook_board(provider, &json).is_some_and(|board| board != m.board_id)
+    });
+    if let Some(update) = parse_webhook(provider, &json).filter(|_| !other_board) {
+        let _ = tx.send(update);
+    }
+    ("200 OK", "ok")
+}
+
+/// Path of a GET request, without query string. None for other methods.
+pub fn request_path(request: &str) -> Option<&str> {
+    let mut parts = request.lines().next()?.split_whitespace();
+    if parts.next()? != "GET" {
+        return None;
+    }
+    let target = parts.next()?;
+    Some(target.split('?').next().unwrap_or(target))
+}
+
+async fn state_json(state: &ServeState) -> serde_json::Value {
+    let items = state.items.read().await.clone();
+    let agents = {
+        let mut store = state.store.lock().await;
+        let _ = store.reload();
+        serde_json::to_value(store.get_all()).unwrap_or_default()
+    };
+    let events = read_events(None, Some(100));
+
+    serde_json::json!({
+        "generated_at": chrono::Utc::now().to_rfc3339(),
+        "items": items,
+        "agents": agents,
+        "events": events,
+    })
+}
+
+const DASHBOARD_HTML: &str = r#"<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>work dashboard</title>
+<style>
+  body { font: 14px ui-monospace, monospace; background: #111; color: #ddd; margin: 2em; }
+  h1 { font-size: 18px; } h2 { font-size: 15px; color: #6cf; margin-top: 2em; }
+  table { border-collapse: collapse; width: 100%; }
+  td, th { text-align: left; padding: 2px 12px 2px 0; vertical-align: top; }
+  th { color: #888; font-weight: normal; }
+  .idle { color: #888; } .provisioning { color: #fc3; } .working { color: #0cc; }
+  .stalled { color: #c6c; } .review { color: #ff6; } .plan { color: #ff9; }
+  .done { color: #3c6; } .error { color: #f55; }
+  #updated { color: #666; }
+  .scroll { overflow-x: auto; }
+  /* Phones: drop the columns that matter least */
+  @media (max-width: 600px) {
+    body { margin: 1em; font-size: 13px; }
+    .wide { display: none; }
+  }
+</style>
+</head>
+<body>
+<h1>work pipeline <span id="updated"></span></h1>
+<div id="summary"></div>
+<h2>Agents</h2>
+<div class="scroll"><table id="agents"><tr><th>Agent</th><th>Status</th><th>Item</th><th class="wide">Since</th></tr></table></div>
+<h2>Items</h2>
+<div class="scroll"><table id="items"><tr><th>ID</th><th>Title</th><th>Status</th><th class="wide">Priority</th><th class="wide">Due</th><th class="wide">Source</th></tr></table></div>
+<h2>Activity</h2>
+<div class="scroll"><table id="events"><tr><th>Time</th><th class="wide">Agent</th><th>Event</th><th>Detail</th></tr></table></div>
+<script>
+// Cells take the header's class, so narrow screens hide whole columns
+function row(table, cells, cls) {
+  const tr = document.createElement('tr');
+  cells.forEach((c, i) => {
+    const td = document.createElement('td');
+    td.textContent = c == null ? '' : c;
+    td.className = table.rows[0].cells[i].className;
+    tr.appendChild(td);
+  });
+  if (cls) tr.className = cls;
+  table.appendChild(tr);
+}
+function reset(id) {
+  const t = document.getElementById(id);
+  while (t.rows.length > 1) t.deleteRow(1);
+  return t;
+}
+async function refresh() {
+  // Passes ?token= on
+  const s = await (await fetch('/api/state' + location.search)).json();
+  const agents = reset('agents');
+  for (const a of s.agents) row(agents, [a.name, a.status, a.work_item_title, a.started_at], a.status);
+  const items = reset('items');
+  for (const i of s.items) row(items, [i.id, i.title, i.status, i.priority, i.due, i.source]);
+  const events = reset('events');
+  for (const e of s.events.slice().reverse()) row(events, [e.timestamp.slice(0, 19), e.agent, e.event, e.message || e.work_item_title]);
+  const busy = s.agents.filter(a => a.status === 'working' || a.status === 'provisioning').length;
+  document.getElementById('summary').textContent =
+    `${s.items.length} items · ${busy}/${s.agents.length} agents busy · ${s.events.length} recent events`;
+  document.getElementById('updated').textContent = 'updated ' + s.generated_at.slice(11, 19);
+}
+refresh();
+setInterval(refresh, 5000);
+</script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(strs: &[&str]) -> Vec<String> {
+        strs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn default_addr_is_localhost() {
+        assert_eq!(
+            parse_serve_args(&args(&[])).unwrap(),
+            ServeArgs {
+                addr: "127.0.0.1:7420".into(),
+                web_only: false,
+                token: None,
+            }
+        );
+    }
+
+    #[test]
+    fn port_and_addr_flags() {
+        assert_eq!(
+            parse_serve_args(&args(&["--port", "9000"])).unwrap().addr,
+            "127.0.0.1:9000"
+        );
+        let web = parse_serve_args(&args(&["--web", "--addr", "0.0.0.0:8080"])).unwrap();
+        assert_eq!(web.addr, "0.0.0.0:8080");
+        assert!(web.web_only);
+        let shared =
+            parse_serve_args(&args(&["--addr", "0.0.0.0:8080", "--token", "s3cret"])).unwrap();
+        assert_eq!(shared.token.as_deref(), Some("s3cret"));
+    }
+
+    #[test]
+    fn only_loopback_addresses_go_without_a_token() {
+        assert!(is_loopback("127.0.0.1:7420"));
+        assert!(is_loopback("[::1]:7420"));
+        assert!(is_loopback("localhost:7420"));
+        assert!(!is_loopback("0.0.0.0:7420"));
+        assert!(!is_loopback("192.168.1.5:7420"));
+    }
+
+    #[test]
+    fn dashboard_requests_need_the_token() {
+        let plain = "GET /api/state HTTP/1.1\r\nHost: x\r\n";
+        assert!(authorized(plain, None));
+        assert!(!authorized(plain, Some("s3cret")));
+        assert!(authorized(
+            "GET /?token=s3cret HTTP/1.1\r\n",
+            Some("s3cret")
+        ));
+        assert!(!authorized(
+            "GET /?token=s3cre HTTP/1.1\r\n",
+            Some("s3cret")
+        ));
+        assert!(authorized(
+            "GET /api/state HTTP/1.1\r\nAuthorization: Bearer s3cret\r\n",
+            Some("s3cret")
+        ));
+    }
+
+    #[test]
+    fn bad_serve_args_fail() {
+        assert!(parse_serve_args(&args(&["--port", "abc"])).is_err());
+        assert!(parse_serve_args(&args(&["--addr"])).is_err());
+        assert!(parse_serve_args(&args(&["--verbose"])).is_err());
+    }
+
+    #[test]
+    fn request_path_strips_query() {
+        assert_eq!(
+            request_path("GET /api/state?x=1 HTTP/1.1\r\nHost: x\r\n\r\n"),
+            Some("/api/state")
+        );
+        assert_eq!(request_path("GET / HTTP/1.1\r\n"), Some("/"));
+    }
+
+    #[test]
+    fn webhook_routes() {
+        assert_eq!(
+            webhook_provider("POST /webhooks/linear HTTP/1.1\r\n"),
+            Some("linear")
+        );
+        assert_eq!(webhook_provider("GET /webhooks/linear HTTP/1.1\r\n"), None);
+        assert_eq!(webhook_provider("POST /api/state HTTP/1.1\r\n"), None);
+        assert_eq!(
+            content_length("POST / HTTP/1.1\r\nContent-Length: 42\r\nHost: x"),
+            Some(42)
+        );
+    }
+
+    #[test]
+    fn webhooks_need_a_valid_signature() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let body = br#"{"type":"Issue","action":"create","data":{"identifier":"ENG-1","title":"T"}}"#;
+        let request = "POST /webhooks/linear HTTP/1.1\r\nHost: x\r\nLinear-Signature: 00";
+        let mut guard = WebhookGuard::default();
+        assert_eq!(
+            handle_webhook("linear", request, body, &guard, &tx).0,
+            "401 Unauthorized"
+        );
+        guard.secrets.insert("linear".into(), "s3cret".into());
+        assert_eq!(
+            handle_webhook("linear", request, body, &guard, &tx).0,
+            "401 Unauthorized"
+        );
+        assert!(rx.try_recv().is_err());
+
+        use hmac::{Hmac, Mac};
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(b"s3cret").unwrap();
+        mac.update(body);
+        let sig: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        let request = format!("POST /webhooks/linear HTTP/1.1\r\nlinear-signature: {sig}");
+        assert_eq!(handle_webhook("linear", &request, body, &guard, &tx).0, "200 OK");
+        assert!(matches!(rx.try_recv(), Ok(ItemUpdate::Upsert(_))));
+    }
+
+    #[test]
+    fn trello_callback_checks_are_answered() {
+        assert!(is_webhook_check("HEAD /webhooks/trello HTTP/1.1\r\n"));
+        assert!(!is_webhook_check("HEAD / HTTP/1.1\r\n"));
+        let (head, body) = split_request(b"POST /webhooks/trello HTTP/1.1\r\nHost: x\r\n\r\n{}");
+        assert_eq!(header(&head, "host"), Some("x"));
+        assert_eq!(body, b"{}");
+    }
+
+    #[test]
+    fn request_path_rejects_non_get() {
+        assert_eq!(request_path("POST /api/state HTTP/1.1\r\n"), None);
+        assert_eq!(request_path(""), None);
+    }
+}
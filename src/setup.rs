@@ -0,0 +1,257 @@
+use anyhow::{bail, Context, Result};
+use std::io::{self, BufRead, Write};
+
+use crate::config::{self, AppConfig};
+use crate::providers;
+
+/// A tracker the wizard knows how to configure: its config.toml section and
+/// the keys it needs.
+struct ProviderSetup {
+    section: &'static str,
+    name: &'static str,
+    fields: &'static [(&'static str, &'static str)],
+}
+
+const PROVIDERS: &[ProviderSetup] = &[
+    ProviderSetup {
+        section: "linear",
+        name: "Linear",
+        fields: &[("api_key", "API key (Settings → API → Personal API keys)")],
+    },
+    ProviderSetup {
+        section: "trello",
+        name: "Trello",
+        fields: &[
+            ("api_key", "API key (trello.com/power-ups/admin)"),
+            ("token", "Token"),
+        ],
+    },
+    ProviderSetup {
+        section: "jira",
+        name: "Jira",
+        fields: &[
+            ("domain", "Domain (e.g. acme.atlassian.net)"),
+            ("email", "Account email"),
+            ("api_token", "API token (id.atlassian.com → Security)"),
+        ],
+    },
+    ProviderSetup {
+        section: "github",
+        name: "GitHub",
+        fields: &[
+            ("owner", "Owner or org"),
+            (
+                "token",
+                "Personal access token (blank to use the gh CLI's login)",
+            ),
+        ],
+    },
+    ProviderSetup {
+        section: "sentry",
+        name: "Sentry",
+        fields: &[
+            ("org", "Organization slug"),
+            ("auth_token", "Auth token (Settings → Auth Tokens)"),
+        ],
+    },
+];
+
+/// `work init`: interactively add tracker credentials, check each with a
+/// test fetch and write config.toml.
+pub async fn handle_init(args: &[String]) -> Result<()> {
+    let force = args.iter().any(|a| a == "--force" || a == "-f");
+    let path = config::config_path();
+    if path.exists() && !force {
+        println!(
+            "{} already exists. Run `work init --force` to replace it.",
+            path.display()
+        );
+        return Ok(());
+    }
+    run_wizard().await
+}
+
+/// Walk through each provider and write whatever was configured. Also runs
+/// on the first launch of the TUI when no config exists yet.
+pub async fn run_wizard() -> Result<()> {
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut out = io::stdout();
+
+    writeln!(out, "Welcome to work! Let's connect your issue trackers.")?;
+    writeln!(out, "Press enter to skip a tracker.\n")?;
+
+    let mut table = toml::Table::new();
+    for setup in PROVIDERS {
+        let answer = ask(
+            &mut input,
+            &mut out,
+            &format!("Set up {}? [y/N]", setup.name),
+        )?;
+        if !is_yes(&answer) {
+            continue;
+        }
+        loop {
+            let mut section = toml::Table::new();
+            for (key, prompt) in setup.fields {
+                let value = ask(&mut input, &mut out, &format!("  {prompt}:"))?;
+                if !value.is_empty() {
+                    section.insert(key.to_string(), toml::Value::String(value));
+                }
+            }
+
+            write!(out, "  Checking {}... ", setup.name)?;
+            out.flush()?;
+            match validate(setup.section, &section).await {
+                Ok(count) => {
+                    writeln!(out, "ok ({count} items)")?;
+                    table.insert(setup.section.to_string(), toml::Value::Table(section));
+                    break;
+                }
+                Err(e) => {
+                    writeln!(out, "failed: {e:#}")?;
+                    let retry = ask(&mut input, &mut out, "  Try again? [Y/n]")?;
+                    if retry.eq_ignore_ascii_case("n") {
+                        let keep =
+                            ask(&mut input, &mut out, "  Save these settings anyway? [y/N]")?;
+                        if is_yes(&keep) {
+                            table.insert(setup.section.to_string(), toml::Value::Table(section));
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    if table.is_empty() {
+        let demo = ask(
+            &mut input,
+            &mut out,
+            "\nNo trackers set up. Try work with fake demo items instead? [y/N]",
+        )?;
+        if is_yes(&demo) {
+            let mut mock = toml::Table::new();
+            mock.insert("items".to_string(), toml::Value::Integer(20));
+            table.insert("mock".to_string(), toml::Value::Table(mock));
+        }
+    }
+
+    // An empty file still stops the wizard from running on every launch
+    let contents = if table.is_empty() {
+        writeln!(
+            out,
+            "\nNo trackers configured. Run `work init --force` any time to set them up."
+        )?;
+        "# Run `work init --force` to add tracker credentials\n".to_string()
+    } else {
+        toml::to_string(&table)?
+    };
+
+    let path = config::config_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, contents)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    // The file holds API keys
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    writeln!(out, "\nSaved {}\n", path.display())?;
+    Ok(())
+}
+
+/// Build the provider from its config section and fetch once, returning
+/// how many items came back.
+async fn validate(section: &str, values: &toml::Table) -> Result<usize> {
+    let mut config = section_config(section, values)?;
+    config.resolve_secrets()?;
+    let provider = providers::create_providers(&config)
+        .into_iter()
+        .next()
+        .context("Provider was not created from these settings")?;
+    Ok(provider.fetch_items().await?.len())
+}
+
+/// Parse one section the same way config.toml is parsed.
+fn section_config(section: &str, values: &toml::Table) -> Result<AppConfig> {
+    let mut table = toml::Table::new();
+    table.insert(section.to_string(), toml::Value::Table(values.clone()));
+    toml::from_str(&toml::to_string(&table)?).context("Invalid settings")
+}
+
+/// Ask before applying the settings in the current repo's `.work.toml` that
+/// run commands or reach outside it. The answer holds until the file
+/// changes; declining starts without them.
+pub fn confirm_project_trust() -> Result<()> {
+    let Some(path) = std::env::current_dir()
+        .ok()
+        .and_then(|dir| config::find_project_config(&dir))
+    else {
+        return Ok(());
+    };
+    let keys = config::untrusted_keys(&path)?;
+    if keys.is_empty() {
+        return Ok(());
+    }
+    let stdin = io::stdin();
+    let mut out = io::stdout();
+    writeln!(out, "{} sets {}.", path.display(), keys.join(", "))?;
+    writeln!(out, "These run commands or reach outside the repository.")?;
+    let answer = ask(&mut stdin.lock(), &mut out, "Trust this file? [y/N]")?;
+    if is_yes(&answer) {
+        config::trust_project_config(&path)?;
+    } else {
+        writeln!(out, "Starting without them.")?;
+    }
+    Ok(())
+}
+
+fn ask(input: &mut impl BufRead, out: &mut impl Write, prompt: &str) -> Result<String> {
+    write!(out, "{prompt} ")?;
+    out.flush()?;
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        bail!("Setup cancelled");
+    }
+    Ok(line.trim().to_string())
+}
+
+fn is_yes(answer: &str) -> bool {
+    matches!(answer.to_lowercase().as_str(), "y" | "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ask_trims_the_answer() {
+        let mut input = io::Cursor::new("  lin_api_123 \n");
+        let mut out = Vec::new();
+        let answer = ask(&mut input, &mut out, "API key:").unwrap();
+        assert_eq!(answer, "lin_api_123");
+        assert_eq!(String::from_utf8(out).unwrap(), "API key: ");
+        assert!(is_yes("Y") && is_yes("yes") && !is_yes(""));
+        assert!(ask(&mut io::Cursor::new(""), &mut Vec::new(), "Token:").is_err());
+    }
+
+    #[test]
+    fn wizard_sections_parse_as_config() {
+        for setup in PROVIDERS {
+            let mut values = toml::Table::new();
+            for (key, _) in setup.fields {
+                values.insert(key.to_string(), toml::Value::String("x\"y".into()));
+            }
+            let config = section_config(setup.section, &values).unwrap();
+            assert_eq!(
+                providers::create_providers(&config).len(),
+                1,
+                "{}",
+                setup.name
+            );
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::config::data_dir;
+use crate::model::agent::{Agent, AgentStatus};
+use crate::model::work_item::WorkItem;
+
+/// The dashboard's last item list, so `work status --short` can summarize it
+/// without calling provider APIs on every prompt redraw.
+fn snapshot_path() -> PathBuf {
+    data_dir().join("items-snapshot.json")
+}
+
+pub fn save_snapshot(items: &[WorkItem]) -> Result<()> {
+    let path = snapshot_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string(items)?)?;
+    Ok(())
+}
+
+pub fn load_snapshot() -> Vec<WorkItem> {
+    std::fs::read_to_string(snapshot_path())
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Whether a provider status name means work has started.
+fn is_started(status: &str) -> bool {
+    let status = status.to_lowercase();
+    ["progress", "doing", "started", "review"]
+        .iter()
+        .any(|s| status.contains(s))
+}
+
+/// One line for a tmux status bar, e.g.
+/// `3 todo · 2 wip · ember:working flow:idle`.
+pub fn summary_line(items: &[WorkItem], agents: &[&Agent]) -> String {
+    let busy = |item: &WorkItem| {
+        agents.iter().any(|a| {
+            a.work_item_id.as_deref() == Some(item.id.as_str())
+                && !matches!(a.status, AgentStatus::Idle | AgentStatus::Done)
+        })
+    };
+    let wip = items
+        .iter()
+        .filter(|item| busy(item) || item.status.as_deref().is_some_and(is_started))
+        .count();
+    let agents: Vec<String> = agents
+        .iter()
+        .map(|a| format!("{}:{}", a.name.as_str(), a.status))
+        .collect();
+
+    let mut line = format!("{} todo · {wip} wip", items.len() - wip);
+    if !agents.is_empty() {
+        line.push_str(&format!(" · {}", agents.join(" ")));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::agent::AgentName;
+
+    fn item(id: &str, status: &str) -> WorkItem {
+        WorkItem::test(id).with_title(id).with_status(status)
+    }
+
+    #[test]
+    fn counts_started_and_agent_held_items_as_wip() {
+        let items = [
+            item("ENG-1", "Todo"),
+            item("ENG-2", "In Progress"),
+            item("ENG-3", "Backlog"),
+            item("ENG-4", "Todo"),
+        ];
+        let mut ember = Agent::new(AgentName::Ember);
+        ember.status = AgentStatus::Working;
+        ember.work_item_id = Some("ENG-3".into());
+        let flow = Agent::new(AgentName::Flow);
+
+        assert_eq!(
+            summary_line(&items, &[&ember, &flow]),
+            "2 todo · 2 wip · ember:working flow:idle"
+        );
+        assert_eq!(summary_line(&[], &[]), "0 todo · 0 wip");
+    }
+}
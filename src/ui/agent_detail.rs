@@ -1,16 +1,87 @@
 use ratatui::{
-    layout::Rect,
-    style::Style,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Wrap},
     Frame,
 };
 
+use crate::agents::diff::{self, BranchDiff};
+use crate::agents::git_summary::GitSummary;
+use crate::agents::log::EventKind;
+use crate::agents::worktree::format_bytes;
 use crate::app::App;
-use crate::model::agent::AgentName;
-use crate::ui::theme::event_color;
+use crate::model::agent::{short_duration, AgentName, AgentStatus};
+use crate::ui::theme::{diff_line_color, event_color};
+use crate::util::highlight::Run;
 
 pub fn render(f: &mut Frame, area: Rect, app: &App, agent_name: AgentName) {
+    if let Some(diff) = app.branch_diff.as_ref().filter(|d| d.agent == agent_name) {
+        render_branch_diff(f, area, app, agent_name, diff);
+        return;
+    }
+
+    if let Some((_, diff)) = app.review_diff.as_ref().filter(|(n, _)| *n == agent_name) {
+        // Activity (30%) above the diff under review (70%)
+        let vertical = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+            .split(area);
+        render_activity(f, vertical[0], app, agent_name);
+        render_diff(f, vertical[1], app, diff);
+        return;
+    }
+
+    let has_output = app
+        .agent_output
+        .get(&agent_name)
+        .is_some_and(|buf| !buf.is_empty());
+
+    if has_output {
+        // Activity (40%) above live output (60%)
+        let vertical = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(area);
+        render_activity(f, vertical[0], app, agent_name);
+        render_output(f, vertical[1], app, agent_name);
+    } else {
+        render_activity(f, area, app, agent_name);
+    }
+}
+
+fn render_activity(f: &mut Frame, area: Rect, app: &App, agent_name: AgentName) {
+    let area = match app.git_summary.get(&agent_name) {
+        Some(summary) if area.height > 16 => {
+            // Branch and push state, stats, then up to four commits
+            let height = (summary.commits.len().min(4) + 4) as u16;
+            let vertical = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(height), Constraint::Min(0)])
+                .split(area);
+            render_git(f, vertical[0], summary);
+            vertical[1]
+        }
+        _ => area,
+    };
+
+    let history_len = app
+        .store
+        .get_agent(agent_name)
+        .map_or(0, |a| a.history.len() + a.backlog.len());
+    let area = if history_len > 0 && area.height > 12 {
+        // Item history and backlog above the activity log
+        let height = (history_len.min(5) + 2) as u16;
+        let vertical = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(height), Constraint::Min(0)])
+            .split(area);
+        render_history(f, vertical[0], app, agent_name);
+        vertical[1]
+    } else {
+        area
+    };
+
     let events = app.agent_events(agent_name);
 
     let visible_height = area.height.saturating_sub(2) as usize;
@@ -57,11 +128,20 @@ pub fn render(f: &mut Frame, area: Rect, app: &App, agent_name: AgentName) {
         })
         .collect();
 
-    let title = format!(
-        " {} {} Activity ",
-        agent_name.emoji(),
-        agent_name.display_name()
-    );
+    let mut title = format!(" {} {} Activity ", agent_name.emoji(), agent_name.display_name());
+    if app.agent_event_kind != EventKind::All {
+        title.push_str(&format!("· {} ", app.agent_event_kind.as_str()));
+    }
+    if let Some(text) = &app.agent_event_search {
+        title.push_str(&format!("· \"{text}\" "));
+    }
+    let usage = app.store.costs().agent_total(agent_name);
+    if usage.total_tokens() > 0 {
+        title.push_str(&format!("· {usage} "));
+    }
+    if let Some(bytes) = app.disk_usage.get(&agent_name) {
+        title.push_str(&format!("· {} on disk ", format_bytes(*bytes)));
+    }
 
     let paragraph = Paragraph::new(lines)
         .block(
@@ -74,3 +154,305 @@ pub fn render(f: &mut Frame, area: Rect, app: &App, agent_name: AgentName) {
 
     f.render_widget(paragraph, area);
 }
+
+fn render_output(f: &mut Frame, area: Rect, app: &App, agent_name: AgentName) {
+    let Some(buf) = app.agent_output.get(&agent_name) else {
+        return;
+    };
+
+    let visible_height = area.height.saturating_sub(2) as usize;
+    let scroll = app.agent_output_scroll.min(buf.len());
+    let end = buf.len() - scroll;
+    let start = end.saturating_sub(visible_height);
+
+    let lines: Vec<Line> = buf
+        .range(start..end)
+        .map(|line| Line::raw(line.as_str()))
+        .collect();
+
+    let live = app
+        .store
+        .get_agent(agent_name)
+        .is_some_and(|a| matches!(a.status, AgentStatus::Working | AgentStatus::Stalled));
+    let title = match (live, scroll) {
+        (_, s) if s > 0 => format!(" Output (\u{2191}{s}) "),
+        (true, _) => " Output (live) ".to_string(),
+        (false, _) => " Output ".to_string(),
+    };
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(ratatui::style::Color::DarkGray))
+            .title(title),
+    );
+
+    f.render_widget(paragraph, area);
+}
+
+fn render_diff(f: &mut Frame, area: Rect, app: &App, diff: &[String]) {
+    let visible_height = area.height.saturating_sub(2) as usize;
+    let scroll = app.review_scroll.min(diff.len().saturating_sub(visible_height));
+
+    let agent = app
+        .review_diff
+        .as_ref()
+        .and_then(|(name, _)| app.store.get_agent(*name));
+    let is_plan = agent.is_some_and(|a| a.status == AgentStatus::Plan);
+
+    let lines: Vec<Line> = diff
+        .iter()
+        .skip(scroll)
+        .take(visible_height)
+        .map(|line| {
+            // Plans are Markdown; their bullets aren't removed lines
+            let style = if is_plan {
+                Style::default()
+            } else {
+                Style::default().fg(diff_line_color(line))
+            };
+            Line::styled(line.as_str(), style)
+        })
+        .collect();
+
+    let title = if is_plan {
+        format!(" Plan awaiting approval ({} lines) ", diff.len())
+    } else {
+        let base = agent.map_or("main", |a| a.base());
+        format!(" Review: diff vs origin/{base} ({} lines) ", diff.len())
+    };
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(ratatui::style::Color::LightYellow))
+            .title(title),
+    );
+
+    f.render_widget(paragraph, area);
+}
+
+fn render_branch_diff(
+    f: &mut Frame,
+    area: Rect,
+    app: &App,
+    name: AgentName,
+    diff: &BranchDiff,
+) {
+    let visible_height = area.height.saturating_sub(2) as usize;
+    let scroll = app.branch_diff_scroll.min(diff.lines.len().saturating_sub(1));
+
+    let lines: Vec<Line> = diff
+        .lines
+        .iter()
+        .zip(&diff.highlights)
+        .skip(scroll)
+        .take(visible_height)
+        .map(|(line, runs)| diff_line(line, runs))
+        .collect();
+
+    let base = app.store.get_agent(name).map_or("main", |a| a.base());
+    let files = &diff.files;
+    let mut title = format!(" Diff vs origin/{base} ");
+    if let Some(i) = diff::file_at(files, scroll) {
+        let file = &files[i];
+        title.push_str(&format!(
+            "· file {}/{} {} +{} \u{2212}{} ",
+            i + 1,
+            files.len(),
+            file.path,
+            file.added,
+            file.removed
+        ));
+    } else if !files.is_empty() {
+        title.push_str(&format!("· {} files ", files.len()));
+    }
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(ratatui::style::Color::LightYellow))
+            .title(title),
+    );
+
+    f.render_widget(paragraph, area);
+}
+
+/// One unified diff line, with file and hunk headers set apart from changes
+/// and code in the syntax colours of `runs`, when it has any.
+fn diff_line<'a>(line: &'a str, runs: &'a [Run]) -> Line<'a> {
+    // Tabs would otherwise render at whatever width the terminal picks
+    let text = line.replace('\t', "    ");
+    if !runs.is_empty() {
+        let (marker, background) = match line.chars().next() {
+            Some('+') => ("+", Some(ratatui::style::Color::Rgb(0, 48, 0))),
+            Some('-') => ("-", Some(ratatui::style::Color::Rgb(64, 0, 0))),
+            _ => (" ", None),
+        };
+        let base = background.map_or_else(Style::default, |bg| Style::default().bg(bg));
+        let mut spans = vec![Span::styled(marker, base.fg(diff_line_color(line)))];
+        spans.extend(runs.iter().map(|((r, g, b), code)| {
+            Span::styled(
+                code.replace('\t', "    "),
+                base.fg(ratatui::style::Color::Rgb(*r, *g, *b)),
+            )
+        }));
+        return Line::from(spans);
+    }
+    if line.starts_with("diff --git ") {
+        Line::styled(
+            text,
+            Style::default()
+                .fg(ratatui::style::Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else if let Some(rest) = line.strip_prefix("@@") {
+        // `@@ -1,2 +1,3 @@ fn enclosing()`: ranges, then the enclosing line
+        let (ranges, context) = rest.split_once("@@").unwrap_or((rest, ""));
+        Line::from(vec![
+            Span::styled(
+                format!("@@{ranges}@@"),
+                Style::default().fg(ratatui::style::Color::Cyan),
+            ),
+            Span::styled(
+                context.replace('\t', "    "),
+                Style::default().fg(ratatui::style::Color::DarkGray),
+            ),
+        ])
+    } else if ["index ", "--- ", "+++ ", "new file", "deleted file", "similarity", "rename "]
+        .iter()
+        .any(|p| line.starts_with(p))
+    {
+        Line::styled(text, Style::default().fg(ratatui::style::Color::DarkGray))
+    } else {
+        Line::styled(text, Style::default().fg(diff_line_color(line)))
+    }
+}
+
+fn render_git(f: &mut Frame, area: Rect, summary: &GitSummary) {
+    let gray = Style::default().fg(ratatui::style::Color::Gray);
+    let push_state = match summary.pushed_to.first() {
+        Some(remote) => Span::styled(
+            format!("pushed to {remote}"),
+            Style::default().fg(ratatui::style::Color::Green),
+        ),
+        None if summary.commits.is_empty() => Span::styled("nothing to push", gray),
+        None => Span::styled(
+            "not pushed",
+            Style::default().fg(ratatui::style::Color::Yellow),
+        ),
+    };
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                summary.branch.as_str(),
+                Style::default().fg(ratatui::style::Color::Cyan),
+            ),
+            Span::styled(" · ", gray),
+            push_state,
+        ]),
+        Line::from(vec![
+            Span::styled(format!("{} commits · ", summary.commits.len()), gray),
+            Span::raw(summary.stat()),
+        ]),
+    ];
+    let room = area.height.saturating_sub(4) as usize;
+    // Leave a line to say how many didn't fit
+    let shown = if summary.commits.len() > room {
+        room.saturating_sub(1)
+    } else {
+        room
+    };
+    lines.extend(summary.commits.iter().take(shown).map(|commit| {
+        let (hash, message) = commit.split_once(' ').unwrap_or((commit, ""));
+        Line::from(vec![
+            Span::styled(
+                format!("{hash} "),
+                Style::default().fg(ratatui::style::Color::LightGreen),
+            ),
+            Span::raw(message),
+        ])
+    }));
+    if summary.commits.len() > shown {
+        lines.push(Line::styled(
+            format!("… {} more", summary.commits.len() - shown),
+            gray,
+        ));
+    }
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(ratatui::style::Color::DarkGray))
+            .title(" Git "),
+    );
+
+    f.render_widget(paragraph, area);
+}
+
+fn render_history(f: &mut Frame, area: Rect, app: &App, agent_name: AgentName) {
+    let Some(agent) = app.store.get_agent(agent_name) else {
+        return;
+    };
+    let visible = area.height.saturating_sub(2) as usize;
+
+    // Stacked items first, then the most recent finished ones
+    let mut lines: Vec<Line> = agent
+        .backlog
+        .iter()
+        .enumerate()
+        .map(|(i, id)| {
+            let title = app
+                .items
+                .iter()
+                .find(|item| item.id == *id)
+                .map_or("", |item| item.title.as_str());
+            Line::from(vec![
+                Span::styled(
+                    format!("next #{:<3}", i + 1),
+                    Style::default().fg(ratatui::style::Color::Blue),
+                ),
+                Span::raw(format!("{id} {title}")),
+            ])
+        })
+        .collect();
+    lines.extend(agent.history.iter().rev().map(|record| {
+        let color = match record.outcome.as_str() {
+            "done" => ratatui::style::Color::Green,
+            "failed" => ratatui::style::Color::Red,
+            _ => ratatui::style::Color::Gray,
+        };
+        let mut spans = vec![
+            Span::styled(format!("{:<9}", record.outcome), Style::default().fg(color)),
+            Span::raw(format!("{} {}", record.work_item_id, record.work_item_title)),
+        ];
+        if let Some(secs) = record.duration_secs {
+            let mut took = format!(" {}", short_duration(chrono::Duration::seconds(secs)));
+            if record.retries > 0 {
+                took.push_str(&format!(", {} retries", record.retries));
+            }
+            spans.push(Span::styled(
+                took,
+                Style::default().fg(ratatui::style::Color::DarkGray),
+            ));
+        }
+        Line::from(spans)
+    }));
+    lines.truncate(visible);
+
+    let mut title = " Items ".to_string();
+    if agent.stats.finished() > 0 {
+        title.push_str(&format!("· {} ", agent.stats.summary()));
+    }
+    let usage = app.store.costs().agent_total(agent.name);
+    if usage.cost_usd > 0.0 {
+        title.push_str(&format!("· ${:.2} ", usage.cost_usd));
+    }
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(ratatui::style::Color::DarkGray))
+            .title(title),
+    );
+
+    f.render_widget(paragraph, area);
+}
@@ -2,13 +2,13 @@ use ratatui::{
     layout::Rect,
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
     Frame,
 };
 
 use crate::app::{App, ViewMode};
-use crate::model::agent::AgentStatus;
-use crate::ui::theme::{agent_color, status_color};
+use crate::model::agent::{Agent, AgentStatus};
+use crate::ui::theme::{agent_color, ci_color, spinner, status_color};
 
 pub fn render(f: &mut Frame, area: Rect, app: &App) {
     let agents = app.store.get_all();
@@ -37,28 +37,71 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
                 name_style,
             );
 
+            let status_text = if matches!(
+                agent.status,
+                AgentStatus::Working | AgentStatus::Provisioning
+            ) {
+                format!("{} {}", spinner(app.frame), agent.status)
+            } else {
+                format!("{}", agent.status)
+            };
             let status = Span::styled(
-                format!("{}", agent.status),
+                status_text,
                 Style::default().fg(status_color(agent.status)),
             );
 
             let mut spans = vec![emoji, name, status];
 
+            // Waiting on an answer from the user
+            if let Some(qs) = app.pending_questions.get(&agent.name) {
+                spans.push(Span::styled(
+                    format!(" \u{2753}{}", qs.len()),
+                    Style::default()
+                        .fg(ratatui::style::Color::LightYellow)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+
+            // Stacked items waiting behind the current one
+            if !agent.backlog.is_empty() {
+                spans.push(Span::styled(
+                    format!(" +{}", agent.backlog.len()),
+                    Style::default().fg(ratatui::style::Color::Blue),
+                ));
+            }
+
             // Elapsed time for working agents
-            if agent.status == AgentStatus::Working || agent.status == AgentStatus::Provisioning {
-                if let Some(started_at) = &agent.started_at {
-                    if let Ok(start) = chrono::DateTime::parse_from_rfc3339(started_at) {
-                        let elapsed = chrono::Utc::now().signed_duration_since(start);
-                        let mins = elapsed.num_minutes();
-                        let secs = elapsed.num_seconds() % 60;
-                        spans.push(Span::styled(
-                            format!(" {mins:02}:{secs:02}"),
-                            Style::default().fg(ratatui::style::Color::Gray),
-                        ));
-                    }
+            if matches!(
+                agent.status,
+                AgentStatus::Working | AgentStatus::Provisioning | AgentStatus::Stalled
+            ) {
+                if let Some(elapsed) = agent.runtime(chrono::Utc::now()) {
+                    let mins = elapsed.num_minutes();
+                    let secs = elapsed.num_seconds() % 60;
+                    spans.push(Span::styled(
+                        format!(" {mins:02}:{secs:02}"),
+                        Style::default().fg(ratatui::style::Color::Gray),
+                    ));
                 }
             }
 
+            // CI result of the agent's last push
+            if let Some(watch) = app.store.ci_watch(agent.name) {
+                spans.push(Span::styled(
+                    format!(" CI: {}", watch.status.label()),
+                    Style::default().fg(ci_color(watch.status)),
+                ));
+            }
+
+            // Accumulated cost
+            let usage = app.store.costs().agent_total(agent.name);
+            if usage.cost_usd > 0.0 {
+                spans.push(Span::styled(
+                    format!(" ${:.2}", usage.cost_usd),
+                    Style::default().fg(ratatui::style::Color::DarkGray),
+                ));
+            }
+
             // Work item title
             if let Some(title) = &agent.work_item_title {
                 let max_len = area.width.saturating_sub(30) as usize;
@@ -69,6 +112,22 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
                 ));
             }
 
+            // What the agent last said it is doing
+            if let Some(status) = app.status_reports.get(&agent.name) {
+                let age = (chrono::Utc::now() - status.at).num_minutes();
+                let age = if age > 0 {
+                    format!(" ({age}m ago)")
+                } else {
+                    String::new()
+                };
+                spans.push(Span::styled(
+                    format!(" \u{2014} {}{age}", status.text),
+                    Style::default()
+                        .fg(ratatui::style::Color::LightCyan)
+                        .add_modifier(Modifier::ITALIC),
+                ));
+            }
+
             // Error message
             if let Some(error) = &agent.error {
                 spans.push(Span::styled(
@@ -90,12 +149,79 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
         })
         .collect();
 
+    let open_prs = app.store.pending_prs().len();
+    let title = if open_prs > 0 {
+        format!(" Agents ({open_prs} PRs open) ")
+    } else {
+        " Agents ".to_string()
+    };
+
     let list = List::new(items).block(
         Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(ratatui::style::Color::Cyan))
-            .title(" Agents "),
+            .title(title),
     );
 
     f.render_widget(list, area);
 }
+
+/// Agents that have finished items, most reliable first, then most done.
+fn ranked(app: &App) -> Vec<&Agent> {
+    let mut agents: Vec<&Agent> = app
+        .store
+        .get_all()
+        .into_iter()
+        .filter(|a| a.stats.finished() > 0)
+        .collect();
+    agents.sort_by(|a, b| {
+        b.stats
+            .success_rate()
+            .unwrap_or(0.0)
+            .total_cmp(&a.stats.success_rate().unwrap_or(0.0))
+            .then(b.stats.done.cmp(&a.stats.done))
+    });
+    agents
+}
+
+/// Rows the leaderboard needs, 0 when no agent has finished anything.
+pub fn leaderboard_rows(app: &App) -> usize {
+    ranked(app).len()
+}
+
+/// Lifetime stats side by side, to tell whether one agent does worse.
+pub fn render_leaderboard(f: &mut Frame, area: Rect, app: &App) {
+    let lines: Vec<Line> = ranked(app)
+        .iter()
+        .enumerate()
+        .map(|(i, agent)| {
+            let mut spans = vec![
+                Span::styled(
+                    format!("{}. ", i + 1),
+                    Style::default().fg(ratatui::style::Color::DarkGray),
+                ),
+                Span::styled(
+                    format!("{} {:<8} ", agent.name.emoji(), agent.name.display_name()),
+                    Style::default().fg(agent_color(agent.name)),
+                ),
+                Span::raw(agent.stats.summary()),
+            ];
+            let usage = app.store.costs().agent_total(agent.name);
+            if usage.cost_usd > 0.0 {
+                spans.push(Span::styled(
+                    format!(" · ${:.2}", usage.cost_usd),
+                    Style::default().fg(ratatui::style::Color::DarkGray),
+                ));
+            }
+            Line::from(spans)
+        })
+        .collect();
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(ratatui::style::Color::DarkGray))
+            .title(" Leaderboard "),
+    );
+    f.render_widget(paragraph, area);
+}
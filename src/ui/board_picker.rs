@@ -6,7 +6,7 @@ use ratatui::{
     Frame,
 };
 
-use crate::app::App;
+use crate::app::{App, BoardLoad};
 use crate::ui::theme;
 
 pub fn render(f: &mut Frame, area: Rect, app: &App) {
@@ -41,25 +41,7 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
     .alignment(Alignment::Center);
     f.render_widget(header, vertical[0]);
 
-    if app.loading {
-        let loading = Paragraph::new("Loading boards...")
-            .style(Style::default().fg(Color::Yellow))
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL).title("Boards"));
-        f.render_widget(loading, vertical[1]);
-        return;
-    }
-
-    if app.available_boards.is_empty() {
-        let empty = Paragraph::new("No boards found")
-            .style(Style::default().fg(Color::DarkGray))
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL).title("Boards"));
-        f.render_widget(empty, vertical[1]);
-        return;
-    }
-
-    let items: Vec<ListItem> = app
+    let mut items: Vec<ListItem> = app
         .available_boards
         .iter()
         .enumerate()
@@ -84,6 +66,41 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
         })
         .collect();
 
+    // One row per provider still listing or that failed; failed ones are
+    // selectable, after the boards, to list them again
+    let mut row = app.available_boards.len();
+    for (source, load) in &app.board_loads {
+        let line = match load {
+            BoardLoad::Loaded => continue,
+            BoardLoad::Loading => Line::from(Span::styled(
+                format!("  {} Loading {source} boards...", theme::spinner(app.frame)),
+                Style::default().fg(Color::Yellow),
+            )),
+            BoardLoad::Failed(e) => {
+                let selected = row == app.selected_board;
+                row += 1;
+                let marker = if selected { "> " } else { "  " };
+                let mut style = Style::default().fg(Color::Red);
+                if selected {
+                    style = style.add_modifier(Modifier::BOLD);
+                }
+                // The hint first, since long errors run off the panel
+                Line::from(vec![
+                    Span::styled(format!("{marker}{source} failed, "), style),
+                    Span::styled("Enter to retry", Style::default().fg(Color::Gray)),
+                    Span::styled(format!(": {e}"), Style::default().fg(Color::Red)),
+                ])
+            }
+        };
+        items.push(ListItem::new(line));
+    }
+    if items.is_empty() {
+        items.push(ListItem::new(Line::from(Span::styled(
+            "  No boards found",
+            Style::default().fg(Color::DarkGray),
+        ))));
+    }
+
     let list = List::new(items).block(
         Block::default()
             .borders(Borders::ALL)
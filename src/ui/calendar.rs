@@ -0,0 +1,143 @@
+use chrono::{Datelike, Local};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::ui::theme::source_color;
+
+/// Items laid out by due date on a week or month grid, with the items due on
+/// the highlighted day listed beside it.
+pub fn render(f: &mut Frame, area: Rect, app: &App) {
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
+        .split(area);
+
+    render_grid(f, horizontal[0], app);
+    render_day(f, horizontal[1], app);
+}
+
+fn render_grid(f: &mut Frame, area: Rect, app: &App) {
+    let cal = &app.calendar;
+    let today = Local::now().date_naive();
+    let undated = app
+        .visible_item_indices()
+        .iter()
+        .filter(|&&i| app.items[i].due_date().is_none())
+        .count();
+    let mut title = format!(" Calendar — {} ", cal.title());
+    if undated > 0 {
+        title.push_str(&format!("({undated} without due dates) "));
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(ratatui::style::Color::Cyan))
+        .title(title);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let grid = cal.grid();
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Ratio(1, grid.len() as u32); grid.len()])
+        .split(inner);
+
+    for (week, row_area) in grid.iter().zip(rows.iter()) {
+        let cells = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, 7); 7])
+            .split(*row_area);
+
+        for (day, cell) in week.iter().zip(cells.iter()) {
+            let header_style = if *day == today {
+                Style::default()
+                    .fg(ratatui::style::Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else if day.month() != cal.day.month() && cal.month {
+                Style::default().fg(ratatui::style::Color::DarkGray)
+            } else {
+                Style::default().fg(ratatui::style::Color::Gray)
+            };
+            let border_style = if *day == cal.day {
+                Style::default().fg(ratatui::style::Color::Cyan)
+            } else {
+                Style::default().fg(ratatui::style::Color::DarkGray)
+            };
+
+            let width = cell.width.saturating_sub(2) as usize;
+            let lines: Vec<Line> = app
+                .items_due_on(*day)
+                .into_iter()
+                .map(|i| {
+                    let item = &app.items[i];
+                    let style = if *day < today {
+                        Style::default().fg(ratatui::style::Color::Red)
+                    } else {
+                        Style::default().fg(source_color(&item.source))
+                    };
+                    let text = format!("{} {}", item.id, item.title);
+                    Line::from(Span::styled(
+                        text.chars().take(width).collect::<String>(),
+                        style,
+                    ))
+                })
+                .collect();
+
+            let paragraph = Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border_style)
+                    .title(Span::styled(
+                        day.format(" %a %-d ").to_string(),
+                        header_style,
+                    )),
+            );
+            f.render_widget(paragraph, *cell);
+        }
+    }
+}
+
+fn render_day(f: &mut Frame, area: Rect, app: &App) {
+    let cal = &app.calendar;
+    let due = app.items_due_on(cal.day);
+
+    let items: Vec<ListItem> = due
+        .iter()
+        .enumerate()
+        .map(|(pos, &i)| {
+            let item = &app.items[i];
+            let title_style = if pos == cal.pick {
+                Style::default()
+                    .fg(ratatui::style::Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} ", item.id),
+                    Style::default().fg(source_color(&item.source)),
+                ),
+                Span::styled(item.title.as_str(), title_style),
+            ]))
+        })
+        .collect();
+
+    let title = if due.is_empty() {
+        format!(" {} — nothing due ", cal.day.format("%a %b %-d"))
+    } else {
+        format!(" {} ({}) ", cal.day.format("%a %b %-d"), due.len())
+    };
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(ratatui::style::Color::Cyan))
+            .title(title),
+    );
+    f.render_widget(list, area);
+}
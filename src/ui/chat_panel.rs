@@ -9,10 +9,11 @@ use ratatui::{
 use crate::app::App;
 use crate::model::agent::AgentName;
 use crate::model::chat::ChatSender;
-use crate::ui::theme::agent_color;
+use crate::ui::theme::{agent_color, spinner};
 
 pub fn render(f: &mut Frame, area: Rect, app: &App) {
     let visible_height = area.height.saturating_sub(2) as usize;
+    let inner_width = area.width.saturating_sub(2).max(1) as usize;
 
     // Build lines from chat messages
     let mut all_lines: Vec<Line> = Vec::new();
@@ -87,61 +88,55 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
     // Show loading indicator if waiting for agent response
     if app.waiting_for_response {
         all_lines.push(Line::from(Span::styled(
-            "  thinking...",
+            format!("  {} thinking...", spinner(app.frame)),
             Style::default()
                 .fg(ratatui::style::Color::DarkGray)
                 .add_modifier(Modifier::ITALIC),
         )));
     }
 
-    // Auto-scroll to bottom
-    let total = all_lines.len();
-    let skip = total.saturating_sub(visible_height);
-    let visible_lines: Vec<Line> = all_lines.into_iter().skip(skip).take(visible_height).collect();
+    // Stick to the bottom unless scrolled up, counting rows after wrapping
+    let total: usize = all_lines
+        .iter()
+        .map(|line| line.width().div_ceil(inner_width).max(1))
+        .sum();
+    let bottom = total.saturating_sub(visible_height);
+    let scroll = app.chat_scroll.min(bottom);
+    let offset = (bottom - scroll).min(u16::MAX as usize) as u16;
 
     let msg_count = app.chat_messages.len();
-    let title = if msg_count > 0 {
+    let mut title = if msg_count > 0 {
         format!(" Chat ({msg_count}) ")
     } else {
         " Chat — press : to start ".to_string()
     };
+    if scroll > 0 {
+        title.push_str(&format!("↑{scroll} "));
+    }
+    if app.chat_focus {
+        title.push_str("— esc to close ");
+    }
 
-    let paragraph = Paragraph::new(visible_lines)
+    let paragraph = Paragraph::new(all_lines)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(ratatui::style::Color::Magenta))
                 .title(title),
         )
-        .wrap(Wrap { trim: false });
+        .wrap(Wrap { trim: false })
+        .scroll((offset, 0));
 
     f.render_widget(paragraph, area);
 }
 
 fn extract_agent_target(text: &str) -> Option<AgentName> {
-    if !text.starts_with('@') {
-        return None;
-    }
-    let after_at = &text[1..];
-    for name in AgentName::ALL {
-        if after_at.starts_with(name.as_str()) {
-            return Some(name);
-        }
-    }
-    None
+    AgentName::parse_mention(text).map(|(name, _)| name)
 }
 
 fn strip_agent_prefix(text: &str) -> String {
-    if !text.starts_with('@') {
-        return text.to_string();
-    }
-    let after_at = &text[1..];
-    for name in AgentName::ALL {
-        let prefix = name.as_str();
-        if after_at.starts_with(prefix) {
-            let rest = &after_at[prefix.len()..];
-            return rest.trim_start().to_string();
-        }
+    match AgentName::parse_mention(text) {
+        Some((_, rest)) => rest.to_string(),
+        None => text.to_string(),
     }
-    text.to_string()
 }
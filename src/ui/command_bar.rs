@@ -15,31 +15,38 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
         return;
     }
 
-    let input = &app.input_buffer;
-    let cursor = app.input_cursor;
+    let input = app.input.text();
+    let mut input_lines = input.split('\n');
+    let first = input_lines.next().unwrap_or("");
 
     // Build styled input with cursor
     let mut spans = Vec::new();
 
     // Highlight @agent prefix if present
-    if let Some(agent_name) = detect_agent_prefix(input) {
+    if let Some(agent_name) = detect_agent_prefix(first) {
         let prefix = format!("@{} ", agent_name.as_str());
         spans.push(Span::styled(
             prefix.clone(),
             Style::default().fg(agent_color(agent_name)),
         ));
-        let rest: String = input.chars().skip(prefix.len()).collect();
+        let rest: String = first.chars().skip(prefix.len()).collect();
         spans.push(Span::raw(rest));
     } else {
-        spans.push(Span::raw(input.clone()));
+        spans.push(Span::raw(first));
     }
+    let mut lines = vec![Line::from(spans)];
+    lines.extend(input_lines.map(Line::raw));
 
     let title = if detect_agent_prefix(input).is_some() {
         " Message Agent "
     } else if input.is_empty() {
-        " Command — @agent msg | new task title "
+        " Command — @agent msg | /command | new task title "
+    } else if input.starts_with('/') {
+        " Command — /help for a list "
+    } else if app.input.line_count() > 1 {
+        " New Task — first line is the title "
     } else {
-        " New Task "
+        " New Task — shift+enter adds a description "
     };
 
     let block = Block::default()
@@ -47,30 +54,25 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
         .border_style(Style::default().fg(ratatui::style::Color::Yellow))
         .title(title);
 
-    let paragraph = Paragraph::new(Line::from(spans)).block(block);
+    // Scroll so the cursor stays in view
+    let inner_width = area.width.saturating_sub(3) as usize;
+    let inner_height = area.height.saturating_sub(2).max(1) as usize;
+    let (row, col) = app.input.cursor_position();
+    let x_offset = col.saturating_sub(inner_width);
+    let y_offset = (row + 1).saturating_sub(inner_height);
+
+    let paragraph = Paragraph::new(lines).block(block).scroll((
+        y_offset.min(u16::MAX as usize) as u16,
+        x_offset.min(u16::MAX as usize) as u16,
+    ));
     f.render_widget(paragraph, area);
 
     // Position cursor
-    let x = area.x + 1 + cursor as u16;
-    let y = area.y + 1;
-    f.set_cursor_position((x.min(area.x + area.width - 2), y));
+    let x = area.x + 1 + (col - x_offset) as u16;
+    let y = area.y + 1 + (row - y_offset) as u16;
+    f.set_cursor_position((x, y));
 }
 
 fn detect_agent_prefix(input: &str) -> Option<AgentName> {
-    if !input.starts_with('@') {
-        return None;
-    }
-    let after_at = &input[1..];
-    for name in AgentName::ALL {
-        let prefix = name.as_str();
-        if after_at.starts_with(prefix)
-            && after_at
-                .chars()
-                .nth(prefix.len())
-                .map_or(true, |c| c == ' ')
-        {
-            return Some(name);
-        }
-    }
-    None
+    AgentName::parse_mention(input).map(|(name, _)| name)
 }
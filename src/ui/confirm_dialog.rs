@@ -0,0 +1,49 @@
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+
+/// Centered y/n prompt drawn over everything else.
+pub fn render(f: &mut Frame, area: Rect, app: &App) {
+    let Some(confirm) = &app.confirm else {
+        return;
+    };
+
+    let width = 50u16.min(area.width.saturating_sub(4));
+    let height = 5u16.min(area.height);
+    let x = area.x + (area.width.saturating_sub(width)) / 2;
+    let y = area.y + (area.height.saturating_sub(height)) / 2;
+    let popup = Rect::new(x, y, width, height);
+
+    let text = vec![
+        Line::from(Span::styled(
+            confirm.prompt(),
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(vec![
+            Span::styled(" y ", Style::default().fg(Color::Black).bg(Color::Red)),
+            Span::raw(" confirm   "),
+            Span::styled(" n ", Style::default().fg(Color::Black).bg(Color::DarkGray)),
+            Span::raw(" cancel"),
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red))
+                .title(" Confirm "),
+        );
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
+}
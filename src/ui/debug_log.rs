@@ -0,0 +1,58 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::logging;
+
+/// Recent log lines drawn over the main content, newest at the bottom.
+pub fn render(f: &mut Frame, area: Rect, app: &App) {
+    if !app.debug_log {
+        return;
+    }
+    let popup = Rect::new(
+        area.x + 2,
+        area.y + 1,
+        area.width.saturating_sub(4),
+        area.height.saturating_sub(2),
+    );
+
+    let recent = logging::recent();
+    let rows = popup.height.saturating_sub(2) as usize;
+    let end = recent.len().saturating_sub(app.debug_log_scroll);
+    let lines: Vec<Line> = recent[end.saturating_sub(rows)..end]
+        .iter()
+        .map(|line| Line::styled(line.as_str(), Style::default().fg(level_color(line))))
+        .collect();
+
+    let title = if recent.is_empty() {
+        format!(
+            " Debug log — nothing logged yet to {} ",
+            logging::log_path().display()
+        )
+    } else {
+        format!(" Debug log — {} ", logging::log_path().display())
+    };
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray))
+            .title(title),
+    );
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
+}
+
+/// Lines look like `2026-10-16T09:12:03.123Z  WARN work::providers::cache: ...`.
+fn level_color(line: &str) -> Color {
+    match line.split_whitespace().nth(1) {
+        Some("ERROR") => Color::Red,
+        Some("WARN") => Color::Yellow,
+        Some("DEBUG" | "TRACE") => Color::DarkGray,
+        _ => Color::Gray,
+    }
+}
@@ -46,6 +46,13 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
         ]));
     }
 
+    if let Some(due) = &item.due {
+        lines.push(Line::from(vec![
+            Span::styled("Due: ", Style::default().fg(ratatui::style::Color::Gray)),
+            Span::raw(due),
+        ]));
+    }
+
     if let Some(team) = &item.team {
         lines.push(Line::from(vec![
             Span::styled("Team: ", Style::default().fg(ratatui::style::Color::Gray)),
@@ -53,6 +60,49 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
         ]));
     }
 
+    if let Some(usage) = app.store.costs().item_total(&item.id) {
+        lines.push(Line::from(vec![
+            Span::styled("Cost: ", Style::default().fg(ratatui::style::Color::Gray)),
+            Span::raw(usage.to_string()),
+        ]));
+    }
+
+    if let Some(suggestion) = app.triage.get(&item.id) {
+        let mut text = suggestion.annotation(item.priority.as_deref());
+        if let Some(reason) = &suggestion.reason {
+            if !text.is_empty() {
+                text.push_str(" — ");
+            }
+            text.push_str(reason);
+        }
+        lines.push(Line::from(vec![
+            Span::styled("Triage: ", Style::default().fg(ratatui::style::Color::Gray)),
+            Span::styled(text, Style::default().fg(ratatui::style::Color::LightYellow)),
+        ]));
+    }
+
+    if item.is_blocked() {
+        lines.push(Line::from(vec![
+            Span::styled("Blocked by: ", Style::default().fg(ratatui::style::Color::Gray)),
+            Span::styled(
+                item.blocked_by.join(", "),
+                Style::default().fg(ratatui::style::Color::LightRed),
+            ),
+        ]));
+    }
+
+    let linked = app.item_links.partners(&item.source, &item.id);
+    if !linked.is_empty() {
+        let linked: Vec<String> = linked
+            .iter()
+            .map(|l| format!("{} {}", l.source, l.id))
+            .collect();
+        lines.push(Line::from(vec![
+            Span::styled("Linked: ", Style::default().fg(ratatui::style::Color::Gray)),
+            Span::raw(linked.join(", ")),
+        ]));
+    }
+
     if let Some(url) = &item.url {
         lines.push(Line::from(vec![
             Span::styled("URL: ", Style::default().fg(ratatui::style::Color::Gray)),
@@ -62,8 +112,7 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
 
     if let Some(desc) = &item.description {
         lines.push(Line::raw(""));
-        let truncated: String = desc.chars().take(300).collect();
-        lines.push(Line::raw(truncated));
+        lines.extend(description_lines(desc));
     }
 
     let paragraph = Paragraph::new(lines)
@@ -73,7 +122,28 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
                 .border_style(Style::default().fg(ratatui::style::Color::Cyan))
                 .title(" Details "),
         )
-        .wrap(Wrap { trim: true });
+        // Untrimmed, so nested lists, code and table columns stay aligned
+        .wrap(Wrap { trim: false });
 
     f.render_widget(paragraph, area);
 }
+
+/// The description line by line, with fenced code set apart.
+fn description_lines(desc: &str) -> Vec<Line<'static>> {
+    let truncated: String = desc.chars().take(500).collect();
+    let mut in_code = false;
+    truncated
+        .lines()
+        .map(|line| {
+            let style = if line.trim_start().starts_with("```") {
+                in_code = !in_code;
+                Style::default().fg(ratatui::style::Color::DarkGray)
+            } else if in_code {
+                Style::default().fg(ratatui::style::Color::Yellow)
+            } else {
+                Style::default()
+            };
+            Line::styled(line.to_string(), style)
+        })
+        .collect()
+}
@@ -0,0 +1,100 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+
+/// Provider failures drawn over the main content, newest first: what
+/// failed, the HTTP status, the error and what to do about it.
+pub fn render(f: &mut Frame, area: Rect, app: &App) {
+    if !app.error_center {
+        return;
+    }
+    let popup = Rect::new(
+        area.x + 2,
+        area.y + 1,
+        area.width.saturating_sub(4),
+        area.height.saturating_sub(2),
+    );
+
+    let failures = app.errors.failures();
+    let mut lines = Vec::new();
+    let mut selected_line = 0;
+    for (i, failure) in failures.iter().enumerate() {
+        let selected = i == app.selected_error;
+        if selected {
+            selected_line = lines.len();
+        }
+        let marker = if selected { "▸ " } else { "  " };
+        let mut header = vec![
+            Span::raw(marker),
+            Span::styled(
+                failure.provider.clone(),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!("  {}", failure.action)),
+        ];
+        if failure.count > 1 {
+            header.push(Span::styled(
+                format!("  ×{}", failure.count),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        header.push(Span::styled(
+            format!(
+                "  {}",
+                failure.at.with_timezone(&chrono::Local).format("%H:%M")
+            ),
+            Style::default().fg(Color::DarkGray),
+        ));
+        lines.push(Line::from(header));
+
+        let status = failure
+            .status()
+            .map(|s| format!("HTTP {s} · "))
+            .unwrap_or_default();
+        if selected && app.error_expanded {
+            lines.push(Line::raw(format!("    {status}")));
+            for line in failure.message.lines() {
+                lines.push(Line::raw(format!("    {line}")));
+            }
+        } else {
+            lines.push(Line::styled(
+                format!("    {status}{}", failure.excerpt()),
+                Style::default().fg(Color::Gray),
+            ));
+        }
+        if let Some(remedy) = failure.remedy() {
+            lines.push(Line::styled(
+                format!("    → {remedy}"),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        lines.push(Line::raw(""));
+    }
+    if failures.is_empty() {
+        lines.push(Line::styled(
+            "  No provider errors",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    // Keep the selected failure in the upper half once the list overflows
+    let rows = popup.height.saturating_sub(2) as usize;
+    let scroll = selected_line.saturating_sub(rows / 2);
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .scroll((scroll as u16, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red))
+                .title(format!(" Errors ({}) ", failures.len())),
+        );
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
+}
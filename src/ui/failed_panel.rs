@@ -0,0 +1,43 @@
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem},
+    Frame,
+};
+
+use crate::app::App;
+
+/// Items that failed every retry, each with its latest error.
+pub fn render(f: &mut Frame, area: Rect, app: &App) {
+    let failed = app.store.failed_items();
+
+    let items: Vec<ListItem> = failed
+        .iter()
+        .map(|item| {
+            let mut lines = vec![Line::from(vec![
+                Span::styled(
+                    format!("{} ", item.work_item_id),
+                    Style::default().fg(ratatui::style::Color::Red),
+                ),
+                Span::raw(item.work_item_title.as_str()),
+            ])];
+            if let Some(error) = item.errors.last() {
+                lines.push(Line::styled(
+                    format!("  {error}"),
+                    Style::default().fg(ratatui::style::Color::DarkGray),
+                ));
+            }
+            ListItem::new(lines)
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(ratatui::style::Color::Red))
+            .title(format!(" Failed ({}) — /requeue <id> ", failed.len())),
+    );
+
+    f.render_widget(list, area);
+}
@@ -7,11 +7,47 @@ use ratatui::{
 };
 
 use crate::app::{App, ViewMode};
+use crate::model::agent::AgentStatus;
+use crate::ui::Breakpoint;
 
 pub fn render(f: &mut Frame, area: Rect, app: &App) {
     let mut spans = Vec::new();
 
+    // Provider failures, until dismissed in the error center
+    if !app.errors.is_empty() && !app.error_center {
+        let count = app.errors.len();
+        spans.push(Span::styled(
+            format!(" ⚠ {count} error{} ", if count == 1 { "" } else { "s" }),
+            Style::default()
+                .fg(ratatui::style::Color::White)
+                .bg(ratatui::style::Color::Red),
+        ));
+        spans.push(hint("!", "details"));
+        spans.push(Span::raw(" "));
+    }
+
     match &app.view_mode {
+        _ if app.debug_log => {
+            spans.push(hint("↑↓", "scroll"));
+            spans.push(hint("pgup/pgdn", "page"));
+            spans.push(hint("esc", "close log"));
+            spans.push(hint("q", "quit"));
+        }
+        _ if app.error_center => {
+            spans.push(hint("↑↓", "select"));
+            spans.push(hint("enter", "expand"));
+            spans.push(hint("x", "dismiss"));
+            spans.push(hint("X", "dismiss all"));
+            spans.push(hint("esc", "close"));
+            spans.push(hint("q", "quit"));
+        }
+        _ if app.chat_focus => {
+            spans.push(hint("↑↓", "scroll"));
+            spans.push(hint("pgup/pgdn", "page"));
+            spans.push(hint("esc", "close chat"));
+            spans.push(hint(":", "command"));
+            spans.push(hint("q", "quit"));
+        }
         ViewMode::BoardSelection => {
             spans.push(hint("↑↓", "navigate"));
             spans.push(hint("enter", "select"));
@@ -19,26 +55,88 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
         }
         ViewMode::Items => {
             spans.push(hint("↑↓", "navigate"));
+            if Breakpoint::for_width(area.width) == Breakpoint::Narrow {
+                let other = if app.show_item_detail { "list" } else { "detail" };
+                spans.push(hint("tab", other));
+            }
             spans.push(hint("→", "agents"));
             spans.push(hint("d", "dispatch"));
+            spans.push(hint("1-4", "assign"));
+            match app.views.len().min(5) {
+                0 => {}
+                1 => spans.push(hint("5", "view")),
+                n => spans.push(hint(&format!("5-{}", n + 4), "views")),
+            }
+            if !app.item_filter.is_empty() {
+                spans.push(hint("0", "all items"));
+            }
+            spans.push(hint("e", "queue"));
+            spans.push(hint("p", "pin"));
+            spans.push(hint("o", "open"));
+            if app.pomodoro.is_some() {
+                spans.push(hint("t", "stop timer"));
+            } else if app.focus.is_some() {
+                spans.push(hint("t", "pomodoro"));
+            }
+            spans.push(hint("[/]", "reorder"));
+            if let Some(action) = app.undo.peek() {
+                spans.push(hint("u", &format!("undo {}", action.label())));
+            }
+            spans.push(hint("v", "calendar"));
             spans.push(hint("m", "auto mode"));
             spans.push(hint("r", "refresh"));
+            spans.push(hint("z", "chat"));
             spans.push(hint(":", "command"));
             spans.push(hint("q", "quit"));
         }
+        ViewMode::Calendar => {
+            spans.push(hint("←→", "day"));
+            spans.push(hint("↑↓", "week"));
+            spans.push(hint("tab", "month/week"));
+            spans.push(hint("[/]", "pick"));
+            spans.push(hint("enter", "open"));
+            spans.push(hint("esc", "items"));
+            spans.push(hint("q", "quit"));
+        }
         ViewMode::Agents => {
             spans.push(hint("↑↓", "navigate"));
             spans.push(hint("→", "detail"));
             spans.push(hint("←", "items"));
             spans.push(hint("c", "clear agent"));
+            spans.push(hint("y/s", "copy path/shell"));
             spans.push(hint(":", "command"));
             spans.push(hint("q", "quit"));
         }
-        ViewMode::AgentDetail(_) => {
+        ViewMode::AgentDetail(_) if app.branch_diff.is_some() => {
+            spans.push(hint("↑↓/pgup/pgdn", "scroll"));
+            spans.push(hint("[/]", "file"));
+            spans.push(hint("d/←", "close diff"));
+            spans.push(hint("q", "quit"));
+        }
+        ViewMode::AgentDetail(name) => {
+            let status = app.store.get_agent(*name).map(|a| a.status);
             spans.push(hint("↑↓", "scroll"));
+            if status == Some(AgentStatus::Review) {
+                spans.push(hint("pgup/pgdn", "diff"));
+                spans.push(hint("a", "approve"));
+                spans.push(hint("f", "feedback"));
+            } else if status == Some(AgentStatus::Plan) {
+                spans.push(hint("pgup/pgdn", "plan"));
+                if app.is_reviewing_pr(*name) {
+                    spans.push(hint("a", "post review"));
+                } else {
+                    spans.push(hint("a", "approve plan"));
+                }
+                spans.push(hint("f", "add note"));
+            } else {
+                spans.push(hint("pgup/pgdn", "output"));
+            }
+            spans.push(hint("d", "diff"));
+            spans.push(hint("e", app.agent_event_kind.next().as_str()));
             spans.push(hint("←", "agents"));
             spans.push(hint("c", "clear agent"));
             spans.push(hint("x", "clear logs"));
+            spans.push(hint("y/s", "copy path/shell"));
             spans.push(hint(":", "command"));
             spans.push(hint("q", "quit"));
         }
@@ -62,6 +160,69 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
         ));
     }
 
+    if app.daemon.is_some() {
+        spans.push(Span::styled(
+            " daemon",
+            Style::default().fg(ratatui::style::Color::DarkGray),
+        ));
+    }
+
+    // Next scheduled switch of auto mode
+    if let Some((on, left)) = app.schedule_countdown() {
+        // Round up, so the last minute reads 1m rather than 0m
+        let total = left.num_minutes() + 1;
+        let (hours, mins) = (total / 60, total % 60);
+        let left = if hours > 0 {
+            format!("{hours}h{mins:02}m")
+        } else {
+            format!("{mins}m")
+        };
+        let next = if on { "on" } else { "off" };
+        spans.push(Span::styled(
+            format!(" auto {next} in {left}"),
+            Style::default().fg(ratatui::style::Color::DarkGray),
+        ));
+    }
+
+    // Pinned item
+    if let Some(focus) = &app.focus {
+        spans.push(Span::styled(
+            format!(" \u{1F4CC} {} {}", focus.id, focus.elapsed(chrono::Utc::now())),
+            Style::default().fg(ratatui::style::Color::Cyan),
+        ));
+    }
+    if let Some(session) = &app.pomodoro {
+        spans.push(Span::styled(
+            format!(" \u{1F345} {}", session.countdown(chrono::Utc::now())),
+            Style::default().fg(ratatui::style::Color::LightRed),
+        ));
+    }
+
+    // Daily budget
+    if let Some((spent, budget)) = app.budget_status() {
+        let color = if spent >= budget {
+            ratatui::style::Color::Red
+        } else {
+            ratatui::style::Color::DarkGray
+        };
+        spans.push(Span::styled(
+            format!(" ${spent:.2}/${budget:.2} today"),
+            Style::default().fg(color),
+        ));
+    }
+
+    // Incident alert
+    if let Some(alert) = &app.incident_alert {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(
+            format!(" \u{1F6A8} {alert} "),
+            Style::default()
+                .fg(ratatui::style::Color::White)
+                .bg(ratatui::style::Color::Red)
+                .add_modifier(ratatui::style::Modifier::BOLD),
+        ));
+    }
+
     // Flash message
     if let Some((msg, _)) = &app.flash_message {
         spans.push(Span::raw("  "));
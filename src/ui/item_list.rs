@@ -2,31 +2,44 @@ use ratatui::{
     layout::Rect,
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem},
+    widgets::{Block, Borders, List, ListItem, ListState},
     Frame,
 };
 
 use crate::app::App;
-use crate::ui::theme::{agent_color, source_color};
+use crate::providers;
+use crate::ui::theme::{agent_color, source_color, spinner};
 
 pub fn render(f: &mut Frame, area: Rect, app: &App) {
+    let queued = app.queued_item_ids();
+
     let items: Vec<ListItem> = app
         .items
         .iter()
         .enumerate()
+        .filter(|(_, item)| app.item_filter.matches(item))
         .map(|(i, item)| {
             let selected = i == app.selected_item;
 
-            // Agent emoji if assigned
-            let agent_indicator = app
-                .assigned_agent(&item.id)
-                .map(|name| {
-                    Span::styled(
-                        format!("{} ", name.emoji()),
-                        Style::default().fg(agent_color(name)),
-                    )
-                })
-                .unwrap_or_else(|| Span::raw("  "));
+            // Agent emoji if assigned, queue position if waiting for auto mode
+            let agent_indicator = if let Some(name) = app.assigned_agent(&item.id) {
+                Span::styled(
+                    format!("{} ", name.emoji()),
+                    Style::default().fg(agent_color(name)),
+                )
+            } else if let Some(name) = app.stacked_agent(&item.id) {
+                Span::styled(
+                    format!("\u{21B3}{} ", &name.display_name()[..1]),
+                    Style::default().fg(agent_color(name)),
+                )
+            } else if let Some(pos) = queued.iter().position(|id| *id == item.id) {
+                Span::styled(
+                    format!("\u{23F3}{} ", pos + 1),
+                    Style::default().fg(ratatui::style::Color::Yellow),
+                )
+            } else {
+                Span::raw("  ")
+            };
 
             let id_span = Span::styled(
                 format!("{} ", item.id),
@@ -36,13 +49,20 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
             // Truncate title to fit
             let max_title = area.width.saturating_sub(20) as usize;
             let title: String = item.title.chars().take(max_title).collect();
-            let title_style = if selected {
+            let mut title_style = if selected {
                 Style::default()
                     .fg(ratatui::style::Color::Cyan)
                     .add_modifier(Modifier::BOLD)
             } else {
                 Style::default()
             };
+            if item.is_blocked() {
+                title_style = title_style.add_modifier(Modifier::DIM);
+            }
+            let review = providers::is_review_request(item);
+            if review {
+                title_style = title_style.add_modifier(Modifier::ITALIC);
+            }
             let title_span = Span::styled(title, title_style);
 
             let source_span = Span::styled(
@@ -50,16 +70,52 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
                 Style::default().fg(source_color(&item.source)),
             );
 
-            let line = Line::from(vec![agent_indicator, id_span, title_span, source_span]);
+            let mut spans = vec![agent_indicator, id_span, title_span, source_span];
+            if review {
+                spans.push(Span::styled(
+                    " review requested",
+                    Style::default().fg(ratatui::style::Color::Magenta),
+                ));
+            }
+            if app.store.is_failed(&item.id) {
+                spans.push(Span::styled(
+                    " \u{2717} failed",
+                    Style::default().fg(ratatui::style::Color::Red),
+                ));
+            }
+            if let Some(suggestion) = app.triage.get(&item.id) {
+                let note = suggestion.annotation(item.priority.as_deref());
+                if !note.is_empty() {
+                    spans.push(Span::styled(
+                        format!(" {note}"),
+                        Style::default().fg(ratatui::style::Color::LightYellow),
+                    ));
+                }
+            }
+            if item.is_blocked() {
+                spans.push(Span::styled(
+                    format!(" blocked by {}", item.blocked_by.join(", ")),
+                    Style::default().fg(ratatui::style::Color::DarkGray),
+                ));
+            }
+
+            let line = Line::from(spans);
             ListItem::new(line)
         })
         .collect();
 
-    let title = if app.loading {
-        " Work Items (loading...) "
+    let mut title = if app.loading {
+        format!(" Work Items ({} loading) ", spinner(app.frame))
+    } else if !queued.is_empty() {
+        format!(" Work Items ({} queued) ", queued.len())
     } else {
-        " Work Items "
+        " Work Items ".to_string()
     };
+    if let Some(view) = &app.active_view {
+        title.push_str(&format!("[{view}] "));
+    } else if !app.item_filter.is_empty() {
+        title.push_str(&format!("[{}] ", app.item_filter));
+    }
 
     let list = List::new(items).block(
         Block::default()
@@ -68,5 +124,13 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
             .title(title),
     );
 
-    f.render_widget(list, area);
+    // Start from where the list was scrolled, but no further than fills the
+    // panel; rendering scrolls only as far as it takes to show the selection
+    let rows = area.height.saturating_sub(2) as usize;
+    let visible = app.visible_item_indices();
+    let mut state = ListState::default()
+        .with_offset(app.item_scroll.get().min(visible.len().saturating_sub(rows)))
+        .with_selected(visible.iter().position(|&i| i == app.selected_item));
+    f.render_stateful_widget(list, area, &mut state);
+    app.item_scroll.set(state.offset());
 }
@@ -1,36 +1,82 @@
 pub mod agent_detail;
 pub mod agent_panel;
 pub mod board_picker;
+pub mod calendar;
 pub mod chat_panel;
 pub mod command_bar;
+pub mod confirm_dialog;
+pub mod debug_log;
 pub mod detail_panel;
+pub mod error_center;
+pub mod failed_panel;
 pub mod footer;
 pub mod item_list;
+pub mod queue_panel;
 pub mod theme;
 
+#[cfg(test)]
+mod tests;
+
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     Frame,
 };
 
 use crate::app::{App, ViewMode};
 
+/// How many panels fit side by side at a given terminal width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Items, detail and agents
+    Wide,
+    /// Items and detail; agents stay one → away
+    Medium,
+    /// One panel at a time, e.g. a tmux split
+    Narrow,
+}
+
+impl Breakpoint {
+    pub fn for_width(width: u16) -> Self {
+        if width >= 100 {
+            Breakpoint::Wide
+        } else if width >= 70 {
+            Breakpoint::Medium
+        } else {
+            Breakpoint::Narrow
+        }
+    }
+}
+
 pub fn render(f: &mut Frame, app: &App) {
     let size = f.area();
 
-    // Determine bottom bar height: command bar (3) when input active, else footer (1)
-    let bottom_height = if app.input_active { 3 } else { 1 };
+    // Determine bottom bar height: command bar (3, growing with multi-line
+    // input up to 8) when input active, else footer (1)
+    let bottom_height = if app.input_active {
+        app.input.line_count().min(6) as u16 + 2
+    } else {
+        1
+    };
 
     // Determine if chat panel should be visible
-    let show_chat = !app.chat_messages.is_empty() || app.input_active;
+    let show_chat = !app.chat_messages.is_empty() || app.input_active || app.chat_focus;
 
     // Split: main content + chat (optional) + bottom bar
-    let vertical = if show_chat {
+    let vertical = if app.chat_focus {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(0),             // main content hidden
+                Constraint::Min(4),                // chat fills the screen
+                Constraint::Length(bottom_height), // footer or command bar
+            ])
+            .split(size)
+    } else if show_chat {
         Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Min(6),        // main content
-                Constraint::Length(12),     // chat panel
+                Constraint::Length(app.chat_height), // chat panel
                 Constraint::Length(bottom_height), // footer or command bar
             ])
             .split(size)
@@ -50,23 +96,46 @@ pub fn render(f: &mut Frame, app: &App) {
     let bottom_area = vertical[2];
 
     match &app.view_mode {
+        _ if app.chat_focus => {}
         ViewMode::BoardSelection => {
             board_picker::render(f, main_area, app);
         }
-        ViewMode::Items => {
-            // Items (50%) + Detail (25%) + Agents (25%)
-            let horizontal = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints([
-                    Constraint::Percentage(50),
-                    Constraint::Percentage(25),
-                    Constraint::Percentage(25),
-                ])
-                .split(main_area);
+        ViewMode::Items => match Breakpoint::for_width(size.width) {
+            Breakpoint::Wide => {
+                // Items (50%) + Detail (25%) + Agents (25%)
+                let horizontal = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([
+                        Constraint::Percentage(50),
+                        Constraint::Percentage(25),
+                        Constraint::Percentage(25),
+                    ])
+                    .split(main_area);
 
-            item_list::render(f, horizontal[0], app);
-            detail_panel::render(f, horizontal[1], app);
-            agent_panel::render(f, horizontal[2], app);
+                item_list::render(f, horizontal[0], app);
+                render_detail_column(f, horizontal[1], app);
+                agent_panel::render(f, horizontal[2], app);
+            }
+            Breakpoint::Medium => {
+                // Items (55%) + Detail (45%)
+                let horizontal = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+                    .split(main_area);
+
+                item_list::render(f, horizontal[0], app);
+                render_detail_column(f, horizontal[1], app);
+            }
+            Breakpoint::Narrow => {
+                if app.show_item_detail {
+                    render_detail_column(f, main_area, app);
+                } else {
+                    item_list::render(f, main_area, app);
+                }
+            }
+        },
+        ViewMode::Agents if Breakpoint::for_width(size.width) == Breakpoint::Narrow => {
+            agent_panel::render(f, main_area, app);
         }
         ViewMode::Agents => {
             // Items (40%) + Agents (60%)
@@ -76,7 +145,22 @@ pub fn render(f: &mut Frame, app: &App) {
                 .split(main_area);
 
             item_list::render(f, horizontal[0], app);
-            agent_panel::render(f, horizontal[1], app);
+            // Leaderboard under the agents once any have finished items
+            let ranked = agent_panel::leaderboard_rows(app);
+            let height = ranked as u16 + 2;
+            if ranked > 0 && horizontal[1].height >= height + 8 {
+                let vertical = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(0), Constraint::Length(height)])
+                    .split(horizontal[1]);
+                agent_panel::render(f, vertical[0], app);
+                agent_panel::render_leaderboard(f, vertical[1], app);
+            } else {
+                agent_panel::render(f, horizontal[1], app);
+            }
+        }
+        ViewMode::Calendar => {
+            calendar::render(f, main_area, app);
         }
         ViewMode::AgentDetail(name) => {
             // Agent detail takes full width
@@ -95,4 +179,37 @@ pub fn render(f: &mut Frame, app: &App) {
     } else {
         footer::render(f, bottom_area, app);
     }
+
+    error_center::render(f, main_area.union(chat_area), app);
+    debug_log::render(f, main_area.union(chat_area), app);
+    confirm_dialog::render(f, size, app);
+}
+
+/// The selected item's detail, above the dispatch queue and the failed
+/// items when there are any.
+fn render_detail_column(f: &mut Frame, area: Rect, app: &App) {
+    let queued = app.queued_item_ids();
+    let failed = app.store.failed_items().len();
+    let mut constraints = vec![Constraint::Min(0)];
+    if !queued.is_empty() {
+        constraints.push(Constraint::Percentage(40));
+    }
+    if failed > 0 {
+        // Two lines per item, capped so the detail keeps most of the column
+        constraints.push(Constraint::Length((failed * 2 + 2).min(10) as u16));
+    }
+    let column = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(area);
+
+    detail_panel::render(f, column[0], app);
+    let mut next = 1;
+    if !queued.is_empty() {
+        queue_panel::render(f, column[next], app, &queued);
+        next += 1;
+    }
+    if failed > 0 {
+        failed_panel::render(f, column[next], app);
+    }
 }
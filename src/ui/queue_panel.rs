@@ -0,0 +1,58 @@
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem},
+    Frame,
+};
+
+use crate::app::App;
+use crate::ui::theme::{priority_color, source_color};
+
+/// Dispatch queue in pick order. Manually queued items are marked with `•`.
+pub fn render(f: &mut Frame, area: Rect, app: &App, queued: &[&str]) {
+    let selected_id = app.items.get(app.selected_item).map(|i| i.id.as_str());
+
+    let items: Vec<ListItem> = queued
+        .iter()
+        .enumerate()
+        .filter_map(|(pos, id)| {
+            let item = app.items.iter().find(|i| i.id == *id)?;
+            let marker = if app.is_manually_queued(id) {
+                "•"
+            } else {
+                " "
+            };
+            let id_style = if selected_id == Some(*id) {
+                Style::default().fg(ratatui::style::Color::Cyan)
+            } else {
+                Style::default().fg(source_color(&item.source))
+            };
+
+            let mut spans = vec![
+                Span::styled(
+                    format!("{:>2}{marker} ", pos + 1),
+                    Style::default().fg(ratatui::style::Color::Yellow),
+                ),
+                Span::styled(format!("{} ", item.id), id_style),
+            ];
+            if let Some(priority) = &item.priority {
+                spans.push(Span::styled(
+                    format!("{priority} "),
+                    Style::default().fg(priority_color(priority)),
+                ));
+            }
+            spans.push(Span::raw(item.title.as_str()));
+            Some(ListItem::new(Line::from(spans)))
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(ratatui::style::Color::Yellow))
+            .title(format!(" Queue ({}) ", queued.len())),
+    );
+
+    f.render_widget(list, area);
+}
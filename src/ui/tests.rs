@@ -0,0 +1,476 @@
+use std::path::Path;
+
+use ratatui::{backend::TestBackend, Terminal};
+use tokio::sync::{mpsc, Mutex, MutexGuard};
+
+use crate::agents::store::AgentStore;
+use crate::app::{Action, App, BoardLoad};
+use crate::config::AppConfig;
+use crate::daemon::{Reply, Request};
+use crate::event::KeyAction;
+use crate::links::{ItemLinks, ItemRef};
+use crate::model::agent::{AgentName, AgentStats, AgentStatus};
+use crate::providers::ErrorKind;
+
+const WIDTH: u16 = 120;
+const HEIGHT: u16 = 32;
+
+/// Harnesses share the test data dir (item snapshot, board mappings, input
+/// history), so they run one at a time.
+static DATA_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// An `App` over the built-in mock provider, driven by scripted actions the
+/// way the event loop drives it.
+struct Harness {
+    app: App,
+    rx: mpsc::UnboundedReceiver<Action>,
+    _store_dir: tempfile::TempDir,
+    _data: MutexGuard<'static, ()>,
+}
+
+impl Harness {
+    async fn new() -> Self {
+        Self::with_config("").await
+    }
+
+    /// A harness whose config adds `extra` to the mock provider's.
+    async fn with_config(extra: &str) -> Self {
+        let data = DATA_LOCK.lock().await;
+        // As `work init` leaves it
+        std::fs::create_dir_all(crate::config::data_dir()).unwrap();
+        let config: AppConfig = toml::from_str(&format!("[mock]\nitems = 8\n{extra}")).unwrap();
+        let store_dir = tempfile::tempdir().unwrap();
+        let store = AgentStore::open(store_dir.path().join("agents.json")).unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut app = App::new(&config, store, tx);
+        // Shown in the board picker; the real one depends on where tests run
+        app.project_dir = "/home/demo/project".into();
+        Self {
+            app,
+            rx,
+            _store_dir: store_dir,
+            _data: data,
+        }
+    }
+
+    /// Apply an action, then whatever it queued, as the event loop would.
+    async fn send(&mut self, action: Action) {
+        self.app.update(action).await;
+        while let Ok(action) = self.rx.try_recv() {
+            self.app.update(action).await;
+        }
+    }
+
+    async fn keys(&mut self, keys: impl IntoIterator<Item = KeyAction>) {
+        for key in keys {
+            self.send(Action::Key(key)).await;
+        }
+    }
+
+    async fn type_line(&mut self, text: &str) {
+        self.keys([KeyAction::ActivateInput]).await;
+        self.keys(text.chars().map(KeyAction::Char)).await;
+        self.keys([KeyAction::Select]).await;
+    }
+
+    /// List the mock's boards in the picker.
+    async fn load_boards(&mut self) {
+        let config: AppConfig = toml::from_str("[mock]").unwrap();
+        let boards = crate::providers::create_providers(&config)[0]
+            .list_boards()
+            .await
+            .unwrap();
+        self.send(Action::BoardsLoaded("Mock".into(), Ok(boards)))
+            .await;
+    }
+
+    /// Open the mock's first board, loading its items.
+    async fn pick_board(&mut self) {
+        self.load_boards().await;
+        self.keys([KeyAction::Select]).await;
+        // Its confirmation fades on a timer
+        self.app.flash_message = None;
+    }
+
+    /// The screen as text, one line per row with trailing blanks trimmed.
+    fn screen(&self) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+        terminal.draw(|f| super::render(f, &self.app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let mut screen = String::new();
+        for y in 0..buffer.area.height {
+            let row: String = (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect();
+            screen.push_str(row.trim_end());
+            screen.push('\n');
+        }
+        screen
+    }
+}
+
+/// Compare the screen with `src/ui/snapshots/<name>.txt`. `UPDATE_SNAPSHOTS=1`
+/// writes new snapshots and rewrites changed ones after an intended UI change;
+/// without it a missing snapshot fails, so CI can't pass by writing its own.
+fn assert_snapshot(name: &str, screen: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/ui/snapshots")
+        .join(format!("{name}.txt"));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, screen).unwrap();
+        return;
+    }
+    let Ok(expected) = std::fs::read_to_string(&path) else {
+        panic!(
+            "{name} has no snapshot at {}; rerun with UPDATE_SNAPSHOTS=1 to write it\n{screen}",
+            path.display()
+        );
+    };
+    if expected != screen {
+        panic!(
+            "{name} no longer matches {}; rerun with UPDATE_SNAPSHOTS=1 if the change is \
+             intended\n--- expected\n{expected}\n--- rendered\n{screen}",
+            path.display()
+        );
+    }
+}
+
+#[tokio::test]
+async fn board_picker_snapshot() {
+    let mut h = Harness::new().await;
+    h.load_boards().await;
+    h.keys([KeyAction::Down]).await;
+    assert_snapshot("board_picker", &h.screen());
+}
+
+#[tokio::test]
+async fn failed_board_listing_is_a_retry_row() {
+    let mut h = Harness::new().await;
+    h.app.board_loads = vec![
+        ("Mock".into(), BoardLoad::Loading),
+        ("Jira".into(), BoardLoad::Loading),
+    ];
+    h.send(Action::BoardsLoaded("Jira".into(), Err("HTTP 503".into())))
+        .await;
+    let screen = h.screen();
+    assert!(screen.contains("Loading Mock boards"));
+    assert!(screen.contains("> Jira failed, Enter to retry: HTTP 503"));
+
+    // Mock's boards come in above the retry row, which stays selected
+    h.load_boards().await;
+    assert_eq!(h.app.selected_board, 3);
+    h.keys([KeyAction::Up]).await;
+    assert!(h.screen().contains("> Demo Mobile"));
+    h.keys([KeyAction::Down, KeyAction::Down]).await;
+    assert_eq!(h.app.selected_board, 3);
+}
+
+#[tokio::test]
+async fn items_snapshot() {
+    let mut h = Harness::new().await;
+    h.pick_board().await;
+    h.keys([KeyAction::Down, KeyAction::Down]).await;
+    assert_snapshot("items", &h.screen());
+}
+
+#[tokio::test]
+async fn views_switch_with_number_keys() {
+    let mut h = Harness::new().await;
+    h.app.views = toml::from_str::<AppConfig>(
+        "[[views]]\nname = \"Mock only\"\nfilter = \"source:mock\"\n\n\
+         [[views]]\nname = \"Nothing\"\nfilter = \"source:jira\"\n",
+    )
+    .unwrap()
+    .views;
+    h.pick_board().await;
+    assert!(h.screen().contains("5-6:views"));
+
+    h.keys([KeyAction::Char('6')]).await;
+    assert!(h.screen().contains("Work Items [Nothing]"));
+    assert!(h.app.visible_item_indices().is_empty());
+
+    h.keys([KeyAction::Char('0')]).await;
+    assert!(h.screen().contains("Work Items ─"));
+    assert_eq!(h.app.visible_item_indices().len(), h.app.items.len());
+
+    // Keys past the configured views do nothing
+    h.keys([KeyAction::Char('9')]).await;
+    assert!(h.app.active_view.is_none());
+}
+
+#[tokio::test]
+async fn agents_snapshot() {
+    let mut h = Harness::new().await;
+    h.pick_board().await;
+    h.keys([KeyAction::Right]).await;
+    assert_snapshot("agents", &h.screen());
+}
+
+#[tokio::test]
+async fn agents_view_ranks_agents_by_lifetime_stats() {
+    let mut h = Harness::new().await;
+    h.pick_board().await;
+    for (name, done, failed) in [(AgentName::Ember, 3, 0), (AgentName::Tempest, 4, 4)] {
+        h.app
+            .store
+            .update_agent(name, |agent| {
+                agent.stats = AgentStats {
+                    done,
+                    failed,
+                    done_secs: i64::from(done) * 600,
+                    ..Default::default()
+                };
+            })
+            .unwrap();
+    }
+    h.keys([KeyAction::Right]).await;
+    let screen = h.screen();
+    assert!(screen.contains("Leaderboard"));
+    let row = |name: &str| screen.lines().position(|l| l.contains(name) && l.contains("done"));
+    // Ember never failed, so outranks Tempest's larger but shakier tally
+    assert!(row("Ember").unwrap() < row("Tempest").unwrap());
+    assert!(screen.contains("4 done · 50% ok · avg 10m"));
+}
+
+#[tokio::test]
+async fn chat_snapshot() {
+    let mut h = Harness::new().await;
+    h.pick_board().await;
+    h.type_line("/help").await;
+    assert_snapshot("chat", &h.screen());
+}
+
+#[tokio::test]
+async fn fetch_errors_stay_in_the_error_center() {
+    let mut h = Harness::new().await;
+    h.pick_board().await;
+    h.send(Action::FetchError {
+        source: "Jira".into(),
+        action: "fetch items",
+        error: "HTTP 401: Unauthorized".into(),
+        kind: Some(ErrorKind::Auth),
+    })
+    .await;
+    assert!(h.screen().contains("⚠ 1 error"));
+
+    h.keys([KeyAction::Char('!')]).await;
+    let screen = h.screen();
+    assert!(screen.contains("Errors (1)"));
+    assert!(screen.contains("HTTP 401"));
+    assert!(screen.contains("work config validate"));
+
+    h.keys([KeyAction::Char('x'), KeyAction::Escape]).await;
+    assert!(!h.screen().contains("error"));
+}
+
+#[tokio::test]
+async fn refresh_keeps_the_selected_row_in_place() {
+    let mut h = Harness::new().await;
+    h.pick_board().await;
+    let template = h.app.items[0].clone();
+    let item = |n: usize| {
+        let mut item = template.clone();
+        item.id = format!("MOCK-{n}");
+        item.source_id = Some(format!("mock-{n}"));
+        item.title = format!("Task number {n}");
+        item
+    };
+    h.send(Action::WorkItemsLoaded((1..=60).map(item).collect()))
+        .await;
+    h.keys(std::iter::repeat_n(KeyAction::Down, 40)).await;
+    let row = |screen: &str| screen.lines().position(|l| l.contains("Task number 41 "));
+    let before = row(&h.screen());
+    assert!(before.is_some());
+
+    // Two new items above it, one gone below
+    let refreshed = [item(100), item(101)]
+        .into_iter()
+        .chain((1..=59).map(item))
+        .collect();
+    h.send(Action::WorkItemsLoaded(refreshed)).await;
+
+    assert_eq!(h.app.items[h.app.selected_item].id, "MOCK-41");
+    assert_eq!(row(&h.screen()), before);
+}
+
+#[tokio::test]
+async fn items_gone_upstream_close_their_mirrors() {
+    let mut h = Harness::new().await;
+    h.pick_board().await;
+    // In memory only, so the link doesn't outlive the test
+    h.app.item_links = ItemLinks::default();
+    let (closed, mirror) = (h.app.items[0].clone(), h.app.items[1].clone());
+    h.app
+        .item_links
+        .add(ItemRef::of(&closed), ItemRef::of(&mirror))
+        .unwrap();
+
+    let refreshed = h.app.items[1..].to_vec();
+    h.send(Action::WorkItemsLoaded(refreshed)).await;
+
+    assert!(h.app.items.iter().all(|i| i.id != mirror.id));
+    assert!(h.app.item_links.partners(&closed.source, &closed.id).is_empty());
+    let note = format!("Closed linked {} {} with {}", mirror.source, mirror.id, closed.id);
+    assert!(h.app.chat_messages.iter().any(|m| m.text == note));
+}
+
+#[tokio::test]
+async fn daemon_answers_status_and_clear_requests() {
+    let mut h = Harness::new().await;
+    h.pick_board().await;
+
+    let Reply::Ok(status) = h.app.handle_request(Request::Status).await else {
+        panic!("status failed");
+    };
+    assert_eq!(status["auto"], false);
+    assert_eq!(status["agents"].as_array().unwrap().len(), 4);
+
+    let missing = Request::Dispatch {
+        id: "NOPE-1".into(),
+        agent: None,
+    };
+    assert_eq!(
+        h.app.handle_request(missing).await,
+        Reply::Error("No item NOPE-1".into())
+    );
+
+    let clear = Request::Clear {
+        agent: AgentName::Flow,
+    };
+    assert_eq!(
+        h.app.handle_request(clear).await,
+        Reply::Ok("Flow is already idle".into())
+    );
+    let approve = Request::ApprovePlan {
+        agent: AgentName::Flow,
+    };
+    assert_eq!(
+        h.app.handle_request(approve).await,
+        Reply::Error("flow has no plan waiting".into())
+    );
+}
+
+#[tokio::test]
+async fn approved_plans_wait_for_capacity() {
+    let mut h = Harness::with_config("[agents]\nmax_concurrent = 1").await;
+    h.pick_board().await;
+    let (planned, other) = (h.app.items[0].clone(), h.app.items[1].clone());
+    let store = &mut h.app.store;
+    for (name, item) in [(AgentName::Flow, &planned), (AgentName::Ember, &other)] {
+        let (branch, wt) = (format!("agent/{name}"), format!("/wt/{name}"));
+        store
+            .mark_provisioning(name, &item.id, &item.title, &branch, &wt, "/repo")
+            .unwrap();
+    }
+    store.mark_plan(AgentName::Flow).unwrap();
+
+    let approve = Request::ApprovePlan {
+        agent: AgentName::Flow,
+    };
+    assert_eq!(
+        h.app.handle_request(approve).await,
+        Reply::Ok(format!("Can't start {} now — 1/1 agents running", planned.id).into())
+    );
+    let flow = h.app.store.get_agent(AgentName::Flow).unwrap();
+    assert_eq!(flow.status, crate::model::agent::AgentStatus::Plan);
+}
+
+#[tokio::test]
+async fn tui_takes_the_pool_back_when_the_daemon_stops() {
+    let mut h = Harness::new().await;
+    h.pick_board().await;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("daemon.sock");
+    let listener = tokio::net::UnixListener::bind(&path).unwrap();
+    let client = crate::daemon::Client::connect_to(path).await.unwrap();
+    // Answer nothing, so the status request fails fast
+    let listener = tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            drop(stream);
+        }
+    });
+
+    h.send(Action::DaemonProbed(Some(client))).await;
+    assert!(h.app.daemon.is_some());
+
+    listener.abort();
+    h.send(Action::DaemonProbed(None)).await;
+    assert!(h.app.daemon.is_none());
+    let (flash, _) = h.app.flash_message.clone().unwrap();
+    assert_eq!(flash, "Agent daemon stopped — running the pool here");
+}
+
+#[tokio::test]
+async fn agent_views_open_a_shell_in_the_worktree() {
+    let mut h = Harness::new().await;
+    h.pick_board().await;
+    h.keys([KeyAction::Right, KeyAction::Char('s')]).await;
+    assert_eq!(h.app.shell_request, None);
+    assert!(h.screen().contains("Ember has no worktree"));
+
+    h.app
+        .store
+        .mark_provisioning(AgentName::Flow, "ENG-1", "Fix login", "agent/flow", "/wt/agent-flow", "/wt")
+        .unwrap();
+    h.keys([KeyAction::Down, KeyAction::Select, KeyAction::Char('s')]).await;
+    assert_eq!(h.app.shell_request.as_deref(), Some("/wt/agent-flow"));
+
+    // Ctrl+Z suspends to the project, keeping a half-typed command
+    h.app.shell_request = None;
+    h.keys([KeyAction::ActivateInput, KeyAction::Char('/')]).await;
+    h.send(Action::Suspend).await;
+    assert_eq!(h.app.shell_request.as_deref(), Some("/home/demo/project"));
+    assert_eq!(h.app.input.text(), "/");
+}
+
+#[tokio::test]
+async fn quitting_logs_a_running_pomodoro() {
+    let mut h = Harness::new().await;
+    h.pick_board().await;
+    h.app.focus = Some(crate::focus::Focus::of(&h.app.items[0]));
+    h.keys([KeyAction::Char('t')]).await;
+    assert!(h.app.pomodoro.is_some());
+
+    h.send(Action::Quit).await;
+    assert!(h.app.should_quit);
+    assert!(h.app.pomodoro.is_none());
+    let (flash, _) = h.app.flash_message.clone().unwrap();
+    assert!(flash.starts_with("Logged "), "{flash}");
+}
+
+#[tokio::test]
+async fn done_keeps_items_the_provider_refuses_to_move() {
+    let mut h = Harness::new().await;
+    h.pick_board().await;
+    let refused = h.app.items[0].id.clone();
+    let moved = h.app.items[1].id.clone();
+    h.app.items[0].source_id = Some("gone-upstream".into());
+
+    h.type_line(&format!("/done {refused}")).await;
+    assert!(h.app.items.iter().any(|i| i.id == refused));
+
+    h.type_line(&format!("/done {moved}")).await;
+    assert!(!h.app.items.iter().any(|i| i.id == moved));
+}
+
+#[tokio::test]
+async fn incidents_wait_for_capacity_like_any_dispatch() {
+    let mut h =
+        Harness::with_config("[agents]\nmax_concurrent = 0\nescalate_incidents = true").await;
+    h.pick_board().await;
+    let mut incident = h.app.items[0].clone();
+    incident.id = "SENTRY-1".into();
+    incident.source = "Sentry".into();
+    incident.title = "Checkout panics".into();
+    // Ranks as Urgent
+    incident.priority = Some("Critical".into());
+    h.send(Action::WorkItemsLoaded(vec![incident])).await;
+
+    assert_eq!(
+        h.app.incident_alert.as_deref(),
+        Some("P1 SENTRY-1: Checkout panics — waiting: 0/0 agents running")
+    );
+    let agents = h.app.store.get_all();
+    assert!(agents.iter().all(|a| a.status == AgentStatus::Idle));
+}
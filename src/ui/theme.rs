@@ -1,6 +1,7 @@
 use ratatui::style::Color;
 
-use crate::model::agent::{AgentName, AgentStatus};
+use crate::agents::ci::CiStatus;
+use crate::model::agent::{AgentName, AgentStatus, Persona};
 
 pub fn source_color(source: &str) -> Color {
     match source {
@@ -8,6 +9,7 @@ pub fn source_color(source: &str) -> Color {
         "Trello" => Color::Rgb(0x00, 0x79, 0xBF),
         "Jira" => Color::Rgb(0x00, 0x52, 0xCC),
         "GitHub" => Color::White,
+        "Sentry" => Color::Rgb(0x96, 0x5C, 0xC8),
         _ => Color::Gray,
     }
 }
@@ -22,12 +24,19 @@ pub fn priority_color(priority: &str) -> Color {
     }
 }
 
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Braille spinner glyph for the given render frame.
+pub fn spinner(frame: usize) -> &'static str {
+    SPINNER[frame % SPINNER.len()]
+}
+
 pub fn agent_color(name: AgentName) -> Color {
-    match name {
-        AgentName::Ember => Color::Rgb(0xFF, 0x70, 0x43),
-        AgentName::Flow => Color::Rgb(0x4F, 0xC3, 0xF7),
-        AgentName::Tempest => Color::Rgb(0xCE, 0x93, 0xD8),
-        AgentName::Terra => Color::Rgb(0x81, 0xC7, 0x84),
+    match name.persona() {
+        Persona::Ember => Color::Rgb(0xFF, 0x70, 0x43),
+        Persona::Flow => Color::Rgb(0x4F, 0xC3, 0xF7),
+        Persona::Tempest => Color::Rgb(0xCE, 0x93, 0xD8),
+        Persona::Terra => Color::Rgb(0x81, 0xC7, 0x84),
     }
 }
 
@@ -36,28 +45,76 @@ pub fn status_color(status: AgentStatus) -> Color {
         AgentStatus::Idle => Color::Gray,
         AgentStatus::Provisioning => Color::Yellow,
         AgentStatus::Working => Color::Cyan,
+        AgentStatus::Stalled => Color::Magenta,
+        AgentStatus::Plan | AgentStatus::Review => Color::LightYellow,
         AgentStatus::Done => Color::Green,
         AgentStatus::Error => Color::Red,
     }
 }
 
+pub fn ci_color(status: CiStatus) -> Color {
+    match status {
+        CiStatus::Pending => Color::Yellow,
+        CiStatus::Passed => Color::Green,
+        CiStatus::Failed => Color::Red,
+        CiStatus::Missing => Color::DarkGray,
+    }
+}
+
+pub fn diff_line_color(line: &str) -> Color {
+    if line.starts_with("+++") || line.starts_with("---") || line.starts_with("diff ") {
+        Color::White
+    } else if line.starts_with('+') {
+        Color::Green
+    } else if line.starts_with('-') {
+        Color::Red
+    } else if line.starts_with("@@") {
+        Color::Cyan
+    } else {
+        Color::Gray
+    }
+}
+
 pub fn event_color(event: &str) -> Color {
     match event {
         "dispatched" => Color::Blue,
         "provisioning" => Color::Yellow,
         "worktree-ready" => Color::Yellow,
         "working" => Color::Cyan,
+        "file-edited" => Color::LightBlue,
+        "tests-run" => Color::Magenta,
+        "commit" => Color::LightGreen,
+        "pushed" => Color::Green,
+        "pr-opened" => Color::LightCyan,
+        "pr-merged" => Color::Green,
+        "pr-closed" => Color::DarkGray,
+        "ci-passed" => Color::Green,
+        "ci-failed" => Color::LightRed,
+        "ci-followup" => Color::Yellow,
+        "question" => Color::LightYellow,
+        "answered" => Color::White,
+        "review" => Color::LightYellow,
+        "approved" => Color::Green,
         "done" => Color::Green,
         "error" => Color::Red,
+        "stalled" => Color::Magenta,
+        "hygiene-flagged" => Color::LightRed,
+        "verified" => Color::Green,
+        "verify-failed" => Color::LightRed,
         "retry" => Color::Yellow,
         "max-retries" => Color::Red,
         "released" => Color::Gray,
+        "stacked" => Color::Blue,
+        "transcript" => Color::DarkGray,
+        "usage" => Color::DarkGray,
+        "budget-exceeded" => Color::LightRed,
         "cleared" => Color::Magenta,
         "logs-cleared" => Color::DarkGray,
         "mode-change" => Color::Blue,
         "user-message" => Color::White,
         "agent-response" => Color::Cyan,
         "task-created" => Color::Green,
+        "status" => Color::LightCyan,
         _ => Color::White,
     }
 }
@@ -0,0 +1,101 @@
+use std::time::{Duration, Instant};
+
+use crate::model::agent::AgentName;
+use crate::model::work_item::WorkItem;
+
+/// How long after an action `u` can still revert it.
+pub const UNDO_WINDOW: Duration = Duration::from_secs(30);
+
+/// A user action that can be reverted.
+#[derive(Debug, Clone)]
+pub enum Undoable {
+    /// Item moved to done; undo reopens it
+    Done(WorkItem),
+    /// Item given to an agent; undo recalls the agent and puts the item back
+    Dispatch { item: WorkItem, agent: AgentName },
+}
+
+impl Undoable {
+    pub fn label(&self) -> String {
+        match self {
+            Undoable::Done(item) => format!("done {}", item.id),
+            Undoable::Dispatch { item, .. } => format!("dispatch {}", item.id),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct UndoStack {
+    entries: Vec<(Undoable, Instant)>,
+}
+
+impl UndoStack {
+    pub fn push(&mut self, action: Undoable) {
+        self.push_at(action, Instant::now());
+    }
+
+    fn push_at(&mut self, action: Undoable, at: Instant) {
+        self.entries.push((action, at));
+    }
+
+    /// The newest action still inside the undo window.
+    pub fn peek(&self) -> Option<&Undoable> {
+        self.peek_at(Instant::now())
+    }
+
+    fn peek_at(&self, now: Instant) -> Option<&Undoable> {
+        self.entries
+            .last()
+            .filter(|(_, at)| now.duration_since(*at) <= UNDO_WINDOW)
+            .map(|(action, _)| action)
+    }
+
+    /// Take the newest action if still undoable; expired entries are dropped.
+    pub fn pop(&mut self) -> Option<Undoable> {
+        self.pop_at(Instant::now())
+    }
+
+    fn pop_at(&mut self, now: Instant) -> Option<Undoable> {
+        self.entries
+            .retain(|(_, at)| now.duration_since(*at) <= UNDO_WINDOW);
+        self.entries.pop().map(|(action, _)| action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str) -> WorkItem {
+        WorkItem::test(id)
+    }
+
+    #[test]
+    fn pops_newest_first() {
+        let mut stack = UndoStack::default();
+        let now = Instant::now();
+        stack.push_at(Undoable::Done(item("ENG-1")), now);
+        stack.push_at(
+            Undoable::Dispatch {
+                item: item("ENG-2"),
+                agent: AgentName::Flow,
+            },
+            now,
+        );
+        assert_eq!(stack.peek_at(now).unwrap().label(), "dispatch ENG-2");
+        assert_eq!(stack.pop_at(now).unwrap().label(), "dispatch ENG-2");
+        assert_eq!(stack.pop_at(now).unwrap().label(), "done ENG-1");
+        assert!(stack.pop_at(now).is_none());
+    }
+
+    #[test]
+    fn expired_actions_cannot_be_undone() {
+        let mut stack = UndoStack::default();
+        let then = Instant::now();
+        stack.push_at(Undoable::Done(item("ENG-1")), then);
+        let later = then + UNDO_WINDOW + Duration::from_secs(1);
+        assert!(stack.peek_at(later).is_none());
+        assert!(stack.pop_at(later).is_none());
+        assert!(stack.pop_at(then).is_none());
+    }
+}
@@ -1,27 +1,505 @@
-use serde_json::Value;
-
-/// Extract plain text from Jira's Atlassian Document Format (ADF).
-pub fn extract_text_from_adf(value: &Value) -> Option<String> {
-    match value {
-        Value::Null => None,
-        Value::String(s) => Some(s.clone()),
-        Value::Array(arr) => {
-            let parts: Vec<String> = arr.iter().filter_map(extract_text_from_adf).collect();
-            if parts.is_empty() {
-                None
-            } else {
-                Some(parts.join(" "))
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+/// Render Jira's Atlassian Document Format (ADF) as Markdown-style text,
+/// keeping paragraphs, headings, code blocks, lists, tables and links.
+/// `markdown_to_adf` reads the same syntax back.
+pub fn adf_to_markdown(value: &Value) -> Option<String> {
+    adf_to_markdown_with(value, &HashMap::new())
+}
+
+/// `adf_to_markdown`, naming mentions that carry only an account id from
+/// `names` (account id to display name).
+pub fn adf_to_markdown_with(value: &Value, names: &HashMap<String, String>) -> Option<String> {
+    let text = match value {
+        Value::Null => return None,
+        // Older API versions and some webhooks send plain strings
+        Value::String(s) => s.trim_end().to_string(),
+        _ => block(value, names).trim_end().to_string(),
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// Account ids of mentions without a display name in the document.
+pub fn unnamed_mentions(value: &Value) -> Vec<String> {
+    let mut ids = Vec::new();
+    collect_unnamed(value, &mut ids);
+    ids
+}
+
+fn collect_unnamed(node: &Value, ids: &mut Vec<String>) {
+    if node["type"] == "mention" && mention_text(node).is_none() {
+        if let Some(id) = node["attrs"]["id"].as_str() {
+            if !ids.iter().any(|known| known == id) {
+                ids.push(id.to_string());
+            }
+        }
+    }
+    for child in children(node) {
+        collect_unnamed(child, ids);
+    }
+}
+
+fn mention_text(node: &Value) -> Option<&str> {
+    node["attrs"]["text"].as_str().filter(|t| !t.is_empty())
+}
+
+fn children(node: &Value) -> &[Value] {
+    node["content"].as_array().map_or(&[], Vec::as_slice)
+}
+
+fn blocks(nodes: &[Value], separator: &str, names: &HashMap<String, String>) -> String {
+    nodes
+        .iter()
+        .map(|node| block(node, names))
+        .filter(|b| !b.is_empty())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn block(node: &Value, names: &HashMap<String, String>) -> String {
+    match node["type"].as_str().unwrap_or_default() {
+        "paragraph" => inline_text(children(node), names),
+        "heading" => {
+            let level = node["attrs"]["level"].as_u64().unwrap_or(1).clamp(1, 6);
+            format!(
+                "{} {}",
+                "#".repeat(level as usize),
+                inline_text(children(node), names)
+            )
+        }
+        "codeBlock" => {
+            let language = node["attrs"]["language"].as_str().unwrap_or_default();
+            format!("```{language}\n{}\n```", inline_text(children(node), names))
+        }
+        "bulletList" => list_items(children(node), names, |_| "- ".to_string()),
+        "orderedList" => {
+            let start = node["attrs"]["order"].as_u64().unwrap_or(1);
+            list_items(children(node), names, |i| format!("{}. ", start + i as u64))
+        }
+        "blockquote" => blocks(children(node), "\n\n", names)
+            .lines()
+            .map(|line| format!("> {line}").trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        "rule" => "---".to_string(),
+        "table" => table(children(node), names),
+        "text" | "hardBreak" | "mention" | "emoji" | "inlineCard" => {
+            inline_text(std::slice::from_ref(node), names)
+        }
+        // doc, panels and anything newer: keep the text inside
+        _ => blocks(children(node), "\n\n", names),
+    }
+}
+
+/// One line per item; an item's further lines are indented under it.
+fn list_items(
+    items: &[Value],
+    names: &HashMap<String, String>,
+    marker: impl Fn(usize) -> String,
+) -> String {
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let text = blocks(children(item), "\n", names).replace('\n', "\n  ");
+            format!("{}{text}", marker(i))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A pipe table with padded columns, and a separator under a header row.
+fn table(rows: &[Value], names: &HashMap<String, String>) -> String {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            children(row)
+                .iter()
+                .map(|cell| blocks(children(cell), " ", names).replace('\n', " "))
+                .collect()
+        })
+        .collect();
+    let columns = cells.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| {
+            cells
+                .iter()
+                .filter_map(|row| row.get(c))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(3)
+        })
+        .collect();
+    let line = |row: &[String]| {
+        let padded: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(c, &width)| {
+                let cell = row.get(c).map_or("", String::as_str);
+                format!("{cell}{}", " ".repeat(width - cell.chars().count()))
+            })
+            .collect();
+        format!("| {} |", padded.join(" | "))
+    };
+
+    let mut lines = Vec::new();
+    for (i, row) in cells.iter().enumerate() {
+        lines.push(line(row));
+        let header = children(&rows[i])
+            .iter()
+            .all(|cell| cell["type"] == "tableHeader");
+        if i == 0 && header && !row.is_empty() {
+            let dashes: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+            lines.push(format!("| {} |", dashes.join(" | ")));
+        }
+    }
+    lines.join("\n")
+}
+
+fn inline_text(nodes: &[Value], names: &HashMap<String, String>) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        match node["type"].as_str().unwrap_or_default() {
+            "text" => {
+                let text = node["text"].as_str().unwrap_or_default();
+                let marks = node["marks"].as_array().map_or(&[][..], Vec::as_slice);
+                let has = |kind: &str| marks.iter().find(|m| m["type"] == kind);
+                if has("code").is_some() {
+                    out.push_str(&format!("`{text}`"));
+                } else if let Some(href) = has("link").and_then(|m| m["attrs"]["href"].as_str()) {
+                    if href == text {
+                        out.push_str(href);
+                    } else {
+                        out.push_str(&format!("[{text}]({href})"));
+                    }
+                } else {
+                    out.push_str(text);
+                }
+            }
+            "hardBreak" => out.push('\n'),
+            "mention" => match mention_text(node) {
+                Some(text) => out.push_str(text),
+                None => {
+                    let id = node["attrs"]["id"].as_str().unwrap_or_default();
+                    let name = names.get(id).map_or("someone", String::as_str);
+                    out.push_str(&format!("@{name}"));
+                }
+            },
+            "emoji" => out.push_str(node["attrs"]["text"].as_str().unwrap_or_default()),
+            "inlineCard" => out.push_str(node["attrs"]["url"].as_str().unwrap_or_default()),
+            _ => out.push_str(&inline_text(children(node), names)),
+        }
+    }
+    out
+}
+
+/// Build an ADF document from Markdown-style text: paragraphs separated by
+/// blank lines, `#` headings, fenced code blocks, `-`/`*` and numbered
+/// lists, `|` tables, `[links](url)`, bare URLs and `inline code`.
+pub fn markdown_to_adf(text: &str) -> Value {
+    let mut content = Vec::new();
+    let mut lines = text.lines().peekable();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        if let Some(language) = trimmed.strip_prefix("```") {
+            let mut code = Vec::new();
+            for line in lines.by_ref() {
+                if line.trim_start().starts_with("```") {
+                    break;
+                }
+                code.push(line);
+            }
+            let mut node = json!({ "type": "codeBlock", "content": [] });
+            if !language.trim().is_empty() {
+                node["attrs"] = json!({ "language": language.trim() });
+            }
+            if !code.is_empty() {
+                node["content"] = json!([{ "type": "text", "text": code.join("\n") }]);
             }
+            content.push(node);
+        } else if trimmed.is_empty() {
+            continue;
+        } else if let Some((level, heading)) = heading(trimmed) {
+            content.push(json!({
+                "type": "heading",
+                "attrs": { "level": level },
+                "content": inline(heading),
+            }));
+        } else if let Some((ordered, item)) = list_item(trimmed) {
+            let mut items = vec![item];
+            while let Some((next_ordered, next)) = lines.peek().and_then(|l| list_item(l.trim())) {
+                if next_ordered != ordered {
+                    break;
+                }
+                items.push(next);
+                lines.next();
+            }
+            let items: Vec<Value> = items
+                .into_iter()
+                .map(|item| json!({ "type": "listItem", "content": [paragraph(&[item])] }))
+                .collect();
+            let kind = if ordered { "orderedList" } else { "bulletList" };
+            content.push(json!({ "type": kind, "content": items }));
+        } else if trimmed.starts_with('|') {
+            let mut rows = vec![table_cells(trimmed)];
+            while let Some(next) = lines
+                .peek()
+                .map(|l| l.trim())
+                .filter(|l| l.starts_with('|'))
+            {
+                rows.push(table_cells(next));
+                lines.next();
+            }
+            content.push(table_node(rows));
+        } else {
+            let mut paragraph_lines = vec![trimmed];
+            while let Some(next) = lines.peek().map(|l| l.trim()) {
+                if next.is_empty()
+                    || next.starts_with("```")
+                    || heading(next).is_some()
+                    || list_item(next).is_some()
+                    || next.starts_with('|')
+                {
+                    break;
+                }
+                paragraph_lines.push(next);
+                lines.next();
+            }
+            content.push(paragraph(&paragraph_lines));
+        }
+    }
+
+    json!({ "type": "doc", "version": 1, "content": content })
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let text = line[level..].strip_prefix(' ')?;
+    (1..=6).contains(&level).then_some((level, text.trim()))
+}
+
+/// A list line's text, and whether the list is numbered.
+fn list_item(line: &str) -> Option<(bool, &str)> {
+    if let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return Some((false, item.trim()));
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let item = line[digits..].strip_prefix(". ")?;
+    (digits > 0).then_some((true, item.trim()))
+}
+
+fn table_cells(line: &str) -> Vec<&str> {
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line.strip_suffix('|').unwrap_or(line);
+    line.split('|').map(str::trim).collect()
+}
+
+/// A table from its rows' cells; a `---` row under the first makes it a
+/// header row.
+fn table_node(mut rows: Vec<Vec<&str>>) -> Value {
+    let is_separator = |row: &Vec<&str>| {
+        row.iter()
+            .all(|cell| !cell.is_empty() && cell.chars().all(|c| c == '-' || c == ':'))
+    };
+    let header = rows.len() > 1 && is_separator(&rows[1]);
+    if header {
+        rows.remove(1);
+    }
+    let rows: Vec<Value> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let kind = if header && i == 0 {
+                "tableHeader"
+            } else {
+                "tableCell"
+            };
+            let cells: Vec<Value> = row
+                .iter()
+                .map(|cell| json!({ "type": kind, "content": [paragraph(&[cell])] }))
+                .collect();
+            json!({ "type": "tableRow", "content": cells })
+        })
+        .collect();
+    json!({ "type": "table", "content": rows })
+}
+
+/// Lines of one paragraph, kept apart with hard breaks.
+fn paragraph(lines: &[&str]) -> Value {
+    let mut content = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            content.push(json!({ "type": "hardBreak" }));
+        }
+        content.extend(inline(line));
+    }
+    json!({ "type": "paragraph", "content": content })
+}
+
+fn text_node(text: &str, marks: Option<Value>) -> Value {
+    let mut node = json!({ "type": "text", "text": text });
+    if let Some(marks) = marks {
+        node["marks"] = marks;
+    }
+    node
+}
+
+fn link_node(text: &str, href: &str) -> Value {
+    text_node(
+        text,
+        Some(json!([{ "type": "link", "attrs": { "href": href } }])),
+    )
+}
+
+/// `[label](url)` at the start of `s`, with its length.
+fn markdown_link(s: &str) -> Option<(&str, &str, usize)> {
+    let close = s.find("](")?;
+    let label = &s[1..close];
+    let end = close + 2 + s[close + 2..].find(')')?;
+    let url = &s[close + 2..end];
+    let valid = !label.is_empty() && !label.contains(']') && !url.contains(char::is_whitespace);
+    (valid && url.contains("://")).then_some((label, url, end + 1))
+}
+
+fn inline(text: &str) -> Vec<Value> {
+    let mut nodes = Vec::new();
+    let mut plain = String::new();
+    let flush = |plain: &mut String, nodes: &mut Vec<Value>| {
+        if !plain.is_empty() {
+            nodes.push(text_node(plain, None));
+            plain.clear();
         }
-        Value::Object(obj) => {
-            if obj.get("type").and_then(|v| v.as_str()) == Some("text") {
-                return obj.get("text").and_then(|v| v.as_str()).map(String::from);
+    };
+
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '`' {
+            if let Some(end) = rest[1..].find('`').filter(|&end| end > 0) {
+                flush(&mut plain, &mut nodes);
+                nodes.push(text_node(&rest[1..=end], Some(json!([{ "type": "code" }]))));
+                rest = &rest[end + 2..];
+                continue;
             }
-            if let Some(content) = obj.get("content") {
-                return extract_text_from_adf(content);
+        } else if c == '[' {
+            if let Some((label, url, len)) = markdown_link(rest) {
+                flush(&mut plain, &mut nodes);
+                nodes.push(link_node(label, url));
+                rest = &rest[len..];
+                continue;
             }
-            None
+        } else if rest.starts_with("https://") || rest.starts_with("http://") {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            // Sentence punctuation after a URL isn't part of it
+            let url = rest[..end].trim_end_matches(['.', ',', ';', ':', ')', '!', '?']);
+            flush(&mut plain, &mut nodes);
+            nodes.push(link_node(url, url));
+            rest = &rest[url.len()..];
+            continue;
         }
-        _ => None,
+        plain.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    flush(&mut plain, &mut nodes);
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_round_trips_through_adf() {
+        let text = "# Login fails\n\nUsers on SSO see a 500.\nSee [the log](https://example.com/log) or https://example.com/run.\n\n- Check `auth.rs`\n- Retry\n\n1. Reproduce\n2. Fix\n\n```rust\nfn main() {}\n```";
+        let doc = markdown_to_adf(text);
+        let types: Vec<&str> = doc["content"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| b["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            types,
+            [
+                "heading",
+                "paragraph",
+                "bulletList",
+                "orderedList",
+                "codeBlock"
+            ]
+        );
+        assert_eq!(doc["content"][4]["attrs"]["language"], "rust");
+        assert_eq!(adf_to_markdown(&doc).as_deref(), Some(text));
+    }
+
+    #[test]
+    fn reads_nested_lists_and_unknown_nodes() {
+        let doc = json!({
+            "type": "doc",
+            "content": [
+                { "type": "bulletList", "content": [{
+                    "type": "listItem",
+                    "content": [
+                        { "type": "paragraph", "content": [{ "type": "text", "text": "outer" }] },
+                        { "type": "bulletList", "content": [{
+                            "type": "listItem",
+                            "content": [{ "type": "paragraph", "content": [
+                                { "type": "mention", "attrs": { "text": "@Ana" } },
+                                { "type": "text", "text": " inner" }
+                            ] }]
+                        }] }
+                    ]
+                }] },
+                { "type": "panel", "content": [
+                    { "type": "paragraph", "content": [{ "type": "text", "text": "note" }] }
+                ] },
+                { "type": "mediaSingle", "content": [{ "type": "media" }] }
+            ]
+        });
+        assert_eq!(
+            adf_to_markdown(&doc).as_deref(),
+            Some("- outer\n  - @Ana inner\n\nnote")
+        );
+        assert_eq!(adf_to_markdown(&Value::Null), None);
+        assert_eq!(adf_to_markdown(&json!("plain")).as_deref(), Some("plain"));
+    }
+
+    #[test]
+    fn aligns_tables_and_round_trips_them() {
+        let text = "| Step  | Owner |\n| ----- | ----- |\n| Login | `auth` |\n| Retry | ops   |";
+        let doc = markdown_to_adf(text);
+        assert_eq!(doc["content"][0]["type"], "table");
+        assert_eq!(
+            doc["content"][0]["content"][0]["content"][0]["type"],
+            "tableHeader"
+        );
+        assert_eq!(
+            doc["content"][0]["content"][1]["content"][0]["type"],
+            "tableCell"
+        );
+        assert_eq!(
+            adf_to_markdown(&doc).as_deref(),
+            Some("| Step  | Owner  |\n| ----- | ------ |\n| Login | `auth` |\n| Retry | ops    |")
+        );
+    }
+
+    #[test]
+    fn names_mentions_by_account_id() {
+        let doc = json!({ "type": "doc", "content": [{ "type": "paragraph", "content": [
+            { "type": "mention", "attrs": { "id": "acc-1" } },
+            { "type": "text", "text": " and " },
+            { "type": "mention", "attrs": { "id": "acc-2", "text": "@Bo" } },
+            { "type": "text", "text": " and " },
+            { "type": "mention", "attrs": { "id": "acc-3", "text": "" } }
+        ] }] });
+        assert_eq!(unnamed_mentions(&doc), ["acc-1", "acc-3"]);
+
+        let names = HashMap::from([("acc-1".to_string(), "Ana".to_string())]);
+        assert_eq!(
+            adf_to_markdown_with(&doc, &names).as_deref(),
+            Some("@Ana and @Bo and @someone")
+        );
     }
 }
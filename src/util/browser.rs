@@ -0,0 +1,17 @@
+use anyhow::{Context, Result};
+
+/// Open a URL in the default browser without waiting for it.
+pub fn open(url: &str) -> Result<()> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    std::process::Command::new(opener)
+        .arg(url)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {opener}"))?;
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use std::path::Path;
+use std::sync::OnceLock;
+
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+
+use crate::agents::diff::DiffFile;
+
+/// A run of text in one colour.
+pub type Run = ((u8, u8, u8), String);
+
+fn syntaxes() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme() -> &'static Theme {
+    static THEME: OnceLock<Theme> = OnceLock::new();
+    THEME.get_or_init(|| {
+        let mut themes = ThemeSet::load_defaults().themes;
+        themes
+            .remove("base16-ocean.dark")
+            .expect("syntect ships base16-ocean.dark")
+    })
+}
+
+/// Syntax colours for the code on each line of a unified diff, after its
+/// `+`/`-`/` ` marker. Headers, and files whose language isn't known by
+/// extension, get no runs and keep the plain diff colours.
+pub fn highlight_diff(lines: &[String], files: &[DiffFile]) -> Vec<Vec<Run>> {
+    let mut runs = vec![Vec::new(); lines.len()];
+    for (i, file) in files.iter().enumerate() {
+        let end = files.get(i + 1).map_or(lines.len(), |next| next.start);
+        let Some(syntax) = Path::new(&file.path)
+            .extension()
+            .and_then(|ext| syntaxes().find_syntax_by_extension(&ext.to_string_lossy()))
+        else {
+            continue;
+        };
+        // Old and new lines share one state; a hunk starts from scratch since
+        // the lines before it aren't in the diff
+        let mut highlighter = None;
+        for (n, line) in lines.iter().enumerate().take(end).skip(file.start) {
+            if line.starts_with("@@") {
+                highlighter = Some(HighlightLines::new(syntax, theme()));
+                continue;
+            }
+            let Some(highlighter) = highlighter.as_mut() else {
+                continue;
+            };
+            let Some(code) = line.get(1..).filter(|_| line.starts_with(['+', '-', ' '])) else {
+                continue;
+            };
+            let code = format!("{code}\n");
+            let Ok(ranges) = highlighter.highlight_line(&code, syntaxes()) else {
+                continue;
+            };
+            runs[n] = ranges
+                .into_iter()
+                .map(|(style, text)| {
+                    let c = style.foreground;
+                    ((c.r, c.g, c.b), text.trim_end_matches('\n').to_string())
+                })
+                .filter(|(_, text)| !text.is_empty())
+                .collect();
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::diff::parse_files;
+
+    #[test]
+    fn colours_code_in_known_languages_only() {
+        let diff = "diff --git a/src/main.rs b/src/main.rs\n--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1 +1 @@\n-fn old() {}\n+fn main() {}\ndiff --git a/notes.unknownext b/notes.unknownext\n@@ -1 +1 @@\n+plain text";
+        let lines: Vec<String> = diff.lines().map(String::from).collect();
+        let runs = highlight_diff(&lines, &parse_files(&lines));
+
+        assert!(runs[..4].iter().all(Vec::is_empty));
+        let added: String = runs[5].iter().map(|(_, text)| text.as_str()).collect();
+        assert_eq!(added, "fn main() {}");
+        // `fn` and `main` are coloured differently
+        assert!(runs[5].len() > 1);
+        assert!(runs[8].is_empty());
+    }
+}
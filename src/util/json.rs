@@ -0,0 +1,38 @@
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+
+/// Parse the JSON array in a model's `what` reply (e.g. "triage"),
+/// tolerating code fences or prose around it.
+pub fn parse_json_array<T: DeserializeOwned>(reply: &str, what: &str) -> Result<Vec<T>> {
+    let start = reply
+        .find('[')
+        .with_context(|| format!("No JSON array in {what} reply"))?;
+    let end = reply
+        .rfind(']')
+        .with_context(|| format!("No JSON array in {what} reply"))?;
+    serde_json::from_str(&reply[start..=end])
+        .with_context(|| format!("Failed to parse {what} reply"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fenced_reply() {
+        let reply = r#"Here you go:
+```json
+[{"id": "ENG-1"}, {"id": "ENG-2"}]
+```"#;
+        let values: Vec<serde_json::Value> = parse_json_array(reply, "triage").unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[1]["id"], "ENG-2");
+
+        let err = parse_json_array::<serde_json::Value>("I can't help with that", "triage");
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "No JSON array in triage reply"
+        );
+        assert!(parse_json_array::<u32>("[\"one\"]", "split").is_err());
+    }
+}
@@ -1 +1,5 @@
 pub mod adf;
+pub mod browser;
+pub mod clipboard;
+pub mod highlight;
+pub mod json;
@@ -0,0 +1,483 @@
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha1::Sha1;
+use sha2::Sha256;
+
+use crate::model::work_item::WorkItem;
+use crate::providers::linear::map_priority;
+use crate::util::adf::adf_to_markdown;
+
+/// An incremental item change pushed by a provider webhook.
+#[derive(Debug, Clone)]
+pub enum ItemUpdate {
+    /// New or changed item. Fields the payload doesn't carry are left `None`
+    /// or empty and keep their current value when merged.
+    Upsert(Box<WorkItem>),
+    /// Item closed, completed or deleted upstream
+    Remove {
+        source: String,
+        id: String,
+        source_id: Option<String>,
+    },
+}
+
+impl ItemUpdate {
+    /// Apply the update to a list of items, merging into an existing entry.
+    /// Items are matched on `WorkItem::key`, like a full reload.
+    pub fn apply(self, items: &mut Vec<WorkItem>) {
+        match self {
+            ItemUpdate::Upsert(update) => {
+                match items.iter_mut().find(|i| i.key() == update.key()) {
+                    Some(item) => merge(item, *update),
+                    None => items.push(*update),
+                }
+            }
+            ItemUpdate::Remove {
+                source,
+                id,
+                source_id,
+            } => {
+                let key = (source.as_str(), source_id.as_deref().unwrap_or(&id));
+                items.retain(|i| i.key() != key);
+                for item in items.iter_mut().filter(|i| i.source == source) {
+                    item.blocked_by.retain(|blocker| *blocker != id);
+                }
+            }
+        }
+    }
+}
+
+fn merge(item: &mut WorkItem, update: WorkItem) {
+    if !update.title.is_empty() {
+        item.title = update.title;
+    }
+    if !update.labels.is_empty() {
+        item.labels = update.labels;
+    }
+    item.source_id = update.source_id.or(item.source_id.take());
+    item.description = update.description.or(item.description.take());
+    item.status = update.status.or(item.status.take());
+    item.priority = update.priority.or(item.priority.take());
+    item.team = update.team.or(item.team.take());
+    item.url = update.url.or(item.url.take());
+    item.due = update.due.or(item.due.take());
+}
+
+/// Parse a webhook payload from `provider` (the path segment after
+/// `/webhooks/`). Events that don't concern an item return None.
+pub fn parse_webhook(provider: &str, body: &Value) -> Option<ItemUpdate> {
+    match provider {
+        "github" => parse_github(body),
+        "linear" => parse_linear(body),
+        "trello" => parse_trello(body),
+        "jira" => parse_jira(body),
+        _ => None,
+    }
+}
+
+/// Check a delivery's signature against the provider's shared secret.
+/// `header` looks up a request header by lowercase name. Trello signs the
+/// body followed by the `callback_url` the webhook was registered with.
+pub fn verify_signature(
+    provider: &str,
+    secret: &str,
+    header: impl Fn(&str) -> Option<String>,
+    body: &[u8],
+    callback_url: &str,
+) -> bool {
+    match provider {
+        "github" => header("x-hub-signature-256")
+            .and_then(|sig| Some(verify_sha256(secret, body, sig.strip_prefix("sha256=")?)))
+            .unwrap_or(false),
+        "linear" => header("linear-signature")
+            .map(|sig| verify_sha256(secret, body, &sig))
+            .unwrap_or(false),
+        "jira" => header("x-hub-signature")
+            .and_then(|sig| Some(verify_sha256(secret, body, sig.strip_prefix("sha256=")?)))
+            .unwrap_or(false),
+        "trello" => {
+            let Some(sig) = header("x-trello-webhook") else {
+                return false;
+            };
+            let Ok(sig) = base64::engine::general_purpose::STANDARD.decode(sig.trim()) else {
+                return false;
+            };
+            let Ok(mut mac) = Hmac::<Sha1>::new_from_slice(secret.as_bytes()) else {
+                return false;
+            };
+            mac.update(body);
+            mac.update(callback_url.as_bytes());
+            mac.verify_slice(&sig).is_ok()
+        }
+        _ => false,
+    }
+}
+
+fn verify_sha256(secret: &str, body: &[u8], hex_sig: &str) -> bool {
+    let Some(sig) = decode_hex(hex_sig.trim()) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&sig).is_ok()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Board a payload belongs to, in the same terms as a board mapping's
+/// `boardId`: the repo for GitHub, the board for Trello and the project key
+/// for Jira. Linear has no boards.
+pub fn webhook_board(provider: &str, body: &Value) -> Option<String> {
+    match provider {
+        "github" => str_at(body, "/repository/full_name"),
+        "trello" => str_at(body, "/action/data/board/id"),
+        "jira" => str_at(body, "/issue/fields/project/key"),
+        _ => None,
+    }
+}
+
+fn str_at(value: &Value, pointer: &str) -> Option<String> {
+    value.pointer(pointer)?.as_str().map(String::from)
+}
+
+fn label_names(labels: Option<&Value>) -> Vec<String> {
+    labels
+        .and_then(|l| l.as_array())
+        .map(|l| {
+            l.iter()
+                .filter_map(|l| l.as_str().or_else(|| l.get("name")?.as_str()))
+                .filter(|n| !n.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_github(body: &Value) -> Option<ItemUpdate> {
+    let issue = body.get("issue")?;
+    let id = format!("#{}", issue.get("number")?.as_u64()?);
+    let action = body.get("action").and_then(|a| a.as_str()).unwrap_or("");
+    let url = str_at(issue, "/html_url");
+    if matches!(action, "closed" | "deleted" | "transferred") {
+        return Some(ItemUpdate::Remove {
+            source: "GitHub".into(),
+            id,
+            source_id: url,
+        });
+    }
+    Some(ItemUpdate::Upsert(Box::new(WorkItem {
+        id,
+        source_id: url.clone(),
+        title: str_at(issue, "/title").unwrap_or_default(),
+        description: str_at(issue, "/body").map(|d| d.chars().take(500).collect()),
+        status: str_at(issue, "/state"),
+        priority: None,
+        labels: label_names(issue.get("labels")),
+        source: "GitHub".into(),
+        team: str_at(body, "/repository/full_name"),
+        url,
+        links: Vec::new(),
+        comments: Vec::new(),
+        blocked_by: Vec::new(),
+        due: None,
+    })))
+}
+
+fn parse_linear(body: &Value) -> Option<ItemUpdate> {
+    if body.get("type")?.as_str()? != "Issue" {
+        return None;
+    }
+    let data = body.get("data")?;
+    let id = str_at(data, "/identifier")?;
+    let finished = matches!(
+        data.pointer("/state/type").and_then(|t| t.as_str()),
+        Some("completed" | "canceled")
+    );
+    if finished || body.get("action").and_then(|a| a.as_str()) == Some("remove") {
+        return Some(ItemUpdate::Remove {
+            source: "Linear".into(),
+            id,
+            source_id: str_at(data, "/id"),
+        });
+    }
+    Some(ItemUpdate::Upsert(Box::new(WorkItem {
+        id,
+        source_id: str_at(data, "/id"),
+        title: str_at(data, "/title").unwrap_or_default(),
+        description: str_at(data, "/description"),
+        status: str_at(data, "/state/name"),
+        priority: map_priority(
+            data.get("priority")
+                .and_then(|p| p.as_u64())
+                .map(|p| p as u8),
+        ),
+        labels: label_names(data.get("labels")),
+        source: "Linear".into(),
+        team: str_at(data, "/team/name"),
+        url: str_at(data, "/url"),
+        links: Vec::new(),
+        comments: Vec::new(),
+        blocked_by: Vec::new(),
+        due: None,
+    })))
+}
+
+fn parse_trello(body: &Value) -> Option<ItemUpdate> {
+    let action = body.get("action")?;
+    let card = action.pointer("/data/card")?;
+    let card_id = str_at(card, "/id")?;
+    let id = card_id[..8.min(card_id.len())].to_string();
+    let kind = action.get("type").and_then(|t| t.as_str()).unwrap_or("");
+    if kind == "deleteCard" || card.get("closed").and_then(|c| c.as_bool()) == Some(true) {
+        return Some(ItemUpdate::Remove {
+            source: "Trello".into(),
+            id,
+            source_id: Some(card_id),
+        });
+    }
+    Some(ItemUpdate::Upsert(Box::new(WorkItem {
+        id,
+        source_id: Some(card_id),
+        title: str_at(card, "/name").unwrap_or_default(),
+        description: str_at(card, "/desc").filter(|d| !d.trim().is_empty()),
+        // Moves carry the new list as listAfter
+        status: str_at(action, "/data/listAfter/name")
+            .or_else(|| str_at(action, "/data/list/name")),
+        priority: None,
+        labels: Vec::new(),
+        source: "Trello".into(),
+        team: str_at(action, "/data/board/name"),
+        url: str_at(card, "/shortLink").map(|l| format!("https://trello.com/c/{l}")),
+        links: Vec::new(),
+        comments: Vec::new(),
+        blocked_by: Vec::new(),
+        due: None,
+    })))
+}
+
+fn parse_jira(body: &Value) -> Option<ItemUpdate> {
+    let issue = body.get("issue")?;
+    let id = str_at(issue, "/key")?;
+    let deleted = body.get("webhookEvent").and_then(|e| e.as_str()) == Some("jira:issue_deleted");
+    let done = str_at(issue, "/fields/status/statusCategory/key").as_deref() == Some("done");
+    if deleted || done {
+        return Some(ItemUpdate::Remove {
+            source: "Jira".into(),
+            id: id.clone(),
+            source_id: Some(id),
+        });
+    }
+    let fields = issue.get("fields")?;
+    Some(ItemUpdate::Upsert(Box::new(WorkItem {
+        id: id.clone(),
+        source_id: Some(id),
+        title: str_at(fields, "/summary").unwrap_or_default(),
+        description: fields.get("description").and_then(adf_to_markdown),
+        status: str_at(fields, "/status/name"),
+        priority: str_at(fields, "/priority/name"),
+        labels: label_names(fields.get("labels")),
+        source: "Jira".into(),
+        team: str_at(fields, "/project/name"),
+        url: None,
+        links: Vec::new(),
+        comments: Vec::new(),
+        blocked_by: Vec::new(),
+        due: None,
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn upsert(update: Option<ItemUpdate>) -> WorkItem {
+        match update {
+            Some(ItemUpdate::Upsert(item)) => *item,
+            other => panic!("expected upsert, got {other:?}"),
+        }
+    }
+
+    fn removed_id(update: Option<ItemUpdate>) -> String {
+        match update {
+            Some(ItemUpdate::Remove { id, .. }) => id,
+            other => panic!("expected remove, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn github_issue_events() {
+        let opened = json!({
+            "action": "opened",
+            "issue": {
+                "number": 17,
+                "title": "Crash on start",
+                "state": "open",
+                "labels": [{ "name": "bug" }],
+                "html_url": "https://github.com/o/r/issues/17"
+            },
+            "repository": { "full_name": "o/r" }
+        });
+        let item = upsert(parse_webhook("github", &opened));
+        assert_eq!(item.id, "#17");
+        assert_eq!(item.labels, vec!["bug"]);
+        assert_eq!(item.team.as_deref(), Some("o/r"));
+
+        let closed = json!({ "action": "closed", "issue": { "number": 17 } });
+        assert_eq!(removed_id(parse_webhook("github", &closed)), "#17");
+
+        // Pushes and other non-issue events are ignored
+        assert!(parse_webhook("github", &json!({ "ref": "refs/heads/main" })).is_none());
+    }
+
+    #[test]
+    fn linear_issue_events() {
+        let update = json!({
+            "action": "update",
+            "type": "Issue",
+            "data": {
+                "id": "uuid-1",
+                "identifier": "ENG-42",
+                "title": "Fix login",
+                "priority": 1,
+                "state": { "name": "In Progress", "type": "started" }
+            }
+        });
+        let item = upsert(parse_webhook("linear", &update));
+        assert_eq!(item.id, "ENG-42");
+        assert_eq!(item.priority.as_deref(), Some("Urgent"));
+
+        let done = json!({
+            "action": "update",
+            "type": "Issue",
+            "data": { "identifier": "ENG-42", "state": { "type": "completed" } }
+        });
+        assert_eq!(removed_id(parse_webhook("linear", &done)), "ENG-42");
+
+        let comment = json!({ "action": "create", "type": "Comment", "data": {} });
+        assert!(parse_webhook("linear", &comment).is_none());
+    }
+
+    #[test]
+    fn trello_and_jira_events() {
+        let moved = json!({
+            "action": {
+                "type": "updateCard",
+                "data": {
+                    "card": { "id": "abcdef1234567890", "name": "Card" },
+                    "listAfter": { "name": "Doing" }
+                }
+            }
+        });
+        let item = upsert(parse_webhook("trello", &moved));
+        assert_eq!(item.id, "abcdef12");
+        assert_eq!(item.status.as_deref(), Some("Doing"));
+
+        let done = json!({
+            "webhookEvent": "jira:issue_updated",
+            "issue": {
+                "key": "PROJ-1",
+                "fields": { "status": { "name": "Done", "statusCategory": { "key": "done" } } }
+            }
+        });
+        assert_eq!(removed_id(parse_webhook("jira", &done)), "PROJ-1");
+    }
+
+    #[test]
+    fn signatures_are_checked_per_provider() {
+        let body = br#"{"action":"opened"}"#;
+        let headers = |name: &'static str, value: String| {
+            move |h: &str| (h == name).then(|| value.clone())
+        };
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"s3cret").unwrap();
+        mac.update(body);
+        let hex: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+
+        let github = headers("x-hub-signature-256", format!("sha256={hex}"));
+        assert!(verify_signature("github", "s3cret", &github, body, ""));
+        assert!(!verify_signature("github", "other", &github, body, ""));
+        assert!(!verify_signature("github", "s3cret", &github, b"{}", ""));
+        assert!(verify_signature("linear", "s3cret", headers("linear-signature", hex.clone()), body, ""));
+        assert!(verify_signature("jira", "s3cret", headers("x-hub-signature", format!("sha256={hex}")), body, ""));
+        // Missing header
+        assert!(!verify_signature("linear", "s3cret", |_: &str| None, body, ""));
+
+        let mut mac = Hmac::<Sha1>::new_from_slice(b"s3cret").unwrap();
+        mac.update(body);
+        mac.update(b"https://hooks.example/webhooks/trello");
+        let sig = base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes());
+        let trello = headers("x-trello-webhook", sig);
+        assert!(verify_signature("trello", "s3cret", &trello, body, "https://hooks.example/webhooks/trello"));
+        assert!(!verify_signature("trello", "s3cret", &trello, body, "http://localhost/webhooks/trello"));
+    }
+
+    #[test]
+    fn boards_come_from_the_payload() {
+        let github = json!({ "issue": { "number": 1 }, "repository": { "full_name": "o/r" } });
+        assert_eq!(webhook_board("github", &github).as_deref(), Some("o/r"));
+        let trello = json!({ "action": { "data": { "board": { "id": "b1" } } } });
+        assert_eq!(webhook_board("trello", &trello).as_deref(), Some("b1"));
+        let jira = json!({ "issue": { "fields": { "project": { "key": "PROJ" } } } });
+        assert_eq!(webhook_board("jira", &jira).as_deref(), Some("PROJ"));
+        assert_eq!(webhook_board("linear", &json!({})), None);
+    }
+
+    #[test]
+    fn upsert_merges_into_existing_item() {
+        let mut items = vec![WorkItem::test("abcdef12")
+            .with_source_id("abcdef1234567890")
+            .with_title("Old")
+            .with_description("Keep me")
+            .with_status("Todo")
+            .with_labels(&["ui"])
+            .with_source("Trello")];
+        let moved = json!({
+            "action": {
+                "type": "updateCard",
+                "data": {
+                    "card": { "id": "abcdef1234567890", "name": "New" },
+                    "listAfter": { "name": "Doing" }
+                }
+            }
+        });
+        parse_webhook("trello", &moved).unwrap().apply(&mut items);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].title, "New");
+        assert_eq!(items[0].status.as_deref(), Some("Doing"));
+        assert_eq!(items[0].description.as_deref(), Some("Keep me"));
+        assert_eq!(items[0].labels, vec!["ui"]);
+
+        items.push(WorkItem {
+            id: "fedcba98".into(),
+            source_id: Some("fedcba9876543210".into()),
+            blocked_by: vec!["abcdef12".into()],
+            ..items[0].clone()
+        });
+        let closed = json!({
+            "action": {
+                "type": "updateCard",
+                "data": { "card": { "id": "abcdef1234567890", "closed": true } }
+            }
+        });
+        parse_webhook("trello", &closed).unwrap().apply(&mut items);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, "fedcba98");
+        assert!(!items[0].is_blocked());
+    }
+}